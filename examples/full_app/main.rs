@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+use saucers::navigation::NavigationRules;
+use saucers::prelude::*;
+use saucers::webview::ConsoleMessageEvent;
+use saucers::webview::Profile;
+use saucers::webview::RenderProcessTerminatedEvent;
+use saucers::webview::Retry;
+
+use crate::window_state::WindowState;
+
+mod rpc;
+mod window_state;
+
+static HTML_FILE: &[u8] = include_bytes!("index.html");
+
+/// This example puts several features together as a starting point for real
+/// apps:
+///
+/// - Pages are embedded in the binary and served from memory.
+/// - The page calls the host with JSON requests and gets replies back as DOM
+///   events, forming a small RPC layer with typed requests (see [`rpc`]).
+/// - Storage and permission decisions are kept in a [`Profile`].
+/// - The window size and maximization are restored from the last run.
+/// - External links are blocked, and console output is forwarded to stdout.
+/// - A crashed or frozen page gets reloaded.
+fn main() {
+    let app = AppManager::new(AppOptions::new_with_id("full-app"));
+
+    app.run(
+        |app| {
+            let data_dir = std::env::temp_dir().join("saucers-full-app");
+            let state_path = data_dir.join("window.json");
+            let saved_state = WindowState::load(&state_path);
+
+            let window = Window::new(
+                &app,
+                WindowListeners::new().on_closed(move |window| {
+                    if let Err(e) = WindowState::from_cached(window.cached()).save(&state_path) {
+                        eprintln!("Failed to save window state: {e}");
+                    }
+                }),
+            )
+            .unwrap();
+
+            window.set_title("Full App");
+            window.set_size((800, 600));
+
+            if let Some(state) = saved_state {
+                state.apply(&window);
+            }
+
+            window.show();
+
+            let profile = Profile::open(&data_dir, "default").expect("failed to open profile");
+
+            let opt = WebviewOptions {
+                profile: Some(profile),
+                ..Default::default()
+            };
+
+            let webview = Webview::new(opt, window, (), ()).unwrap();
+
+            webview.set_navigation_rules(Some(NavigationRules::new().block_external()));
+            webview.set_retry_policy(Some(Retry::exponential(3, Duration::from_secs(1))));
+            webview.set_render_watchdog(Some(Duration::from_secs(5)));
+
+            webview.on::<MessageEvent>(rpc::handle);
+
+            webview.on::<ConsoleMessageEvent>(|_, ev| {
+                println!("[page {:?}] {}", ev.level, ev.text);
+            });
+
+            webview.on::<RenderProcessTerminatedEvent>(|webview, ev| {
+                eprintln!("Page is gone ({:?}), reloading", ev.reason);
+                webview.recover();
+            });
+
+            webview.embed("/index.html", Stash::new_view(HTML_FILE), "text/html");
+            webview.serve("/index.html");
+
+            webview
+        },
+        (),
+    )
+    .unwrap();
+}
@@ -0,0 +1,54 @@
+//! The RPC layer of the `full_app` example, shared with its tests.
+use std::collections::BTreeMap;
+
+use saucers::prelude::*;
+
+/// A request from the page, parsed from `{ id, method, params }`.
+enum Request {
+    Add(Vec<f64>),
+}
+
+impl Request {
+    fn parse(req: &JsValue) -> std::result::Result<Self, &'static str> {
+        let params = req.get("params").and_then(JsValue::as_array).unwrap_or(&[]);
+
+        match req.get("method").and_then(JsValue::as_str) {
+            Some("add") => params
+                .iter()
+                .map(JsValue::as_f64)
+                .collect::<Option<Vec<_>>>()
+                .map(Self::Add)
+                .ok_or("numbers expected"),
+            _ => Err("unknown method"),
+        }
+    }
+
+    fn call(self) -> JsValue {
+        match self {
+            Self::Add(numbers) => numbers.iter().sum::<f64>().into(),
+        }
+    }
+}
+
+/// Handles a request sent by the page, replying with an `rpc-reply` DOM event
+/// carrying either `result` or `error`.
+pub fn handle(webview: Webview, ev: &MessageEvent) -> HandleStatus {
+    let Ok(req) = ev.json() else {
+        return HandleStatus::Unhandled;
+    };
+
+    let id = req.get("id").cloned().unwrap_or(JsValue::Null);
+    let mut reply = BTreeMap::from([("id".to_owned(), id)]);
+
+    match Request::parse(&req) {
+        Ok(req) => reply.insert("result".to_owned(), req.call()),
+        Err(error) => reply.insert("error".to_owned(), error.into()),
+    };
+
+    webview.execute(format!(
+        "window.dispatchEvent(new CustomEvent('rpc-reply', {{ detail: {} }}));",
+        JsValue::Object(reply)
+    ));
+
+    HandleStatus::Handled
+}
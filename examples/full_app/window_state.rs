@@ -0,0 +1,55 @@
+//! Window state persistence of the `full_app` example, shared with its tests.
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use saucers::prelude::*;
+use saucers::window::CachedWindowState;
+
+/// The window state kept across runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WindowState {
+    pub size: Size,
+    pub maximized: bool,
+}
+
+impl WindowState {
+    /// Takes the state from a snapshot, which stays readable after the window
+    /// is closed.
+    pub fn from_cached(cached: CachedWindowState) -> Self {
+        Self {
+            size: cached.size,
+            maximized: cached.is_maximized,
+        }
+    }
+
+    /// Loads the state saved at the given path, if any.
+    pub fn load(path: &Path) -> Option<Self> {
+        let state = JsValue::parse(&std::fs::read_to_string(path).ok()?).ok()?;
+        let dimension = |key| state.get(key)?.as_f64().map(|v| v as i32);
+
+        Some(Self {
+            size: Size::new(dimension("width")?, dimension("height")?),
+            maximized: state.get("maximized")?.as_bool()?,
+        })
+    }
+
+    /// Saves the state to the given path.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let state = JsValue::Object(BTreeMap::from([
+            ("width".to_owned(), self.size.width.into()),
+            ("height".to_owned(), self.size.height.into()),
+            ("maximized".to_owned(), self.maximized.into()),
+        ]));
+
+        std::fs::write(path, state.to_string())
+    }
+
+    /// Applies the state to the window.
+    pub fn apply(&self, window: &Window) {
+        if !self.size.is_empty() {
+            window.set_size(self.size);
+        }
+
+        window.set_maximized(self.maximized);
+    }
+}
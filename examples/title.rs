@@ -1,7 +1,7 @@
 use saucers::app::AppManager;
 use saucers::app::AppOptions;
 use saucers::webview::Webview;
-use saucers::webview::WebviewEventListener;
+use saucers::webview::WebviewListeners;
 use saucers::webview::WebviewOptions;
 use saucers::window::Window;
 
@@ -18,15 +18,11 @@ fn main() {
             window.set_title("Super Secret Title");
             window.show();
 
-            struct WebviewEv;
+            let listeners = WebviewListeners::new().on_title(|webview, title| {
+                webview.window().set_title(title);
+            });
 
-            impl WebviewEventListener for WebviewEv {
-                fn on_title(&self, webview: Webview, title: String) {
-                    webview.window().set_title(title);
-                }
-            }
-
-            let webview = Webview::new(WebviewOptions::default(), window, WebviewEv, ()).unwrap();
+            let webview = Webview::new(WebviewOptions::default(), window, listeners, ()).unwrap();
 
             webview.set_html("<title>You didn't see anything!</title>");
 
@@ -0,0 +1,192 @@
+//! Event loop activity module.
+//!
+//! See [`crate::app::App::on_idle`] and [`crate::app::App::on_busy`] for
+//! details.
+use std::ffi::c_void;
+use std::panic::RefUnwindSafe;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use saucer_sys::*;
+
+use crate::app::App;
+use crate::app::AppRef;
+use crate::util::MutexExt;
+use crate::util::ffi_callback_passive;
+
+/// Bounds of the interval at which the event loop is probed.
+const MIN_PROBE_INTERVAL: Duration = Duration::from_millis(20);
+const MAX_PROBE_INTERVAL: Duration = Duration::from_secs(1);
+
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+
+/// The last time (relative to [`EPOCH`]) a callback has been dispatched by the
+/// event loop. Only one app can run at a time, thus this is process-wide.
+static LAST_ACTIVITY: AtomicU64 = AtomicU64::new(0);
+
+fn now() -> Duration { EPOCH.get_or_init(Instant::now).elapsed() }
+
+/// Records that the event loop has dispatched a callback.
+pub(crate) fn record_activity() {
+    LAST_ACTIVITY.store(now().as_millis() as u64, Ordering::Relaxed);
+}
+
+// Handlers are shared so that they're invoked without holding the lists, as
+// they may register other handlers.
+pub(crate) type SharedIdleHandler = Arc<dyn Fn(&App) + Send + Sync + RefUnwindSafe + 'static>;
+pub(crate) type SharedBusyHandler =
+    Arc<dyn Fn(&App, Duration) + Send + Sync + RefUnwindSafe + 'static>;
+
+struct IdleHandler {
+    after: Duration,
+    callback: SharedIdleHandler,
+    /// The activity after which the handler has been invoked, so that it's
+    /// only invoked once per idle period.
+    fired_after: Option<u64>,
+}
+
+struct BusyHandler {
+    threshold: Duration,
+    callback: SharedBusyHandler,
+}
+
+/// Idle and busy handlers of an app, and the state of probing.
+#[derive(Default)]
+pub(crate) struct ActivityMonitor {
+    idle: Mutex<Vec<IdleHandler>>,
+    busy: Mutex<Vec<BusyHandler>>,
+    /// Whether a probe has been posted and not yet run.
+    probing: AtomicBool,
+    started: AtomicBool,
+}
+
+impl ActivityMonitor {
+    pub(crate) fn add_idle(&self, after: Duration, callback: SharedIdleHandler) {
+        self.idle.lock_unpoisoned().push(IdleHandler {
+            after,
+            callback,
+            fired_after: None,
+        });
+    }
+
+    pub(crate) fn add_busy(&self, threshold: Duration, callback: SharedBusyHandler) {
+        self.busy.lock_unpoisoned().push(BusyHandler {
+            threshold,
+            callback,
+        });
+    }
+
+    /// Gets how often to probe, which is a fraction of the shortest duration
+    /// handlers are interested in.
+    fn probe_interval(&self) -> Duration {
+        let idle = self.idle.lock_unpoisoned().iter().map(|h| h.after).min();
+        let busy = self
+            .busy
+            .lock_unpoisoned()
+            .iter()
+            .map(|h| h.threshold)
+            .min();
+        let shortest = idle
+            .into_iter()
+            .chain(busy)
+            .min()
+            .unwrap_or(MAX_PROBE_INTERVAL);
+
+        (shortest / 2).clamp(MIN_PROBE_INTERVAL, MAX_PROBE_INTERVAL)
+    }
+
+    /// Starts the thread probing the event loop of the app, if not yet.
+    pub(crate) fn start(monitor: &Arc<Self>, app: &App) {
+        if monitor.started.swap(true, Ordering::Relaxed) {
+            return;
+        }
+
+        let monitor = Arc::downgrade(monitor);
+        let app = app.downgrade();
+
+        std::thread::spawn(move || {
+            loop {
+                let Some(m) = monitor.upgrade() else {
+                    return;
+                };
+
+                let interval = m.probe_interval();
+
+                if !m.probing.swap(true, Ordering::Relaxed) {
+                    let Some(a) = app.upgrade() else {
+                        return;
+                    };
+
+                    let data = Box::into_raw(Box::new(ProbeData {
+                        sent: Instant::now(),
+                        app: a.downgrade(),
+                    }));
+
+                    unsafe {
+                        saucer_application_post(a.as_ptr(), Some(probe_tp), data as *mut c_void)
+                    };
+                }
+
+                drop(m);
+                std::thread::sleep(interval);
+            }
+        });
+    }
+
+    /// Runs on the event thread when a probe arrives.
+    fn on_probe(&self, app: &App, delay: Duration) {
+        self.probing.store(false, Ordering::Relaxed);
+
+        let busy = self
+            .busy
+            .lock_unpoisoned()
+            .iter()
+            .filter(|h| delay >= h.threshold)
+            .map(|h| h.callback.clone())
+            .collect::<Vec<_>>();
+
+        for cb in busy {
+            cb(app, delay);
+        }
+
+        let last = LAST_ACTIVITY.load(Ordering::Relaxed);
+        let idle_for = now().saturating_sub(Duration::from_millis(last));
+
+        let idle = self
+            .idle
+            .lock_unpoisoned()
+            .iter_mut()
+            .filter(|h| idle_for >= h.after && h.fired_after != Some(last))
+            .map(|h| {
+                h.fired_after = Some(last);
+                h.callback.clone()
+            })
+            .collect::<Vec<_>>();
+
+        for cb in idle {
+            cb(app);
+        }
+    }
+}
+
+struct ProbeData {
+    sent: Instant,
+    app: AppRef,
+}
+
+extern "C" fn probe_tp(data: *mut c_void) {
+    // Probes are not activity, or the loop would never look idle
+    ffi_callback_passive("event loop probe", (), || {
+        // SAFETY: The method is invoked only once.
+        let data = unsafe { Box::from_raw(data as *mut ProbeData) };
+        if let Some(app) = data.app.upgrade() {
+            app.activity_monitor().on_probe(&app, data.sent.elapsed());
+        }
+    });
+}
@@ -0,0 +1,106 @@
+//! Time-sliced task module.
+//!
+//! See [`crate::app::App::run_chunked`] for details.
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::app::App;
+
+#[derive(Default)]
+struct ChunkedStatus {
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+    processed: AtomicUsize,
+}
+
+/// A handle to a task started with [`App::run_chunked`].
+///
+/// Dropping the handle doesn't cancel the task.
+#[derive(Clone)]
+pub struct ChunkedTask(Arc<ChunkedStatus>);
+
+impl ChunkedTask {
+    /// Stops the task before the next item. Items already processed are not
+    /// affected.
+    pub fn cancel(&self) { self.0.cancelled.store(true, Ordering::Relaxed); }
+
+    /// Checks whether the task has ended, either because all items have been
+    /// processed, it has been cancelled, the callback has panicked, or the
+    /// app has quit.
+    pub fn is_finished(&self) -> bool { self.0.finished.load(Ordering::Acquire) }
+
+    /// Gets how many items have been processed.
+    pub fn processed(&self) -> usize { self.0.processed.load(Ordering::Relaxed) }
+}
+
+/// The state of a chunked task, moved between event loop turns.
+pub(crate) struct ChunkedJob<I, F> {
+    iter: I,
+    process: F,
+    budget: Duration,
+    status: Arc<ChunkedStatus>,
+}
+
+// Marks the task finished however it ends, including panics and the app
+// dropping the posted continuation
+impl<I, F> Drop for ChunkedJob<I, F> {
+    fn drop(&mut self) { self.status.finished.store(true, Ordering::Release); }
+}
+
+impl<I, F> ChunkedJob<I, F>
+where
+    I: Iterator + Send + 'static,
+    F: FnMut(&App, I::Item) + Send + 'static,
+{
+    pub(crate) fn start(app: &App, iter: I, process: F, budget: Duration) -> ChunkedTask {
+        let status = Arc::new(ChunkedStatus::default());
+        let job = Self {
+            iter,
+            process,
+            budget,
+            status: status.clone(),
+        };
+
+        job.schedule(app);
+        ChunkedTask(status)
+    }
+
+    fn schedule(self, app: &App) {
+        // A panicking callback only ends the task, which is then finished
+        let job = AssertUnwindSafe(self);
+        app.post(move |app| {
+            let job = job;
+            job.0.step(&app)
+        });
+    }
+
+    /// Processes items until the budget is used up, then yields to the event
+    /// loop. At least one item is processed per turn.
+    fn step(mut self, app: &App) {
+        let start = Instant::now();
+
+        loop {
+            if self.status.cancelled.load(Ordering::Relaxed) {
+                return;
+            }
+
+            let Some(item) = self.iter.next() else {
+                return;
+            };
+
+            (self.process)(app, item);
+            self.status.processed.fetch_add(1, Ordering::Relaxed);
+
+            if start.elapsed() >= self.budget {
+                break;
+            }
+        }
+
+        self.schedule(app);
+    }
+}
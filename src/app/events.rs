@@ -11,6 +11,7 @@ pub trait AppEventListener: RefUnwindSafe {
     /// [`Policy::Allow`]ed, as it's actually fired inside [`App::quit`].
     /// This also means that [`App::quit`] must not be called
     /// in this listener. Consider using [`crate::app::FinishRoutine`] if you
-    /// need a one-time callback.
+    /// need a one-time callback, or [`App::on_shutdown`] for cleanup that must
+    /// happen before the event loop exits.
     fn on_quit(&self, _app: App) -> Policy { Policy::Allow }
 }
@@ -0,0 +1,134 @@
+//! Event-thread-local cell module.
+//!
+//! See [`EventLocal`] for details.
+use std::cell::RefCell;
+use std::panic::RefUnwindSafe;
+use std::panic::UnwindSafe;
+use std::sync::Arc;
+use std::thread::ThreadId;
+
+use crate::app::App;
+use crate::app::AppRef;
+
+struct EventLocalInner<T> {
+    value: RefCell<T>,
+    host_tid: ThreadId,
+    app: AppRef,
+}
+
+/// A cell that's only accessible on the event thread.
+///
+/// Handles are cheap to clone and can be sent to other threads or captured in
+/// listeners without locking. Accessing the value is checked at runtime and
+/// panics if not on the event thread, while updates from other threads can be
+/// posted with [`Self::post_set`] and [`Self::post_update`].
+///
+/// Like [`std::cell::RefCell`], borrowing the value mutably while it's being
+/// borrowed (e.g. calling [`Self::set`] inside [`Self::with`]) panics.
+pub struct EventLocal<T> {
+    inner: Arc<EventLocalInner<T>>,
+}
+
+// SAFETY: The value is only accessed on the event thread, and is moved
+// (dropped) across threads only as a whole, which requires `T: Send`.
+unsafe impl<T: Send> Send for EventLocal<T> {}
+unsafe impl<T: Send> Sync for EventLocal<T> {}
+
+// A panic while accessing the value can't leave the cell itself in a broken
+// state. The value may be partially updated, like any other `&mut` access.
+impl<T> UnwindSafe for EventLocal<T> {}
+impl<T> RefUnwindSafe for EventLocal<T> {}
+
+impl<T> Clone for EventLocal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: 'static> EventLocal<T> {
+    /// Creates a cell holding the given value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread.
+    pub fn new(app: &App, value: T) -> Self {
+        if !app.is_thread_safe() {
+            panic!("event-local cells must be created on the event thread");
+        }
+
+        Self {
+            inner: Arc::new(EventLocalInner {
+                value: RefCell::new(value),
+                host_tid: std::thread::current().id(),
+                app: app.downgrade(),
+            }),
+        }
+    }
+
+    /// Checks whether we're on the event thread, i.e. whether the value can be
+    /// accessed.
+    pub fn is_thread_safe(&self) -> bool { std::thread::current().id() == self.inner.host_tid }
+
+    /// Invokes the callback with a reference to the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, or if the value is mutably
+    /// borrowed.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R { f(&self.value().borrow()) }
+
+    /// Invokes the callback with a mutable reference to the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, or if the value is borrowed.
+    pub fn with_mut<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.value().borrow_mut())
+    }
+
+    /// Gets a copy of the value.
+    ///
+    /// # Panics
+    ///
+    /// See [`Self::with`].
+    pub fn get(&self) -> T
+    where T: Clone {
+        self.with(T::clone)
+    }
+
+    /// Replaces the value, returning the old one.
+    ///
+    /// # Panics
+    ///
+    /// See [`Self::with_mut`].
+    pub fn set(&self, value: T) -> T { self.with_mut(|v| std::mem::replace(v, value)) }
+
+    /// Posts a callback updating the value on the event thread. Can be called
+    /// on any thread. The callback is discarded if the app has been dropped.
+    pub fn post_update(&self, f: impl FnOnce(&mut T) + Send + UnwindSafe + 'static)
+    where T: Send {
+        let Some(app) = self.inner.app.upgrade() else {
+            return;
+        };
+
+        let this = self.clone();
+        app.post(move |_| this.with_mut(f));
+    }
+
+    /// Posts a callback replacing the value on the event thread. See
+    /// [`Self::post_update`].
+    pub fn post_set(&self, value: T)
+    where T: Send + UnwindSafe {
+        self.post_update(move |v| *v = value);
+    }
+
+    fn value(&self) -> &RefCell<T> {
+        if !self.is_thread_safe() {
+            panic!("event-local cells can only be accessed on the event thread");
+        }
+
+        &self.inner.value
+    }
+}
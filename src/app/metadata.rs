@@ -0,0 +1,191 @@
+//! App metadata module.
+//!
+//! See [`AppMetadata`] for details.
+use crate::app::App;
+use crate::icon::Icon;
+use crate::thread::check_event_thread;
+use crate::util::MutexExt;
+
+/// Describes the application to surfaces of the OS, e.g. the about panel on
+/// macOS or the taskbar on Windows. Set with
+/// [`crate::app::AppOptions::metadata`] or [`App::set_metadata`].
+///
+/// Fields left as [`None`] keep what the OS derives from the executable or the
+/// bundle. On Linux, shells like GNOME match windows to the `.desktop` file
+/// named after [`crate::app::AppOptions::id`], which provides the name and icon
+/// shown there.
+#[derive(Clone, Default)]
+pub struct AppMetadata {
+    /// The name shown to users, e.g. `Saucer Notes`.
+    pub name: Option<String>,
+    /// The version shown to users, e.g. `1.2.0`.
+    pub version: Option<String>,
+    /// The copyright notice, e.g. `© 2026 Saucer Notes Authors`.
+    pub copyright: Option<String>,
+    /// The icon of the application. It's used as the dock icon on macOS, and
+    /// as the icon of windows created later elsewhere.
+    pub icon: Option<Icon>,
+    /// The AppUserModelID grouping windows in the taskbar on Windows, e.g.
+    /// `Example.SaucerNotes`. Pinned shortcuts must carry the same ID to be
+    /// grouped with the windows. Ignored elsewhere.
+    pub app_user_model_id: Option<String>,
+}
+
+impl App {
+    /// Gets the metadata of the application. See [`AppMetadata`].
+    pub fn metadata(&self) -> AppMetadata { self.0.metadata.lock_unpoisoned().clone() }
+
+    /// Replaces the metadata of the application and applies it to the OS. See
+    /// [`AppMetadata`].
+    ///
+    /// The AppUserModelID only applies to windows created later, thus it
+    /// should be set with [`crate::app::AppOptions::metadata`] instead.
+    pub fn set_metadata(&self, metadata: AppMetadata) {
+        apply_metadata(&metadata);
+        *self.0.metadata.lock_unpoisoned() = metadata;
+    }
+
+    /// Shows the standard about panel of the OS with the name, version,
+    /// copyright and icon of the application (see [`AppMetadata`]).
+    ///
+    /// Only macOS has such a panel, thus this fails with
+    /// [`crate::error::Error::Unsupported`] elsewhere.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, unless allowed by the
+    /// [`crate::thread::ThreadViolationPolicy`].
+    pub fn show_about_panel(&self) -> crate::error::Result<()> {
+        if check_event_thread(self.is_thread_safe(), "showing the about panel").is_some() {
+            return Err(crate::error::Error::WrongThread);
+        }
+
+        show_about_panel(&self.metadata())
+    }
+}
+
+/// Applies the metadata to the OS.
+pub(crate) fn apply_metadata(metadata: &AppMetadata) {
+    #[cfg(target_os = "windows")]
+    if let Some(id) = &metadata.app_user_model_id {
+        #[link(name = "shell32")]
+        unsafe extern "system" {
+            fn SetCurrentProcessExplicitAppUserModelID(id: *const u16) -> i32;
+        }
+
+        let id = id.encode_utf16().chain([0]).collect::<Vec<_>>();
+
+        // SAFETY: The string is null-terminated and copied by the call
+        unsafe { SetCurrentProcessExplicitAppUserModelID(id.as_ptr()) };
+    }
+
+    #[cfg(target_os = "macos")]
+    if let Some(icon) = &metadata.icon {
+        use objc2::class;
+        use objc2::msg_send;
+        use objc2::runtime::AnyObject;
+
+        // SAFETY: On the main thread, which is the event thread on macOS
+        unsafe {
+            let app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+            if let Some(image) = macos::image(icon)
+                && !app.is_null()
+            {
+                let _: () = msg_send![app, setApplicationIconImage: &*image];
+            }
+        }
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let _ = metadata;
+}
+
+#[cfg(target_os = "macos")]
+fn show_about_panel(metadata: &AppMetadata) -> crate::error::Result<()> {
+    use objc2::class;
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+
+    // SAFETY: On the main thread, which is the event thread on macOS
+    unsafe {
+        let options: Retained<AnyObject> = msg_send![class!(NSMutableDictionary), dictionary];
+
+        let set = |key: &str, value: &AnyObject| {
+            if let Some(key) = macos::string(key) {
+                let _: () = msg_send![&*options, setObject: value, forKey: &*key];
+            }
+        };
+
+        let texts = [
+            ("ApplicationName", &metadata.name),
+            ("ApplicationVersion", &metadata.version),
+            ("Copyright", &metadata.copyright),
+        ];
+
+        for (key, value) in texts {
+            if let Some(value) = value.as_deref().and_then(macos::string) {
+                set(key, &value);
+            }
+        }
+
+        if let Some(image) = metadata.icon.as_ref().and_then(macos::image) {
+            set("ApplicationIcon", &image);
+        }
+
+        let app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+        if !app.is_null() {
+            let _: () = msg_send![app, orderFrontStandardAboutPanelWithOptions: &*options];
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn show_about_panel(_: &AppMetadata) -> crate::error::Result<()> {
+    Err(crate::error::Error::unsupported(
+        "about panels",
+        Some("show the details from App::metadata in a window"),
+    ))
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::ffi::CString;
+    use std::ffi::c_void;
+
+    use objc2::class;
+    use objc2::msg_send;
+    use objc2::rc::Allocated;
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+
+    use crate::icon::Icon;
+
+    /// Creates an `NSString`.
+    pub(super) fn string(s: &str) -> Option<Retained<AnyObject>> {
+        let s = CString::new(s).ok()?;
+
+        // SAFETY: The string is null-terminated and copied by the call
+        unsafe { msg_send![class!(NSString), stringWithUTF8String: s.as_ptr()] }
+    }
+
+    /// Creates an `NSImage` from the encoded data of the icon.
+    pub(super) fn image(icon: &Icon) -> Option<Retained<AnyObject>> {
+        let stash = icon.data();
+        let bytes = stash.data();
+
+        // SAFETY: The bytes are copied into the `NSData`
+        unsafe {
+            let data: Option<Retained<AnyObject>> = msg_send![
+                class!(NSData),
+                dataWithBytes: bytes.as_ptr() as *const c_void,
+                length: bytes.len()
+            ];
+            let data = data?;
+            let image: Allocated<AnyObject> = msg_send![class!(NSImage), alloc];
+            msg_send![image, initWithData: &*data]
+        }
+    }
+}
@@ -2,15 +2,28 @@
 //!
 //! See [`App`] and [`AppManager`] for details.
 
+mod activity;
+mod chunked;
 mod events;
+mod local;
+mod metadata;
 mod options;
+mod policies;
+mod shutdown;
+mod temp;
+mod timer;
 
 use std::ffi::c_void;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::net::ToSocketAddrs;
+use std::panic::RefUnwindSafe;
 use std::panic::UnwindSafe;
 use std::ptr::NonNull;
 use std::ptr::null_mut;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::sync::OnceLock;
 use std::sync::Weak;
 use std::sync::mpsc::Receiver;
 use std::sync::mpsc::Sender;
@@ -18,27 +31,64 @@ use std::thread::JoinHandle;
 use std::thread::ThreadId;
 use std::time::Duration;
 
+pub use chunked::ChunkedTask;
 pub use events::*;
+pub use local::*;
+pub use metadata::AppMetadata;
 pub use options::*;
 use saucer_sys::*;
-
+pub use shutdown::ShutdownToken;
+pub use temp::*;
+
+use crate::app::activity::ActivityMonitor;
+pub(crate) use crate::app::activity::record_activity;
+use crate::app::chunked::ChunkedJob;
+use crate::app::policies::PolicyState;
+use crate::app::shutdown::Shutdown;
+use crate::app::shutdown::ShutdownPhase;
+pub(crate) use crate::app::timer::schedule as schedule_delayed;
 use crate::cleanup::CleanUpHolder;
+use crate::cleanup::DropSender;
+use crate::cleanup::HandleCounters;
+use crate::companion::CompanionRef;
 use crate::macros::ffi_forward;
 use crate::macros::load_range;
+use crate::macros::trace_event;
+use crate::macros::trace_span;
 use crate::policy::Policy;
+use crate::scheme::Drain;
+use crate::scheme::SchemeTracker;
+use crate::scheme::register_scheme;
 use crate::screen::Screen;
+use crate::url::Url;
+use crate::util::MutexExt;
 use crate::util::ffi_callback;
+use crate::webview::STREAM_SCHEME;
 use crate::webview::Webview;
 use crate::window::Window;
 
+/// The app currently running in this process. Saucer is not designed for
+/// multiple apps, thus only one can run at a time.
+static RUNNING_APP: Mutex<Option<AppRef>> = Mutex::new(None);
+
 /// An unprotected owned app handle.
 struct RawApp {
     inner: NonNull<saucer_application>,
     /// Drop sender for other handles.
-    drop_sender: Sender<CleanUpHolder>,
+    drop_sender: DropSender,
     /// Drop sender for the app itself.
     app_drop_sender: Sender<CleanUpHolder>,
     host_tid: ThreadId,
+    temp_dir: Arc<OnceLock<TempDir>>,
+    /// Companion processes to kill when the event loop exits.
+    companions: Mutex<Vec<CompanionRef>>,
+    shutdown: Mutex<Shutdown>,
+    activity: Arc<ActivityMonitor>,
+    schemes: Arc<SchemeTracker>,
+    policies: PolicyState,
+    metadata: Mutex<AppMetadata>,
+    /// Whether the stream scheme has been registered.
+    streams: bool,
 }
 
 // SAFETY: App handles are thread-safe for dispatching, and dropping is handled
@@ -59,14 +109,25 @@ impl Drop for RawApp {
 impl RawApp {
     pub(crate) fn new(
         inner: NonNull<saucer_application>,
-        drop_sender: Sender<CleanUpHolder>,
+        drop_sender: DropSender,
         app_drop_sender: Sender<CleanUpHolder>,
+        temp_dir: Arc<OnceLock<TempDir>>,
+        metadata: AppMetadata,
+        streams: bool,
     ) -> Self {
         Self {
             inner,
             drop_sender,
             app_drop_sender,
             host_tid: std::thread::current().id(),
+            temp_dir,
+            companions: Mutex::new(Vec::new()),
+            shutdown: Mutex::new(Shutdown::default()),
+            activity: Arc::new(ActivityMonitor::default()),
+            schemes: Arc::new(SchemeTracker::default()),
+            policies: PolicyState::default(),
+            metadata: Mutex::new(metadata),
+            streams,
         }
     }
 
@@ -86,6 +147,8 @@ pub struct AppManager {
     // App needs to be destroyed after all other handles, thus a dedicated channel
     app_drop_sender: Option<Sender<CleanUpHolder>>,
     app_receiver: Receiver<CleanUpHolder>,
+    temp_dir: Arc<OnceLock<TempDir>>,
+    counters: Arc<HandleCounters>,
 }
 
 impl AppManager {
@@ -97,19 +160,40 @@ impl AppManager {
 
         // As long as there is still one sender alive, this call would block, which
         // guarantees that no handles shall remain reachable after this loop.
+        let _span = trace_span!(target: "saucers::collector", "collect_handles");
+
         while let Ok(p) = self.receiver.recv() {
             unsafe { p.discard() };
+            self.counters.collected_pending();
+            trace_event!(target: "saucers::collector", "collected a pending handle");
         }
 
         // Now that all handles are destroyed, we can safely destroy the app.
+        trace_event!(target: "saucers::collector", "collecting the app");
         drop(self.app_drop_sender.take());
         while let Ok(p) = self.app_receiver.recv() {
             unsafe { p.discard() };
         }
     }
 
+    /// Gets statistics of the handles managed by this manager. Also available
+    /// via [`App::collector_stats`] when the app is running.
+    pub fn stats(&self) -> CollectorStats { self.counters.stats() }
+
+    /// Lists the windows and webviews managed by this manager that are alive.
+    /// Also available via [`App::live_handles`] when the app is running.
+    pub fn live_handles(&self) -> Vec<LiveHandle> { self.counters.live_handles() }
+
     /// Constructs an app manager from the given options.
+    ///
+    /// This also registers the schemes internal to the bindings enabled by the
+    /// options (see [`AppOptions::streams`]), which must be done before the
+    /// app is created.
     pub fn new(opt: AppOptions) -> Self {
+        if opt.streams {
+            register_scheme(STREAM_SCHEME);
+        }
+
         let (sender, receiver) = std::sync::mpsc::channel();
         let (app_sender, app_receiver) = std::sync::mpsc::channel();
         Self {
@@ -118,6 +202,8 @@ impl AppManager {
             receiver,
             app_drop_sender: Some(app_sender),
             app_receiver,
+            temp_dir: Arc::new(OnceLock::new()),
+            counters: Arc::new(HandleCounters::default()),
         }
     }
 
@@ -147,6 +233,10 @@ impl AppManager {
     /// Any handle created in the start callback must be dropped no later than
     /// its finish routine. Failing to do so would lead to a deadlock, as this
     /// method tries to join all handles before returning.
+    ///
+    /// Only one app can run in a process at a time. This method fails with
+    /// [`crate::error::Error::AppAlreadyExists`] if another one is running,
+    /// which carries a reference to it (see [`App::running`]).
     pub fn run<F>(
         mut self,
         start: impl FnOnce(App) -> F + UnwindSafe + 'static,
@@ -158,17 +248,39 @@ impl AppManager {
         #[cfg(target_os = "macos")]
         objc2::MainThreadMarker::new().expect("event loop must be started from the main thread");
 
+        // Held until the app is registered, so that racing calls can't both pass
+        let mut running = RUNNING_APP.lock_unpoisoned();
+        if let Some(existing) = running.as_ref().filter(|a| a.upgrade().is_some()) {
+            return Err(crate::error::Error::AppAlreadyExists(existing.clone()));
+        }
+
         let mut ex = -1;
 
         // SAFETY: The options are kept valid until the app quits.
-        let raw_opt = RawAppOptions::new(self.opt.take().unwrap());
+        let mut opt = self.opt.take().unwrap();
+        let metadata = std::mem::take(&mut opt.metadata);
+        let streams = opt.streams;
+        let raw_opt = RawAppOptions::new(opt);
         let ptr = unsafe { saucer_application_new(raw_opt.as_ptr(), &raw mut ex) };
 
         let app = NonNull::new(ptr).ok_or(crate::error::Error::Saucer(ex))?;
 
-        let sender = self.drop_sender.take().unwrap();
+        let sender = DropSender::new(self.drop_sender.take().unwrap(), self.counters.clone());
         let app_sender = self.app_drop_sender.take().unwrap();
-        let app = App(Arc::new(RawApp::new(app, sender, app_sender)));
+        let app = App(Arc::new(RawApp::new(
+            app,
+            sender,
+            app_sender,
+            self.temp_dir.clone(),
+            metadata,
+            streams,
+        )));
+
+        // Before any window is created, which picks up the AppUserModelID
+        metadata::apply_metadata(&app.metadata());
+
+        *running = Some(app.downgrade());
+        drop(running);
 
         // The listener is only dropped after the events are removed
         let data = Box::into_raw(Box::new(EventListenerData::new(
@@ -192,10 +304,22 @@ impl AppManager {
             saucer_application_run(ptr, Some(run_callback_tp), Some(finish_callback_tp), cdata)
         };
 
+        for c in app.0.companions.lock_unpoisoned().drain(..) {
+            if let Some(c) = c.upgrade() {
+                c.kill();
+            }
+        }
+
         drop(app); // Ensure the handle is kept to the very end to prevent immature frees
+        *RUNNING_APP.lock_unpoisoned() = None;
 
+        let temp_dir = self.temp_dir.clone();
         unsafe { self.collect_handles() }; // SAFETY: On the event thread
 
+        if let Some(t) = temp_dir.get() {
+            t.remove();
+        }
+
         // App handles are invalid here, yet AppRef won't be able to upgrade anyway.
         unsafe { drop(Box::from_raw(data)) };
 
@@ -205,6 +329,39 @@ impl AppManager {
     }
 }
 
+/// Statistics of handles managed by the collector, useful for detecting leaks.
+///
+/// Windows and webviews dropped on the event thread are destroyed immediately,
+/// while those dropped on other threads are posted to the collector and stay
+/// pending until the event loop exits. A steadily growing number of live or
+/// pending handles usually indicates a leak.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CollectorStats {
+    /// The number of windows and webviews that are alive.
+    pub live_handles: usize,
+    /// The number of dropped handles waiting to be destroyed.
+    pub pending_handles: usize,
+    /// The number of handles destroyed.
+    pub collected_handles: usize,
+}
+
+/// The kind of a [`LiveHandle`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HandleKind {
+    Window,
+    Webview,
+}
+
+/// A window or webview that is alive, listed by [`App::live_handles`].
+///
+/// IDs are unique in the process and never reused, thus the same handle can
+/// be recognized across listings when hunting leaks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LiveHandle {
+    pub id: u64,
+    pub kind: HandleKind,
+}
+
 /// An application handle.
 ///
 /// This handle manages a dedicated event loop and other resources (like event
@@ -223,9 +380,145 @@ pub struct App(Arc<RawApp>);
 impl App {
     ffi_forward! {
         /// Quits the app.
+        ///
+        /// This fires [`AppEventListener::on_quit`], and runs shutdown hooks
+        /// (see [`Self::on_shutdown`]) if it's allowed, in which case the app
+        /// quits after they finish rather than immediately.
         pub fn quit(Self) => saucer_application_quit;
     }
 
+    /// Like [`Self::quit`], but can be called on any thread, in which case
+    /// quitting is posted to the event thread.
+    pub fn request_quit(&self) {
+        if self.is_thread_safe() {
+            self.clone().quit();
+        } else {
+            self.post(|app| app.quit());
+        }
+    }
+
+    /// Like [`Self::request_quit`], but settles in-flight requests of custom
+    /// schemes before the event loop exits.
+    ///
+    /// Once the quit is allowed by [`AppEventListener::on_quit`], new scheme
+    /// requests are rejected without reaching handlers, and requests already
+    /// passed to handlers are waited for as described by the [`Drain`], which
+    /// also receives the outcome. Requests still pending at the deadline are
+    /// rejected. This runs before other shutdown hooks (see
+    /// [`Self::on_shutdown`]) and is subject to the same shutdown timeout.
+    ///
+    /// Quitting by other means doesn't wait for scheme requests, which are
+    /// abandoned when the event loop exits.
+    pub fn shutdown(&self, drain: Drain) {
+        self.0.shutdown.lock_unpoisoned().drain = Some(drain);
+        self.request_quit();
+    }
+
+    /// Registers a hook to be run on the event thread when the app quits,
+    /// after [`AppEventListener::on_quit`] allows it and before the event
+    /// loop exits. Hooks are run once, in the order they're registered.
+    ///
+    /// Each hook receives a [`ShutdownToken`], and the event loop keeps running
+    /// until all tokens are dropped, or until the timeout set with
+    /// [`Self::set_shutdown_timeout`] elapses. This allows asynchronous cleanup
+    /// like saving state from pages. Hooks registered while shutting down are
+    /// not run.
+    pub fn on_shutdown(
+        &self,
+        hook: impl FnOnce(&App, ShutdownToken) + Send + UnwindSafe + 'static,
+    ) {
+        self.0.shutdown.lock_unpoisoned().hooks.push(Box::new(hook));
+    }
+
+    /// Sets how long to wait for shutdown hooks before quitting anyway. Passing
+    /// [`None`] (the default) waits indefinitely.
+    pub fn set_shutdown_timeout(&self, timeout: Option<Duration>) {
+        self.0.shutdown.lock_unpoisoned().timeout = timeout;
+    }
+
+    /// Checks whether the app is running shutdown hooks.
+    pub fn is_shutting_down(&self) -> bool {
+        self.0.shutdown.lock_unpoisoned().phase == ShutdownPhase::Running
+    }
+
+    /// Registers a callback to be invoked on the event thread once the event
+    /// loop has been idle for the given duration. The callback is invoked
+    /// once per idle period, and again after the loop becomes active and then
+    /// idle again.
+    ///
+    /// The loop is considered active when it dispatches callbacks of the
+    /// bindings, including events of apps, windows and webviews, and posted
+    /// callbacks. This is useful for deferring heavy work until the app is not
+    /// in use. The loop is checked periodically, thus the callback may be
+    /// invoked a bit later than the given duration.
+    pub fn on_idle(
+        &self,
+        after: Duration,
+        callback: impl Fn(&App) + Send + Sync + RefUnwindSafe + 'static,
+    ) {
+        self.0.activity.add_idle(after, Arc::new(callback));
+        ActivityMonitor::start(&self.0.activity, self);
+    }
+
+    /// Registers a callback to be invoked on the event thread when the event
+    /// loop has been busy, i.e. it has taken at least the given threshold to
+    /// get to a callback posted to it. The callback receives the measured
+    /// delay.
+    ///
+    /// Busy loops drop frames and delay input, thus this is useful for
+    /// diagnosing stalls of the event thread (e.g. blocking work in handlers).
+    /// The callback is invoked after the loop has recovered, as nothing can
+    /// run on the event thread while it's stalled.
+    pub fn on_busy(
+        &self,
+        threshold: Duration,
+        callback: impl Fn(&App, Duration) + Send + Sync + RefUnwindSafe + 'static,
+    ) {
+        self.0.activity.add_busy(threshold, Arc::new(callback));
+        ActivityMonitor::start(&self.0.activity, self);
+    }
+
+    pub(crate) fn activity_monitor(&self) -> &ActivityMonitor { &self.0.activity }
+
+    pub(crate) fn scheme_tracker(&self) -> &Arc<SchemeTracker> { &self.0.schemes }
+
+    /// Checks whether webviews can open streams. See [`AppOptions::streams`].
+    pub(crate) fn has_streams(&self) -> bool { self.0.streams }
+
+    /// Starts running shutdown hooks if there are any. Returns whether the
+    /// current quit must be blocked to wait for them.
+    fn begin_shutdown(&self) -> bool {
+        let (hooks, timeout) = {
+            let mut shutdown = self.0.shutdown.lock_unpoisoned();
+            let mut hooks = std::mem::take(&mut shutdown.hooks);
+
+            // Scheme handlers may depend on state other hooks tear down
+            if let Some(drain) = shutdown.drain.take() {
+                let tracker = self.0.schemes.clone();
+                hooks.insert(
+                    0,
+                    Box::new(move |app: &App, token| tracker.drain(app, drain, token)),
+                );
+            }
+
+            if hooks.is_empty() {
+                return false;
+            }
+
+            shutdown.phase = ShutdownPhase::Running;
+            (hooks, shutdown.timeout)
+        };
+
+        Shutdown::run_hooks(self, hooks, timeout);
+        true
+    }
+
+    /// Quits the app after shutdown hooks have finished.
+    pub(crate) fn finish_shutdown(&self) {
+        self.0.shutdown.lock_unpoisoned().phase = ShutdownPhase::Done;
+        self.clone().quit();
+    }
+
     pub(crate) fn as_ptr(&self) -> *mut saucer_application { self.0.as_ptr() }
 
     /// Checks whether we're on the event thread.
@@ -268,6 +561,85 @@ impl App {
         })
     }
 
+    /// Processes items of the iterator on the event thread in time slices,
+    /// yielding to the event loop whenever a slice has run for `budget`
+    /// (at least one item is processed per slice). Continuations are posted
+    /// automatically, thus input and painting can happen in between, which
+    /// keeps windows responsive during CPU-heavy work that must touch UI
+    /// state.
+    ///
+    /// The first slice runs on the next event loop. Returns a [`ChunkedTask`]
+    /// to cancel the task or check its progress. A panic in `process` ends
+    /// the task.
+    pub fn run_chunked<I>(
+        &self,
+        items: I,
+        budget: Duration,
+        process: impl FnMut(&App, I::Item) + Send + 'static,
+    ) -> ChunkedTask
+    where
+        I: IntoIterator,
+        I::IntoIter: Send + 'static,
+    {
+        ChunkedJob::start(self, items.into_iter(), process, budget)
+    }
+
+    /// Resolves the hosts of the given URLs in the background ahead of loading
+    /// them, which fills the DNS cache of the system on most platforms and
+    /// cuts the latency of the first page. Returns a [`JoinHandle`] of the
+    /// thread doing the lookups.
+    ///
+    /// This is a DNS prefetch only: backends don't expose preconnecting or
+    /// preloading, thus no connection is made and nothing is downloaded.
+    /// URLs other than HTTP(S) ones (including custom schemes and embedded
+    /// content) are skipped. To have a page loaded and parsed before it's
+    /// shown, load it in a hidden webview instead (see
+    /// [`crate::webview::WebviewPool`]).
+    pub fn prefetch_dns(&self, urls: impl IntoIterator<Item = impl AsRef<str>>) -> JoinHandle<()> {
+        let hosts = urls
+            .into_iter()
+            .filter_map(|u| Url::new_parse(u.as_ref()).ok())
+            .filter_map(|u| {
+                let port = match u.scheme().as_str() {
+                    "http" => 80,
+                    "https" => 443,
+                    _ => return None,
+                };
+
+                Some((u.host(), u.port().map_or(port, |p| p as u16)))
+            })
+            .filter(|(host, _)| !host.is_empty())
+            .collect::<Vec<_>>();
+
+        std::thread::spawn(move || {
+            for (host, port) in hosts {
+                let _ = (host.as_str(), port).to_socket_addrs(); // Only the lookup matters
+            }
+        })
+    }
+
+    /// Gets statistics of the handles managed by the collector. See
+    /// [`CollectorStats`].
+    pub fn collector_stats(&self) -> CollectorStats { self.0.drop_sender.stats() }
+
+    /// Lists the windows and webviews that are alive, ordered by creation. See
+    /// [`LiveHandle`].
+    pub fn live_handles(&self) -> Vec<LiveHandle> { self.0.drop_sender.live_handles() }
+
+    /// Gets the app running in this process, if any.
+    pub fn running() -> Option<App> { RUNNING_APP.lock_unpoisoned().as_ref()?.upgrade() }
+
+    /// Gets the temporary directory of the app, creating it if needed. See
+    /// [`TempDir`] for its lifecycle.
+    pub fn temp_dir(&self) -> crate::error::Result<&TempDir> {
+        if let Some(t) = self.0.temp_dir.get() {
+            return Ok(t);
+        }
+
+        let t = TempDir::create()?;
+        Ok(self.0.temp_dir.get_or_init(|| t))
+    }
+
     /// Gets a list of screens available.
     pub fn screens(&self) -> Vec<Screen> {
         let data = load_range!(ptr[size] = null_mut(); {
@@ -282,8 +654,15 @@ impl App {
     /// Gets a weak [`AppRef`].
     pub fn downgrade(&self) -> AppRef { AppRef(Arc::downgrade(&self.0)) }
 
+    /// Records a companion process to be killed when the event loop exits.
+    pub(crate) fn track_companion(&self, companion: CompanionRef) {
+        let mut companions = self.0.companions.lock_unpoisoned();
+        companions.retain(|c| c.upgrade().is_some());
+        companions.push(companion);
+    }
+
     /// Clones a drop sender.
-    pub(crate) fn drop_sender(&self) -> Sender<CleanUpHolder> { self.0.drop_sender.clone() }
+    pub(crate) fn drop_sender(&self) -> DropSender { self.0.drop_sender.clone() }
 }
 
 /// A weak app handle.
@@ -299,6 +678,12 @@ impl AppRef {
     pub fn upgrade(&self) -> Option<App> { Some(App(self.0.upgrade()?)) }
 }
 
+impl Debug for AppRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AppRef").finish_non_exhaustive()
+    }
+}
+
 /// A one-shot routine invoked after the app event loop stops.
 pub trait FinishRoutine: UnwindSafe {
     /// Runs this routine once after the event loop stops.
@@ -355,13 +740,13 @@ extern "C" fn run_callback_tp(_: *mut saucer_application, data: *mut c_void) {
     let mut data = unsafe { Box::from_raw(data as *mut RunCallbackData) };
     if let Some(start) = data.callback.take() {
         let app = data.app.clone();
-        data.finish_routine = ffi_callback(None, move || Some(start(app)));
+        data.finish_routine = ffi_callback("app start callback", None, move || Some(start(app)));
     }
     let _ = Box::into_raw(data); // It will be used in the finish callback
 }
 
 extern "C" fn finish_callback_tp(_: *mut saucer_application, data: *mut c_void) {
-    ffi_callback((), || {
+    ffi_callback("app finish routine", (), || {
         // SAFETY: The method will not be invoked before the run callback returns,
         // making it safe to reclaim the ownership of the user data.
         let data = unsafe { Box::from_raw(data as *mut RunCallbackData) };
@@ -391,7 +776,7 @@ impl PostCallbackData {
 }
 
 extern "C" fn post_callback_tp(data: *mut c_void) {
-    ffi_callback((), || {
+    ffi_callback("posted callback", (), || {
         // SAFETY: The method is invoked only once.
         let data = unsafe { Box::from_raw(data as *mut PostCallbackData) };
         if let Some(app) = data.app.upgrade() {
@@ -421,7 +806,7 @@ impl PostTimeoutCallbackData {
 }
 
 extern "C" fn post_timeout_callback_tp(data: *mut c_void) {
-    ffi_callback((), || {
+    ffi_callback("posted callback", (), || {
         // SAFETY: The method is invoked only once.
         let data = unsafe { Box::from_raw(data as *mut PostTimeoutCallbackData) };
         let cb = {
@@ -454,11 +839,21 @@ extern "C" fn ev_on_quit_tp(_: *mut saucer_application, data: *mut c_void) -> sa
     // SAFETY: The borrow inside the data is guaranteed to be valid as long as the
     // app runs.
     let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback(Policy::Allow.into(), || {
-        if let Some(app) = data.app.upgrade() {
-            data.listener.on_quit(app).into()
-        } else {
-            Policy::Allow.into()
-        }
+    ffi_callback("app quit event", Policy::Allow.into(), || {
+        let Some(app) = data.app.upgrade() else {
+            return Policy::Allow.into();
+        };
+
+        let phase = app.0.shutdown.lock_unpoisoned().phase;
+        let policy = match phase {
+            ShutdownPhase::Done => Policy::Allow,
+            ShutdownPhase::Running => Policy::Block,
+            ShutdownPhase::Idle => match data.listener.on_quit(app.clone()) {
+                Policy::Allow if app.begin_shutdown() => Policy::Block,
+                policy => policy,
+            },
+        };
+
+        policy.into()
     })
 }
@@ -8,6 +8,7 @@ use std::ptr::NonNull;
 
 use saucer_sys::*;
 
+use crate::app::AppMetadata;
 use crate::macros::use_string;
 
 /// Options for the application.
@@ -16,6 +17,14 @@ pub struct AppOptions {
     pub id: String,
     pub args: Vec<String>,
     pub quit_on_last_window_closed: bool,
+    /// Describes the application to the OS. See [`AppMetadata`].
+    pub metadata: AppMetadata,
+    /// Whether webviews can send streams to pages with
+    /// [`crate::webview::Webview::open_stream`]. Enabling this registers the
+    /// `saucers` scheme for the whole process when the
+    /// [`crate::app::AppManager`] is created, as schemes can't be registered
+    /// once an app exists.
+    pub streams: bool,
 }
 
 impl AppOptions {
@@ -25,12 +34,27 @@ impl AppOptions {
             id,
             args,
             quit_on_last_window_closed,
+            metadata: AppMetadata::default(),
+            streams: false,
         }
     }
 
     /// Constructs options with ID, leaving other fields as default.
     pub fn new_with_id(id: impl Into<String>) -> Self { Self::new(id.into(), Vec::new(), true) }
 
+    /// Sets the metadata describing the application to the OS. See
+    /// [`AppMetadata`].
+    pub fn with_metadata(mut self, metadata: AppMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Sets whether webviews can open streams. See [`AppOptions::streams`].
+    pub fn with_streams(mut self, streams: bool) -> Self {
+        self.streams = streams;
+        self
+    }
+
     /// Makes this options inherit [`std::env::args`] as its args.
     pub fn inherit_args(&mut self) { self.args = std::env::args().collect(); }
 }
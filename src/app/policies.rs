@@ -0,0 +1,121 @@
+//! App policy module.
+//!
+//! See [`crate::app::App::apply_policies`] for details.
+use std::panic::RefUnwindSafe;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use crate::app::App;
+use crate::policy::PolicySet;
+use crate::util::MutexExt;
+
+/// A [`PolicySet`] with the version it was applied as.
+#[derive(Debug, Default)]
+pub(crate) struct VersionedPolicies {
+    pub(crate) version: u64,
+    pub(crate) policies: PolicySet,
+}
+
+/// Updates the policies of a webview. Returns whether to keep receiving
+/// updates.
+type PolicySubscriber = Arc<dyn Fn(&VersionedPolicies) -> bool + Send + Sync>;
+
+type SharedAppliedHandler = Arc<dyn Fn(&App, u64) + Send + Sync + RefUnwindSafe + 'static>;
+
+/// The policies of an app and their consumers.
+#[derive(Default)]
+pub(crate) struct PolicyState {
+    current: Mutex<Arc<VersionedPolicies>>,
+    subscribers: Mutex<Vec<PolicySubscriber>>,
+    applied_handlers: Mutex<Vec<SharedAppliedHandler>>,
+}
+
+impl App {
+    /// Replaces the policies of all webviews of the app at once, returning the
+    /// version of the new policies. Versions start at 1 and increase with each
+    /// call, with 0 being the empty set the app starts with.
+    ///
+    /// Navigation rules and the permission table take effect immediately, for
+    /// events fired after this call. Content rules are pushed to pages on the
+    /// event thread, after which handlers registered with
+    /// [`Self::on_policies_applied`] are invoked, confirming that the version
+    /// is in effect everywhere. Webviews created later use the policies
+    /// current at the time.
+    ///
+    /// When called again before the previous version is pushed, the previous
+    /// one is skipped and only the latest version is confirmed.
+    pub fn apply_policies(&self, policies: PolicySet) -> u64 {
+        let version = {
+            let mut current = self.0.policies.current.lock_unpoisoned();
+            let version = current.version + 1;
+            *current = Arc::new(VersionedPolicies { version, policies });
+            version
+        };
+
+        self.post(move |app| {
+            let current = app.current_policies();
+            if current.version != version {
+                return; // Superseded, pushed by the later call
+            }
+
+            // Called without the lock, as subscribers may re-enter, e.g. by
+            // creating webviews that subscribe in turn
+            let subscribers = app.0.policies.subscribers.lock_unpoisoned().clone();
+            let stale: Vec<_> = subscribers.into_iter().filter(|s| !s(&current)).collect();
+
+            if !stale.is_empty() {
+                app.0
+                    .policies
+                    .subscribers
+                    .lock_unpoisoned()
+                    .retain(|s| !stale.iter().any(|d| Arc::ptr_eq(s, d)));
+            }
+
+            let handlers = app.0.policies.applied_handlers.lock_unpoisoned().clone();
+            for h in handlers {
+                h(&app, version);
+            }
+        });
+
+        version
+    }
+
+    /// Gets a copy of the policies currently applied. See
+    /// [`Self::apply_policies`].
+    pub fn policies(&self) -> PolicySet { self.current_policies().policies.clone() }
+
+    /// Gets the version of the policies currently applied. See
+    /// [`Self::apply_policies`].
+    pub fn policy_version(&self) -> u64 { self.current_policies().version }
+
+    /// Registers a callback to be invoked on the event thread once policies
+    /// passed to [`Self::apply_policies`] are in effect in all webviews. The
+    /// callback receives the applied version.
+    pub fn on_policies_applied(
+        &self,
+        callback: impl Fn(&App, u64) + Send + Sync + RefUnwindSafe + 'static,
+    ) {
+        self.0
+            .policies
+            .applied_handlers
+            .lock_unpoisoned()
+            .push(Arc::new(callback));
+    }
+
+    /// Gets a snapshot of the current policies.
+    pub(crate) fn current_policies(&self) -> Arc<VersionedPolicies> {
+        self.0.policies.current.lock_unpoisoned().clone()
+    }
+
+    /// Registers a consumer of policy updates, invoked on the event thread.
+    pub(crate) fn subscribe_policies(
+        &self,
+        subscriber: impl Fn(&VersionedPolicies) -> bool + Send + Sync + 'static,
+    ) {
+        self.0
+            .policies
+            .subscribers
+            .lock_unpoisoned()
+            .push(Arc::new(subscriber));
+    }
+}
@@ -0,0 +1,69 @@
+//! Graceful shutdown module.
+//!
+//! See [`crate::app::App::on_shutdown`] for details.
+use std::panic::UnwindSafe;
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use crate::app::App;
+use crate::scheme::Drain;
+
+pub(crate) type BoxedShutdownHook = Box<dyn FnOnce(&App, ShutdownToken) + Send + UnwindSafe>;
+
+/// A token held by a shutdown hook while its cleanup is in progress.
+///
+/// The event loop exits once all tokens are dropped or the shutdown timeout
+/// elapses. Hooks doing synchronous cleanup can simply ignore the token, which
+/// is dropped when they return. Hooks doing asynchronous cleanup (e.g. on
+/// another thread, or in a page) can move the token and drop it when done.
+pub struct ShutdownToken(#[allow(dead_code)] Sender<()>);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum ShutdownPhase {
+    /// No shutdown has been started.
+    #[default]
+    Idle,
+    /// Hooks have been run and the app is waiting for their tokens.
+    Running,
+    /// Hooks have finished, the next quit proceeds unconditionally.
+    Done,
+}
+
+/// The shutdown hooks of an app and their progress.
+#[derive(Default)]
+pub(crate) struct Shutdown {
+    pub(crate) hooks: Vec<BoxedShutdownHook>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) phase: ShutdownPhase,
+    /// How to drain scheme requests, set by [`App::shutdown`].
+    pub(crate) drain: Option<Drain>,
+}
+
+impl Shutdown {
+    /// Runs the hooks and spawns a thread quitting the app once they finish.
+    ///
+    /// Must be called on the event thread without holding the lock of the
+    /// shutdown state, as hooks may access the app.
+    pub(crate) fn run_hooks(app: &App, hooks: Vec<BoxedShutdownHook>, timeout: Option<Duration>) {
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        for hook in hooks {
+            hook(app, ShutdownToken(sender.clone()));
+        }
+
+        drop(sender);
+
+        let app = app.downgrade();
+        std::thread::spawn(move || {
+            // Either way, the senders are gone or the time is up
+            let _ = match timeout {
+                Some(t) => receiver.recv_timeout(t).ok(),
+                None => receiver.recv().ok(),
+            };
+
+            if let Some(app) = app.upgrade() {
+                app.post(|app| app.finish_shutdown());
+            }
+        });
+    }
+}
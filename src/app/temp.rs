@@ -0,0 +1,212 @@
+//! Temporary directory module.
+//!
+//! See [`TempDir`] for details.
+use std::fs::File;
+use std::io::ErrorKind;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+const DIR_PREFIX: &str = "saucers-";
+
+/// The file held locked by the process owning a directory.
+const LOCK_FILE: &str = ".lock";
+
+/// A temporary directory managed by the app, obtained via
+/// [`crate::app::App::temp_dir`].
+///
+/// The directory is created on first use with a random name, readable only by
+/// the current user where permissions apply, and removed after the event loop
+/// exits and all handles are collected. Each directory holds a lock for as
+/// long as its process runs. Directories left behind by processes that didn't
+/// exit orderly (e.g. crashed or killed) no longer hold it, and are removed the
+/// next time a directory is created.
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+    counter: AtomicU64,
+    /// The lock file, which is held while the file is open.
+    _lock: File,
+}
+
+impl TempDir {
+    /// Creates the directory for the current process, sweeping stale ones.
+    pub(crate) fn create() -> crate::error::Result<Self> {
+        let base = std::env::temp_dir();
+        sweep_stale(&base);
+
+        loop {
+            let path = base.join(format!("{DIR_PREFIX}{}", crate::util::random_token()));
+
+            match create_private_dir(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            }
+
+            let lock = File::create_new(path.join(LOCK_FILE))?;
+            if !try_lock(&lock) {
+                return Err(std::io::Error::other("failed to lock the temporary directory").into());
+            }
+
+            return Ok(Self {
+                path,
+                counter: AtomicU64::new(0),
+                _lock: lock,
+            });
+        }
+    }
+
+    /// Gets the path of the directory.
+    pub fn path(&self) -> &Path { &self.path }
+
+    /// Gets a unique path in the directory whose file name ends with the given
+    /// name (e.g. `report.pdf`). The file is not created.
+    ///
+    /// Fails with [`ErrorKind::InvalidInput`] if the name is not a plain file
+    /// name, e.g. it contains path separators.
+    pub fn unique_path(&self, name: &str) -> crate::error::Result<PathBuf> {
+        let mut components = Path::new(name).components();
+        let is_plain = matches!(components.next(), Some(Component::Normal(_)))
+            && components.next().is_none()
+            && !name.contains(['/', '\\', ':', '\0']);
+
+        if !is_plain {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("not a plain file name: {name}"),
+            )
+            .into());
+        }
+
+        let n = self.counter.fetch_add(1, Ordering::Relaxed);
+        Ok(self.path.join(format!("{n}-{name}")))
+    }
+
+    /// Creates a new file at a unique path. See [`Self::unique_path`].
+    pub fn create_file(&self, name: &str) -> crate::error::Result<(PathBuf, File)> {
+        loop {
+            let path = self.unique_path(name)?;
+            match File::create_new(&path) {
+                Ok(f) => return Ok((path, f)),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Removes the directory and its contents.
+    pub(crate) fn remove(&self) { let _ = std::fs::remove_dir_all(&self.path); }
+}
+
+#[cfg(unix)]
+fn create_private_dir(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+    std::fs::DirBuilder::new().mode(0o700).create(path)
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(path: &Path) -> std::io::Result<()> { std::fs::create_dir(path) }
+
+/// Tries to take the lock of a directory, which is released when the file is
+/// closed, including when the process dies.
+#[cfg(unix)]
+fn try_lock(file: &File) -> bool {
+    use std::os::fd::AsRawFd;
+
+    const LOCK_EX: i32 = 2;
+    const LOCK_NB: i32 = 4;
+
+    unsafe extern "C" {
+        fn flock(fd: i32, operation: i32) -> i32;
+    }
+
+    // SAFETY: The descriptor is owned by the file, which outlives the call
+    unsafe { flock(file.as_raw_fd(), LOCK_EX | LOCK_NB) == 0 }
+}
+
+/// Tries to take the lock of a directory. Files are opened without sharing,
+/// thus the open file is the lock.
+#[cfg(not(unix))]
+fn try_lock(_: &File) -> bool { true }
+
+/// Opens the lock file of a directory of another process.
+#[cfg(unix)]
+fn open_lock(path: &Path) -> std::io::Result<File> { File::open(path) }
+
+#[cfg(windows)]
+fn open_lock(path: &Path) -> std::io::Result<File> {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    // Fails while the owning process has the file open
+    std::fs::OpenOptions::new()
+        .read(true)
+        .share_mode(0)
+        .open(path)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn open_lock(_: &Path) -> std::io::Result<File> { Err(ErrorKind::Unsupported.into()) }
+
+/// Removes directories whose processes are gone, as told by their locks.
+/// Directories without lock files (e.g. being created) are left alone.
+fn sweep_stale(base: &Path) {
+    let Ok(entries) = std::fs::read_dir(base) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let is_ours = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|n| n.starts_with(DIR_PREFIX));
+
+        if !is_ours || !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+
+        let path = entry.path();
+        let Ok(lock) = open_lock(&path.join(LOCK_FILE)) else {
+            continue;
+        };
+
+        if try_lock(&lock) {
+            drop(lock); // Windows can't remove open files
+            let _ = std::fs::remove_dir_all(&path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_paths_reject_separators() {
+        let dir = TempDir::create().unwrap();
+
+        let path = dir.unique_path("report.pdf").unwrap();
+        assert_eq!(path.parent(), Some(dir.path()));
+
+        for name in ["../x", "a/b", "a\\b", "C:x", "..", ""] {
+            assert!(
+                dir.unique_path(name).is_err(),
+                "{name:?} should be rejected"
+            );
+        }
+
+        dir.remove();
+    }
+
+    #[test]
+    fn live_directories_survive_sweeping() {
+        let dir = TempDir::create().unwrap();
+
+        sweep_stale(dir.path().parent().unwrap());
+        assert!(dir.path().exists());
+
+        dir.remove();
+    }
+}
@@ -0,0 +1,120 @@
+//! Delayed callback module.
+//!
+//! Delays are waited on a single thread shared by the process, instead of a
+//! thread per delay, as webviews schedule them for every load and tick.
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::sync::LazyLock;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+use std::time::Instant;
+
+type Task = Box<dyn FnOnce() + Send>;
+
+/// A task waiting for its deadline. Tasks of the same deadline run in the
+/// order they're scheduled.
+struct Entry {
+    deadline: Instant,
+    seq: u64,
+    task: Task,
+}
+
+impl Entry {
+    fn key(&self) -> (Instant, u64) { (self.deadline, self.seq) }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool { self.key() == other.key() }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering { self.key().cmp(&other.key()) }
+}
+
+static TIMER: LazyLock<Sender<(Instant, Task)>> = LazyLock::new(|| {
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    std::thread::Builder::new()
+        .name("saucers-timer".into())
+        .spawn(move || run(rx))
+        .expect("failed to spawn the timer thread");
+
+    tx
+});
+
+/// Runs the task on the timer thread after the given delay. Tasks must be
+/// short, as they delay the ones behind them (posting to the event thread is
+/// fine).
+pub(crate) fn schedule(delay: Duration, task: impl FnOnce() + Send + 'static) {
+    // Delays too large to be represented never pass
+    let Some(deadline) = Instant::now().checked_add(delay) else {
+        return;
+    };
+
+    let _ = TIMER.send((deadline, Box::new(task)));
+}
+
+fn run(rx: Receiver<(Instant, Task)>) {
+    let mut queue = BinaryHeap::<Reverse<Entry>>::new();
+    let mut seq = 0;
+
+    loop {
+        let received = match queue.peek() {
+            Some(Reverse(next)) => {
+                rx.recv_timeout(next.deadline.saturating_duration_since(Instant::now()))
+            }
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+
+        match received {
+            Ok((deadline, task)) => {
+                seq += 1;
+                queue.push(Reverse(Entry {
+                    deadline,
+                    seq,
+                    task,
+                }));
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+
+        let now = Instant::now();
+        while queue.peek().is_some_and(|Reverse(e)| e.deadline <= now) {
+            if let Some(Reverse(e)) = queue.pop() {
+                crate::util::ffi_callback("delayed task", (), std::panic::AssertUnwindSafe(e.task));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+    use std::time::Duration;
+
+    use super::schedule;
+
+    #[test]
+    fn tasks_run_in_deadline_order() {
+        let (tx, rx) = channel();
+
+        for (i, delay) in [(0, 60), (1, 20), (2, 40), (3, 20)] {
+            let tx = tx.clone();
+            schedule(Duration::from_millis(delay), move || tx.send(i).unwrap());
+        }
+
+        let order = (0..4)
+            .map(|_| rx.recv_timeout(Duration::from_secs(5)).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(order, [1, 3, 2, 0]);
+    }
+}
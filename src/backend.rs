@@ -0,0 +1,67 @@
+//! Identification of the webview backend.
+//!
+//! The backend is decided at build time by the target platform and the `qt`
+//! feature. This module exposes it at runtime, so that downstream crates can
+//! branch on it without repeating the same `cfg` conditions.
+
+/// A webview backend of saucer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// Microsoft Edge WebView2, used on Windows.
+    WebView2,
+    /// WKWebView, used on macOS.
+    WebKit,
+    /// WebKitGTK, used on Linux and other Unix platforms.
+    WebKitGtk,
+    /// Qt WebEngine, used when the `qt` feature is enabled.
+    Qt,
+}
+
+impl Backend {
+    /// Gets the backend this crate is built with.
+    pub const fn current() -> Self {
+        if cfg!(feature = "qt") {
+            Self::Qt
+        } else if cfg!(target_os = "windows") {
+            Self::WebView2
+        } else if cfg!(target_os = "macos") {
+            Self::WebKit
+        } else {
+            Self::WebKitGtk
+        }
+    }
+
+    /// Checks whether the backend is based on Chromium.
+    pub const fn is_chromium(&self) -> bool { matches!(self, Self::WebView2 | Self::Qt) }
+
+    /// Gets the capabilities of the backend.
+    pub const fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            browser_flags: self.is_chromium(),
+            remote_debugging: self.is_chromium(),
+            material_effects: false,
+            speech_recognition: matches!(self, Self::WebView2 | Self::WebKit),
+        }
+    }
+}
+
+/// Features that may or may not be available depending on the backend. See
+/// [`Backend::capabilities`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    /// Whether browser flags (including those derived from other options)
+    /// take effect. See [`crate::webview::WebviewOptions::browser_flags`].
+    pub browser_flags: bool,
+    /// Whether [`crate::webview::WebviewOptions::remote_debugging_port`] takes
+    /// effect.
+    pub remote_debugging: bool,
+    /// Whether material window effects are available. See
+    /// [`crate::window::WindowEffect`].
+    pub material_effects: bool,
+    /// Whether the engine implements speech recognition of the Web Speech API.
+    /// See [`crate::permission::PermissionType::SpeechRecognition`].
+    pub speech_recognition: bool,
+}
+
+/// Gets the backend this crate is built with. Same as [`Backend::current`].
+pub const fn backend() -> Backend { Backend::current() }
@@ -1,9 +1,22 @@
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::ptr::NonNull;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::SendError;
+use std::sync::mpsc::Sender;
 
 use saucer_sys::*;
 
+use crate::app::CollectorStats;
+use crate::app::HandleKind;
+use crate::app::LiveHandle;
+use crate::macros::trace_event;
 use crate::macros::use_string;
+use crate::util::MutexExt;
 use crate::webview::SchemeHandlerData;
 
 /// Provides a unified interface for handles to transfer resources that must be
@@ -68,3 +81,93 @@ impl CleanUpHolder {
         }
     }
 }
+
+/// The ID of the next tracked handle. IDs are unique in the process, even
+/// across apps.
+static NEXT_HANDLE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Counters of handles managed by the collector.
+#[derive(Default)]
+pub(crate) struct HandleCounters {
+    live: AtomicUsize,
+    pending: AtomicUsize,
+    collected: AtomicUsize,
+    live_ids: Mutex<BTreeMap<u64, HandleKind>>,
+}
+
+impl HandleCounters {
+    /// Records a holder discarded by the collector.
+    pub(crate) fn collected_pending(&self) {
+        self.pending.fetch_sub(1, Ordering::Relaxed);
+        self.collected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn stats(&self) -> CollectorStats {
+        CollectorStats {
+            live_handles: self.live.load(Ordering::Relaxed),
+            pending_handles: self.pending.load(Ordering::Relaxed),
+            collected_handles: self.collected.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn live_handles(&self) -> Vec<LiveHandle> {
+        self.live_ids
+            .lock_unpoisoned()
+            .iter()
+            .map(|(&id, &kind)| LiveHandle { id, kind })
+            .collect()
+    }
+}
+
+/// A sender of [`CleanUpHolder`]s that also keeps [`HandleCounters`] updated.
+#[derive(Clone)]
+pub(crate) struct DropSender {
+    sender: Sender<CleanUpHolder>,
+    counters: Arc<HandleCounters>,
+}
+
+impl DropSender {
+    pub(crate) fn new(sender: Sender<CleanUpHolder>, counters: Arc<HandleCounters>) -> Self {
+        Self { sender, counters }
+    }
+
+    pub(crate) fn stats(&self) -> CollectorStats { self.counters.stats() }
+
+    pub(crate) fn live_handles(&self) -> Vec<LiveHandle> { self.counters.live_handles() }
+
+    /// Records a newly created handle, returning its ID.
+    pub(crate) fn track(&self, kind: HandleKind) -> u64 {
+        let id = NEXT_HANDLE_ID.fetch_add(1, Ordering::Relaxed);
+        trace_event!(target: "saucers::collector", id, ?kind, "handle created");
+        self.counters.live_ids.lock_unpoisoned().insert(id, kind);
+        self.counters.live.fetch_add(1, Ordering::Relaxed);
+        id
+    }
+
+    fn untrack(&self, id: u64) {
+        self.counters.live_ids.lock_unpoisoned().remove(&id);
+        self.counters.live.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Posts the holder of the handle with the given ID to the collector.
+    pub(crate) fn send(
+        &self,
+        id: u64,
+        holder: CleanUpHolder,
+    ) -> Result<(), SendError<CleanUpHolder>> {
+        trace_event!(target: "saucers::collector", id, "handle drop posted to the collector");
+        self.untrack(id);
+        self.counters.pending.fetch_add(1, Ordering::Relaxed);
+        self.sender.send(holder)
+    }
+
+    /// Discards the holder of the handle with the given ID immediately.
+    ///
+    /// SAFETY: Must be called on the event thread.
+    pub(crate) unsafe fn discard(&self, id: u64, holder: CleanUpHolder) {
+        trace_event!(target: "saucers::collector", id, "handle dropped on the event thread");
+        self.untrack(id);
+        unsafe { holder.discard() };
+        self.counters.collected.fetch_add(1, Ordering::Relaxed);
+    }
+}
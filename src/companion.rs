@@ -0,0 +1,309 @@
+//! Companion process module.
+//!
+//! Apps often rely on helper processes (e.g. media encoders or language
+//! servers). A [`Companion`] is a child process bound to the app lifetime: it's
+//! killed when the handle is dropped or when the event loop exits, and its
+//! output is delivered as events on the event thread.
+//!
+//! Pages never talk to companions directly. Instead, a webview can be bridged
+//! to a companion with [`crate::webview::Webview::bridge_companion`], which
+//! passes messages from the page through a Rust handler deciding what reaches
+//! the process.
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::io::Write;
+use std::panic::AssertUnwindSafe;
+use std::process::Child;
+use std::process::ChildStdin;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::Weak;
+use std::sync::mpsc::SyncSender;
+use std::sync::mpsc::TrySendError;
+use std::time::Duration;
+
+use crate::app::App;
+use crate::app::AppRef;
+use crate::util::MutexExt;
+use crate::util::js_string;
+use crate::webview::WebviewRef;
+
+/// How often to check whether the process has exited after its output closes.
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How many writes can be queued for stdin before [`Companion::write`] fails.
+const STDIN_QUEUE_CAPACITY: usize = 64;
+
+/// The output stream of a companion process.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompanionStream {
+    Stdout,
+    Stderr,
+}
+
+impl CompanionStream {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Stdout => "stdout",
+            Self::Stderr => "stderr",
+        }
+    }
+}
+
+/// An event of a companion process, delivered on the event thread.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CompanionEvent {
+    /// A line has been written to the output. Line endings are stripped, and
+    /// invalid UTF-8 is replaced.
+    Output {
+        stream: CompanionStream,
+        line: String,
+    },
+    /// The process has exited with the given code, which is [`None`] if it was
+    /// terminated by a signal.
+    Exited(Option<i32>),
+}
+
+type BoxedCompanionHandler = Box<dyn Fn(&App, &Companion, CompanionEvent) + Send + Sync + 'static>;
+
+struct RawCompanion {
+    name: String,
+    child: Mutex<Child>,
+    /// Queue of the thread writing to stdin, as the process may stop reading
+    /// and block writes.
+    stdin: Mutex<Option<SyncSender<Vec<u8>>>>,
+    handler: BoxedCompanionHandler,
+    app: AppRef,
+    /// Webviews the output is forwarded to.
+    bridges: Mutex<Vec<WebviewRef>>,
+}
+
+impl Drop for RawCompanion {
+    fn drop(&mut self) {
+        let child = self.child.get_mut().unwrap();
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+/// A handle to a companion process.
+///
+/// Cloning this handle creates a shared reference to the same process. The
+/// process is killed when the last handle is dropped, or when the event loop
+/// of the app exits, whichever comes first.
+#[derive(Clone)]
+pub struct Companion(Arc<RawCompanion>);
+
+impl Companion {
+    /// Spawns the command as a companion process of the app, with the given
+    /// name identifying it to bridged pages.
+    ///
+    /// Stdin, stdout and stderr of the command are piped, overriding any
+    /// configuration done before. Output is read line by line in background
+    /// threads, and each line is delivered to the handler on the event thread.
+    /// [`CompanionEvent::Exited`] is delivered once the process exits.
+    pub fn spawn(
+        app: &App,
+        name: impl Into<String>,
+        mut cmd: Command,
+        handler: impl Fn(&App, &Companion, CompanionEvent) + Send + Sync + 'static,
+    ) -> crate::error::Result<Self> {
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let stdin = child.stdin.take().map(Self::write_input);
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+
+        let comp = Self(Arc::new(RawCompanion {
+            name: name.into(),
+            child: Mutex::new(child),
+            stdin: Mutex::new(stdin),
+            handler: Box::new(handler),
+            app: app.downgrade(),
+            bridges: Mutex::new(Vec::new()),
+        }));
+
+        app.track_companion(comp.downgrade());
+
+        let readers = [
+            stdout.map(|s| comp.read_output(CompanionStream::Stdout, s)),
+            stderr.map(|s| comp.read_output(CompanionStream::Stderr, s)),
+        ];
+
+        let weak = Arc::downgrade(&comp.0);
+        std::thread::spawn(move || {
+            for r in readers.into_iter().flatten() {
+                let _ = r.join();
+            }
+
+            let code = loop {
+                let Some(comp) = weak.upgrade() else {
+                    return; // Killed by dropping, nobody to tell
+                };
+
+                match comp.child.lock_unpoisoned().try_wait() {
+                    Ok(Some(status)) => break status.code(),
+                    Ok(None) => {}
+                    Err(_) => break None,
+                }
+
+                drop(comp);
+                std::thread::sleep(EXIT_POLL_INTERVAL);
+            };
+
+            Self::dispatch(&weak, CompanionEvent::Exited(code));
+        });
+
+        Ok(comp)
+    }
+
+    /// Writes the queued data to stdin in a background thread, until the queue
+    /// is closed or the process stops accepting input.
+    fn write_input(mut stdin: ChildStdin) -> SyncSender<Vec<u8>> {
+        let (tx, rx) = std::sync::mpsc::sync_channel::<Vec<u8>>(STDIN_QUEUE_CAPACITY);
+
+        std::thread::spawn(move || {
+            for data in rx {
+                if stdin.write_all(&data).and_then(|_| stdin.flush()).is_err() {
+                    return;
+                }
+            }
+        });
+
+        tx
+    }
+
+    fn read_output(
+        &self,
+        stream: CompanionStream,
+        src: impl Read + Send + 'static,
+    ) -> std::thread::JoinHandle<()> {
+        let weak = Arc::downgrade(&self.0);
+        std::thread::spawn(move || {
+            let mut src = BufReader::new(src);
+            let mut buf = Vec::new();
+
+            while let Ok(n) = src.read_until(b'\n', &mut buf)
+                && n > 0
+            {
+                let line = String::from_utf8_lossy(&buf)
+                    .trim_end_matches(['\r', '\n'])
+                    .to_owned();
+                buf.clear();
+
+                Self::dispatch(&weak, CompanionEvent::Output { stream, line });
+            }
+        })
+    }
+
+    /// Delivers the event on the event thread, if both the companion and the
+    /// app are still alive.
+    fn dispatch(weak: &Weak<RawCompanion>, ev: CompanionEvent) {
+        let Some(app) = weak.upgrade().and_then(|c| c.app.upgrade()) else {
+            return;
+        };
+
+        // The handler is not required to be unwind-safe, as in other listeners
+        let weak = AssertUnwindSafe(weak.clone());
+        app.post(move |app| {
+            let Some(comp) = weak.upgrade().map(Self) else {
+                return;
+            };
+
+            if let CompanionEvent::Output { stream, line } = &ev {
+                comp.forward_output(*stream, line);
+            }
+
+            (comp.0.handler)(&app, &comp, ev);
+        });
+    }
+
+    /// Dispatches the output line to bridged pages as DOM events.
+    fn forward_output(&self, stream: CompanionStream, line: &str) {
+        let js = format!(
+            "window.dispatchEvent(new CustomEvent('saucers-companion', {{ detail: {{ name: {}, \
+             stream: {}, line: {} }} }}));",
+            js_string(&self.0.name),
+            js_string(stream.name()),
+            js_string(line),
+        );
+
+        self.0.bridges.lock_unpoisoned().retain(|w| {
+            let Some(w) = w.upgrade() else {
+                return false;
+            };
+
+            w.execute(js.as_str());
+            true
+        });
+    }
+
+    /// Gets the name of the companion.
+    pub fn name(&self) -> &str { &self.0.name }
+
+    /// Gets the OS-assigned process ID.
+    pub fn pid(&self) -> u32 { self.0.child.lock_unpoisoned().id() }
+
+    /// Queues the data to be written to the stdin of the process, which
+    /// happens in a background thread. Fails with
+    /// [`std::io::ErrorKind::BrokenPipe`] if stdin has been closed by
+    /// [`Self::close_stdin`] or by the process, or with
+    /// [`std::io::ErrorKind::WouldBlock`] if the process doesn't keep up with
+    /// the writes queued, in which case the data is dropped.
+    pub fn write(&self, data: &[u8]) -> crate::error::Result<()> {
+        let stdin = self.0.stdin.lock_unpoisoned();
+        let Some(stdin) = stdin.as_ref() else {
+            return Err(std::io::Error::from(std::io::ErrorKind::BrokenPipe).into());
+        };
+
+        match stdin.try_send(data.to_vec()) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => {
+                Err(std::io::Error::from(std::io::ErrorKind::WouldBlock).into())
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                Err(std::io::Error::from(std::io::ErrorKind::BrokenPipe).into())
+            }
+        }
+    }
+
+    /// Writes the line with a trailing newline to the stdin of the process.
+    pub fn write_line(&self, line: &str) -> crate::error::Result<()> {
+        self.write(format!("{line}\n").as_bytes())
+    }
+
+    /// Closes the stdin of the process once queued writes are done, which many
+    /// processes take as a request to exit.
+    pub fn close_stdin(&self) { self.0.stdin.lock_unpoisoned().take(); }
+
+    /// Kills the process. Does nothing if it has already exited.
+    pub fn kill(&self) { let _ = self.0.child.lock_unpoisoned().kill(); }
+
+    /// Checks whether the process is still running.
+    pub fn is_running(&self) -> bool {
+        matches!(self.0.child.lock_unpoisoned().try_wait(), Ok(None))
+    }
+
+    /// Forwards the output to the webview. See
+    /// [`crate::webview::Webview::bridge_companion`].
+    pub(crate) fn add_bridge(&self, webview: WebviewRef) {
+        self.0.bridges.lock_unpoisoned().push(webview);
+    }
+
+    pub(crate) fn downgrade(&self) -> CompanionRef { CompanionRef(Arc::downgrade(&self.0)) }
+}
+
+/// A weak companion handle.
+#[derive(Clone)]
+pub(crate) struct CompanionRef(Weak<RawCompanion>);
+
+impl CompanionRef {
+    pub(crate) fn upgrade(&self) -> Option<Companion> { Some(Companion(self.0.upgrade()?)) }
+}
@@ -5,16 +5,63 @@
 mod picker;
 
 use std::ffi::c_char;
+use std::path::Path;
 use std::ptr::NonNull;
 
 pub use picker::*;
 use saucer_sys::*;
 
 use crate::app::App;
+use crate::geometry::Position;
 use crate::macros::load_range;
 use crate::macros::use_string;
+use crate::url::Url;
 use crate::util::inflate_strings;
 
+/// A URL scheme allowed by [`Desktop::open_checked`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Scheme {
+    Http,
+    Https,
+    Mailto,
+    Tel,
+    /// Any other scheme, given without the trailing colon (e.g. `zoommtg`).
+    Other(String),
+}
+
+impl Scheme {
+    fn matches(&self, scheme: &str) -> bool {
+        let name = match self {
+            Self::Http => "http",
+            Self::Https => "https",
+            Self::Mailto => "mailto",
+            Self::Tel => "tel",
+            Self::Other(s) => s,
+        };
+
+        name.eq_ignore_ascii_case(scheme)
+    }
+}
+
+/// Parses the URL and checks its scheme, returning the URL as parsed, which is
+/// what gets opened. URLs with NUL are rejected up front, as the native side
+/// would cut them short after the check.
+fn checked_url(url: &str, allowed: &[Scheme]) -> crate::error::Result<String> {
+    if url.contains('\0') {
+        return Err(crate::error::Error::InvalidUrl(url.to_owned()));
+    }
+
+    let parsed =
+        Url::new_parse(url).map_err(|_| crate::error::Error::InvalidUrl(url.to_owned()))?;
+    let scheme = parsed.scheme();
+
+    if !allowed.iter().any(|s| s.matches(&scheme)) {
+        return Err(crate::error::Error::SchemeNotAllowed(scheme));
+    }
+
+    Ok(parsed.content())
+}
+
 /// The desktop module providing file picking and URL opening.
 pub struct Desktop {
     ptr: NonNull<saucer_desktop>,
@@ -48,17 +95,51 @@ impl Desktop {
     /// This method passes the URL to the underlying system API (e.g. `open`
     /// command on Windows) without validation. Passing any user input can
     /// cause **SEVERE SECURITY RISK** to the application. It's highly
-    /// recommended to provide only controlled content to this method.
+    /// recommended to provide only controlled content to this method, and use
+    /// [`Self::open_checked`] or [`Self::open_path`] otherwise.
     pub fn open(&self, url: impl Into<Vec<u8>>) {
         use_string!(url; unsafe { saucer_desktop_open(self.ptr.as_ptr(), url) });
     }
 
+    /// Opens the given URL using system-wide handler if its scheme is one of
+    /// the allowed ones. Fails with [`crate::error::Error::InvalidUrl`] if the
+    /// URL can't be parsed, or [`crate::error::Error::SchemeNotAllowed`] if
+    /// its scheme is not allowed.
+    ///
+    /// This is the preferred way of opening URLs from untrusted sources (e.g.
+    /// links clicked in pages). Allowing only what's needed (usually
+    /// [`Scheme::Https`]) keeps pages from launching arbitrary handlers. Use
+    /// [`Self::open_path`] to open files.
+    pub fn open_checked(&self, url: &str, allowed: &[Scheme]) -> crate::error::Result<()> {
+        self.open(checked_url(url, allowed)?);
+        Ok(())
+    }
+
+    /// Opens the given file or folder using system-wide handler.
+    ///
+    /// The path is resolved to an absolute path of an existing file or folder
+    /// before being opened, thus it's never interpreted as a URL. Fails if
+    /// the path can't be resolved.
+    pub fn open_path(&self, path: impl AsRef<Path>) -> crate::error::Result<()> {
+        let path = std::fs::canonicalize(path)?;
+        let path = path.to_string_lossy();
+
+        // Canonical paths on Windows are verbatim, which handlers may not accept
+        let path = match path.strip_prefix(r"\\?\") {
+            Some(p) if !p.starts_with("UNC\\") => p,
+            _ => &path,
+        };
+
+        self.open(path);
+        Ok(())
+    }
+
     /// Gets the cursor position.
-    pub fn mouse_position(&self) -> (i32, i32) {
+    pub fn mouse_position(&self) -> Position {
         let mut x = 0;
         let mut y = 0;
         unsafe { saucer_desktop_mouse_position(self.ptr.as_ptr(), &raw mut x, &raw mut y) };
-        (x, y)
+        Position::new(x, y)
     }
 
     /// Picks a file with the given options.
@@ -127,3 +208,24 @@ impl Desktop {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schemes_match_case_insensitively() {
+        assert!(Scheme::Https.matches("HTTPS"));
+        assert!(Scheme::Other("zoommtg".into()).matches("ZoomMtg"));
+        assert!(!Scheme::Http.matches("https"));
+        assert!(!Scheme::Mailto.matches("mailto:"));
+    }
+
+    #[test]
+    fn urls_with_nul_are_rejected() {
+        assert!(matches!(
+            checked_url("https://example.com\0file:///etc/passwd", &[Scheme::Https]),
+            Err(crate::error::Error::InvalidUrl(_))
+        ));
+    }
+}
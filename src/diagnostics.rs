@@ -0,0 +1,331 @@
+//! Environment diagnostics.
+//!
+//! See [`diagnostics`] for details.
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::path::PathBuf;
+
+use crate::backend::Backend;
+
+/// Features of this crate that may be enabled at build time.
+const FEATURES: &[(&str, bool)] = &[
+    ("qt", cfg!(feature = "qt")),
+    ("lto", cfg!(feature = "lto")),
+    ("image", cfg!(feature = "image")),
+    ("serde", cfg!(feature = "serde")),
+    ("tracing", cfg!(feature = "tracing")),
+    ("bundle", cfg!(feature = "bundle")),
+    ("native-handles", cfg!(feature = "native-handles")),
+    ("raw-window-handle", cfg!(feature = "raw-window-handle")),
+];
+
+/// Name fragments of libraries worth reporting.
+#[allow(unused)] // Not used by all platforms
+const LIBRARY_NAMES: &[&str] = &[
+    "saucer",
+    "webkit",
+    "javascriptcore",
+    "webview2",
+    "qt6webengine",
+    "qt6core",
+    "gtk",
+    "adwaita",
+];
+
+/// A report of the environment the app runs in, for attaching to bug reports.
+/// See [`diagnostics`].
+///
+/// Versions that can't be determined are left as [`None`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Diagnostics {
+    /// The version of this crate.
+    pub crate_version: &'static str,
+    /// The version of saucer. See [`crate::version`].
+    pub saucer_version: String,
+    pub backend: Backend,
+    /// The version of the web engine, e.g. of WebView2 Runtime. WebKitGTK
+    /// names its libraries by ABI versions instead, thus it's not available
+    /// there, and the library is listed in [`Self::libraries`].
+    pub engine_version: Option<String>,
+    /// The version of Qt, only with the `qt` feature.
+    pub qt_version: Option<String>,
+    /// The OS, as in [`std::env::consts::OS`].
+    pub os: &'static str,
+    /// The version of the OS, e.g. `14.5` on macOS.
+    pub os_version: Option<String>,
+    /// The CPU architecture, as in [`std::env::consts::ARCH`].
+    pub arch: &'static str,
+    /// Features of this crate enabled at build time.
+    pub features: Vec<&'static str>,
+    /// Paths of the loaded engine and toolkit libraries. Only collected on
+    /// Linux and macOS.
+    pub libraries: Vec<PathBuf>,
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let unknown = |v: &Option<String>| v.clone().unwrap_or_else(|| "unknown".to_owned());
+
+        writeln!(f, "saucers: {}", self.crate_version)?;
+        writeln!(f, "saucer: {}", self.saucer_version)?;
+        writeln!(f, "backend: {:?}", self.backend)?;
+        writeln!(f, "engine: {}", unknown(&self.engine_version))?;
+
+        if self.backend == Backend::Qt {
+            writeln!(f, "qt: {}", unknown(&self.qt_version))?;
+        }
+
+        writeln!(
+            f,
+            "os: {} {} ({})",
+            self.os,
+            unknown(&self.os_version),
+            self.arch
+        )?;
+        writeln!(f, "features: {}", self.features.join(", "))?;
+        write!(f, "libraries:")?;
+
+        if self.libraries.is_empty() {
+            write!(f, " unknown")?;
+        }
+
+        for lib in &self.libraries {
+            write!(f, "\n  {}", lib.display())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Collects a report of the environment, including versions of the backend,
+/// the engine and the OS, and the libraries loaded. Its [`Display`] output is
+/// meant to be pasted into bug reports.
+///
+/// Versions are read from the loaded libraries and files of the system, thus
+/// this may block briefly and can be called before the app runs. Nothing
+/// identifying the user is included.
+///
+/// ```no_run
+/// eprintln!("{}", saucers::diagnostics());
+/// ```
+pub fn diagnostics() -> Diagnostics {
+    let libraries = loaded_libraries();
+    let backend = Backend::current();
+
+    let engine_version = match backend {
+        Backend::WebView2 => webview2_version(),
+        Backend::WebKit => plist_value(
+            "/System/Library/Frameworks/WebKit.framework/Resources/Info.plist",
+            "CFBundleVersion",
+        ),
+        Backend::WebKitGtk => None,
+        Backend::Qt => library_version(&libraries, &["libqt6webenginecore"]),
+    };
+
+    let qt_version = if backend == Backend::Qt {
+        library_version(&libraries, &["libqt6core"])
+    } else {
+        None
+    };
+
+    Diagnostics {
+        crate_version: env!("CARGO_PKG_VERSION"),
+        saucer_version: crate::version().to_owned(),
+        backend,
+        engine_version,
+        qt_version,
+        os: std::env::consts::OS,
+        os_version: os_version(),
+        arch: std::env::consts::ARCH,
+        features: FEATURES
+            .iter()
+            .filter(|(_, on)| *on)
+            .map(|(name, _)| *name)
+            .collect(),
+        libraries,
+    }
+}
+
+#[allow(unused)] // Not used by all platforms
+fn is_relevant_library(path: &str) -> bool {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    let name = name.to_ascii_lowercase();
+    LIBRARY_NAMES.iter().any(|n| name.contains(n))
+}
+
+/// Extracts the version from the file name of a shared library, e.g. `6.8.1`
+/// from `libQt6Core.so.6.8.1`.
+fn library_version(libraries: &[PathBuf], prefixes: &[&str]) -> Option<String> {
+    libraries.iter().find_map(|p| {
+        let name = p.file_name()?.to_str()?.to_ascii_lowercase();
+        if !prefixes.iter().any(|pre| name.starts_with(pre)) {
+            return None;
+        }
+
+        let (_, version) = name.split_once(".so.")?;
+        Some(version.to_owned())
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn loaded_libraries() -> Vec<PathBuf> {
+    let Ok(maps) = std::fs::read_to_string("/proc/self/maps") else {
+        return Vec::new();
+    };
+
+    let mut libs = Vec::new();
+
+    for line in maps.lines() {
+        // The path is the 6th column, and may contain spaces
+        let Some(path) = line.splitn(6, ' ').nth(5).map(str::trim_start) else {
+            continue;
+        };
+
+        if path.starts_with('/') && is_relevant_library(path) {
+            let path = PathBuf::from(path);
+            if !libs.contains(&path) {
+                libs.push(path);
+            }
+        }
+    }
+
+    libs
+}
+
+#[cfg(target_os = "macos")]
+fn loaded_libraries() -> Vec<PathBuf> {
+    use std::ffi::CStr;
+    use std::ffi::c_char;
+
+    unsafe extern "C" {
+        fn _dyld_image_count() -> u32;
+        fn _dyld_get_image_name(index: u32) -> *const c_char;
+    }
+
+    let mut libs = Vec::new();
+
+    // SAFETY: The names are owned by dyld and stay valid while the images are
+    // loaded
+    unsafe {
+        for i in 0.._dyld_image_count() {
+            let name = _dyld_get_image_name(i);
+            if name.is_null() {
+                continue;
+            }
+
+            if let Ok(path) = CStr::from_ptr(name).to_str()
+                && is_relevant_library(path)
+            {
+                libs.push(PathBuf::from(path));
+            }
+        }
+    }
+
+    libs
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn loaded_libraries() -> Vec<PathBuf> { Vec::new() }
+
+/// Reads a string value from an XML property list. Only handles the flat
+/// layout of `Info.plist`.
+fn plist_value(path: &str, key: &str) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    parse_plist_value(&content, key)
+}
+
+fn parse_plist_value(content: &str, key: &str) -> Option<String> {
+    let (_, rest) = content.split_once(&format!("<key>{key}</key>"))?;
+    let (_, rest) = rest.split_once("<string>")?;
+    let (value, _) = rest.split_once("</string>")?;
+    Some(value.trim().to_owned())
+}
+
+#[cfg(target_os = "windows")]
+fn webview2_version() -> Option<String> {
+    // The client ID of WebView2 Runtime, registered by the Evergreen installer
+    const CLIENT: &str = r"Microsoft\EdgeUpdate\Clients\{F3017226-FE2A-4295-8BDF-00C3A9A7E4C5}";
+
+    let keys = [
+        format!(r"HKLM\SOFTWARE\WOW6432Node\{CLIENT}"),
+        format!(r"HKLM\SOFTWARE\{CLIENT}"),
+        format!(r"HKCU\SOFTWARE\{CLIENT}"),
+    ];
+
+    keys.iter().find_map(|k| {
+        let out = std::process::Command::new("reg")
+            .args(["query", k, "/v", "pv"])
+            .output()
+            .ok()?;
+
+        let out = String::from_utf8_lossy(&out.stdout);
+        let version = out.lines().find_map(|l| l.trim().strip_prefix("pv"))?;
+        let version = version.trim().strip_prefix("REG_SZ")?.trim();
+        (!version.is_empty() && version != "0.0.0.0").then(|| version.to_owned())
+    })
+}
+
+#[cfg(not(target_os = "windows"))]
+fn webview2_version() -> Option<String> { None }
+
+#[cfg(target_os = "linux")]
+fn os_version() -> Option<String> {
+    let content = std::fs::read_to_string("/etc/os-release").ok()?;
+    content.lines().find_map(|l| {
+        let v = l.strip_prefix("PRETTY_NAME=")?;
+        Some(v.trim_matches('"').to_owned())
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn os_version() -> Option<String> {
+    plist_value(
+        "/System/Library/CoreServices/SystemVersion.plist",
+        "ProductVersion",
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn os_version() -> Option<String> {
+    let out = std::process::Command::new("cmd")
+        .args(["/C", "ver"])
+        .output()
+        .ok()?;
+
+    // e.g. `Microsoft Windows [Version 10.0.22631.3880]`
+    let out = String::from_utf8_lossy(&out.stdout);
+    let (_, rest) = out.split_once("Version ")?;
+    let (version, _) = rest.split_once(']')?;
+    Some(version.trim().to_owned())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn os_version() -> Option<String> { None }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_versions() {
+        let plist = "<dict>\n\t<key>CFBundleName</key>\n\t<string>WebKit</string>\n\\
+                     t<key>CFBundleVersion</key>\n\t<string>19618.2.12.11.6</string>\n</dict>";
+        assert_eq!(
+            parse_plist_value(plist, "CFBundleVersion").as_deref(),
+            Some("19618.2.12.11.6")
+        );
+        assert!(parse_plist_value(plist, "Copyright").is_none());
+
+        let libs = [
+            PathBuf::from("/usr/lib/libgtk-4.so.1.1400.2"),
+            PathBuf::from("/usr/lib/libQt6WebEngineCore.so.6.8.1"),
+        ];
+        assert_eq!(
+            library_version(&libs, &["libqt6webenginecore"]).as_deref(),
+            Some("6.8.1")
+        );
+        assert!(library_version(&libs, &["libqt6core"]).is_none());
+        assert!(is_relevant_library("/usr/lib/libwebkitgtk-6.0.so.4"));
+        assert!(!is_relevant_library("/usr/lib/libc.so.6"));
+    }
+}
@@ -1,10 +1,104 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+
 use thiserror::Error;
 
+use crate::backend::Backend;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// Errors produced in this library.
+///
+/// New variants may be added (including by enabling features), thus matches
+/// must have a wildcard arm.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum Error {
     #[error("saucer error: {0}")]
     Saucer(i32),
+
+    #[error("the window has been closed")]
+    Closed,
+
+    #[error("operation timed out")]
+    Timeout,
+
+    /// A feature is not available on the current backend (or in the current
+    /// situation). Apps can branch on the feature and try the fallback, if
+    /// any.
+    #[error("{feature} is not supported by the {backend:?} backend")]
+    Unsupported {
+        /// A short description of the feature, e.g. `material window effects`.
+        feature: &'static str,
+        backend: Backend,
+        fallback: Option<Suggestion>,
+    },
+
+    #[error("invalid URL: {0}")]
+    InvalidUrl(String),
+
+    #[error("URL scheme not allowed: {0}")]
+    SchemeNotAllowed(String),
+
+    #[error("the scheme already has a handler: {0}")]
+    SchemeInUse(String),
+
+    #[error("invalid profile name: {0}")]
+    InvalidProfileName(String),
+
+    #[error("invalid JSON at byte {0}")]
+    InvalidJson(usize),
+
+    #[error("script error: {0}")]
+    Script(String),
+
+    #[error("no document is ready to run scripts")]
+    NoDocument,
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("the operation must be done on the event thread")]
+    WrongThread,
+
+    #[error("an app is already running in this process")]
+    AppAlreadyExists(crate::app::AppRef),
+
+    #[error("streams are not enabled, see AppOptions::streams")]
+    StreamsDisabled,
+
+    #[error("browser flags conflict with another webview sharing the browser process")]
+    BrowserFlagsNotIsolated,
+
+    #[error("a window can't be the parent of itself or of its ancestors")]
+    ParentCycle,
+
+    #[cfg(feature = "image")]
+    #[error("image error: {0}")]
+    Image(#[from] image::ImageError),
+}
+
+impl Error {
+    /// Creates an [`Error::Unsupported`] for the current backend.
+    pub(crate) fn unsupported(feature: &'static str, fallback: Option<&'static str>) -> Self {
+        Self::Unsupported {
+            feature,
+            backend: Backend::current(),
+            fallback: fallback.map(Suggestion),
+        }
+    }
+}
+
+/// A suggested alternative to a feature that is not supported. See
+/// [`Error::Unsupported`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Suggestion(&'static str);
+
+impl Suggestion {
+    /// Gets the description of the alternative.
+    pub fn description(&self) -> &'static str { self.0 }
+}
+
+impl Display for Suggestion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { f.write_str(self.0) }
 }
@@ -0,0 +1,211 @@
+//! Screen geometry module.
+//!
+//! Coordinates and sizes are in logical pixels, the same unit used by windows
+//! and screens. [`Size`] and [`Position`] convert from and into `(i32, i32)`
+//! tuples, thus methods taking `impl Into<Size>` also accept tuples.
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Div;
+use std::ops::Mul;
+use std::ops::Sub;
+use std::ops::SubAssign;
+
+/// A size with width and height.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Size {
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Size {
+    /// Creates a size.
+    pub const fn new(width: i32, height: i32) -> Self { Self { width, height } }
+
+    /// Checks whether the size has no area.
+    pub const fn is_empty(&self) -> bool { self.width <= 0 || self.height <= 0 }
+
+    /// Limits both dimensions to the given range.
+    pub fn clamp(self, min: Size, max: Size) -> Self {
+        Self::new(
+            self.width.max(min.width).min(max.width),
+            self.height.max(min.height).min(max.height),
+        )
+    }
+}
+
+/// A position of the top-left corner of something.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Position {
+    /// Creates a position.
+    pub const fn new(x: i32, y: i32) -> Self { Self { x, y } }
+}
+
+/// A rectangle with the position of its top-left corner and its size.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub pos: Position,
+    pub size: Size,
+}
+
+impl Rect {
+    /// Creates a rectangle.
+    pub fn new(pos: impl Into<Position>, size: impl Into<Size>) -> Self {
+        Self {
+            pos: pos.into(),
+            size: size.into(),
+        }
+    }
+
+    /// Gets the X coordinate of the left edge.
+    pub const fn left(&self) -> i32 { self.pos.x }
+
+    /// Gets the Y coordinate of the top edge.
+    pub const fn top(&self) -> i32 { self.pos.y }
+
+    /// Gets the X coordinate of the right edge (exclusive).
+    pub const fn right(&self) -> i32 { self.pos.x + self.size.width }
+
+    /// Gets the Y coordinate of the bottom edge (exclusive).
+    pub const fn bottom(&self) -> i32 { self.pos.y + self.size.height }
+
+    /// Gets the center point.
+    pub const fn center(&self) -> Position {
+        Position::new(
+            self.pos.x + self.size.width / 2,
+            self.pos.y + self.size.height / 2,
+        )
+    }
+
+    /// Checks whether the point is inside the rectangle.
+    pub fn contains(&self, point: impl Into<Position>) -> bool {
+        let p = point.into();
+        p.x >= self.left() && p.x < self.right() && p.y >= self.top() && p.y < self.bottom()
+    }
+
+    /// Gets the overlapping area of two rectangles, or [`None`] if they don't
+    /// overlap.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let left = self.left().max(other.left());
+        let top = self.top().max(other.top());
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+
+        let size = Size::new(right - left, bottom - top);
+        (!size.is_empty()).then(|| Rect::new((left, top), size))
+    }
+
+    /// Checks whether two rectangles overlap.
+    pub fn intersects(&self, other: &Rect) -> bool { self.intersection(other).is_some() }
+
+    /// Gets a rectangle of the given size centered in this one.
+    pub fn centered(&self, size: impl Into<Size>) -> Rect {
+        let size = size.into();
+        let pos = Position::new(
+            self.pos.x + (self.size.width - size.width) / 2,
+            self.pos.y + (self.size.height - size.height) / 2,
+        );
+        Rect { pos, size }
+    }
+}
+
+impl From<(i32, i32)> for Size {
+    fn from((width, height): (i32, i32)) -> Self { Self::new(width, height) }
+}
+
+impl From<Size> for (i32, i32) {
+    fn from(s: Size) -> Self { (s.width, s.height) }
+}
+
+impl From<(i32, i32)> for Position {
+    fn from((x, y): (i32, i32)) -> Self { Self::new(x, y) }
+}
+
+impl From<Position> for (i32, i32) {
+    fn from(p: Position) -> Self { (p.x, p.y) }
+}
+
+impl Add for Size {
+    type Output = Size;
+
+    fn add(self, rhs: Size) -> Size { Size::new(self.width + rhs.width, self.height + rhs.height) }
+}
+
+impl Sub for Size {
+    type Output = Size;
+
+    fn sub(self, rhs: Size) -> Size { Size::new(self.width - rhs.width, self.height - rhs.height) }
+}
+
+impl Mul<i32> for Size {
+    type Output = Size;
+
+    fn mul(self, rhs: i32) -> Size { Size::new(self.width * rhs, self.height * rhs) }
+}
+
+impl Div<i32> for Size {
+    type Output = Size;
+
+    fn div(self, rhs: i32) -> Size { Size::new(self.width / rhs, self.height / rhs) }
+}
+
+/// Offsets the position.
+impl Add<Size> for Position {
+    type Output = Position;
+
+    fn add(self, rhs: Size) -> Position { Position::new(self.x + rhs.width, self.y + rhs.height) }
+}
+
+/// Offsets the position.
+impl Sub<Size> for Position {
+    type Output = Position;
+
+    fn sub(self, rhs: Size) -> Position { Position::new(self.x - rhs.width, self.y - rhs.height) }
+}
+
+/// Gets the offset between two positions.
+impl Sub for Position {
+    type Output = Size;
+
+    fn sub(self, rhs: Position) -> Size { Size::new(self.x - rhs.x, self.y - rhs.y) }
+}
+
+impl AddAssign<Size> for Position {
+    fn add_assign(&mut self, rhs: Size) { *self = *self + rhs; }
+}
+
+impl SubAssign<Size> for Position {
+    fn sub_assign(&mut self, rhs: Size) { *self = *self - rhs; }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rect_math() {
+        let a = Rect::new((0, 0), (100, 50));
+        let b = Rect::new((50, 25), (100, 50));
+
+        assert_eq!(a.right(), 100);
+        assert_eq!(a.center(), Position::new(50, 25));
+        assert!(a.contains((99, 49)));
+        assert!(!a.contains((100, 0)));
+        assert_eq!(a.intersection(&b), Some(Rect::new((50, 25), (50, 25))));
+        assert!(!a.intersects(&Rect::new((100, 0), (10, 10))));
+        assert_eq!(a.centered((20, 10)), Rect::new((40, 20), (20, 10)));
+
+        let p = Position::new(10, 10) + Size::new(5, -5);
+        assert_eq!(p, Position::new(15, 5));
+        assert_eq!(p - Position::new(10, 10), Size::new(5, -5));
+        assert_eq!(Size::new(10, 4) * 2 / 4, Size::new(5, 2));
+        assert_eq!(
+            Size::new(10, 500).clamp(Size::new(20, 20), Size::new(100, 100)),
+            Size::new(20, 100)
+        );
+    }
+}
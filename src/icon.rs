@@ -87,3 +87,65 @@ impl Icon {
 
     pub(crate) fn as_ptr(&self) -> *mut saucer_icon { self.ptr.as_ptr() }
 }
+
+#[cfg(feature = "image")]
+impl Icon {
+    /// Decodes the icon content into an [`image::DynamicImage`].
+    pub fn to_image(&self) -> crate::error::Result<image::DynamicImage> {
+        Ok(image::load_from_memory(self.data().data())?)
+    }
+
+    /// Gets the width and height of the icon. Only the header is decoded.
+    pub fn dimensions(&self) -> crate::error::Result<(u32, u32)> {
+        let data = self.data();
+        let reader = image::ImageReader::new(std::io::Cursor::new(data.data()));
+        Ok(reader.with_guessed_format()?.into_dimensions()?)
+    }
+
+    /// Encodes the icon as PNG. If a size is given, the icon is scaled to fit
+    /// in a square of that size, preserving its aspect ratio.
+    pub fn to_png(&self, size: Option<u32>) -> crate::error::Result<Vec<u8>> {
+        let mut img = self.to_image()?;
+
+        if let Some(size) = size
+            && (img.width() != size || img.height() != size)
+        {
+            img = img.resize(size, size, image::imageops::FilterType::Lanczos3);
+        }
+
+        let mut buf = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut buf, image::ImageFormat::Png)?;
+        Ok(buf.into_inner())
+    }
+
+    /// Creates an icon from RGBA pixels in row-major order. The buffer length
+    /// must be `width * height * 4`.
+    pub fn from_rgba(buf: Vec<u8>, width: u32, height: u32) -> crate::error::Result<Self> {
+        let img = image::RgbaImage::from_raw(width, height, buf).ok_or_else(|| {
+            image::ImageError::Parameter(image::error::ParameterError::from_kind(
+                image::error::ParameterErrorKind::DimensionMismatch,
+            ))
+        })?;
+
+        Self::try_from(image::DynamicImage::ImageRgba8(img))
+    }
+}
+
+/// Encodes the image as PNG and loads it as an icon.
+#[cfg(feature = "image")]
+impl TryFrom<&image::DynamicImage> for Icon {
+    type Error = crate::error::Error;
+
+    fn try_from(value: &image::DynamicImage) -> crate::error::Result<Self> {
+        let mut buf = std::io::Cursor::new(Vec::new());
+        value.write_to(&mut buf, image::ImageFormat::Png)?;
+        Self::from_data(Stash::new_view(buf.get_ref()))
+    }
+}
+
+#[cfg(feature = "image")]
+impl TryFrom<image::DynamicImage> for Icon {
+    type Error = crate::error::Error;
+
+    fn try_from(value: image::DynamicImage) -> crate::error::Result<Self> { Self::try_from(&value) }
+}
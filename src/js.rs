@@ -0,0 +1,442 @@
+//! JavaScript value module.
+//!
+//! See [`JsValue`] for details.
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+use crate::util::js_string;
+
+/// Maximum nesting depth accepted by the parser.
+const MAX_DEPTH: usize = 128;
+
+/// A JSON-compatible JavaScript value.
+///
+/// Values can be parsed from JSON (e.g. messages sent with
+/// `JSON.stringify`) with [`JsValue::parse`], and are formatted as JSON via
+/// [`Display`], which is also a valid JavaScript expression and can be
+/// embedded into scripts directly. Non-finite numbers are formatted as `null`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum JsValue {
+    #[default]
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsValue>),
+    Object(BTreeMap<String, JsValue>),
+}
+
+impl JsValue {
+    /// Parses the given JSON text.
+    pub fn parse(src: &str) -> crate::error::Result<Self> {
+        let mut parser = Parser {
+            src: src.as_bytes(),
+            pos: 0,
+        };
+        let value = parser.value(0)?;
+        parser.skip_ws();
+
+        if parser.pos != src.len() {
+            return Err(parser.error());
+        }
+
+        Ok(value)
+    }
+
+    /// Checks whether the value is null.
+    pub fn is_null(&self) -> bool { matches!(self, Self::Null) }
+
+    /// Gets the value as a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a number.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as an array.
+    pub fn as_array(&self) -> Option<&[JsValue]> {
+        match self {
+            Self::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as an object.
+    pub fn as_object(&self) -> Option<&BTreeMap<String, JsValue>> {
+        match self {
+            Self::Object(o) => Some(o),
+            _ => None,
+        }
+    }
+
+    /// Gets a property of an object value.
+    pub fn get(&self, key: &str) -> Option<&JsValue> { self.as_object()?.get(key) }
+}
+
+impl Display for JsValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Number(n) if n.is_finite() => write!(f, "{n}"),
+            Self::Number(_) => write!(f, "null"),
+            Self::String(s) => write!(f, "{}", js_string(s)),
+            Self::Array(a) => {
+                write!(f, "[")?;
+                for (i, v) in a.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{v}")?;
+                }
+                write!(f, "]")
+            }
+            Self::Object(o) => {
+                write!(f, "{{")?;
+                for (i, (k, v)) in o.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{v}", js_string(k))?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl FromStr for JsValue {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> { Self::parse(s) }
+}
+
+impl From<bool> for JsValue {
+    fn from(value: bool) -> Self { Self::Bool(value) }
+}
+
+impl From<f64> for JsValue {
+    fn from(value: f64) -> Self { Self::Number(value) }
+}
+
+impl From<i32> for JsValue {
+    fn from(value: i32) -> Self { Self::Number(value.into()) }
+}
+
+impl From<u32> for JsValue {
+    fn from(value: u32) -> Self { Self::Number(value.into()) }
+}
+
+impl From<&str> for JsValue {
+    fn from(value: &str) -> Self { Self::String(value.to_owned()) }
+}
+
+impl From<String> for JsValue {
+    fn from(value: String) -> Self { Self::String(value) }
+}
+
+impl<T: Into<JsValue>> From<Vec<T>> for JsValue {
+    fn from(value: Vec<T>) -> Self { Self::Array(value.into_iter().map(Into::into).collect()) }
+}
+
+impl<T: Into<JsValue>> From<Option<T>> for JsValue {
+    fn from(value: Option<T>) -> Self { value.map_or(Self::Null, Into::into) }
+}
+
+struct Parser<'a> {
+    src: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self) -> crate::error::Error { crate::error::Error::InvalidJson(self.pos) }
+
+    fn skip_ws(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.src.get(self.pos) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, b: u8) -> bool {
+        self.skip_ws();
+        if self.src.get(self.pos) == Some(&b) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn keyword(&mut self, word: &str, value: JsValue) -> crate::error::Result<JsValue> {
+        if self.src[self.pos..].starts_with(word.as_bytes()) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> crate::error::Result<JsValue> {
+        if depth > MAX_DEPTH {
+            return Err(self.error());
+        }
+
+        self.skip_ws();
+        match self.src.get(self.pos) {
+            Some(b'n') => self.keyword("null", JsValue::Null),
+            Some(b't') => self.keyword("true", JsValue::Bool(true)),
+            Some(b'f') => self.keyword("false", JsValue::Bool(false)),
+            Some(b'"') => Ok(JsValue::String(self.string()?)),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                if !self.eat(b']') {
+                    loop {
+                        items.push(self.value(depth + 1)?);
+                        if self.eat(b']') {
+                            break;
+                        }
+                        if !self.eat(b',') {
+                            return Err(self.error());
+                        }
+                    }
+                }
+                Ok(JsValue::Array(items))
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let mut entries = BTreeMap::new();
+                if !self.eat(b'}') {
+                    loop {
+                        self.skip_ws();
+                        let key = self.string()?;
+                        if !self.eat(b':') {
+                            return Err(self.error());
+                        }
+                        entries.insert(key, self.value(depth + 1)?);
+                        if self.eat(b'}') {
+                            break;
+                        }
+                        if !self.eat(b',') {
+                            return Err(self.error());
+                        }
+                    }
+                }
+                Ok(JsValue::Object(entries))
+            }
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            _ => Err(self.error()),
+        }
+    }
+
+    fn number(&mut self) -> crate::error::Result<JsValue> {
+        let start = self.pos;
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.src.get(self.pos) {
+            self.pos += 1;
+        }
+
+        std::str::from_utf8(&self.src[start..self.pos])
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(JsValue::Number)
+            .ok_or(crate::error::Error::InvalidJson(start))
+    }
+
+    fn hex4(&mut self) -> crate::error::Result<u32> {
+        let digits = self.src.get(self.pos..self.pos + 4).ok_or(self.error())?;
+        let n = std::str::from_utf8(digits)
+            .ok()
+            .and_then(|s| u32::from_str_radix(s, 16).ok())
+            .ok_or(self.error())?;
+        self.pos += 4;
+        Ok(n)
+    }
+
+    fn string(&mut self) -> crate::error::Result<String> {
+        if self.src.get(self.pos) != Some(&b'"') {
+            return Err(self.error());
+        }
+        self.pos += 1;
+
+        let mut out = Vec::new();
+        loop {
+            match *self.src.get(self.pos).ok_or(self.error())? {
+                b'"' => {
+                    self.pos += 1;
+                    break;
+                }
+                b'\\' => {
+                    self.pos += 1;
+                    let esc = *self.src.get(self.pos).ok_or(self.error())?;
+                    self.pos += 1;
+
+                    let c = match esc {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let mut n = self.hex4()?;
+                            if (0xd800..0xdc00).contains(&n)
+                                && self.src[self.pos..].starts_with(b"\\u")
+                            {
+                                self.pos += 2;
+                                let low = self.hex4()?;
+                                n = 0x10000
+                                    + ((n - 0xd800) << 10)
+                                    + (low.wrapping_sub(0xdc00) & 0x3ff);
+                            }
+                            char::from_u32(n).unwrap_or(char::REPLACEMENT_CHARACTER)
+                        }
+                        _ => return Err(crate::error::Error::InvalidJson(self.pos - 1)),
+                    };
+
+                    out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                b => {
+                    out.push(b);
+                    self.pos += 1;
+                }
+            }
+        }
+
+        String::from_utf8(out).map_err(|_| self.error())
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::collections::BTreeMap;
+    use std::fmt::Formatter;
+
+    use serde::Deserialize;
+    use serde::Deserializer;
+    use serde::Serialize;
+    use serde::Serializer;
+    use serde::de::MapAccess;
+    use serde::de::SeqAccess;
+    use serde::de::Visitor;
+    use serde::ser::SerializeMap;
+    use serde::ser::SerializeSeq;
+
+    use super::JsValue;
+
+    impl Serialize for JsValue {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                Self::Null => serializer.serialize_unit(),
+                Self::Bool(b) => serializer.serialize_bool(*b),
+                Self::Number(n) => serializer.serialize_f64(*n),
+                Self::String(s) => serializer.serialize_str(s),
+                Self::Array(a) => {
+                    let mut seq = serializer.serialize_seq(Some(a.len()))?;
+                    for v in a {
+                        seq.serialize_element(v)?;
+                    }
+                    seq.end()
+                }
+                Self::Object(o) => {
+                    let mut map = serializer.serialize_map(Some(o.len()))?;
+                    for (k, v) in o {
+                        map.serialize_entry(k, v)?;
+                    }
+                    map.end()
+                }
+            }
+        }
+    }
+
+    struct JsValueVisitor;
+
+    impl<'de> Visitor<'de> for JsValueVisitor {
+        type Value = JsValue;
+
+        fn expecting(&self, f: &mut Formatter) -> std::fmt::Result { write!(f, "a JSON value") }
+
+        fn visit_unit<E>(self) -> Result<JsValue, E> { Ok(JsValue::Null) }
+
+        fn visit_none<E>(self) -> Result<JsValue, E> { Ok(JsValue::Null) }
+
+        fn visit_some<D: Deserializer<'de>>(self, d: D) -> Result<JsValue, D::Error> {
+            JsValue::deserialize(d)
+        }
+
+        fn visit_bool<E>(self, v: bool) -> Result<JsValue, E> { Ok(JsValue::Bool(v)) }
+
+        fn visit_i64<E>(self, v: i64) -> Result<JsValue, E> { Ok(JsValue::Number(v as f64)) }
+
+        fn visit_u64<E>(self, v: u64) -> Result<JsValue, E> { Ok(JsValue::Number(v as f64)) }
+
+        fn visit_f64<E>(self, v: f64) -> Result<JsValue, E> { Ok(JsValue::Number(v)) }
+
+        fn visit_str<E>(self, v: &str) -> Result<JsValue, E> { Ok(JsValue::String(v.to_owned())) }
+
+        fn visit_string<E>(self, v: String) -> Result<JsValue, E> { Ok(JsValue::String(v)) }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<JsValue, A::Error> {
+            let mut items = Vec::new();
+            while let Some(v) = seq.next_element()? {
+                items.push(v);
+            }
+            Ok(JsValue::Array(items))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<JsValue, A::Error> {
+            let mut entries = BTreeMap::new();
+            while let Some((k, v)) = map.next_entry()? {
+                entries.insert(k, v);
+            }
+            Ok(JsValue::Object(entries))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for JsValue {
+        fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+            d.deserialize_any(JsValueVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsValue;
+
+    #[test]
+    fn js_value_round_trips() {
+        let src = r#"{"a":[1,2.5,-3e2],"b":{"c":null,"d":true},"e":"x\"é😀"}"#;
+        let value = JsValue::parse(src).unwrap();
+
+        assert_eq!(value.get("e").and_then(JsValue::as_str), Some("x\"é😀"));
+        assert_eq!(
+            value.get("a").and_then(|a| a.as_array()).map(|a| a.len()),
+            Some(3)
+        );
+        assert_eq!(JsValue::parse(&value.to_string()).unwrap(), value);
+
+        assert!(JsValue::parse("[1,]").is_err());
+        assert!(JsValue::parse("{} x").is_err());
+    }
+}
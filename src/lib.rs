@@ -12,27 +12,48 @@ use std::ffi::CStr;
 use saucer_sys::saucer_version;
 
 use crate::app::AppEventListener;
+use crate::app::AppManager;
+use crate::app::AppOptions;
+pub use crate::backend::backend;
+pub use crate::diagnostics::diagnostics;
+pub use crate::panic::set_panic_hook;
+pub use crate::thread::set_thread_violation_policy;
+use crate::webview::Webview;
 use crate::webview::WebviewEventListener;
+use crate::webview::WebviewOptions;
 use crate::webview::WebviewSchemeHandler;
+use crate::window::Window;
 use crate::window::WindowEventListener;
 
 pub mod app;
+pub mod backend;
 mod cleanup;
+pub mod companion;
 pub mod desktop;
+pub mod diagnostics;
 pub mod error;
+pub mod geometry;
 pub mod icon;
+pub mod js;
 mod macros;
+pub mod menu;
 pub mod navigation;
+pub mod panic;
 pub mod pdf;
 pub mod permission;
 pub mod policy;
+pub mod prelude;
 pub mod scheme;
 pub mod screen;
 pub mod stash;
 pub mod state;
 pub mod status;
+pub mod tabs;
+pub mod testing;
+pub mod thread;
 pub mod url;
 mod util;
+mod weak;
 pub mod webview;
 pub mod window;
 
@@ -42,6 +63,30 @@ pub fn version() -> &'static str {
     unsafe { CStr::from_ptr(saucer_version()).to_str().unwrap_or("") }
 }
 
+/// Opens a window showing the given URL and runs until it's closed.
+///
+/// This is a shortcut for the simplest apps. Use [`AppManager`] directly for
+/// anything more than that.
+pub fn quickstart(url: &str) -> crate::error::Result<()> {
+    let url = url.to_owned();
+    let app = AppManager::new(AppOptions::new_with_id("saucers"));
+
+    app.run(
+        move |app| {
+            let window = Window::new(&app, ()).expect("failed to create window");
+            window.set_size((1024, 768));
+            window.show();
+
+            let webview = Webview::new(WebviewOptions::default(), window, (), ())
+                .expect("failed to create webview");
+            webview.set_url_str(url);
+
+            webview
+        },
+        (),
+    )
+}
+
 impl AppEventListener for () {}
 impl WindowEventListener for () {}
 impl WebviewEventListener for () {}
@@ -34,9 +34,49 @@ macro_rules! load_range {
 }
 
 /// Forwards Rust calls to C FFI functions with `self` pointer as receiver.
+///
+/// Functions returning [`crate::error::Result`] check `self.ensure_alive()`
+/// before forwarding. Functions in blocks starting with `@alive` do nothing
+/// unless `self.is_alive()`, which suits setters.
 macro_rules! ffi_forward {
     () => {};
 
+    (@alive) => {};
+
+    (
+        @alive
+        $(#[$meta:meta])*
+        $vis:vis fn $name:ident($self_ty:ty $(, $arg:ident: $ty:ty)*) => $ffi:path;
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        $vis fn $name(self: $self_ty $(, $arg: $ty)*) {
+            if !self.is_alive() {
+                return;
+            }
+
+            crate::macros::trace_event!(target: "saucers::ffi", "calling {}", stringify!($ffi));
+            unsafe { $ffi(self.as_ptr(), $($arg),*) }
+        }
+
+        ffi_forward! { @alive $($rest)* }
+    };
+
+    (
+        $(#[$meta:meta])*
+        $vis:vis fn $name:ident($self_ty:ty $(, $arg:ident: $ty:ty)*) -> crate::error::Result<$ret:ty> => $ffi:path;
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        $vis fn $name(self: $self_ty $(, $arg: $ty)*) -> crate::error::Result<$ret> {
+            self.ensure_alive()?;
+            crate::macros::trace_event!(target: "saucers::ffi", "calling {}", stringify!($ffi));
+            Ok(unsafe { $ffi(self.as_ptr(), $($arg),*) })
+        }
+
+        ffi_forward! { $($rest)* }
+    };
+
     (
         $(#[$meta:meta])*
         $vis:vis fn $name:ident($self_ty:ty $(, $arg:ident: $ty:ty)*) $(-> $ret:ty)? => $ffi:path;
@@ -44,6 +84,7 @@ macro_rules! ffi_forward {
     ) => {
         $(#[$meta])*
         $vis fn $name(self: $self_ty $(, $arg: $ty)*) $(-> $ret)? {
+            crate::macros::trace_event!(target: "saucers::ffi", "calling {}", stringify!($ffi));
             unsafe { $ffi(self.as_ptr(), $($arg),*) }
         }
 
@@ -51,6 +92,33 @@ macro_rules! ffi_forward {
     };
 }
 
+/// Emits a [`tracing`](https://docs.rs/tracing) event at the trace level when
+/// the `tracing` feature is enabled, does nothing otherwise.
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)*);
+    };
+}
+
+/// Enters a trace level span until the returned guard is dropped when the
+/// `tracing` feature is enabled, returns a dummy guard otherwise.
+macro_rules! trace_span {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        let guard = tracing::trace_span!($($arg)*).entered();
+        #[cfg(not(feature = "tracing"))]
+        let guard = crate::macros::NoSpan;
+        guard
+    }};
+}
+
+/// The guard returned by [`trace_span`] without the `tracing` feature.
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoSpan;
+
 pub(crate) use ffi_forward;
 pub(crate) use load_range;
+pub(crate) use trace_event;
+pub(crate) use trace_span;
 pub(crate) use use_string;
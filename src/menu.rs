@@ -0,0 +1,441 @@
+//! Menu module.
+//!
+//! Menus are built with [`MenuBuilder`] and bound to windows as accelerators
+//! with [`crate::window::Window::set_menu_accelerators`]. They are not drawn
+//! natively, but can be rendered in the page from [`Menu::items`].
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::panic::RefUnwindSafe;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use crate::webview::EditCommand;
+use crate::window::Window;
+
+static NEXT_ITEM_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Identifies an item in menus. IDs are unique in the process.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MenuItemId(u64);
+
+impl MenuItemId {
+    fn next() -> Self { Self(NEXT_ITEM_ID.fetch_add(1, Ordering::Relaxed)) }
+
+    pub(crate) fn from_raw(id: u64) -> Self { Self(id) }
+
+    /// Gets the raw value of the ID.
+    pub fn get(self) -> u64 { self.0 }
+}
+
+/// Modifier keys of an [`Accelerator`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// The Command key on macOS, or the Windows (Super) key elsewhere.
+    pub meta: bool,
+}
+
+/// A keyboard shortcut activating a menu item, e.g. `CmdOrCtrl+Shift+S`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Accelerator {
+    pub modifiers: Modifiers,
+    /// The key, as named by `KeyboardEvent.key` (e.g. `s`, `F5` or `Delete`).
+    /// Single characters are lowercase.
+    pub key: String,
+}
+
+impl Accelerator {
+    /// Parses an accelerator from modifiers and a key joined by `+`.
+    ///
+    /// Modifiers are `Ctrl` (or `Control`), `Alt` (or `Option`), `Shift`,
+    /// `Cmd` (or `Command`, `Meta`, `Super`) and `CmdOrCtrl` (or
+    /// `CommandOrControl`), which is `Cmd` on macOS and `Ctrl` elsewhere.
+    /// Names are case-insensitive. Returns [`None`] if the accelerator has no
+    /// key or an unknown modifier.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut modifiers = Modifiers::default();
+        let mut parts = s.split('+').map(str::trim).collect::<Vec<_>>();
+
+        // Allow `Ctrl++`
+        if s.ends_with("++") {
+            parts.truncate(parts.len().saturating_sub(2));
+            parts.push("+");
+        }
+
+        let (key, mods) = parts.split_last()?;
+        if key.is_empty() {
+            return None;
+        }
+
+        for m in mods {
+            match m.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers.ctrl = true,
+                "alt" | "option" => modifiers.alt = true,
+                "shift" => modifiers.shift = true,
+                "cmd" | "command" | "meta" | "super" => modifiers.meta = true,
+                "cmdorctrl" | "commandorcontrol" if cfg!(target_os = "macos") => {
+                    modifiers.meta = true
+                }
+                "cmdorctrl" | "commandorcontrol" => modifiers.ctrl = true,
+                _ => return None,
+            }
+        }
+
+        let key = if key.chars().count() == 1 {
+            key.to_lowercase()
+        } else {
+            (*key).to_owned()
+        };
+
+        Some(Self { modifiers, key })
+    }
+}
+
+type MenuCallback = Arc<dyn Fn(Window, bool) + Send + Sync + RefUnwindSafe + 'static>;
+
+/// A clickable item of a menu.
+#[derive(Clone)]
+pub struct MenuAction {
+    id: MenuItemId,
+    label: String,
+    accelerator: Option<Accelerator>,
+    enabled: Arc<AtomicBool>,
+    /// The checked state, for checkable items.
+    checked: Option<Arc<AtomicBool>>,
+    edit: Option<EditCommand>,
+    callback: MenuCallback,
+}
+
+impl MenuAction {
+    /// Gets the ID of the item.
+    pub fn id(&self) -> MenuItemId { self.id }
+
+    /// Gets the label of the item.
+    pub fn label(&self) -> &str { &self.label }
+
+    /// Gets the accelerator of the item.
+    pub fn accelerator(&self) -> Option<&Accelerator> { self.accelerator.as_ref() }
+
+    /// Checks whether the item can be activated.
+    pub fn is_enabled(&self) -> bool { self.enabled.load(Ordering::Relaxed) }
+
+    /// Checks whether the item is checkable.
+    pub fn is_checkable(&self) -> bool { self.checked.is_some() }
+
+    /// Gets the editing command run by the item, for items added with
+    /// [`MenuBuilder::edit_item`].
+    pub fn edit_command(&self) -> Option<EditCommand> { self.edit }
+
+    /// Checks whether the item is checked. Always `false` for items that are
+    /// not checkable.
+    pub fn is_checked(&self) -> bool {
+        self.checked
+            .as_ref()
+            .is_some_and(|c| c.load(Ordering::Relaxed))
+    }
+}
+
+impl Debug for MenuAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MenuAction")
+            .field("id", &self.id)
+            .field("label", &self.label)
+            .field("accelerator", &self.accelerator)
+            .field("enabled", &self.is_enabled())
+            .field("checked", &self.checked.as_ref().map(|_| self.is_checked()))
+            .field("edit", &self.edit)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An entry of a menu.
+#[derive(Clone, Debug)]
+pub enum MenuItem {
+    Action(MenuAction),
+    Submenu { label: String, items: Vec<MenuItem> },
+    Separator,
+}
+
+/// A menu bar, or the content of a menu. Menus are cheap to clone, and clones
+/// share the enabled and checked states of items.
+#[derive(Clone, Debug, Default)]
+pub struct Menu {
+    items: Vec<MenuItem>,
+}
+
+impl Menu {
+    /// Gets the entries of the menu.
+    pub fn items(&self) -> &[MenuItem] { &self.items }
+
+    /// Finds the item with the given ID, including those in submenus.
+    pub fn find(&self, id: MenuItemId) -> Option<&MenuAction> {
+        self.actions().find(|a| a.id == id)
+    }
+
+    /// Iterates all clickable items, including those in submenus.
+    pub fn actions(&self) -> impl Iterator<Item = &MenuAction> {
+        let mut stack = vec![self.items.iter()];
+
+        std::iter::from_fn(move || {
+            loop {
+                let item = match stack.last_mut()?.next() {
+                    Some(item) => item,
+                    None => {
+                        stack.pop();
+                        continue;
+                    }
+                };
+
+                match item {
+                    MenuItem::Action(a) => return Some(a),
+                    MenuItem::Submenu { items, .. } => stack.push(items.iter()),
+                    MenuItem::Separator => {}
+                }
+            }
+        })
+    }
+
+    /// Enables or disables the item with the given ID. Returns whether the
+    /// item exists.
+    pub fn set_enabled(&self, id: MenuItemId, enabled: bool) -> bool {
+        self.find(id)
+            .map(|a| a.enabled.store(enabled, Ordering::Relaxed))
+            .is_some()
+    }
+
+    /// Checks or unchecks the checkable item with the given ID, without
+    /// invoking its callback. Returns whether the item exists and is
+    /// checkable.
+    pub fn set_checked(&self, id: MenuItemId, checked: bool) -> bool {
+        self.find(id)
+            .and_then(|a| a.checked.as_ref())
+            .map(|c| c.store(checked, Ordering::Relaxed))
+            .is_some()
+    }
+
+    /// Activates the item with the given ID as if it's clicked, toggling it if
+    /// checkable and invoking its callback. Returns whether the item exists
+    /// and is enabled.
+    pub(crate) fn activate(&self, window: &Window, id: MenuItemId) -> bool {
+        let Some(action) = self.find(id).filter(|a| a.is_enabled()) else {
+            return false;
+        };
+
+        let checked = action
+            .checked
+            .as_ref()
+            .is_some_and(|c| !c.fetch_xor(true, Ordering::Relaxed));
+
+        (action.callback)(window.clone(), checked);
+        true
+    }
+}
+
+/// Builds [`Menu`]s.
+///
+/// ```no_run
+/// use saucers::menu::MenuBuilder;
+///
+/// let menu = MenuBuilder::new()
+///     .submenu("File", |m| {
+///         m.item("Save", Some("CmdOrCtrl+S"), |_| println!("Saving"))
+///             .separator()
+///             .item("Quit", Some("CmdOrCtrl+Q"), |w| w.close())
+///     })
+///     .submenu("Edit", |m| m.edit_items())
+///     .submenu("View", |m| {
+///         m.check_item("Sidebar", true, None, |_, shown| {
+///             println!("Sidebar: {shown}")
+///         })
+///     })
+///     .build();
+/// ```
+#[derive(Default)]
+pub struct MenuBuilder {
+    items: Vec<MenuItem>,
+    last_id: Option<MenuItemId>,
+}
+
+impl MenuBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self { Self::default() }
+
+    /// Adds a clickable item. Accelerators are parsed with
+    /// [`Accelerator::parse`], and invalid ones are ignored.
+    pub fn item(
+        self,
+        label: impl Into<String>,
+        accelerator: Option<&str>,
+        callback: impl Fn(Window) + Send + Sync + RefUnwindSafe + 'static,
+    ) -> Self {
+        self.push_action(
+            label.into(),
+            accelerator,
+            None,
+            Arc::new(move |w, _| callback(w)),
+        )
+    }
+
+    /// Adds an item running an editing command in the focused webview of the
+    /// window (see [`Window::exec_command`]).
+    ///
+    /// The accelerator is handled by the page itself instead of activating
+    /// the item, thus the command behaves as the native shortcut there.
+    pub fn edit_item(
+        mut self,
+        label: impl Into<String>,
+        accelerator: Option<&str>,
+        command: EditCommand,
+    ) -> Self {
+        self = self.push_action(
+            label.into(),
+            accelerator,
+            None,
+            Arc::new(move |w, _| w.exec_command(command)),
+        );
+
+        if let Some(MenuItem::Action(a)) = self.items.last_mut() {
+            a.edit = Some(command);
+        }
+
+        self
+    }
+
+    /// Adds the usual editing items: undo, redo, cut, copy, paste and select
+    /// all, with their common accelerators. See [`Self::edit_item`].
+    pub fn edit_items(self) -> Self {
+        self.edit_item("Undo", Some("CmdOrCtrl+Z"), EditCommand::Undo)
+            .edit_item("Redo", Some("CmdOrCtrl+Shift+Z"), EditCommand::Redo)
+            .separator()
+            .edit_item("Cut", Some("CmdOrCtrl+X"), EditCommand::Cut)
+            .edit_item("Copy", Some("CmdOrCtrl+C"), EditCommand::Copy)
+            .edit_item("Paste", Some("CmdOrCtrl+V"), EditCommand::Paste)
+            .edit_item("Select All", Some("CmdOrCtrl+A"), EditCommand::SelectAll)
+    }
+
+    /// Adds a checkable item, which is toggled when clicked. The callback
+    /// receives the new checked state.
+    pub fn check_item(
+        self,
+        label: impl Into<String>,
+        checked: bool,
+        accelerator: Option<&str>,
+        callback: impl Fn(Window, bool) + Send + Sync + RefUnwindSafe + 'static,
+    ) -> Self {
+        self.push_action(
+            label.into(),
+            accelerator,
+            Some(Arc::new(AtomicBool::new(checked))),
+            Arc::new(callback),
+        )
+    }
+
+    /// Adds a submenu with the items built by the given closure.
+    pub fn submenu(mut self, label: impl Into<String>, build: impl FnOnce(Self) -> Self) -> Self {
+        let sub = build(Self::new());
+        self.items.push(MenuItem::Submenu {
+            label: label.into(),
+            items: sub.items,
+        });
+        self.last_id = sub.last_id.or(self.last_id);
+        self
+    }
+
+    /// Adds a separator line.
+    pub fn separator(mut self) -> Self {
+        self.items.push(MenuItem::Separator);
+        self
+    }
+
+    /// Disables the item added last, e.g. `.item(..).disabled()`.
+    pub fn disabled(self) -> Self {
+        if let Some(MenuItem::Action(a)) = self.items.last() {
+            a.enabled.store(false, Ordering::Relaxed);
+        }
+        self
+    }
+
+    /// Gets the ID of the clickable item added last, including those in
+    /// submenus, for updating it later.
+    pub fn last_id(&self) -> Option<MenuItemId> { self.last_id }
+
+    /// Builds the menu.
+    pub fn build(self) -> Menu { Menu { items: self.items } }
+
+    fn push_action(
+        mut self,
+        label: String,
+        accelerator: Option<&str>,
+        checked: Option<Arc<AtomicBool>>,
+        callback: MenuCallback,
+    ) -> Self {
+        let id = MenuItemId::next();
+        self.items.push(MenuItem::Action(MenuAction {
+            id,
+            label,
+            accelerator: accelerator.and_then(Accelerator::parse),
+            enabled: Arc::new(AtomicBool::new(true)),
+            checked,
+            edit: None,
+            callback,
+        }));
+        self.last_id = Some(id);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_accelerator() {
+        let a = Accelerator::parse("Ctrl+Shift+S").unwrap();
+        assert!(a.modifiers.ctrl && a.modifiers.shift && !a.modifiers.alt);
+        assert_eq!(a.key, "s");
+
+        let a = Accelerator::parse("alt+F4").unwrap();
+        assert!(a.modifiers.alt);
+        assert_eq!(a.key, "F4");
+
+        assert_eq!(Accelerator::parse("Ctrl++").unwrap().key, "+");
+        assert!(Accelerator::parse("Hyper+X").is_none());
+        assert!(Accelerator::parse("Ctrl+").is_none());
+    }
+
+    #[test]
+    fn test_menu_items() {
+        let builder = MenuBuilder::new()
+            .submenu("File", |m| m.item("Open", Some("CmdOrCtrl+O"), |_| {}))
+            .submenu("View", |m| m.check_item("Sidebar", true, None, |_, _| {}));
+
+        let sidebar = builder.last_id().unwrap();
+        let menu = builder.build();
+
+        assert_eq!(menu.actions().count(), 2);
+        assert!(menu.find(sidebar).unwrap().is_checked());
+        assert!(menu.set_checked(sidebar, false));
+        assert!(!menu.find(sidebar).unwrap().is_checked());
+        assert!(menu.set_enabled(sidebar, false));
+        assert!(!menu.clone().find(sidebar).unwrap().is_enabled());
+    }
+
+    #[test]
+    fn test_edit_items() {
+        let menu = MenuBuilder::new()
+            .submenu("Edit", |m| m.edit_items())
+            .build();
+
+        let commands = menu
+            .actions()
+            .filter_map(|a| a.edit_command())
+            .collect::<Vec<_>>();
+
+        assert_eq!(commands.len(), 6);
+        assert_eq!(commands[2], EditCommand::Cut);
+    }
+}
@@ -4,7 +4,9 @@ use std::ptr::NonNull;
 use saucer_sys::*;
 
 use crate::macros::ffi_forward;
+use crate::policy::Policy;
 use crate::url::Url;
+use crate::util::Glob;
 
 /// A navigation descriptor.
 ///
@@ -45,3 +47,51 @@ impl Navigation<'_> {
 
     fn as_ptr(&self) -> *mut saucer_navigation { self.ptr.as_ptr() }
 }
+
+/// A declarative navigation filter, set via
+/// [`crate::webview::Webview::set_navigation_rules`].
+///
+/// Rules are glob patterns (`*` matches any sequence of characters, `?`
+/// matches one) matched against the full URL, and are evaluated in the order
+/// they're added. The first matching rule decides. Navigations matching no rule
+/// are allowed, unless [`Self::block_external`] is called.
+///
+/// Rules are evaluated before navigation handlers and the listener, which
+/// don't see blocked navigations. This includes navigations started by the
+/// app (e.g. via [`crate::webview::Webview::set_url`]).
+#[derive(Clone, Debug, Default)]
+pub struct NavigationRules {
+    rules: Vec<(Glob, Policy)>,
+    fallback: Policy,
+}
+
+impl NavigationRules {
+    /// Creates an empty set of rules, which allows everything.
+    pub fn new() -> Self { Self::default() }
+
+    /// Allows URLs matching the given pattern.
+    pub fn allow(mut self, pattern: &str) -> Self {
+        self.rules.push((Glob::new(pattern), Policy::Allow));
+        self
+    }
+
+    /// Blocks URLs matching the given pattern.
+    pub fn block(mut self, pattern: &str) -> Self {
+        self.rules.push((Glob::new(pattern), Policy::Block));
+        self
+    }
+
+    /// Blocks URLs matching no rule.
+    pub fn block_external(mut self) -> Self {
+        self.fallback = Policy::Block;
+        self
+    }
+
+    /// Gets the policy for the given URL.
+    pub fn evaluate(&self, url: &str) -> Policy {
+        self.rules
+            .iter()
+            .find(|(glob, _)| glob.matches(url))
+            .map_or(self.fallback, |(_, policy)| *policy)
+    }
+}
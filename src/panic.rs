@@ -0,0 +1,75 @@
+//! Handling of panics in callbacks.
+//!
+//! Callbacks invoked by saucer (event listeners, handlers, posted callbacks,
+//! etc.) never unwind into the C++ frames below them. Instead, the panic is
+//! caught, and the callback returns a fallback result (e.g. the default policy
+//! of the event). [`set_panic_hook`] allows observing such panics, or aborting
+//! the process instead.
+use std::any::Any;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+type BoxedPanicHook = Arc<dyn Fn(&CallbackPanic) -> PanicAction + Send + Sync + 'static>;
+
+static HOOK: RwLock<Option<BoxedPanicHook>> = RwLock::new(None);
+
+/// A panic caught in a callback invoked by saucer.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CallbackPanic {
+    /// The callback that has panicked, e.g. `webview title event`.
+    pub context: &'static str,
+    /// The panic message, if the payload is a string.
+    pub message: Option<String>,
+}
+
+/// Decides what happens after a panic in a callback. See [`set_panic_hook`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PanicAction {
+    /// Returns the fallback result from the callback and keeps running.
+    #[default]
+    Continue,
+    /// Prints the panic and aborts the process.
+    Abort,
+}
+
+/// Sets the hook invoked when a callback panics, on the thread the callback
+/// runs on. Without a hook, panics are recovered from silently (the standard
+/// panic hook still prints them).
+///
+/// The hook is crate-wide. It runs after the panic has been caught, thus
+/// panicking in the hook itself aborts the process.
+pub fn set_panic_hook(hook: impl Fn(&CallbackPanic) -> PanicAction + Send + Sync + 'static) {
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(hook));
+}
+
+/// Removes the hook set with [`set_panic_hook`].
+pub fn clear_panic_hook() { *HOOK.write().unwrap_or_else(|e| e.into_inner()) = None; }
+
+/// Reports a panic caught in the given callback to the hook.
+pub(crate) fn report_panic(context: &'static str, payload: &(dyn Any + Send)) {
+    let hook = HOOK.read().unwrap_or_else(|e| e.into_inner()).clone();
+    let Some(hook) = hook else {
+        return;
+    };
+
+    let panic = CallbackPanic {
+        context,
+        message: payload
+            .downcast_ref::<&str>()
+            .map(|s| (*s).to_owned())
+            .or_else(|| payload.downcast_ref::<String>().cloned()),
+    };
+
+    // Unwinding out of here would cross the FFI boundary
+    let action = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| hook(&panic)))
+        .unwrap_or(PanicAction::Abort);
+
+    if action == PanicAction::Abort {
+        eprintln!(
+            "saucers: {} panicked: {}, aborting",
+            panic.context,
+            panic.message.as_deref().unwrap_or("<non-string payload>")
+        );
+        std::process::abort();
+    }
+}
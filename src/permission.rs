@@ -1,11 +1,18 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
 use std::ptr::NonNull;
 
 use saucer_sys::*;
 
 use crate::macros::ffi_forward;
+use crate::policy::Policy;
 use crate::url::Url;
 
 /// Possible permission request types.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PermissionType {
     Unknown,
     AudioMedia,
@@ -16,6 +23,16 @@ pub enum PermissionType {
     Location,
     Clipboard,
     Notification,
+    /// Speech recognition via the Web Speech API.
+    ///
+    /// Backends report these requests as microphone access or unknown ones.
+    /// The bindings tell them apart by tracking speech recognition started by
+    /// the page, on a best-effort basis (see
+    /// [`crate::webview::InternalScripts::speech`]). Whether speech
+    /// recognition is available at all depends on the backend, see
+    /// [`crate::backend::Capabilities::speech_recognition`]. Speech synthesis
+    /// doesn't require permissions.
+    SpeechRecognition,
 }
 
 impl From<saucer_permission_type> for PermissionType {
@@ -35,9 +52,44 @@ impl From<saucer_permission_type> for PermissionType {
     }
 }
 
+impl PermissionType {
+    const ALL: [Self; 10] = [
+        Self::Unknown,
+        Self::AudioMedia,
+        Self::VideoMedia,
+        Self::DesktopMedia,
+        Self::MouseLock,
+        Self::DeviceInfo,
+        Self::Location,
+        Self::Clipboard,
+        Self::Notification,
+        Self::SpeechRecognition,
+    ];
+
+    /// Gets a stable name of the type, used for persistence.
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Unknown => "unknown",
+            Self::AudioMedia => "audio-media",
+            Self::VideoMedia => "video-media",
+            Self::DesktopMedia => "desktop-media",
+            Self::MouseLock => "mouse-lock",
+            Self::DeviceInfo => "device-info",
+            Self::Location => "location",
+            Self::Clipboard => "clipboard",
+            Self::Notification => "notification",
+            Self::SpeechRecognition => "speech-recognition",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> { Self::ALL.into_iter().find(|t| t.name() == name) }
+}
+
 /// A permission request handle.
 pub struct PermissionRequest {
     inner: NonNull<saucer_permission_request>,
+    /// The type as classified by the bindings, overriding the reported one.
+    kind: Option<PermissionType>,
 }
 
 impl Drop for PermissionRequest {
@@ -46,7 +98,10 @@ impl Drop for PermissionRequest {
 
 impl Clone for PermissionRequest {
     fn clone(&self) -> Self {
-        unsafe { Self::from_ptr(saucer_permission_request_copy(self.inner.as_ptr())) }
+        let mut req =
+            unsafe { Self::from_ptr(saucer_permission_request_copy(self.inner.as_ptr())) };
+        req.kind = self.kind;
+        req
     }
 }
 
@@ -59,12 +114,21 @@ impl PermissionRequest {
     pub(crate) unsafe fn from_ptr(ptr: *mut saucer_permission_request) -> Self {
         Self {
             inner: NonNull::new(ptr).expect("invalid permission request ptr"),
+            kind: None,
         }
     }
 
+    /// Overrides the type reported by the backend.
+    pub(crate) fn with_kind(mut self, kind: PermissionType) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
     /// Gets the requested permission type.
     pub fn kind(&self) -> PermissionType {
-        unsafe { saucer_permission_request_type(self.inner.as_ptr()) }.into()
+        self.kind.unwrap_or_else(|| {
+            unsafe { saucer_permission_request_type(self.inner.as_ptr()) }.into()
+        })
     }
 
     /// Gets the request URL.
@@ -75,3 +139,261 @@ impl PermissionRequest {
 
     fn as_ptr(&self) -> *mut saucer_permission_request { self.inner.as_ptr() }
 }
+
+/// A table of permission decisions keyed by origin and permission type.
+///
+/// When set on a webview via
+/// [`crate::webview::Webview::set_permission_rules`], the table is consulted
+/// before the permission event is fired. Matched requests are resolved
+/// automatically and the event is not fired for them.
+#[derive(Clone, Debug, Default)]
+pub struct PermissionRules {
+    rules: HashMap<(Option<String>, PermissionType), Policy>,
+}
+
+impl PermissionRules {
+    /// Creates an empty table.
+    pub fn new() -> Self { Self::default() }
+
+    /// Sets the policy of the given permission type for all origins.
+    pub fn set(&mut self, kind: PermissionType, policy: Policy) {
+        self.rules.insert((None, kind), policy);
+    }
+
+    /// Sets the policy of the given permission type for the given origin (e.g.
+    /// `https://example.com`), which takes precedence over [`Self::set`].
+    pub fn set_for_origin(
+        &mut self,
+        origin: impl Into<String>,
+        kind: PermissionType,
+        policy: Policy,
+    ) {
+        self.rules.insert((Some(origin.into()), kind), policy);
+    }
+
+    /// Removes the policy set with [`Self::set`].
+    pub fn remove(&mut self, kind: PermissionType) -> Option<Policy> {
+        self.rules.remove(&(None, kind))
+    }
+
+    /// Removes the policy set with [`Self::set_for_origin`].
+    pub fn remove_for_origin(&mut self, origin: &str, kind: PermissionType) -> Option<Policy> {
+        self.rules.remove(&(Some(origin.to_owned()), kind))
+    }
+
+    /// Finds the policy for the given origin and permission type.
+    pub fn lookup(&self, origin: &str, kind: PermissionType) -> Option<Policy> {
+        self.rules
+            .get(&(Some(origin.to_owned()), kind))
+            .or_else(|| self.rules.get(&(None, kind)))
+            .copied()
+    }
+
+    /// Checks whether the table has no rules.
+    pub fn is_empty(&self) -> bool { self.rules.is_empty() }
+}
+
+/// A store of permission decisions remembered per origin and permission type.
+///
+/// When set on a webview via
+/// [`crate::webview::Webview::set_permission_store`], the store is consulted
+/// after [`PermissionRules`] and before the permission event is fired.
+/// Handlers can record decisions with
+/// [`crate::webview::Webview::remember_permission`].
+pub trait PermissionStore: Send {
+    /// Gets the remembered decision.
+    fn get(&self, origin: &str, kind: PermissionType) -> Option<Policy>;
+
+    /// Remembers a decision.
+    fn set(
+        &mut self,
+        origin: &str,
+        kind: PermissionType,
+        policy: Policy,
+    ) -> crate::error::Result<()>;
+
+    /// Forgets a decision.
+    fn remove(&mut self, origin: &str, kind: PermissionType) -> crate::error::Result<()>;
+
+    /// Forgets all decisions.
+    fn clear(&mut self) -> crate::error::Result<()>;
+}
+
+/// A [`PermissionStore`] kept in memory, which forgets everything when dropped.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryPermissionStore {
+    entries: HashMap<(String, PermissionType), Policy>,
+}
+
+impl MemoryPermissionStore {
+    /// Creates an empty store.
+    pub fn new() -> Self { Self::default() }
+}
+
+impl PermissionStore for MemoryPermissionStore {
+    fn get(&self, origin: &str, kind: PermissionType) -> Option<Policy> {
+        self.entries.get(&(origin.to_owned(), kind)).copied()
+    }
+
+    fn set(
+        &mut self,
+        origin: &str,
+        kind: PermissionType,
+        policy: Policy,
+    ) -> crate::error::Result<()> {
+        self.entries.insert((origin.to_owned(), kind), policy);
+        Ok(())
+    }
+
+    fn remove(&mut self, origin: &str, kind: PermissionType) -> crate::error::Result<()> {
+        self.entries.remove(&(origin.to_owned(), kind));
+        Ok(())
+    }
+
+    fn clear(&mut self) -> crate::error::Result<()> {
+        self.entries.clear();
+        Ok(())
+    }
+}
+
+/// A [`PermissionStore`] persisted to a file, which is replaced on each
+/// change.
+///
+/// The file contains one decision per line, in the form of
+/// `<type>\t<allow|block>\t<origin>`. Unrecognized lines are skipped.
+#[derive(Debug)]
+pub struct FilePermissionStore {
+    path: PathBuf,
+    memory: MemoryPermissionStore,
+}
+
+impl FilePermissionStore {
+    /// Opens the store at the given path. A missing file is treated as an empty
+    /// store and is created upon the first change.
+    pub fn open(path: impl AsRef<Path>) -> crate::error::Result<Self> {
+        let path = path.as_ref().to_owned();
+        let mut memory = MemoryPermissionStore::new();
+
+        let content = match std::fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        for line in content.lines() {
+            let mut parts = line.splitn(3, '\t');
+            let (Some(kind), Some(policy), Some(origin)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+
+            let Some(kind) = PermissionType::from_name(kind) else {
+                continue;
+            };
+
+            let policy = match policy {
+                "allow" => Policy::Allow,
+                "block" => Policy::Block,
+                _ => continue,
+            };
+
+            memory.entries.insert((origin.to_owned(), kind), policy);
+        }
+
+        Ok(Self { path, memory })
+    }
+
+    /// Gets the path of the file.
+    pub fn path(&self) -> &Path { &self.path }
+
+    fn save(&self) -> crate::error::Result<()> {
+        let mut content = String::new();
+
+        for ((origin, kind), policy) in &self.memory.entries {
+            let policy = match policy {
+                Policy::Allow => "allow",
+                Policy::Block => "block",
+            };
+            content += &format!("{}\t{policy}\t{origin}\n", kind.name());
+        }
+
+        // Written aside and renamed over, so that a failed write keeps the
+        // previous decisions
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+
+        let mut file = File::create(&tmp)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        drop(file);
+
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+impl PermissionStore for FilePermissionStore {
+    fn get(&self, origin: &str, kind: PermissionType) -> Option<Policy> {
+        self.memory.get(origin, kind)
+    }
+
+    fn set(
+        &mut self,
+        origin: &str,
+        kind: PermissionType,
+        policy: Policy,
+    ) -> crate::error::Result<()> {
+        self.memory.set(origin, kind, policy)?;
+        self.save()
+    }
+
+    fn remove(&mut self, origin: &str, kind: PermissionType) -> crate::error::Result<()> {
+        self.memory.remove(origin, kind)?;
+        self.save()
+    }
+
+    fn clear(&mut self) -> crate::error::Result<()> {
+        self.memory.clear()?;
+        self.save()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::TempDir;
+
+    #[test]
+    fn file_store_round_trips() {
+        let dir = TempDir::create().unwrap();
+        let path = dir.path().join("permissions");
+
+        let mut store = FilePermissionStore::open(&path).unwrap();
+        store
+            .set("https://a.test", PermissionType::Location, Policy::Allow)
+            .unwrap();
+        store
+            .set("https://b.test", PermissionType::Clipboard, Policy::Block)
+            .unwrap();
+        store
+            .set("https://b.test", PermissionType::Location, Policy::Block)
+            .unwrap();
+        store
+            .remove("https://b.test", PermissionType::Location)
+            .unwrap();
+
+        let store = FilePermissionStore::open(&path).unwrap();
+        assert_eq!(
+            store.get("https://a.test", PermissionType::Location),
+            Some(Policy::Allow)
+        );
+        assert_eq!(
+            store.get("https://b.test", PermissionType::Clipboard),
+            Some(Policy::Block)
+        );
+        assert_eq!(store.get("https://b.test", PermissionType::Location), None);
+
+        dir.remove();
+    }
+}
@@ -1,8 +1,15 @@
 use saucer_sys::*;
 
+use crate::navigation::NavigationRules;
+use crate::permission::PermissionRules;
+use crate::util::Glob;
+use crate::util::js_string;
+
 /// The policy towards an event. Can be used to allow or block the default
 /// behavior.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub enum Policy {
+    #[default]
     Allow,
     Block,
 }
@@ -15,3 +22,127 @@ impl From<Policy> for saucer_policy {
         }
     }
 }
+
+/// Rules blocking subresources (scripts, images, frames, fetches and the like)
+/// of pages by URL.
+///
+/// Patterns are globs (`*` matches any sequence of characters and `?` matches
+/// one) matched against the full URL, and are evaluated in the order they're
+/// added. The first matching rule decides, and URLs matching no rule are
+/// allowed.
+///
+/// Engines don't expose request filtering, thus rules are enforced by a script
+/// injected into pages, which rejects `fetch` and `XMLHttpRequest` calls and
+/// strips the sources of elements added to the document. Resources loaded
+/// before the script runs, or by other frames, are not affected. This keeps
+/// well-behaved content in check, but is not a security boundary.
+#[derive(Clone, Debug, Default)]
+pub struct ContentRules {
+    rules: Vec<(String, Glob, Policy)>,
+}
+
+impl ContentRules {
+    /// Creates an empty set of rules, which allows everything.
+    pub fn new() -> Self { Self::default() }
+
+    /// Allows URLs matching the given pattern.
+    pub fn allow(mut self, pattern: &str) -> Self {
+        self.rules
+            .push((pattern.to_owned(), Glob::new(pattern), Policy::Allow));
+        self
+    }
+
+    /// Blocks URLs matching the given pattern.
+    pub fn block(mut self, pattern: &str) -> Self {
+        self.rules
+            .push((pattern.to_owned(), Glob::new(pattern), Policy::Block));
+        self
+    }
+
+    /// Gets the policy for the given URL.
+    pub fn evaluate(&self, url: &str) -> Policy {
+        self.rules
+            .iter()
+            .find(|(_, glob, _)| glob.matches(url))
+            .map_or(Policy::Allow, |(_, _, policy)| *policy)
+    }
+
+    /// Checks whether there are no rules.
+    pub fn is_empty(&self) -> bool { self.rules.is_empty() }
+
+    /// Gets the rules as a JavaScript array of `[pattern, allowed]` pairs.
+    pub(crate) fn to_js(&self) -> String {
+        let items = self
+            .rules
+            .iter()
+            .map(|(p, _, policy)| format!("[{},{}]", js_string(p), *policy == Policy::Allow))
+            .collect::<Vec<_>>();
+
+        format!("[{}]", items.join(","))
+    }
+}
+
+/// A set of declarative policies applied to all webviews of an app, which can
+/// be replaced at runtime with [`crate::app::App::apply_policies`].
+///
+/// App policies are consulted before the rules set on individual webviews
+/// (e.g. [`crate::webview::Webview::set_navigation_rules`]), thus they can't
+/// be bypassed by them.
+#[derive(Clone, Debug, Default)]
+pub struct PolicySet {
+    /// Rules filtering navigations. See [`NavigationRules`].
+    pub navigation: Option<NavigationRules>,
+    /// Decisions of permission requests. See [`PermissionRules`].
+    pub permissions: PermissionRules,
+    /// Rules blocking subresources. See [`ContentRules`].
+    pub content: Option<ContentRules>,
+}
+
+impl PolicySet {
+    /// Creates an empty set, which allows everything.
+    pub fn new() -> Self { Self::default() }
+
+    /// Sets the navigation rules.
+    pub fn navigation(mut self, rules: NavigationRules) -> Self {
+        self.navigation = Some(rules);
+        self
+    }
+
+    /// Sets the permission table.
+    pub fn permissions(mut self, rules: PermissionRules) -> Self {
+        self.permissions = rules;
+        self
+    }
+
+    /// Sets the content blocking rules.
+    pub fn content(mut self, rules: ContentRules) -> Self {
+        self.content = Some(rules);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_rules_apply_first_matching_rule() {
+        let rules = ContentRules::new()
+            .allow("https://ads.example.com/ok/*")
+            .block("https://ads.example.com/*");
+
+        assert_eq!(
+            rules.evaluate("https://ads.example.com/ok/a.js"),
+            Policy::Allow
+        );
+        assert_eq!(
+            rules.evaluate("https://ads.example.com/b.js"),
+            Policy::Block
+        );
+        assert_eq!(rules.evaluate("https://example.com/"), Policy::Allow);
+        assert_eq!(
+            rules.to_js(),
+            r#"[["https://ads.example.com/ok/*",true],["https://ads.example.com/*",false]]"#
+        );
+    }
+}
@@ -0,0 +1,40 @@
+//! Commonly used items.
+//!
+//! This module re-exports the types needed by most apps, so that they can be
+//! imported at once:
+//!
+//! ```no_run
+//! use saucers::prelude::*;
+//! ```
+pub use crate::app::App;
+pub use crate::app::AppManager;
+pub use crate::app::AppOptions;
+pub use crate::app::FinishRoutine;
+pub use crate::error::Error;
+pub use crate::error::Result;
+pub use crate::geometry::Position;
+pub use crate::geometry::Rect;
+pub use crate::geometry::Size;
+pub use crate::icon::Icon;
+pub use crate::js::JsValue;
+pub use crate::navigation::Navigation;
+pub use crate::policy::Policy;
+pub use crate::stash::Stash;
+pub use crate::state::LoadState;
+pub use crate::status::HandleStatus;
+pub use crate::url::Url;
+pub use crate::webview::DomReadyEvent;
+pub use crate::webview::LoadEvent;
+pub use crate::webview::MessageEvent;
+pub use crate::webview::NavigateEvent;
+pub use crate::webview::TitleEvent;
+pub use crate::webview::Webview;
+pub use crate::webview::WebviewEventListener;
+pub use crate::webview::WebviewListeners;
+pub use crate::webview::WebviewOptions;
+pub use crate::webview::WebviewRef;
+pub use crate::webview::WebviewSchemeHandler;
+pub use crate::window::Window;
+pub use crate::window::WindowEventListener;
+pub use crate::window::WindowListeners;
+pub use crate::window::WindowRef;
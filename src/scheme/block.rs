@@ -0,0 +1,134 @@
+use std::pin::pin;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Wake;
+use std::thread::Thread;
+use std::time::Duration;
+use std::time::Instant;
+
+/// A waker that unparks the blocked thread.
+struct ThreadWaker {
+    thread: Thread,
+    woken: AtomicBool,
+}
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) { self.wake_by_ref() }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::Release);
+        self.thread.unpark();
+    }
+}
+
+/// Blocks the current thread on the given future until it completes, or
+/// returns [`crate::error::Error::Timeout`] once the timeout elapses.
+///
+/// The future may borrow from the caller (e.g. the [`crate::scheme::Request`])
+/// and is dropped before this function returns, which cancels it on timeout.
+/// It's polled on the current thread, so it must not depend on an executor
+/// running on the same thread. Futures of runtimes like Tokio usually need to
+/// be spawned on the runtime, and only their join handles awaited here.
+///
+/// Scheme handlers may be invoked on the event thread, in which case blocking
+/// freezes the UI. For long-running work, move the
+/// [`crate::scheme::Executor`] to another thread and resolve the request
+/// there instead.
+///
+/// Timeouts too large to be represented (e.g. [`Duration::MAX`]) never elapse.
+pub fn block_on_scoped<F: Future>(future: F, timeout: Duration) -> crate::error::Result<F::Output> {
+    let deadline = Instant::now().checked_add(timeout);
+    let waker = Arc::new(ThreadWaker {
+        thread: std::thread::current(),
+        woken: AtomicBool::new(false),
+    });
+    let task_waker = waker.clone().into();
+    let mut cx = Context::from_waker(&task_waker);
+    let mut future = pin!(future);
+
+    loop {
+        if let Poll::Ready(out) = future.as_mut().poll(&mut cx) {
+            return Ok(out);
+        }
+
+        // Parking may return spuriously, thus the flag
+        while !waker.woken.swap(false, Ordering::Acquire) {
+            let Some(deadline) = deadline else {
+                std::thread::park();
+                continue;
+            };
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(crate::error::Error::Timeout);
+            }
+
+            std::thread::park_timeout(deadline - now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+    use std::sync::mpsc::Receiver;
+    use std::sync::mpsc::Sender;
+    use std::sync::mpsc::channel;
+    use std::task::Context;
+    use std::task::Poll;
+    use std::task::Waker;
+    use std::time::Duration;
+
+    use super::block_on_scoped;
+    use crate::error::Error;
+
+    #[test]
+    fn block_on_scoped_resolves_or_times_out() {
+        let local = 42;
+        let out = block_on_scoped(async { local }, Duration::from_secs(1));
+        assert!(matches!(out, Ok(42)));
+
+        let out = block_on_scoped(std::future::pending::<()>(), Duration::from_millis(10));
+        assert!(matches!(out, Err(Error::Timeout)));
+
+        let out = block_on_scoped(async { local }, Duration::MAX);
+        assert!(matches!(out, Ok(42)));
+    }
+
+    #[test]
+    fn block_on_scoped_wakes_from_other_threads() {
+        /// Resolves with the received value, handing out its waker once.
+        struct Recv(Receiver<u32>, Option<Sender<Waker>>);
+
+        impl Future for Recv {
+            type Output = u32;
+
+            fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+                if let Ok(v) = self.0.try_recv() {
+                    return Poll::Ready(v);
+                }
+
+                if let Some(tx) = self.1.take() {
+                    tx.send(cx.waker().clone()).unwrap();
+                }
+
+                Poll::Pending
+            }
+        }
+
+        let (value_tx, value_rx) = channel();
+        let (waker_tx, waker_rx) = channel::<Waker>();
+
+        std::thread::spawn(move || {
+            let waker = waker_rx.recv().unwrap();
+            value_tx.send(7).unwrap();
+            waker.wake();
+        });
+
+        let out = block_on_scoped(Recv(value_rx, Some(waker_tx)), Duration::from_secs(5));
+        assert!(matches!(out, Ok(7)));
+    }
+}
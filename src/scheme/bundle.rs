@@ -0,0 +1,326 @@
+//! Encrypted asset bundle module.
+//!
+//! See [`EncryptedBundle`] for details.
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use aes_gcm::Aes256Gcm;
+use aes_gcm::Key;
+use aes_gcm::Nonce;
+use aes_gcm::aead::Aead;
+use aes_gcm::aead::AeadCore;
+use aes_gcm::aead::KeyInit;
+use aes_gcm::aead::OsRng;
+use aes_gcm::aead::Payload;
+
+use crate::scheme::Vfs;
+use crate::scheme::VfsEntry;
+use crate::scheme::VfsMetadata;
+use crate::scheme::vfs::normalize_path;
+
+const MAGIC: &[u8; 8] = b"SAUCERSB";
+const VERSION: u8 = 1;
+const NONCE_LEN: usize = 12;
+
+/// The length of keys of bundles, in bytes.
+pub const BUNDLE_KEY_LEN: usize = 32;
+
+/// An encrypted file in a bundle.
+struct BundleFile {
+    nonce: [u8; NONCE_LEN],
+    /// The range of the ciphertext in the bundle.
+    start: usize,
+    end: usize,
+    /// The size of the plaintext.
+    len: u64,
+}
+
+/// A bundle of files encrypted with AES-256-GCM, served as a [`Vfs`] (see
+/// [`crate::webview::Webview::mount`]). Bundles are created with
+/// [`BundleWriter`], usually at build time, and the key is provided at
+/// runtime.
+///
+/// Each file is encrypted separately and decrypted when requested, with its
+/// path authenticated, thus files can't be swapped or tampered with. Paths
+/// and sizes of files are not encrypted.
+///
+/// This keeps assets from being trivially extracted from the installed app,
+/// but can't stop a determined attacker, as the key must be present on the
+/// machine at runtime.
+///
+/// The format is: the magic `SAUCERSB`, a version byte (`1`), then entries of
+/// a little-endian `u32` path length, the UTF-8 path, a 12-byte nonce, a
+/// little-endian `u64` ciphertext length and the ciphertext.
+pub struct EncryptedBundle {
+    cipher: Aes256Gcm,
+    data: Vec<u8>,
+    files: BTreeMap<String, BundleFile>,
+}
+
+impl EncryptedBundle {
+    /// Reads the bundle at the given path.
+    pub fn open(path: impl AsRef<Path>, key: &[u8; BUNDLE_KEY_LEN]) -> crate::error::Result<Self> {
+        Self::from_bytes(std::fs::read(path)?, key)
+    }
+
+    /// Parses the bundle from bytes. Files are only decrypted when read, thus
+    /// a wrong key is not detected here.
+    pub fn from_bytes(data: Vec<u8>, key: &[u8; BUNDLE_KEY_LEN]) -> crate::error::Result<Self> {
+        let invalid = || std::io::Error::new(ErrorKind::InvalidData, "malformed bundle");
+
+        if !data.starts_with(MAGIC) || data.get(MAGIC.len()) != Some(&VERSION) {
+            return Err(invalid().into());
+        }
+
+        let mut files = BTreeMap::new();
+        let mut cursor = Cursor {
+            data: &data,
+            pos: MAGIC.len() + 1,
+        };
+
+        while !cursor.is_done() {
+            let (path, file) = cursor.entry().ok_or_else(invalid)?;
+            files.insert(path, file);
+        }
+
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+            data,
+            files,
+        })
+    }
+
+    /// Gets the paths of all files in the bundle, sorted.
+    pub fn paths(&self) -> impl Iterator<Item = &str> { self.files.keys().map(String::as_str) }
+
+    fn is_dir(&self, path: &str) -> bool {
+        let prefix = format!("{}/", path.trim_end_matches('/'));
+        self.files.keys().any(|p| p.starts_with(&prefix))
+    }
+}
+
+impl Vfs for EncryptedBundle {
+    fn open(&self, path: &str) -> std::io::Result<Vec<u8>> {
+        let path = normalize_path(path).ok_or(ErrorKind::NotFound)?;
+        let file = self.files.get(&path).ok_or(ErrorKind::NotFound)?;
+
+        let payload = Payload {
+            msg: &self.data[file.start..file.end],
+            aad: path.as_bytes(),
+        };
+
+        self.cipher
+            .decrypt(Nonce::from_slice(&file.nonce), payload)
+            .map_err(|_| std::io::Error::new(ErrorKind::InvalidData, "failed to decrypt file"))
+    }
+
+    fn stat(&self, path: &str) -> std::io::Result<VfsMetadata> {
+        let path = normalize_path(path).ok_or(ErrorKind::NotFound)?;
+
+        if let Some(file) = self.files.get(&path) {
+            return Ok(VfsMetadata {
+                len: file.len,
+                is_dir: false,
+            });
+        }
+
+        if self.is_dir(&path) {
+            return Ok(VfsMetadata {
+                len: 0,
+                is_dir: true,
+            });
+        }
+
+        Err(ErrorKind::NotFound.into())
+    }
+
+    fn list(&self, path: &str) -> std::io::Result<Vec<VfsEntry>> {
+        let path = normalize_path(path).ok_or(ErrorKind::NotFound)?;
+        let prefix = format!("{}/", path.trim_end_matches('/'));
+
+        let mut entries: Vec<VfsEntry> = Vec::new();
+
+        for p in self.files.keys() {
+            let Some(rest) = p.strip_prefix(&prefix) else {
+                continue;
+            };
+
+            let (name, is_dir) = match rest.split_once('/') {
+                Some((dir, _)) => (dir, true),
+                None => (rest, false),
+            };
+
+            // Keys are sorted, thus items of the same directory are adjacent
+            if entries.last().is_none_or(|e| e.name != name) {
+                entries.push(VfsEntry {
+                    name: name.to_owned(),
+                    is_dir,
+                });
+            }
+        }
+
+        if entries.is_empty() && !self.is_dir(&path) {
+            return Err(ErrorKind::NotFound.into());
+        }
+
+        Ok(entries)
+    }
+}
+
+/// Reads entries of a bundle.
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Cursor<'_> {
+    fn is_done(&self) -> bool { self.pos >= self.data.len() }
+
+    fn take(&mut self, len: usize) -> Option<std::ops::Range<usize>> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|e| *e <= self.data.len())?;
+        let range = self.pos..end;
+        self.pos = end;
+        Some(range)
+    }
+
+    fn bytes<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let range = self.take(N)?;
+        self.data[range].try_into().ok()
+    }
+
+    fn entry(&mut self) -> Option<(String, BundleFile)> {
+        let len = u32::from_le_bytes(self.bytes()?) as usize;
+        let path = self.take(len)?;
+        let path = std::str::from_utf8(&self.data[path]).ok()?.to_owned();
+        let nonce = self.bytes()?;
+        let ct_len = u64::from_le_bytes(self.bytes()?);
+        let ct = self.take(usize::try_from(ct_len).ok()?)?;
+
+        // The authentication tag is appended to the ciphertext
+        let len = ct_len.checked_sub(16)?;
+        Some((path, BundleFile {
+            nonce,
+            start: ct.start,
+            end: ct.end,
+            len,
+        }))
+    }
+}
+
+/// Creates [`EncryptedBundle`]s.
+///
+/// ```no_run
+/// use saucers::scheme::BundleWriter;
+///
+/// let key = [0u8; 32]; // Use a random key kept out of the source
+/// let mut writer = BundleWriter::new(&key);
+/// writer.add_dir("dist").unwrap();
+/// std::fs::write("assets.bin", writer.finish()).unwrap();
+/// ```
+pub struct BundleWriter {
+    cipher: Aes256Gcm,
+    out: Vec<u8>,
+}
+
+impl BundleWriter {
+    /// Creates a writer encrypting files with the given key.
+    pub fn new(key: &[u8; BUNDLE_KEY_LEN]) -> Self {
+        let mut out = MAGIC.to_vec();
+        out.push(VERSION);
+
+        Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+            out,
+        }
+    }
+
+    /// Adds a file at the given path (e.g. `/index.html`).
+    pub fn add(&mut self, path: &str, content: &[u8]) -> crate::error::Result<()> {
+        let path = normalize_path(path)
+            .ok_or_else(|| std::io::Error::new(ErrorKind::InvalidInput, "path escapes the root"))?;
+
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let payload = Payload {
+            msg: content,
+            aad: path.as_bytes(),
+        };
+
+        let ct = self
+            .cipher
+            .encrypt(&nonce, payload)
+            .map_err(|_| std::io::Error::other("failed to encrypt file"))?;
+
+        self.out
+            .extend_from_slice(&(path.len() as u32).to_le_bytes());
+        self.out.extend_from_slice(path.as_bytes());
+        self.out.extend_from_slice(nonce.as_slice());
+        self.out.extend_from_slice(&(ct.len() as u64).to_le_bytes());
+        self.out.extend_from_slice(&ct);
+        Ok(())
+    }
+
+    /// Adds all files in the directory recursively, with paths relative to
+    /// it.
+    pub fn add_dir(&mut self, dir: impl AsRef<Path>) -> crate::error::Result<()> {
+        self.add_dir_at(dir.as_ref(), "")
+    }
+
+    fn add_dir_at(&mut self, dir: &Path, prefix: &str) -> crate::error::Result<()> {
+        let mut entries = std::fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|e| e.file_name()); // Reproducible output
+
+        for e in entries {
+            let name = e.file_name();
+            let path = format!("{prefix}/{}", name.to_string_lossy());
+
+            if e.file_type()?.is_dir() {
+                self.add_dir_at(&e.path(), &path)?;
+            } else {
+                self.add(&path, &std::fs::read(e.path())?)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets the bytes of the bundle.
+    pub fn finish(self) -> Vec<u8> { self.out }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let key = [7u8; BUNDLE_KEY_LEN];
+        let mut writer = BundleWriter::new(&key);
+        writer.add("/index.html", b"<h1>Hi</h1>").unwrap();
+        writer.add("/js/main.js", b"main()").unwrap();
+        writer.add("/js/lib/a.js", b"a()").unwrap();
+
+        let bytes = writer.finish();
+        let bundle = EncryptedBundle::from_bytes(bytes.clone(), &key).unwrap();
+        assert_eq!(bundle.open("/js/main.js").unwrap(), b"main()");
+        assert_eq!(bundle.stat("/index.html").unwrap().len, 11);
+        assert!(bundle.stat("/js").unwrap().is_dir);
+        assert_eq!(bundle.list("/js").unwrap(), vec![
+            VfsEntry {
+                name: "lib".to_owned(),
+                is_dir: true
+            },
+            VfsEntry {
+                name: "main.js".to_owned(),
+                is_dir: false
+            },
+        ]);
+
+        let wrong = EncryptedBundle::from_bytes(bytes, &[0u8; BUNDLE_KEY_LEN]).unwrap();
+        assert!(wrong.open("/index.html").is_err());
+        assert!(EncryptedBundle::from_bytes(b"SAUCERSB\x01\x05".to_vec(), &key).is_err());
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use crate::scheme::Response;
+use crate::stash::Stash;
+use crate::util::MutexExt;
+
+/// A response kept in a [`ResponseCache`].
+///
+/// [`Response`] can't be read back once created, thus the cache stores the
+/// parts it's made of and creates a new one for each hit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedResponse {
+    pub data: Arc<[u8]>,
+    pub mime: String,
+    pub status: Option<i32>,
+    pub headers: Vec<(String, String)>,
+}
+
+impl CachedResponse {
+    /// Creates a response with the given content and MIME type.
+    pub fn new(data: impl Into<Arc<[u8]>>, mime: impl Into<String>) -> Self {
+        Self {
+            data: data.into(),
+            mime: mime.into(),
+            status: None,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Creates a [`Response`] for replying a request. The content is copied.
+    pub fn to_response(&self) -> Response<'static> {
+        let mut res = Response::new(Stash::new_copy(&self.data), self.mime.as_str());
+
+        if let Some(status) = self.status {
+            res.set_status(status);
+        }
+
+        for (name, value) in &self.headers {
+            res.add_header(name.as_str(), value.as_str());
+        }
+
+        res
+    }
+}
+
+struct CacheEntry {
+    response: CachedResponse,
+    validator: String,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    size: usize,
+    clock: u64,
+}
+
+/// An in-memory LRU cache of scheme responses, keyed by URL.
+///
+/// This is useful when responses are expensive to produce (e.g. rendered
+/// templates or generated thumbnails). Each entry carries a validator, which
+/// is any string that changes when the content does (e.g. a file modification
+/// time or a version number). Lookups with a different validator miss, so
+/// stale entries are never served.
+///
+/// The cache is bounded by the total size of response contents and can be
+/// shared between threads and handlers. Use separate caches to give schemes
+/// separate budgets.
+pub struct ResponseCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+impl ResponseCache {
+    /// Creates a cache holding up to `capacity` bytes of response contents.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Gets the cached response of the URL if it has the given validator.
+    pub fn get(&self, url: &str, validator: &str) -> Option<CachedResponse> {
+        let mut state = self.state.lock_unpoisoned();
+        state.clock += 1;
+        let clock = state.clock;
+
+        let entry = state.entries.get_mut(url)?;
+        if entry.validator != validator {
+            return None;
+        }
+
+        entry.last_used = clock;
+        Some(entry.response.clone())
+    }
+
+    /// Caches the response of the URL, replacing the existing one and evicting
+    /// the least recently used entries to stay within the capacity. Responses
+    /// larger than the capacity are not cached.
+    pub fn insert(
+        &self,
+        url: impl Into<String>,
+        validator: impl Into<String>,
+        res: CachedResponse,
+    ) {
+        let size = res.data.len();
+        let url = url.into();
+        let mut state = self.state.lock_unpoisoned();
+
+        if let Some(old) = state.entries.remove(&url) {
+            state.size -= old.response.data.len();
+        }
+
+        if size > self.capacity {
+            return;
+        }
+
+        while state.size + size > self.capacity {
+            let Some(lru) = state
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone())
+            else {
+                break;
+            };
+
+            let old = state.entries.remove(&lru).unwrap();
+            state.size -= old.response.data.len();
+        }
+
+        state.clock += 1;
+        state.size += size;
+        let last_used = state.clock;
+        state.entries.insert(url, CacheEntry {
+            response: res,
+            validator: validator.into(),
+            last_used,
+        });
+    }
+
+    /// Gets the cached response of the URL, or produces and caches one with the
+    /// given function. Responses produced as [`None`] are not cached.
+    pub fn get_or_insert_with(
+        &self,
+        url: &str,
+        validator: &str,
+        f: impl FnOnce() -> Option<CachedResponse>,
+    ) -> Option<CachedResponse> {
+        if let Some(res) = self.get(url, validator) {
+            return Some(res);
+        }
+
+        let res = f()?;
+        self.insert(url, validator, res.clone());
+        Some(res)
+    }
+
+    /// Removes the entry of the URL. Returns whether it has been found.
+    pub fn invalidate(&self, url: &str) -> bool {
+        let mut state = self.state.lock_unpoisoned();
+        let Some(old) = state.entries.remove(url) else {
+            return false;
+        };
+
+        state.size -= old.response.data.len();
+        true
+    }
+
+    /// Removes all entries whose URL matches the predicate, e.g. all URLs under
+    /// a path prefix.
+    pub fn invalidate_matching(&self, mut predicate: impl FnMut(&str) -> bool) {
+        let mut state = self.state.lock_unpoisoned();
+        let mut removed = 0;
+
+        state.entries.retain(|url, e| {
+            let keep = !predicate(url);
+            if !keep {
+                removed += e.response.data.len();
+            }
+            keep
+        });
+
+        state.size -= removed;
+    }
+
+    /// Removes all entries.
+    pub fn clear(&self) {
+        let mut state = self.state.lock_unpoisoned();
+        state.entries.clear();
+        state.size = 0;
+    }
+
+    /// Gets the number of entries.
+    pub fn len(&self) -> usize { self.state.lock_unpoisoned().entries.len() }
+
+    /// Checks whether the cache is empty.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Gets the total size of cached contents in bytes.
+    pub fn size(&self) -> usize { self.state.lock_unpoisoned().size }
+
+    /// Gets the maximum size of cached contents in bytes.
+    pub fn capacity(&self) -> usize { self.capacity }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CachedResponse;
+    use super::ResponseCache;
+
+    fn res(size: usize) -> CachedResponse { CachedResponse::new(vec![0u8; size], "text/plain") }
+
+    #[test]
+    fn cache_evicts_least_recently_used() {
+        let cache = ResponseCache::new(10);
+        cache.insert("a://1", "v1", res(4));
+        cache.insert("a://2", "v1", res(4));
+
+        assert!(cache.get("a://1", "v1").is_some());
+        assert!(cache.get("a://1", "v2").is_none());
+
+        cache.insert("a://3", "v1", res(4));
+        assert!(cache.get("a://2", "v1").is_none());
+        assert!(cache.get("a://1", "v1").is_some());
+        assert_eq!(cache.size(), 8);
+
+        cache.insert("a://4", "v1", res(11));
+        assert_eq!(cache.len(), 2);
+
+        cache.invalidate_matching(|u| u.ends_with('3'));
+        assert_eq!(cache.size(), 4);
+    }
+}
@@ -0,0 +1,176 @@
+use std::panic::UnwindSafe;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::sync::Weak;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::app::App;
+use crate::app::ShutdownToken;
+use crate::scheme::Executor;
+use crate::scheme::ExecutorCell;
+use crate::scheme::SchemeError;
+use crate::util::MutexExt;
+
+type BoxedDrainCallback = Box<dyn FnOnce(&App, DrainReport) + Send + UnwindSafe + 'static>;
+
+/// Describes how in-flight scheme requests are treated when the app quits. See
+/// [`crate::app::App::shutdown`].
+pub struct Drain {
+    timeout: Option<Duration>,
+    callback: Option<BoxedDrainCallback>,
+}
+
+impl Drain {
+    /// Waits for in-flight requests up to the given duration, then cancels
+    /// those still pending.
+    pub fn timeout(timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            callback: None,
+        }
+    }
+
+    /// Waits for in-flight requests however long they take.
+    pub fn indefinitely() -> Self {
+        Self {
+            timeout: None,
+            callback: None,
+        }
+    }
+
+    /// Sets a callback to be invoked on the event thread with the outcome once
+    /// draining finishes.
+    pub fn on_finish(
+        mut self,
+        callback: impl FnOnce(&App, DrainReport) + Send + UnwindSafe + 'static,
+    ) -> Self {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+}
+
+/// The outcome of draining scheme requests.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DrainReport {
+    /// Requests that were in flight and resolved (or dropped) by their
+    /// handlers before the deadline.
+    pub completed: usize,
+    /// Requests that were still pending at the deadline and have been
+    /// rejected with [`SchemeError::Failed`].
+    pub cancelled: usize,
+    /// Requests that arrived while draining and have been rejected with
+    /// [`SchemeError::Failed`] without reaching the handlers.
+    pub refused: usize,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    draining: bool,
+    pending: Vec<Weak<ExecutorCell>>,
+    refused: usize,
+}
+
+/// Tracks the scheme requests of an app which are in flight.
+#[derive(Default)]
+pub(crate) struct SchemeTracker {
+    state: Mutex<TrackerState>,
+    changed: Condvar,
+}
+
+impl SchemeTracker {
+    /// Starts tracking the request. Returns [`None`] if the request has been
+    /// refused, as the app is draining.
+    pub(crate) fn track(self: &Arc<Self>, exc: Executor) -> Option<Executor> {
+        let mut state = self.state.lock_unpoisoned();
+
+        if state.draining {
+            state.refused += 1;
+            drop(state);
+            exc.reject(SchemeError::Failed);
+            return None;
+        }
+
+        exc.cell().set_tracker(Arc::downgrade(self));
+        state.pending.retain(|c| c.strong_count() > 0);
+        state.pending.push(Arc::downgrade(exc.cell()));
+        Some(exc)
+    }
+
+    /// Wakes up the drain waiting for requests.
+    pub(crate) fn notify(&self) {
+        // Locking ensures that the drain is either waiting or yet to check
+        let _state = self.state.lock_unpoisoned();
+        self.changed.notify_all();
+    }
+
+    /// Stops accepting requests, then waits for the in-flight ones in the
+    /// background, holding the token until done.
+    pub(crate) fn drain(self: &Arc<Self>, app: &App, drain: Drain, token: ShutdownToken) {
+        let in_flight = {
+            let mut state = self.state.lock_unpoisoned();
+            state.draining = true;
+            state.pending.retain(|c| c.strong_count() > 0);
+            state.pending.len()
+        };
+
+        let tracker = self.clone();
+        let app = app.downgrade();
+        let deadline = drain.timeout.map(|t| Instant::now() + t);
+
+        std::thread::spawn(move || {
+            let mut state = tracker.state.lock_unpoisoned();
+
+            loop {
+                state.pending.retain(|c| c.strong_count() > 0);
+                if state.pending.is_empty() {
+                    break;
+                }
+
+                state = match deadline {
+                    None => tracker
+                        .changed
+                        .wait(state)
+                        .unwrap_or_else(PoisonError::into_inner),
+                    Some(d) => match d.checked_duration_since(Instant::now()) {
+                        Some(left) if !left.is_zero() => {
+                            tracker
+                                .changed
+                                .wait_timeout(state, left)
+                                .unwrap_or_else(PoisonError::into_inner)
+                                .0
+                        }
+                        _ => break,
+                    },
+                };
+            }
+
+            let pending = std::mem::take(&mut state.pending);
+            let refused = state.refused;
+            drop(state);
+
+            let cancelled = pending
+                .iter()
+                .filter_map(Weak::upgrade)
+                .filter(|c| c.reject(SchemeError::Failed))
+                .count();
+
+            let report = DrainReport {
+                completed: in_flight.saturating_sub(cancelled),
+                cancelled,
+                refused,
+            };
+
+            if let Some(callback) = drain.callback
+                && let Some(app) = app.upgrade()
+            {
+                app.post(move |app| {
+                    callback(&app, report);
+                    drop(token);
+                });
+            }
+        });
+    }
+}
@@ -1,8 +1,14 @@
 use std::ptr::NonNull;
+use std::sync::Arc;
+use std::sync::OnceLock;
+use std::sync::Weak;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 
 use saucer_sys::*;
 
 use crate::scheme::Response;
+use crate::scheme::SchemeTracker;
 
 /// Error types that can be used as the argument of [`Executor::reject`].
 pub enum SchemeError {
@@ -30,15 +36,55 @@ impl From<SchemeError> for saucer_scheme_error {
 /// request. Crucially, the executor is never passed as value, only shared
 /// reference, which is important to prevent use-after-free in case the
 /// webview is destroyed.
+///
+/// Requests still pending when a drain times out are rejected by the bindings
+/// (see [`crate::app::App::shutdown`]), in which case resolving the executor
+/// later does nothing.
 pub struct Executor {
+    cell: Arc<ExecutorCell>,
+}
+
+/// The shared part of an executor, which can be cancelled while the handler
+/// holds it.
+pub(crate) struct ExecutorCell {
     ptr: NonNull<saucer_scheme_executor>,
+    resolved: AtomicBool,
+    /// The tracker to notify when the executor is gone.
+    tracker: OnceLock<Weak<SchemeTracker>>,
 }
 
-unsafe impl Send for Executor {}
-unsafe impl Sync for Executor {}
+unsafe impl Send for ExecutorCell {}
+unsafe impl Sync for ExecutorCell {}
 
-impl Drop for Executor {
-    fn drop(&mut self) { unsafe { saucer_scheme_executor_free(self.ptr.as_ptr()) } }
+impl Drop for ExecutorCell {
+    fn drop(&mut self) {
+        unsafe { saucer_scheme_executor_free(self.ptr.as_ptr()) };
+
+        if let Some(t) = self.tracker.get().and_then(Weak::upgrade) {
+            t.notify();
+        }
+    }
+}
+
+impl ExecutorCell {
+    pub(crate) fn set_tracker(&self, tracker: Weak<SchemeTracker>) {
+        let _ = self.tracker.set(tracker);
+    }
+
+    /// Rejects the request unless it has been resolved. Returns whether it has
+    /// been rejected by this call.
+    pub(crate) fn reject(&self, ex: SchemeError) -> bool {
+        self.resolve(|p| unsafe { saucer_scheme_executor_reject(p, ex.into()) })
+    }
+
+    fn resolve(&self, f: impl FnOnce(*mut saucer_scheme_executor)) -> bool {
+        if self.resolved.swap(true, Ordering::AcqRel) {
+            return false;
+        }
+
+        f(self.ptr.as_ptr());
+        true
+    }
 }
 
 impl Executor {
@@ -46,21 +92,26 @@ impl Executor {
     /// dropped before the webview quits.
     pub(crate) unsafe fn from_ptr(ptr: *mut saucer_scheme_executor) -> Self {
         Self {
-            ptr: NonNull::new(ptr).expect("invalid scheme executor"),
+            cell: Arc::new(ExecutorCell {
+                ptr: NonNull::new(ptr).expect("invalid scheme executor"),
+                resolved: AtomicBool::new(false),
+                tracker: OnceLock::new(),
+            }),
         }
     }
 
+    pub(crate) fn cell(&self) -> &Arc<ExecutorCell> { &self.cell }
+
     /// Resolves with the given response.
     ///
     /// The response is consumed, yet it's unclear when it will be polled, thus
     /// it's 'static.
     pub fn accept(self, res: Response<'static>) {
         // The inner stash is copied for unbound usage, thus 'static
-        unsafe { saucer_scheme_executor_accept(self.ptr.as_ptr(), res.as_ptr()) }
+        self.cell
+            .resolve(|p| unsafe { saucer_scheme_executor_accept(p, res.as_ptr()) });
     }
 
     /// Rejects with the given [`SchemeError`].
-    pub fn reject(self, ex: SchemeError) {
-        unsafe { saucer_scheme_executor_reject(self.ptr.as_ptr(), ex.into()) }
-    }
+    pub fn reject(self, ex: SchemeError) { self.cell.reject(ex); }
 }
@@ -1,15 +1,37 @@
 //! Scheme handling module.
 //!
 //! This module includes [`Executor`], [`Request`] and [`Response`] to handle
-//! requests to custom schemes.
+//! requests to custom schemes, [`block_on_scoped`] for using async code in
+//! handlers, [`ResponseCache`] for reusing expensive responses, [`Drain`] for
+//! settling in-flight requests on quit, and [`Vfs`] for serving virtual file
+//! systems.
+mod block;
+#[cfg(feature = "bundle")]
+mod bundle;
+mod cache;
+mod drain;
 mod executor;
 mod request;
 mod response;
+mod vfs;
 
+pub use block::*;
+#[cfg(feature = "bundle")]
+pub use bundle::*;
+pub use cache::*;
+pub use drain::Drain;
+pub use drain::DrainReport;
+pub(crate) use drain::SchemeTracker;
 pub use executor::*;
 pub use request::*;
 pub use response::*;
 use saucer_sys::saucer_webview_register_scheme;
+pub use vfs::DirVfs;
+pub use vfs::Vfs;
+pub use vfs::VfsEntry;
+pub use vfs::VfsMetadata;
+pub use vfs::guess_mime;
+pub(crate) use vfs::serve_vfs;
 
 use crate::macros::use_string;
 
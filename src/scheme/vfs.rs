@@ -0,0 +1,218 @@
+//! Virtual file system module.
+//!
+//! See [`Vfs`] for details.
+use std::io::ErrorKind;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::scheme::Executor;
+use crate::scheme::Response;
+use crate::scheme::SchemeError;
+use crate::stash::Stash;
+
+/// Metadata of an item in a [`Vfs`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct VfsMetadata {
+    /// The size of the content in bytes. Zero for directories.
+    pub len: u64,
+    pub is_dir: bool,
+}
+
+/// An item listed by [`Vfs::list`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VfsEntry {
+    /// The name of the item, without its parent path.
+    pub name: String,
+    pub is_dir: bool,
+}
+
+/// A source of files that can be served to pages with
+/// [`crate::webview::Webview::mount`], like embedded content.
+///
+/// Paths are absolute and normalized (e.g. `/assets/main.js`), with `/` being
+/// the root. Methods are called on worker threads, thus implementations may
+/// block, e.g. to decrypt content or fetch it from the network.
+pub trait Vfs: Send + Sync + 'static {
+    /// Reads the whole content of the file at the given path.
+    fn open(&self, path: &str) -> std::io::Result<Vec<u8>>;
+
+    /// Gets the metadata of the item at the given path.
+    fn stat(&self, path: &str) -> std::io::Result<VfsMetadata>;
+
+    /// Lists the items in the directory at the given path.
+    fn list(&self, path: &str) -> std::io::Result<Vec<VfsEntry>>;
+
+    /// Gets the MIME type of the file at the given path. Defaults to guessing
+    /// from the extension (see [`guess_mime`]).
+    fn mime(&self, path: &str) -> String { guess_mime(path).to_owned() }
+}
+
+/// A [`Vfs`] serving a directory of the local file system.
+///
+/// Paths can't escape the directory, though symbolic links inside it are
+/// followed.
+pub struct DirVfs {
+    root: PathBuf,
+}
+
+impl DirVfs {
+    /// Creates a VFS serving the given directory.
+    pub fn new(root: impl Into<PathBuf>) -> Self { Self { root: root.into() } }
+
+    fn resolve(&self, path: &str) -> std::io::Result<PathBuf> {
+        let path = normalize_path(path).ok_or(ErrorKind::NotFound)?;
+        let mut resolved = self.root.clone();
+
+        // Pushed one by one, so that no segment can be taken as a root or
+        // prefix by the platform
+        for segment in path.split('/').filter(|s| !s.is_empty()) {
+            let mut components = Path::new(segment).components();
+            match (components.next(), components.next()) {
+                (Some(Component::Normal(c)), None) => resolved.push(c),
+                _ => return Err(ErrorKind::NotFound.into()),
+            }
+        }
+
+        if !resolved.starts_with(&self.root) {
+            return Err(ErrorKind::NotFound.into());
+        }
+
+        Ok(resolved)
+    }
+}
+
+impl Vfs for DirVfs {
+    fn open(&self, path: &str) -> std::io::Result<Vec<u8>> { std::fs::read(self.resolve(path)?) }
+
+    fn stat(&self, path: &str) -> std::io::Result<VfsMetadata> {
+        let meta = std::fs::metadata(self.resolve(path)?)?;
+        Ok(VfsMetadata {
+            len: if meta.is_dir() { 0 } else { meta.len() },
+            is_dir: meta.is_dir(),
+        })
+    }
+
+    fn list(&self, path: &str) -> std::io::Result<Vec<VfsEntry>> {
+        std::fs::read_dir(self.resolve(path)?)?
+            .map(|e| {
+                let e = e?;
+                Ok(VfsEntry {
+                    name: e.file_name().to_string_lossy().into_owned(),
+                    is_dir: e.file_type()?.is_dir(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Normalizes the path of a request into an absolute path without `.` and `..`
+/// segments. Returns [`None`] if the path escapes the root, or has segments
+/// with `:`, which may name drives or streams on Windows.
+pub(crate) fn normalize_path(path: &str) -> Option<String> {
+    let mut segments = Vec::new();
+
+    for s in path.split(['/', '\\']) {
+        match s {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            _ if s.contains(':') => return None,
+            _ => segments.push(s),
+        }
+    }
+
+    Some(format!("/{}", segments.join("/")))
+}
+
+/// Guesses the MIME type of a file from its extension. Unknown extensions
+/// are mapped to `application/octet-stream`.
+pub fn guess_mime(path: &str) -> &'static str {
+    let ext = path
+        .rsplit_once('.')
+        .map(|(_, e)| e.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" | "mjs" => "text/javascript",
+        "json" | "map" => "application/json",
+        "wasm" => "application/wasm",
+        "txt" => "text/plain",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "pdf" => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Serves the request path from the VFS on a worker thread. Directories are
+/// served with their `index.html`.
+pub(crate) fn serve_vfs(vfs: Arc<dyn Vfs>, path: String, exc: Executor) {
+    std::thread::spawn(move || {
+        let Some(mut path) = normalize_path(&path) else {
+            exc.reject(SchemeError::NotFound);
+            return;
+        };
+
+        let res = vfs.stat(&path).and_then(|meta| {
+            if meta.is_dir {
+                path = format!("{}/index.html", path.trim_end_matches('/'));
+            }
+
+            vfs.open(&path)
+        });
+
+        match res {
+            Ok(data) => exc.accept(Response::new(Stash::new_copy(data), vfs.mime(&path))),
+            Err(e) => exc.reject(match e.kind() {
+                ErrorKind::NotFound => SchemeError::NotFound,
+                ErrorKind::PermissionDenied => SchemeError::Denied,
+                ErrorKind::InvalidInput | ErrorKind::InvalidData => SchemeError::Invalid,
+                _ => SchemeError::Failed,
+            }),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("/a/./b//c").as_deref(), Some("/a/b/c"));
+        assert_eq!(normalize_path("a/b/../c").as_deref(), Some("/a/c"));
+        assert_eq!(normalize_path("").as_deref(), Some("/"));
+        assert_eq!(normalize_path("/a/../../etc"), None);
+        assert_eq!(normalize_path("\\..\\x"), None);
+        assert_eq!(normalize_path("/C:/Windows/win.ini"), None);
+        assert_eq!(normalize_path("\\\\?\\C:\\x"), None);
+        assert_eq!(normalize_path("/a/file.txt:stream"), None);
+    }
+
+    #[test]
+    fn test_guess_mime() {
+        assert_eq!(guess_mime("/index.HTML"), "text/html");
+        assert_eq!(guess_mime("/a.b/main.js"), "text/javascript");
+        assert_eq!(guess_mime("/LICENSE"), "application/octet-stream");
+    }
+}
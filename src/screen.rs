@@ -4,16 +4,22 @@ use saucer_sys::saucer_screen_name;
 use saucer_sys::saucer_screen_position;
 use saucer_sys::saucer_screen_size;
 
+use crate::geometry::Position;
+use crate::geometry::Rect;
+use crate::geometry::Size;
 use crate::util::make_owned_string;
 
 /// A struct containing information of a display screen.
 pub struct Screen {
     pub name: String,
-    pub size: (i32, i32),
-    pub pos: (i32, i32),
+    pub size: Size,
+    pub pos: Position,
 }
 
 impl Screen {
+    /// Gets the area covered by the screen.
+    pub fn bounds(&self) -> Rect { Rect::new(self.pos, self.size) }
+
     /// Takes the given raw pointer and converts it into a [`Screen`]. Returns
     /// [`None`] if the pointer is null.
     pub(crate) unsafe fn from_raw(ptr: *mut saucer_screen) -> Option<Self> {
@@ -37,8 +43,8 @@ impl Screen {
 
         Some(Self {
             name,
-            size: (w, h),
-            pos: (x, y),
+            size: Size::new(w, h),
+            pos: Position::new(x, y),
         })
     }
 }
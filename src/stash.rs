@@ -122,6 +122,10 @@ impl<'a> Stash<'a> {
     }
 }
 
+impl<'a> AsRef<Stash<'a>> for Stash<'a> {
+    fn as_ref(&self) -> &Stash<'a> { self }
+}
+
 impl AsRef<[u8]> for Stash<'_> {
     fn as_ref(&self) -> &[u8] { self.data() }
 }
@@ -133,7 +137,7 @@ struct LazyCallbackData {
 
 #[cfg(false)]
 extern "C" fn stash_lazy_tp(data: *mut std::ffi::c_void) -> *mut saucer_stash {
-    crate::util::ffi_callback(std::ptr::null_mut(), || {
+    crate::util::ffi_callback("lazy stash", std::ptr::null_mut(), || {
         let bb = unsafe { Box::from_raw(data as *mut LazyCallbackData) };
         // The C library will free the stash object, so only the handle is dropped.
         let mut st = (bb.callback)();
@@ -2,6 +2,7 @@ use saucer_sys::*;
 
 /// The load state of a web page. Used to distinguish stages in
 /// [`crate::webview::WebviewEventListener::on_load`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum LoadState {
     Started,
     Finished,
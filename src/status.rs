@@ -1,8 +1,10 @@
 use saucer_sys::*;
 
 /// A status returned by handler describing whether an event has been handled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub enum HandleStatus {
     Handled,
+    #[default]
     Unhandled,
 }
 
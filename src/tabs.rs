@@ -0,0 +1,499 @@
+//! Tabbed browsing module.
+//!
+//! [`Tabs`] manages a set of webviews sharing one window, one of which is
+//! shown at a time, as the content of a browser-like shell. The tab strip
+//! itself is left to the app, e.g. drawn by another webview placed above the
+//! tabs (see [`Tabs::set_bounds`]).
+use std::collections::BTreeMap;
+use std::panic::RefUnwindSafe;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::Weak;
+
+use crate::icon::Icon;
+use crate::js::JsValue;
+use crate::url::Url;
+use crate::util::MutexExt;
+use crate::webview::Webview;
+use crate::webview::WebviewEventListener;
+use crate::webview::WebviewOptions;
+use crate::window::Window;
+
+/// Identifies a tab of [`Tabs`]. IDs are not reused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TabId(u64);
+
+impl TabId {
+    /// Gets the raw value of the ID.
+    pub fn get(self) -> u64 { self.0 }
+}
+
+/// An event of [`Tabs`], delivered on the event thread.
+#[derive(Clone)]
+pub enum TabEvent {
+    Opened(TabId),
+    /// The tab has been closed, and its webview is destroyed.
+    Closed(TabId),
+    /// The tab is now shown.
+    Activated(TabId),
+    Title {
+        id: TabId,
+        title: String,
+    },
+    Favicon {
+        id: TabId,
+        icon: Icon,
+    },
+    /// The tab has navigated to the given URL.
+    Navigated {
+        id: TabId,
+        url: String,
+    },
+}
+
+/// The saved state of a tab. See [`TabsState`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TabState {
+    pub url: String,
+    pub title: String,
+}
+
+/// The saved state of [`Tabs`], for restoring them in a later session with
+/// [`Tabs::restore`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TabsState {
+    pub tabs: Vec<TabState>,
+    /// The index of the active tab.
+    pub active: Option<usize>,
+}
+
+impl TabsState {
+    /// Serializes the state as JSON.
+    pub fn to_json(&self) -> String {
+        let tabs = self
+            .tabs
+            .iter()
+            .map(|t| {
+                JsValue::Object(BTreeMap::from([
+                    ("url".to_owned(), t.url.clone().into()),
+                    ("title".to_owned(), t.title.clone().into()),
+                ]))
+            })
+            .collect::<Vec<_>>();
+
+        let state = BTreeMap::from([
+            ("tabs".to_owned(), JsValue::Array(tabs)),
+            (
+                "active".to_owned(),
+                self.active.map(|i| JsValue::Number(i as f64)).into(),
+            ),
+        ]);
+
+        JsValue::Object(state).to_string()
+    }
+
+    /// Parses the state serialized with [`Self::to_json`]. Returns [`None`]
+    /// if the JSON is malformed.
+    pub fn from_json(json: &str) -> Option<Self> {
+        let value = JsValue::parse(json).ok()?;
+
+        let tabs = value
+            .get("tabs")?
+            .as_array()?
+            .iter()
+            .map(|t| {
+                Some(TabState {
+                    url: t.get("url")?.as_str()?.to_owned(),
+                    title: t
+                        .get("title")
+                        .and_then(JsValue::as_str)
+                        .unwrap_or("")
+                        .to_owned(),
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let active = value
+            .get("active")
+            .and_then(JsValue::as_f64)
+            .map(|i| i as usize)
+            .filter(|i| *i < tabs.len());
+
+        Some(Self { tabs, active })
+    }
+}
+
+type TabEventHandler = Box<dyn Fn(&Tabs, TabEvent) + Send + Sync + RefUnwindSafe + 'static>;
+type OptionsFactory = Box<dyn Fn() -> WebviewOptions + Send + Sync + RefUnwindSafe + 'static>;
+
+struct Tab {
+    id: TabId,
+    webview: Webview,
+    title: String,
+}
+
+#[derive(Default)]
+struct TabList {
+    tabs: Vec<Tab>,
+    active: Option<TabId>,
+    next_id: u64,
+    /// The area of the window covered by tabs, or [`None`] for all of it.
+    bounds: Option<(i32, i32, i32, i32)>,
+}
+
+struct RawTabs {
+    window: Window,
+    list: Mutex<TabList>,
+    options: Mutex<Option<OptionsFactory>>,
+    handler: TabEventHandler,
+}
+
+/// A set of webviews sharing one window as tabs, one of which is shown at a
+/// time. Handles are cheap to clone and refer to the same set.
+///
+/// Tabs must be managed on the event thread, as they create and destroy
+/// webviews. Events are delivered to the handler given at creation:
+///
+/// ```no_run
+/// # fn f(window: saucers::window::Window) -> saucers::error::Result<()> {
+/// use saucers::tabs::TabEvent;
+/// use saucers::tabs::Tabs;
+///
+/// let tabs = Tabs::new(window, |_, ev| {
+///     if let TabEvent::Title { id, title } = ev {
+///         println!("Tab {} is now {title}", id.get());
+///     }
+/// });
+///
+/// let id = tabs.open("https://example.com")?;
+/// tabs.activate(id);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Tabs(Arc<RawTabs>);
+
+impl Tabs {
+    /// Creates an empty set of tabs in the given window.
+    pub fn new(
+        window: Window,
+        handler: impl Fn(&Tabs, TabEvent) + Send + Sync + RefUnwindSafe + 'static,
+    ) -> Self {
+        Self(Arc::new(RawTabs {
+            window,
+            list: Mutex::new(TabList::default()),
+            options: Mutex::new(None),
+            handler: Box::new(handler),
+        }))
+    }
+
+    /// Sets how options of webviews of new tabs are made. Defaults to
+    /// [`WebviewOptions::default`].
+    pub fn set_options(
+        &self,
+        factory: impl Fn() -> WebviewOptions + Send + Sync + RefUnwindSafe + 'static,
+    ) {
+        *self.0.options.lock_unpoisoned() = Some(Box::new(factory));
+    }
+
+    /// Sets the area of the window covered by tabs, in the order of x, y,
+    /// width and height, leaving room for e.g. a tab strip. [`None`] makes
+    /// tabs fill the window, which is the default.
+    pub fn set_bounds(&self, bounds: Option<(i32, i32, i32, i32)>) {
+        let webviews = {
+            let mut list = self.0.list.lock_unpoisoned();
+            list.bounds = bounds;
+            list.tabs
+                .iter()
+                .map(|t| t.webview.clone())
+                .collect::<Vec<_>>()
+        };
+
+        for w in webviews {
+            place(&w, bounds);
+        }
+    }
+
+    /// Gets the window of the tabs.
+    pub fn window(&self) -> &Window { &self.0.window }
+
+    /// Opens a tab showing the given URL after the existing ones. The tab is
+    /// hidden unless it's the only one, see [`Self::activate`].
+    pub fn open(&self, url: &str) -> crate::error::Result<TabId> {
+        let id = {
+            let mut list = self.0.list.lock_unpoisoned();
+            list.next_id += 1;
+            TabId(list.next_id)
+        };
+
+        let options = self
+            .0
+            .options
+            .lock_unpoisoned()
+            .as_ref()
+            .map_or_else(WebviewOptions::default, |f| f());
+
+        let listener = TabListener {
+            tabs: Arc::downgrade(&self.0),
+            id,
+        };
+
+        let webview = Webview::new(options, self.0.window.clone(), listener, ())?;
+
+        let first = {
+            let mut list = self.0.list.lock_unpoisoned();
+            place(&webview, list.bounds);
+
+            let first = list.tabs.is_empty();
+            if first {
+                list.active = Some(id);
+            } else {
+                webview.set_visible(false);
+            }
+
+            list.tabs.push(Tab {
+                id,
+                webview: webview.clone(),
+                title: String::new(),
+            });
+            first
+        };
+
+        webview.set_url_str(url);
+
+        self.emit(TabEvent::Opened(id));
+
+        if first {
+            self.emit(TabEvent::Activated(id));
+        }
+
+        Ok(id)
+    }
+
+    /// Closes the tab, destroying its webview. If it's active, the tab after
+    /// it (or before it, for the last tab) is activated. Returns whether the
+    /// tab exists.
+    pub fn close(&self, id: TabId) -> bool {
+        let (tab, next) = {
+            let mut list = self.0.list.lock_unpoisoned();
+            let Some(index) = list.tabs.iter().position(|t| t.id == id) else {
+                return false;
+            };
+
+            let tab = list.tabs.remove(index);
+            let next = if list.active == Some(id) {
+                let next = list.tabs.get(index).or(list.tabs.last()).map(|t| t.id);
+                list.active = None;
+                next
+            } else {
+                None
+            };
+
+            (tab, next)
+        };
+
+        drop(tab); // Destroys the webview
+        self.emit(TabEvent::Closed(id));
+
+        if let Some(next) = next {
+            self.activate(next);
+        }
+
+        true
+    }
+
+    /// Shows the tab and hides others. Returns whether the tab exists.
+    pub fn activate(&self, id: TabId) -> bool {
+        let (shown, hidden) = {
+            let mut list = self.0.list.lock_unpoisoned();
+            let Some(shown) = list.tabs.iter().find(|t| t.id == id) else {
+                return false;
+            };
+
+            if list.active == Some(id) {
+                return true;
+            }
+
+            let shown = shown.webview.clone();
+            let hidden = list
+                .tabs
+                .iter()
+                .find(|t| Some(t.id) == list.active)
+                .map(|t| t.webview.clone());
+
+            list.active = Some(id);
+            (shown, hidden)
+        };
+
+        // Show first to avoid flashing the window background
+        shown.set_visible(true);
+
+        if let Some(h) = hidden {
+            h.set_visible(false);
+        }
+
+        self.emit(TabEvent::Activated(id));
+        true
+    }
+
+    /// Gets the active tab.
+    pub fn active(&self) -> Option<TabId> { self.0.list.lock_unpoisoned().active }
+
+    /// Gets the tabs in order.
+    pub fn ids(&self) -> Vec<TabId> {
+        self.0
+            .list
+            .lock_unpoisoned()
+            .tabs
+            .iter()
+            .map(|t| t.id)
+            .collect()
+    }
+
+    /// Gets the webview of the tab.
+    pub fn webview(&self, id: TabId) -> Option<Webview> { self.with_tab(id, |t| t.webview.clone()) }
+
+    /// Gets the title of the tab, as last reported by its page.
+    pub fn title(&self, id: TabId) -> Option<String> { self.with_tab(id, |t| t.title.clone()) }
+
+    /// Saves the URLs and titles of the tabs and the active one.
+    pub fn state(&self) -> TabsState {
+        let list = self.0.list.lock_unpoisoned();
+
+        TabsState {
+            tabs: list
+                .tabs
+                .iter()
+                .map(|t| TabState {
+                    url: t.webview.url().map(|u| u.to_string()).unwrap_or_default(),
+                    title: t.title.clone(),
+                })
+                .collect(),
+            active: list.tabs.iter().position(|t| Some(t.id) == list.active),
+        }
+    }
+
+    /// Opens the tabs of a saved state after the existing ones, and activates
+    /// the saved active tab. Returns the IDs of the opened tabs.
+    ///
+    /// Stops at the first tab that fails to open, keeping those opened.
+    pub fn restore(&self, state: &TabsState) -> crate::error::Result<Vec<TabId>> {
+        let mut ids = Vec::new();
+
+        for t in &state.tabs {
+            let id = self.open(&t.url)?;
+            if let Some(tab) = self
+                .0
+                .list
+                .lock_unpoisoned()
+                .tabs
+                .iter_mut()
+                .find(|x| x.id == id)
+            {
+                tab.title = t.title.clone();
+            }
+            ids.push(id);
+        }
+
+        if let Some(id) = state.active.and_then(|i| ids.get(i)) {
+            self.activate(*id);
+        }
+
+        Ok(ids)
+    }
+
+    fn with_tab<T>(&self, id: TabId, f: impl FnOnce(&Tab) -> T) -> Option<T> {
+        self.0
+            .list
+            .lock_unpoisoned()
+            .tabs
+            .iter()
+            .find(|t| t.id == id)
+            .map(f)
+    }
+
+    fn emit(&self, ev: TabEvent) { (self.0.handler)(self, ev) }
+}
+
+fn place(webview: &Webview, bounds: Option<(i32, i32, i32, i32)>) {
+    match bounds {
+        Some((x, y, w, h)) => webview.set_bounds(x, y, w, h),
+        None => webview.reset_bounds(),
+    }
+}
+
+/// Forwards events of the webview of a tab.
+struct TabListener {
+    tabs: Weak<RawTabs>,
+    id: TabId,
+}
+
+impl TabListener {
+    fn emit(&self, ev: TabEvent) {
+        if let Some(tabs) = self.tabs.upgrade() {
+            Tabs(tabs).emit(ev);
+        }
+    }
+}
+
+impl WebviewEventListener for TabListener {
+    fn on_title(&self, _: Webview, title: String) {
+        let Some(tabs) = self.tabs.upgrade() else {
+            return;
+        };
+
+        let tabs = Tabs(tabs);
+        if let Some(tab) = tabs
+            .0
+            .list
+            .lock_unpoisoned()
+            .tabs
+            .iter_mut()
+            .find(|t| t.id == self.id)
+        {
+            tab.title = title.clone();
+        }
+
+        tabs.emit(TabEvent::Title { id: self.id, title });
+    }
+
+    fn on_favicon(&self, _: Webview, icon: Icon) {
+        self.emit(TabEvent::Favicon { id: self.id, icon });
+    }
+
+    fn on_navigated(&self, _: Webview, url: Url) {
+        self.emit(TabEvent::Navigated {
+            id: self.id,
+            url: url.to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tabs_state_json() {
+        let state = TabsState {
+            tabs: vec![
+                TabState {
+                    url: "https://example.com/".to_owned(),
+                    title: "Example \"Domain\"".to_owned(),
+                },
+                TabState {
+                    url: "about:blank".to_owned(),
+                    title: String::new(),
+                },
+            ],
+            active: Some(1),
+        };
+
+        assert_eq!(TabsState::from_json(&state.to_json()), Some(state));
+        assert!(TabsState::from_json("{\"tabs\":[{}]}").is_none());
+        assert_eq!(
+            TabsState::from_json("{\"tabs\":[],\"active\":3}"),
+            Some(TabsState::default())
+        );
+    }
+}
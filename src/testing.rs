@@ -0,0 +1,317 @@
+//! Test harness module.
+//!
+//! See [`TestHarness`] for details.
+use std::any::Any;
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+use std::panic::UnwindSafe;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use crate::app::App;
+use crate::app::AppEventListener;
+use crate::app::AppManager;
+use crate::app::AppOptions;
+use crate::app::AppRef;
+use crate::util::MutexExt;
+use crate::webview::EventVisitor;
+use crate::webview::Webview;
+use crate::webview::WebviewEvent;
+use crate::webview::visit_events;
+
+thread_local! {
+    static STAGE: RefCell<Option<Stage>> = const { RefCell::new(None) };
+}
+
+/// Counts events fired by watched webviews, until they're consumed by
+/// [`TestDriver::wait_for`].
+#[derive(Default)]
+struct EventLog {
+    counts: Mutex<HashMap<TypeId, usize>>,
+    changed: Condvar,
+}
+
+impl EventLog {
+    fn record<E: WebviewEvent>(&self) {
+        *self
+            .counts
+            .lock_unpoisoned()
+            .entry(TypeId::of::<E>())
+            .or_default() += 1;
+        self.changed.notify_all();
+    }
+
+    fn consume<E: WebviewEvent>(&self, timeout: Duration) -> bool {
+        let counts = self.counts.lock_unpoisoned();
+        let (mut counts, _) = self
+            .changed
+            .wait_timeout_while(counts, timeout, |c| {
+                c.get(&TypeId::of::<E>()).is_none_or(|n| *n == 0)
+            })
+            .unwrap_or_else(PoisonError::into_inner);
+
+        match counts.get_mut(&TypeId::of::<E>()) {
+            Some(n) if *n > 0 => {
+                *n -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+struct Recorder<'a> {
+    webview: &'a Webview,
+    log: &'a Arc<EventLog>,
+}
+
+impl EventVisitor for Recorder<'_> {
+    fn visit<E: WebviewEvent>(&mut self) {
+        let log = self.log.clone();
+        self.webview.observe::<E>(move |_, _| {
+            log.record::<E>();
+            E::Output::default()
+        });
+    }
+}
+
+/// The state of a test on the event thread, passed to steps posted with
+/// [`TestDriver::step`].
+///
+/// Values kept here (including watched webviews) are dropped before the app
+/// quits.
+pub struct Stage {
+    app: App,
+    log: Arc<EventLog>,
+    webviews: Vec<Webview>,
+    kept: Vec<Box<dyn Any>>,
+}
+
+impl Stage {
+    /// Gets the app being tested.
+    pub fn app(&self) -> &App { &self.app }
+
+    /// Records events fired by the given webview, so that they can be awaited
+    /// with [`TestDriver::wait_for`], and keeps it until the test ends.
+    ///
+    /// Events fired before watching are not recorded. Watch webviews in the
+    /// same step that creates them to catch every event. Watching doesn't
+    /// install internal scripts (see [`crate::webview::InternalScripts`]),
+    /// thus events reported by them are only recorded once the test uses
+    /// them.
+    pub fn watch(&mut self, webview: &Webview) {
+        visit_events(&mut Recorder {
+            webview,
+            log: &self.log,
+        });
+        self.webviews.push(webview.clone());
+    }
+
+    /// Gets the first watched webview.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no webview has been watched.
+    pub fn webview(&self) -> Webview {
+        self.webviews
+            .first()
+            .cloned()
+            .expect("no webview has been watched")
+    }
+
+    /// Gets all watched webviews, in the order they've been watched.
+    pub fn webviews(&self) -> &[Webview] { &self.webviews }
+
+    /// Keeps the given value until the test ends.
+    pub fn keep(&mut self, value: impl Any) { self.kept.push(Box::new(value)); }
+}
+
+/// Drives a test from a thread other than the event thread. See
+/// [`TestHarness::run`].
+pub struct TestDriver {
+    app: AppRef,
+    log: Arc<EventLog>,
+    timeout: Duration,
+}
+
+impl TestDriver {
+    /// Runs the step on the event thread and waits for its result, with the
+    /// timeout of the harness.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the step panics, doesn't finish in time, or the app has quit.
+    pub fn step<T: Send + 'static>(
+        &self,
+        step: impl FnOnce(&mut Stage) -> T + Send + UnwindSafe + 'static,
+    ) -> T {
+        self.step_timeout(step, self.timeout)
+    }
+
+    /// Like [`Self::step`], but with the given timeout.
+    pub fn step_timeout<T: Send + 'static>(
+        &self,
+        step: impl FnOnce(&mut Stage) -> T + Send + UnwindSafe + 'static,
+        timeout: Duration,
+    ) -> T {
+        let app = self.app.upgrade().expect("the app has quit");
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        app.post(move |_| {
+            let out = STAGE.with_borrow_mut(|s| s.as_mut().map(step));
+            if let Some(out) = out {
+                let _ = tx.send(out);
+            }
+        });
+
+        drop(app); // Don't keep the app alive while waiting
+
+        match rx.recv_timeout(timeout) {
+            Ok(out) => out,
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                panic!("step didn't finish in {timeout:?}")
+            }
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                panic!("step has panicked or the app has quit")
+            }
+        }
+    }
+
+    /// Waits until a watched webview fires the given event, and consumes it.
+    /// Each event fired can only be awaited once, thus waiting twice requires
+    /// the event to be fired twice.
+    ///
+    /// Returns [`crate::error::Error::Timeout`] if the event is not fired in
+    /// time.
+    pub fn wait_for<E: WebviewEvent>(&self, timeout: Duration) -> crate::error::Result<()> {
+        if self.log.consume::<E>(timeout) {
+            Ok(())
+        } else {
+            Err(crate::error::Error::Timeout)
+        }
+    }
+}
+
+/// A harness for tests driven by the event loop.
+///
+/// The harness runs the app on the current thread, and the test on another
+/// thread, which posts steps sequentially and awaits events (see
+/// [`TestDriver`]). Tests thus read top to bottom, instead of being split
+/// across listeners and channels. When the test returns, values kept on the
+/// [`Stage`] are dropped and the app quits.
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use saucers::app::AppOptions;
+/// use saucers::testing::TestHarness;
+/// use saucers::webview::TitleEvent;
+/// use saucers::webview::Webview;
+/// use saucers::webview::WebviewOptions;
+/// use saucers::window::Window;
+///
+/// TestHarness::new(AppOptions::new_with_id("test"))
+///     .run(
+///         |h| {
+///             h.step(|s| {
+///                 let window = Window::new(s.app(), ()).unwrap();
+///                 let webview = Webview::new(WebviewOptions::default(), window, (), ()).unwrap();
+///                 s.watch(&webview);
+///                 webview.set_html("<title>Hello</title>");
+///             });
+///
+///             h.wait_for::<TitleEvent>(Duration::from_secs(5)).unwrap();
+///             assert_eq!(h.step(|s| s.webview().page_title().unwrap()), "Hello");
+///         },
+///         (),
+///     )
+///     .unwrap();
+/// ```
+pub struct TestHarness {
+    manager: AppManager,
+    timeout: Duration,
+}
+
+impl TestHarness {
+    /// Creates a harness running an app with the given options.
+    pub fn new(opt: AppOptions) -> Self {
+        Self {
+            manager: AppManager::new(opt),
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Sets the timeout of [`TestDriver::step`]. Defaults to 10 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Runs the app with the given event listener, and the test on another
+    /// thread. Returns after the app quits.
+    ///
+    /// # Panics
+    ///
+    /// Resumes the panic of the test, if any, after the app quits.
+    pub fn run(
+        self,
+        test: impl FnOnce(&TestDriver) + Send + UnwindSafe + 'static,
+        event_listener: impl AppEventListener,
+    ) -> crate::error::Result<()> {
+        let timeout = self.timeout;
+        let driver = Arc::new(Mutex::new(None));
+        let driver_handle = driver.clone();
+
+        self.manager.run(
+            move |app| {
+                let log = Arc::new(EventLog::default());
+
+                STAGE.set(Some(Stage {
+                    app: app.clone(),
+                    log: log.clone(),
+                    webviews: Vec::new(),
+                    kept: Vec::new(),
+                }));
+
+                let driver = TestDriver {
+                    app: app.downgrade(),
+                    log,
+                    timeout,
+                };
+
+                let handle = std::thread::spawn(move || {
+                    let res = std::panic::catch_unwind(AssertUnwindSafe(|| test(&driver)));
+
+                    if let Some(app) = driver.app.upgrade() {
+                        app.post(|_| drop(STAGE.take()));
+                        app.request_quit();
+                    }
+
+                    res
+                });
+
+                *driver_handle.lock_unpoisoned() = Some(handle);
+
+                // Also covers apps quitting before the test ends
+                |_| drop(STAGE.take())
+            },
+            event_listener,
+        )?;
+
+        let handle = driver.lock_unpoisoned().take();
+        if let Some(handle) = handle {
+            // If the app has quit early, pending steps fail, which ends the test
+            match handle.join() {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) | Err(e) => std::panic::resume_unwind(e),
+            }
+        }
+
+        Ok(())
+    }
+}
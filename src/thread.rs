@@ -0,0 +1,62 @@
+//! Handling of event thread violations.
+//!
+//! Many APIs are confined to the event thread. By default, calling them on
+//! other threads panics, which can be changed with
+//! [`set_thread_violation_policy`].
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering;
+
+static POLICY: AtomicU8 = AtomicU8::new(ThreadViolationPolicy::Panic as u8);
+
+/// Decides what happens when event thread APIs are called on other threads.
+///
+/// The policy applies to APIs that can report failures, namely the
+/// constructors of [`crate::window::Window`], [`crate::webview::Webview`] and
+/// [`crate::webview::WebviewPool`], and [`crate::webview::Webview::off`].
+/// Other APIs (e.g. [`crate::webview::Webview::on`], which takes a handler that
+/// can't be moved between threads) always panic.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ThreadViolationPolicy {
+    /// Panics.
+    #[default]
+    Panic,
+    /// Fails with [`crate::error::Error::WrongThread`].
+    Error,
+    /// Posts the operation to the event thread when it doesn't produce a value,
+    /// fails with [`crate::error::Error::WrongThread`] otherwise.
+    Queue,
+}
+
+/// Sets the policy for event thread violations. The policy is crate-wide.
+pub fn set_thread_violation_policy(policy: ThreadViolationPolicy) {
+    POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+/// Gets the policy for event thread violations.
+pub fn thread_violation_policy() -> ThreadViolationPolicy {
+    match POLICY.load(Ordering::Relaxed) {
+        1 => ThreadViolationPolicy::Error,
+        2 => ThreadViolationPolicy::Queue,
+        _ => ThreadViolationPolicy::Panic,
+    }
+}
+
+/// Checks a call that must happen on the event thread, with `what` describing
+/// it for the panic message.
+///
+/// Returns the policy if the call is made on another thread and shouldn't
+/// panic, so that the caller can queue or fail it.
+pub(crate) fn check_event_thread(
+    is_thread_safe: bool,
+    what: &str,
+) -> Option<ThreadViolationPolicy> {
+    if is_thread_safe {
+        return None;
+    }
+
+    match thread_violation_policy() {
+        ThreadViolationPolicy::Panic => panic!("{what} must be done on the event thread"),
+        policy => Some(policy),
+    }
+}
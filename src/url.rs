@@ -23,6 +23,15 @@ impl Drop for Url {
     fn drop(&mut self) { unsafe { saucer_url_free(self.inner.as_ptr()) } }
 }
 
+impl Clone for Url {
+    fn clone(&self) -> Self {
+        let ptr = unsafe { saucer_url_copy(self.as_ptr()) };
+        Self {
+            inner: NonNull::new(ptr).expect("copied URL should be non-null"),
+        }
+    }
+}
+
 impl AsRef<Url> for Url {
     fn as_ref(&self) -> &Url { self }
 }
@@ -158,5 +167,13 @@ impl Url {
         ok.then_some(port)
     }
 
+    /// Gets the origin of the URL, i.e. `scheme://host[:port]`.
+    pub fn origin(&self) -> String {
+        match self.port() {
+            Some(port) => format!("{}://{}:{}", self.scheme(), self.host(), port),
+            None => format!("{}://{}", self.scheme(), self.host()),
+        }
+    }
+
     pub(crate) fn as_ptr(&self) -> *mut saucer_url { self.inner.as_ptr() }
 }
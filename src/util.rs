@@ -2,6 +2,12 @@ use std::ffi::CStr;
 use std::ffi::c_char;
 use std::panic::UnwindSafe;
 use std::panic::catch_unwind;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+use crate::macros::trace_event;
+use crate::macros::trace_span;
 
 /// Copies the given C string into an owned [`String`]. Performs lossy UTF-8
 /// conversion if needed.
@@ -38,27 +44,228 @@ pub(crate) fn inflate_strings(mut src: &[u8]) -> Vec<String> {
     out
 }
 
+/// Percent-encodes all bytes of the given string except unreserved characters
+/// of RFC 3986.
+pub(crate) fn percent_encode(s: &str) -> String { percent_encode_bytes(s.as_bytes()) }
+
+/// Like [`percent_encode`], but for arbitrary bytes, e.g. data URLs.
+pub(crate) fn percent_encode_bytes(s: &[u8]) -> String {
+    let mut out = String::with_capacity(s.len());
+
+    for &b in s {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                out.push(b as char)
+            }
+            b => out.push_str(&format!("%{b:02X}")),
+        }
+    }
+
+    out
+}
+
+/// Decodes percent-encoded bytes in the given string. Invalid escapes are kept
+/// as-is and invalid UTF-8 is replaced.
+pub(crate) fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok());
+
+        match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+            Some(b) if bytes[i] == b'%' => {
+                out.push(b);
+                i += 3;
+            }
+            _ => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Generates a token that can't be guessed by pages, in hex.
+///
+/// Each [`RandomState`](std::hash::RandomState) is keyed by randomness of the
+/// OS, thus hashing with two of them yields 128 unpredictable bits without
+/// depending on a random number generator.
+pub(crate) fn random_token() -> String {
+    use std::hash::BuildHasher;
+    use std::hash::RandomState;
+
+    let seed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+
+    let a = RandomState::new().hash_one(seed);
+    let b = RandomState::new().hash_one((seed, a));
+    format!("{a:016x}{b:016x}")
+}
+
+/// Quotes the given string as a JavaScript string literal.
+pub(crate) fn js_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            // Line terminators in JS, and `<` to avoid closing script tags
+            '\u{2028}' | '\u{2029}' | '<' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
 /// Runs a Rust callback without allowing a panic to unwind across an FFI
-/// boundary.
+/// boundary. The context describes the callback for the panic hook (see
+/// [`crate::panic::set_panic_hook`]).
 ///
 /// The panic payload is intentionally leaked because dropping an arbitrary
 /// payload can itself panic. The panic hook still runs before the unwind is
 /// caught.
-pub(crate) fn ffi_callback<R>(fallback: R, callback: impl FnOnce() -> R + UnwindSafe) -> R {
+///
+/// Callbacks invoked by the event loop count as its activity, see
+/// [`crate::app::App::on_idle`].
+pub(crate) fn ffi_callback<R>(
+    context: &'static str,
+    fallback: R,
+    callback: impl FnOnce() -> R + UnwindSafe,
+) -> R {
+    crate::app::record_activity();
+    ffi_callback_passive(context, fallback, callback)
+}
+
+/// Like [`ffi_callback`], but doesn't count as activity of the event loop.
+pub(crate) fn ffi_callback_passive<R>(
+    context: &'static str,
+    fallback: R,
+    callback: impl FnOnce() -> R + UnwindSafe,
+) -> R {
+    let _span = trace_span!(target: "saucers::ffi", "callback", context);
+
     match catch_unwind(callback) {
         Ok(result) => result,
         Err(payload) => {
+            trace_event!(target: "saucers::ffi", context, "callback panicked");
+            crate::panic::report_panic(context, payload.as_ref());
             std::mem::forget(payload);
             fallback
         }
     }
 }
 
+/// Locking that recovers from poisoning.
+///
+/// Panics in callbacks are caught at the FFI boundary (see [`ffi_callback`]),
+/// leaving the mutexes they held poisoned. States guarded by the bindings stay
+/// consistent across such panics, thus later callbacks should keep working
+/// rather than panic again on every lock.
+pub(crate) trait MutexExt<T: ?Sized> {
+    /// Locks the mutex, ignoring whether it's poisoned.
+    fn lock_unpoisoned(&self) -> MutexGuard<'_, T>;
+}
+
+impl<T: ?Sized> MutexExt<T> for Mutex<T> {
+    fn lock_unpoisoned(&self) -> MutexGuard<'_, T> {
+        self.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A glob pattern, where `*` matches any sequence of characters and `?`
+/// matches a single character.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Glob(Vec<GlobToken>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum GlobToken {
+    Char(char),
+    Any,
+    Star,
+}
+
+impl Glob {
+    /// Compiles the given pattern.
+    pub(crate) fn new(pattern: &str) -> Self {
+        let mut tokens = Vec::with_capacity(pattern.len());
+
+        for c in pattern.chars() {
+            let token = match c {
+                '*' if tokens.last() == Some(&GlobToken::Star) => continue,
+                '*' => GlobToken::Star,
+                '?' => GlobToken::Any,
+                c => GlobToken::Char(c),
+            };
+            tokens.push(token);
+        }
+
+        Self(tokens)
+    }
+
+    /// Checks whether the whole input matches the pattern.
+    pub(crate) fn matches(&self, input: &str) -> bool {
+        let input: Vec<char> = input.chars().collect();
+        let (mut p, mut i) = (0, 0);
+        let mut backtrack = None;
+
+        while i < input.len() {
+            match self.0.get(p) {
+                Some(GlobToken::Star) => {
+                    backtrack = Some((p, i));
+                    p += 1;
+                }
+                Some(GlobToken::Any) => {
+                    p += 1;
+                    i += 1;
+                }
+                Some(GlobToken::Char(c)) if *c == input[i] => {
+                    p += 1;
+                    i += 1;
+                }
+                _ => match backtrack {
+                    // Let the last star consume one more character
+                    Some((bp, bi)) => {
+                        backtrack = Some((bp, bi + 1));
+                        p = bp + 1;
+                        i = bi + 1;
+                    }
+                    None => return false,
+                },
+            }
+        }
+
+        self.0[p..].iter().all(|t| *t == GlobToken::Star)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::panic::panic_any;
+    use std::sync::Arc;
+    use std::sync::Mutex;
 
+    use super::Glob;
     use super::ffi_callback;
+    use super::js_string;
+    use super::percent_decode;
+    use super::percent_encode;
+    use super::random_token;
+    use crate::panic::CallbackPanic;
+    use crate::panic::PanicAction;
 
     struct PanicOnDrop;
 
@@ -68,8 +275,67 @@ mod tests {
 
     #[test]
     fn ffi_callback_contains_panics() {
-        assert_eq!(ffi_callback(0, || 1), 1);
-        assert_eq!(ffi_callback(0, || panic!("callback panicked")), 0);
-        assert_eq!(ffi_callback(0, || panic_any(PanicOnDrop)), 0);
+        assert_eq!(ffi_callback("test", 0, || 1), 1);
+        assert_eq!(ffi_callback("test", 0, || panic!("callback panicked")), 0);
+        assert_eq!(ffi_callback("test", 0, || panic_any(PanicOnDrop)), 0);
+
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let r = reported.clone();
+        crate::panic::set_panic_hook(move |p| {
+            r.lock().unwrap().push(p.clone());
+            PanicAction::Continue
+        });
+
+        assert_eq!(ffi_callback("hooked", 0, || panic!("callback panicked")), 0);
+        assert_eq!(ffi_callback("hooked", 0, || panic_any(PanicOnDrop)), 0);
+        crate::panic::clear_panic_hook();
+
+        assert_eq!(*reported.lock().unwrap(), [
+            CallbackPanic {
+                context: "hooked",
+                message: Some("callback panicked".into()),
+            },
+            CallbackPanic {
+                context: "hooked",
+                message: None,
+            },
+        ]);
+    }
+
+    #[test]
+    fn js_string_escapes_specials() {
+        assert_eq!(js_string("plain"), r#""plain""#);
+        assert_eq!(js_string("a\"b\\c"), r#""a\"b\\c""#);
+        assert_eq!(js_string("l1\nl2\u{2028}"), r#""l1\nl2\u2028""#);
+        assert_eq!(js_string("</script>"), r#""\u003c/script>""#);
+    }
+
+    #[test]
+    fn random_tokens_are_distinct() {
+        let a = random_token();
+        assert_eq!(a.len(), 32);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(a, random_token());
+    }
+
+    #[test]
+    fn percent_encode_keeps_unreserved() {
+        assert_eq!(percent_encode("user.name~1"), "user.name~1");
+        assert_eq!(percent_encode("p@ss:w/d é"), "p%40ss%3Aw%2Fd%20%C3%A9");
+        assert_eq!(percent_decode("p%40ss%3Aw%2Fd%20%C3%A9"), "p@ss:w/d é");
+        assert_eq!(percent_decode("100%"), "100%");
+    }
+
+    #[test]
+    fn glob_matches_wildcards() {
+        let glob = Glob::new("https://*.example.com/*");
+        assert!(glob.matches("https://app.example.com/"));
+        assert!(glob.matches("https://a.b.example.com/path?q=1"));
+        assert!(!glob.matches("https://example.com/"));
+        assert!(!glob.matches("http://app.example.com/"));
+
+        assert!(Glob::new("a?c").matches("abc"));
+        assert!(!Glob::new("a?c").matches("ac"));
+        assert!(Glob::new("**").matches(""));
     }
 }
@@ -0,0 +1,87 @@
+//! Weak capturing module.
+//!
+//! Handles stored in listeners and handlers of the same (or a related) handle
+//! form reference cycles, which keep them alive forever. See [`weak_clone`] for
+//! capturing them without strong references.
+
+/// Creates a closure capturing the given handles weakly.
+///
+/// Each listed handle is downgraded (with its `downgrade` method) before being
+/// moved into the closure. When the closure is invoked, the handles are
+/// upgraded again and shadow the weak ones in the body. If any of them has
+/// been dropped, the body is skipped and the closure returns the default value
+/// of its return type (e.g. [`crate::policy::Policy::Allow`]).
+///
+/// This works with [`crate::app::App`], [`crate::window::Window`],
+/// [`crate::webview::Webview`] and other handles providing `downgrade` and
+/// `upgrade` methods.
+///
+/// ```no_run
+/// use saucers::prelude::*;
+/// use saucers::weak_clone;
+///
+/// fn sync_title(webview: &Webview, window: &Window) {
+///     webview.on::<TitleEvent>(weak_clone!(window => move |_, ev| {
+///         window.set_title(ev.title.as_str());
+///     }));
+/// }
+/// ```
+#[macro_export]
+macro_rules! weak_clone {
+    ($($handle:ident),+ $(,)? => $(move)? || $body:expr) => {{
+        $(let $handle = $handle.downgrade();)+
+        move || {
+            $(let Some($handle) = $handle.upgrade() else {
+                return ::core::default::Default::default();
+            };)+
+            $body
+        }
+    }};
+
+    ($($handle:ident),+ $(,)? => $(move)? |$($arg:tt $(: $ty:ty)?),* $(,)?| $body:expr) => {{
+        $(let $handle = $handle.downgrade();)+
+        move |$($arg $(: $ty)?),*| {
+            $(let Some($handle) = $handle.upgrade() else {
+                return ::core::default::Default::default();
+            };)+
+            $body
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::Weak;
+
+    struct Handle(Arc<u32>);
+
+    impl Handle {
+        fn downgrade(&self) -> HandleRef { HandleRef(Arc::downgrade(&self.0)) }
+    }
+
+    struct HandleRef(Weak<u32>);
+
+    impl HandleRef {
+        fn upgrade(&self) -> Option<Handle> { Some(Handle(self.0.upgrade()?)) }
+    }
+
+    #[test]
+    fn weak_clone_skips_dropped_handles() {
+        let a = Handle(Arc::new(1));
+        let b = Handle(Arc::new(2));
+
+        let sum = weak_clone!(a, b => move |x: u32| *a.0 + *b.0 + x);
+        let get = weak_clone!(a => || Some(*a.0));
+
+        assert_eq!(sum(3), 6);
+        assert_eq!(get(), Some(1));
+
+        drop(b);
+        assert_eq!(sum(3), 0);
+        assert_eq!(get(), Some(1));
+
+        drop(a);
+        assert_eq!(get(), None);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::js::JsValue;
+
+/// A node in the accessibility tree taken by
+/// [`crate::webview::Webview::accessibility_snapshot`].
+///
+/// The tree is computed from the DOM following ARIA roles and common implicit
+/// roles of HTML elements. Hidden elements are left out, and elements without
+/// a role (e.g. plain `div`s) are flattened into their parents. Text not
+/// belonging to a named element appears as `text` nodes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AxNode {
+    /// The ARIA role (e.g. `button`), or `document` for the root.
+    pub role: String,
+    /// The accessible name. Empty if there is none.
+    pub name: String,
+    /// The value of form controls (except passwords), if any.
+    pub value: Option<String>,
+    /// The level of headings.
+    pub level: Option<u32>,
+    pub states: Vec<AxState>,
+    pub children: Vec<AxNode>,
+}
+
+/// A state of an [`AxNode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AxState {
+    Disabled,
+    Checked,
+    /// Partially checked.
+    Mixed,
+    Expanded,
+    Collapsed,
+    Selected,
+    Focused,
+    Required,
+    ReadOnly,
+}
+
+impl AxState {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "disabled" => Self::Disabled,
+            "checked" => Self::Checked,
+            "mixed" => Self::Mixed,
+            "expanded" => Self::Expanded,
+            "collapsed" => Self::Collapsed,
+            "selected" => Self::Selected,
+            "focused" => Self::Focused,
+            "required" => Self::Required,
+            "readonly" => Self::ReadOnly,
+            _ => return None,
+        })
+    }
+}
+
+impl AxNode {
+    /// Converts the node reported by the page.
+    pub(crate) fn from_js(value: &JsValue) -> Option<Self> {
+        let text = |key| {
+            value
+                .get(key)
+                .and_then(JsValue::as_str)
+                .map(ToOwned::to_owned)
+        };
+
+        Some(Self {
+            role: text("role")?,
+            name: text("name").unwrap_or_default(),
+            value: text("value"),
+            level: value
+                .get("level")
+                .and_then(JsValue::as_f64)
+                .map(|l| l as u32),
+            states: value
+                .get("states")
+                .and_then(JsValue::as_array)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|s| AxState::from_name(s.as_str()?))
+                .collect(),
+            children: value
+                .get("children")
+                .and_then(JsValue::as_array)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Self::from_js)
+                .collect(),
+        })
+    }
+
+    /// Checks whether the node has the given state.
+    pub fn has_state(&self, state: AxState) -> bool { self.states.contains(&state) }
+
+    /// Iterates over the node and its descendants in document order.
+    pub fn iter(&self) -> impl Iterator<Item = &AxNode> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+
+    /// Finds the first node (including this one) with the given role and name.
+    pub fn find(&self, role: &str, name: &str) -> Option<&AxNode> {
+        self.iter().find(|n| n.role == role && n.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AxNode;
+    use super::AxState;
+    use crate::js::JsValue;
+
+    #[test]
+    fn ax_tree_is_converted() {
+        let js = JsValue::parse(
+            r#"{"role":"document","name":"Home","children":[
+                {"role":"heading","name":"Welcome","value":null,"level":1,"states":[],"children":[]},
+                {"role":"navigation","name":"","value":null,"level":null,"states":[],"children":[
+                    {"role":"button","name":"Save","value":null,"level":null,"states":["disabled","unknown"],"children":[]}
+                ]},
+                {"role":"text","name":"Hello","children":[]}
+            ]}"#,
+        )
+        .unwrap();
+
+        let tree = AxNode::from_js(&js).unwrap();
+        let roles = tree.iter().map(|n| n.role.as_str()).collect::<Vec<_>>();
+        assert_eq!(roles, [
+            "document",
+            "heading",
+            "navigation",
+            "button",
+            "text"
+        ]);
+
+        let save = tree.find("button", "Save").unwrap();
+        assert_eq!(save.states, [AxState::Disabled]);
+        assert_eq!(tree.find("heading", "Welcome").unwrap().level, Some(1));
+        assert!(tree.find("button", "Cancel").is_none());
+    }
+}
@@ -0,0 +1,366 @@
+//! Authentication challenge module.
+//!
+//! See [`crate::webview::AuthChallengeEvent`] for details.
+use crate::url::Url;
+use crate::util::MutexExt;
+use crate::webview::AuthAction;
+use crate::webview::AuthChallengeEvent;
+use crate::webview::Webview;
+
+/// How many times in a row the same challenge is passed to handlers. Later
+/// ones are cancelled, so that rejected credentials don't loop forever.
+#[allow(unused)] // Not used by all backends
+const MAX_AUTH_ATTEMPTS: u32 = 3;
+
+/// A challenge as reported by the backend.
+#[allow(unused)] // Not used by all backends
+struct Challenge {
+    url: String,
+    realm: String,
+    is_proxy: bool,
+}
+
+impl Webview {
+    /// Fires [`AuthChallengeEvent`] for the challenge, returning the action to
+    /// take.
+    #[allow(unused)] // Not used by all backends
+    fn on_auth_challenge(&self, challenge: Challenge) -> AuthAction {
+        let key = format!("{}\n{}", challenge.url, challenge.realm);
+
+        let attempts = {
+            let mut last = self.0.auth_attempts.lock_unpoisoned();
+            match &mut *last {
+                Some((k, n)) if *k == key => {
+                    *n += 1;
+                    *n
+                }
+                _ => {
+                    *last = Some((key, 1));
+                    1
+                }
+            }
+        };
+
+        if attempts > MAX_AUTH_ATTEMPTS {
+            return AuthAction::Cancel;
+        }
+
+        let Ok(url) = Url::new_parse(challenge.url.as_str()) else {
+            return AuthAction::Default;
+        };
+
+        self.event_listener_data()
+            .emit(self, || AuthChallengeEvent {
+                url,
+                realm: challenge.realm,
+                is_proxy: challenge.is_proxy,
+                is_retry: attempts > 1,
+            })
+    }
+
+    /// Forgets answered challenges once a page has loaded.
+    pub(crate) fn reset_auth_attempts(&self) { *self.0.auth_attempts.lock_unpoisoned() = None; }
+}
+
+/// Extracts the realm from a `WWW-Authenticate` challenge, e.g. `example`
+/// from `Basic realm="example"`.
+#[allow(unused)] // Not used by all backends
+fn challenge_realm(challenge: &str) -> String {
+    let lower = challenge.to_ascii_lowercase();
+    let Some(start) = lower.find("realm=").map(|i| i + "realm=".len()) else {
+        return String::new();
+    };
+
+    let rest = &challenge[start..];
+    match rest.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next().unwrap_or_default().to_owned(),
+        None => rest.split([',', ' ']).next().unwrap_or_default().to_owned(),
+    }
+}
+
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+pub(crate) fn install(webview: &Webview) -> crate::error::Result<()> {
+    use crate::webview::webview2;
+    use crate::webview::webview2::S_OK;
+
+    // ICoreWebView2BasicAuthenticationRequestedEventArgs
+    const GET_URI: usize = 3;
+    const GET_CHALLENGE: usize = 4;
+    const GET_RESPONSE: usize = 5;
+    const PUT_CANCEL: usize = 7;
+    // ICoreWebView2BasicAuthenticationResponse
+    const PUT_USER_NAME: usize = 4;
+    const PUT_PASSWORD: usize = 6;
+
+    let Some(core) = webview2::core_webview(webview)?.cast(&webview2::IID_ICOREWEBVIEW2_10) else {
+        return Err(crate::error::Error::unsupported(
+            "authentication challenges",
+            None,
+        ));
+    };
+
+    let weak = webview.downgrade();
+
+    // SAFETY: The slot is `add_BasicAuthenticationRequested`, whose arguments
+    // are borrowed for the call
+    unsafe {
+        webview2::add_event_handler(
+            &core,
+            webview2::slot::ADD_BASIC_AUTHENTICATION_REQUESTED,
+            "webview basic authentication event",
+            move |_, args| {
+                let (Some(w), Some(args)) = (weak.upgrade(), webview2::ComPtr::from_borrowed(args))
+                else {
+                    return S_OK;
+                };
+
+                let challenge = Challenge {
+                    url: args.get_string(GET_URI).unwrap_or_default(),
+                    realm: challenge_realm(&args.get_string(GET_CHALLENGE).unwrap_or_default()),
+                    is_proxy: false, // Not told apart
+                };
+
+                match w.on_auth_challenge(challenge) {
+                    AuthAction::Default => {}
+                    AuthAction::Cancel => {
+                        args.put(PUT_CANCEL, 1i32);
+                    }
+                    AuthAction::Credentials { user, password } => {
+                        if let Some(response) = args.get(GET_RESPONSE) {
+                            response.put_string(PUT_USER_NAME, &user);
+                            response.put_string(PUT_PASSWORD, &password);
+                        }
+                    }
+                }
+
+                S_OK
+            },
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(all(target_os = "macos", not(feature = "qt")))]
+pub(crate) fn install(webview: &Webview) -> crate::error::Result<()> {
+    use std::ffi::c_void;
+    use std::panic::AssertUnwindSafe;
+
+    use objc2::class;
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+    use objc2::runtime::Sel;
+    use objc2::sel;
+
+    use crate::webview::wkwebview;
+    use crate::webview::wkwebview::Block;
+
+    // NSURLSessionAuthChallengeDisposition
+    const USE_CREDENTIAL: isize = 0;
+    const PERFORM_DEFAULT_HANDLING: isize = 1;
+    const CANCEL: isize = 2;
+    // NSURLCredentialPersistenceForSession
+    const PERSISTENCE_FOR_SESSION: usize = 1;
+
+    type CompletionHandler = unsafe extern "C" fn(*mut Block<c_void>, isize, *mut AnyObject);
+
+    extern "C" fn did_receive_challenge(
+        _: *mut AnyObject,
+        _: Sel,
+        view: *mut AnyObject,
+        challenge: *mut AnyObject,
+        handler: *mut Block<CompletionHandler>,
+    ) {
+        let answer = AssertUnwindSafe(|| -> Option<(isize, Option<Retained<AnyObject>>)> {
+            let w = wkwebview::find(view)?;
+
+            // SAFETY: On the main thread, and the challenge is borrowed for
+            // the call
+            unsafe {
+                let space: *mut AnyObject = msg_send![challenge, protectionSpace];
+                let method: *mut AnyObject = msg_send![space, authenticationMethod];
+
+                // Others are about TLS and left to the engine
+                let is_http = matches!(
+                    wkwebview::string(method).as_deref(),
+                    Some(
+                        "NSURLAuthenticationMethodDefault"
+                            | "NSURLAuthenticationMethodHTTPBasic"
+                            | "NSURLAuthenticationMethodHTTPDigest"
+                            | "NSURLAuthenticationMethodNTLM"
+                    )
+                );
+
+                if !is_http {
+                    return None;
+                }
+
+                let protocol: *mut AnyObject = msg_send![space, protocol];
+                let host: *mut AnyObject = msg_send![space, host];
+                let realm: *mut AnyObject = msg_send![space, realm];
+                let port: isize = msg_send![space, port];
+                let is_proxy: bool = msg_send![space, isProxy];
+
+                let protocol = wkwebview::string(protocol).unwrap_or_else(|| "https".into());
+                let host = wkwebview::string(host).unwrap_or_default();
+
+                let challenge = Challenge {
+                    url: if port > 0 {
+                        format!("{protocol}://{host}:{port}")
+                    } else {
+                        format!("{protocol}://{host}")
+                    },
+                    realm: wkwebview::string(realm).unwrap_or_default(),
+                    is_proxy,
+                };
+
+                match w.on_auth_challenge(challenge) {
+                    AuthAction::Default => None,
+                    AuthAction::Cancel => Some((CANCEL, None)),
+                    AuthAction::Credentials { user, password } => {
+                        let (user, password) = (
+                            wkwebview::ns_string(&user)?,
+                            wkwebview::ns_string(&password)?,
+                        );
+                        let credential: Option<Retained<AnyObject>> = msg_send![
+                            class!(NSURLCredential),
+                            credentialWithUser: &*user,
+                            password: &*password,
+                            persistence: PERSISTENCE_FOR_SESSION
+                        ];
+                        Some((USE_CREDENTIAL, Some(credential?)))
+                    }
+                }
+            }
+        });
+
+        let answer = crate::util::ffi_callback("webview authentication challenge", None, answer);
+        let (disposition, credential) = answer.unwrap_or((PERFORM_DEFAULT_HANDLING, None));
+
+        let credential = credential.as_deref().map_or(std::ptr::null_mut(), |c| {
+            c as *const AnyObject as *mut AnyObject
+        });
+
+        // SAFETY: The handler must be called exactly once, which is here
+        unsafe { ((*handler).invoke)(handler.cast(), disposition, credential) };
+    }
+
+    wkwebview::add_delegate_method(
+        webview,
+        sel!(webView:didReceiveAuthenticationChallenge:completionHandler:),
+        did_receive_challenge as *const c_void,
+        c"v@:@@@?",
+    )
+}
+
+#[cfg(all(
+    not(any(target_os = "windows", target_os = "macos")),
+    not(feature = "qt")
+))]
+pub(crate) fn install(webview: &Webview) -> crate::error::Result<()> {
+    use std::ffi::CString;
+    use std::ffi::c_char;
+    use std::ffi::c_void;
+
+    use crate::webview::webkitgtk;
+
+    // WEBKIT_CREDENTIAL_PERSISTENCE_FOR_SESSION
+    const PERSISTENCE_FOR_SESSION: i32 = 1;
+
+    unsafe extern "C" {
+        fn webkit_authentication_request_get_realm(request: *mut c_void) -> *const c_char;
+        fn webkit_authentication_request_is_for_proxy(request: *mut c_void) -> i32;
+        fn webkit_authentication_request_get_security_origin(request: *mut c_void) -> *mut c_void;
+        fn webkit_security_origin_to_string(origin: *mut c_void) -> *mut c_char;
+        fn webkit_security_origin_unref(origin: *mut c_void);
+        fn webkit_authentication_request_authenticate(
+            request: *mut c_void,
+            credential: *mut c_void,
+        );
+        fn webkit_authentication_request_cancel(request: *mut c_void);
+        fn webkit_credential_new(
+            user: *const c_char,
+            password: *const c_char,
+            persistence: i32,
+        ) -> *mut c_void;
+        fn webkit_credential_free(credential: *mut c_void);
+        fn g_free(ptr: *mut c_void);
+    }
+
+    extern "C" fn on_authenticate(_: *mut c_void, request: *mut c_void, data: *mut c_void) -> i32 {
+        crate::util::ffi_callback("webview authenticate signal", 0, || {
+            // SAFETY: On the event thread, and the request is borrowed for the
+            // signal
+            unsafe {
+                let Some(w) = webkitgtk::webview(data) else {
+                    return 0;
+                };
+
+                let origin = webkit_authentication_request_get_security_origin(request);
+                let url = webkit_security_origin_to_string(origin);
+                let challenge = Challenge {
+                    url: webkitgtk::string(url).unwrap_or_default(),
+                    realm: webkitgtk::string(webkit_authentication_request_get_realm(request))
+                        .unwrap_or_default(),
+                    is_proxy: webkit_authentication_request_is_for_proxy(request) != 0,
+                };
+
+                g_free(url.cast());
+                webkit_security_origin_unref(origin);
+
+                match w.on_auth_challenge(challenge) {
+                    AuthAction::Default => 0,
+                    AuthAction::Cancel => {
+                        webkit_authentication_request_cancel(request);
+                        1
+                    }
+                    AuthAction::Credentials { user, password } => {
+                        let (Ok(user), Ok(password)) = (CString::new(user), CString::new(password))
+                        else {
+                            webkit_authentication_request_cancel(request);
+                            return 1;
+                        };
+
+                        let credential = webkit_credential_new(
+                            user.as_ptr(),
+                            password.as_ptr(),
+                            PERSISTENCE_FOR_SESSION,
+                        );
+                        webkit_authentication_request_authenticate(request, credential);
+                        webkit_credential_free(credential);
+                        1
+                    }
+                }
+            }
+        })
+    }
+
+    webkitgtk::connect(webview, c"authenticate", on_authenticate as *const c_void)
+}
+
+#[cfg(feature = "qt")]
+pub(crate) fn install(_: &Webview) -> crate::error::Result<()> {
+    Err(crate::error::Error::unsupported(
+        "authentication challenges",
+        None,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn challenge_realm_is_extracted() {
+        assert_eq!(
+            challenge_realm(r#"Basic realm="Intranet, EU""#),
+            "Intranet, EU"
+        );
+        assert_eq!(
+            challenge_realm("Basic REALM=intranet, charset=UTF-8"),
+            "intranet"
+        );
+        assert_eq!(challenge_realm("Negotiate"), "");
+    }
+}
@@ -0,0 +1,66 @@
+//! Window event bridge module.
+//!
+//! See [`WindowEventBridge`] for details.
+use std::ops::BitOr;
+use std::ops::Sub;
+
+/// A set of window events mirrored into the page as DOM
+/// [`CustomEvent`](https://developer.mozilla.org/docs/Web/API/CustomEvent)s.
+/// See [`crate::webview::Webview::set_window_event_bridge`].
+///
+/// Events are dispatched on `window`, with their states in `detail`:
+///
+/// | Flag                   | Event name                   | Detail              |
+/// |------------------------|------------------------------|---------------------|
+/// | [`Self::FOCUS`]        | `saucer:window-focus`        | `{ focused }`       |
+/// | [`Self::RESIZE`]       | `saucer:window-resize`       | `{ width, height }` |
+/// | [`Self::MAXIMIZE`]     | `saucer:window-maximize`     | `{ maximized }`     |
+/// | [`Self::MINIMIZE`]     | `saucer:window-minimize`     | `{ minimized }`     |
+/// | [`Self::COLOR_SCHEME`] | `saucer:window-color-scheme` | `{ dark }`          |
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct WindowEventBridge(u32);
+
+impl WindowEventBridge {
+    /// The system color scheme changes. This follows
+    /// `prefers-color-scheme` of the page, thus it also reflects
+    /// [`crate::webview::Webview::set_force_dark`] where supported.
+    pub const COLOR_SCHEME: Self = Self(1 << 4);
+    /// The window is focused or blurred.
+    pub const FOCUS: Self = Self(1 << 0);
+    /// The window maximization changes.
+    pub const MAXIMIZE: Self = Self(1 << 2);
+    /// The window minimization changes.
+    pub const MINIMIZE: Self = Self(1 << 3);
+    /// The window size changes.
+    pub const RESIZE: Self = Self(1 << 1);
+
+    /// Gets an empty set.
+    pub const fn empty() -> Self { Self(0) }
+
+    /// Gets a set containing all events.
+    pub const fn all() -> Self { Self((1 << 5) - 1) }
+
+    /// Gets the raw bits.
+    pub const fn bits(&self) -> u32 { self.0 }
+
+    /// Creates a set from raw bits, ignoring unknown ones.
+    pub const fn from_bits_truncate(bits: u32) -> Self { Self(bits & Self::all().0) }
+
+    /// Checks whether the set is empty.
+    pub const fn is_empty(&self) -> bool { self.0 == 0 }
+
+    /// Checks whether all events in `other` are contained in this set.
+    pub const fn contains(&self, other: Self) -> bool { self.0 & other.0 == other.0 }
+}
+
+impl BitOr for WindowEventBridge {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self { Self(self.0 | rhs.0) }
+}
+
+impl Sub for WindowEventBridge {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self { Self(self.0 & !rhs.0) }
+}
@@ -0,0 +1,274 @@
+//! Certificate error module.
+//!
+//! See [`crate::webview::CertificateErrorEvent`] for details.
+use crate::url::Url;
+use crate::webview::CertificateAction;
+use crate::webview::CertificateError;
+use crate::webview::CertificateErrorEvent;
+use crate::webview::Webview;
+
+impl Webview {
+    /// Fires [`CertificateErrorEvent`] for the failing load, returning the
+    /// action to take.
+    #[allow(unused)] // Not used by all backends
+    fn on_certificate_error(
+        &self,
+        url: &str,
+        error: CertificateError,
+        certificate: impl FnOnce() -> Vec<u8>,
+    ) -> CertificateAction {
+        let Ok(url) = Url::new_parse(url) else {
+            return CertificateAction::Default;
+        };
+
+        self.event_listener_data()
+            .emit(self, || CertificateErrorEvent {
+                host: url.host(),
+                url,
+                error,
+                certificate: certificate(),
+            })
+    }
+}
+
+/// Decodes the first certificate of a PEM document into DER.
+#[allow(unused)] // Not used by all backends
+fn pem_to_der(pem: &str) -> Option<Vec<u8>> {
+    let body = pem
+        .lines()
+        .map(str::trim)
+        .skip_while(|l| !l.starts_with("-----BEGIN"))
+        .skip(1)
+        .take_while(|l| !l.starts_with("-----END"))
+        .flat_map(str::bytes);
+
+    let mut der = Vec::new();
+    let (mut acc, mut bits) = (0u32, 0);
+
+    for c in body {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            _ => return None,
+        };
+
+        acc = (acc << 6) | v as u32;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            der.push((acc >> bits) as u8);
+        }
+    }
+
+    (!der.is_empty()).then_some(der)
+}
+
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+pub(crate) fn install(webview: &Webview) -> crate::error::Result<()> {
+    use crate::webview::webview2;
+    use crate::webview::webview2::S_OK;
+
+    // ICoreWebView2ServerCertificateErrorDetectedEventArgs
+    const GET_ERROR_STATUS: usize = 3;
+    const GET_REQUEST_URI: usize = 4;
+    const GET_SERVER_CERTIFICATE: usize = 5;
+    const PUT_ACTION: usize = 7;
+    // ICoreWebView2Certificate
+    const TO_PEM_ENCODING: usize = 9;
+    // COREWEBVIEW2_SERVER_CERTIFICATE_ERROR_ACTION
+    const ALWAYS_ALLOW: i32 = 0;
+    const CANCEL: i32 = 1;
+
+    let Some(core) = webview2::core_webview(webview)?.cast(&webview2::IID_ICOREWEBVIEW2_14) else {
+        return Err(crate::error::Error::unsupported("certificate errors", None));
+    };
+
+    let weak = webview.downgrade();
+
+    // SAFETY: The slot is `add_ServerCertificateErrorDetected`, whose
+    // arguments are borrowed for the call
+    unsafe {
+        webview2::add_event_handler(
+            &core,
+            webview2::slot::ADD_SERVER_CERTIFICATE_ERROR_DETECTED,
+            "webview certificate error event",
+            move |_, args| {
+                let (Some(w), Some(args)) = (weak.upgrade(), webview2::ComPtr::from_borrowed(args))
+                else {
+                    return S_OK;
+                };
+
+                // COREWEBVIEW2_WEB_ERROR_STATUS
+                let error = match args.get_value::<i32>(GET_ERROR_STATUS) {
+                    Some(1) => CertificateError::HostMismatch,
+                    Some(2) => CertificateError::Expired,
+                    Some(4) => CertificateError::Revoked,
+                    Some(5) => CertificateError::Invalid,
+                    _ => CertificateError::Other,
+                };
+
+                let url = args.get_string(GET_REQUEST_URI).unwrap_or_default();
+                let certificate = || {
+                    args.get(GET_SERVER_CERTIFICATE)
+                        .and_then(|c| c.get_string(TO_PEM_ENCODING))
+                        .and_then(|pem| pem_to_der(&pem))
+                        .unwrap_or_default()
+                };
+
+                match w.on_certificate_error(&url, error, certificate) {
+                    CertificateAction::Default => {}
+                    CertificateAction::Proceed => {
+                        args.put(PUT_ACTION, ALWAYS_ALLOW);
+                    }
+                    CertificateAction::Block => {
+                        args.put(PUT_ACTION, CANCEL);
+                    }
+                }
+
+                S_OK
+            },
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(all(
+    not(any(target_os = "windows", target_os = "macos")),
+    not(feature = "qt")
+))]
+pub(crate) fn install(webview: &Webview) -> crate::error::Result<()> {
+    use std::ffi::CString;
+    use std::ffi::c_char;
+    use std::ffi::c_void;
+
+    use crate::webview::webkitgtk;
+
+    // GTlsCertificateFlags
+    const UNKNOWN_CA: u32 = 1 << 0;
+    const BAD_IDENTITY: u32 = 1 << 1;
+    const NOT_ACTIVATED: u32 = 1 << 2;
+    const EXPIRED: u32 = 1 << 3;
+    const REVOKED: u32 = 1 << 4;
+
+    #[repr(C)]
+    struct GByteArray {
+        data: *const u8,
+        len: u32,
+    }
+
+    unsafe extern "C" {
+        fn g_object_get(object: *mut c_void, first_property: *const c_char, ...);
+        fn g_byte_array_unref(array: *mut GByteArray);
+        fn webkit_web_view_get_network_session(view: *mut c_void) -> *mut c_void;
+        fn webkit_network_session_allow_tls_certificate_for_host(
+            session: *mut c_void,
+            certificate: *mut c_void,
+            host: *const c_char,
+        );
+        fn webkit_web_view_load_uri(view: *mut c_void, uri: *const c_char);
+    }
+
+    extern "C" fn on_tls_errors(
+        view: *mut c_void,
+        uri: *const c_char,
+        certificate: *mut c_void,
+        errors: u32,
+        data: *mut c_void,
+    ) -> i32 {
+        crate::util::ffi_callback("webview load failed with TLS errors signal", 0, || {
+            // SAFETY: On the event thread, and the arguments are borrowed for
+            // the signal
+            unsafe {
+                let Some(w) = webkitgtk::webview(data) else {
+                    return 0;
+                };
+
+                let error = if errors & BAD_IDENTITY != 0 {
+                    CertificateError::HostMismatch
+                } else if errors & (EXPIRED | NOT_ACTIVATED) != 0 {
+                    CertificateError::Expired
+                } else if errors & REVOKED != 0 {
+                    CertificateError::Revoked
+                } else if errors & UNKNOWN_CA != 0 {
+                    CertificateError::UntrustedIssuer
+                } else {
+                    CertificateError::Other
+                };
+
+                let url = webkitgtk::string(uri).unwrap_or_default();
+                let der = || {
+                    let mut array = std::ptr::null_mut::<GByteArray>();
+                    g_object_get(
+                        certificate,
+                        c"certificate".as_ptr(),
+                        &raw mut array,
+                        std::ptr::null::<c_char>(),
+                    );
+
+                    if array.is_null() {
+                        return Vec::new();
+                    }
+
+                    let der = std::slice::from_raw_parts((*array).data, (*array).len as usize);
+                    let der = der.to_vec();
+                    g_byte_array_unref(array);
+                    der
+                };
+
+                match w.on_certificate_error(&url, error, der) {
+                    // The engine shows its error page
+                    CertificateAction::Default => 0,
+                    CertificateAction::Block => 1,
+                    CertificateAction::Proceed => {
+                        let Ok(host) = Url::new_parse(url.as_str()).map(|u| u.host()) else {
+                            return 0;
+                        };
+                        let Ok(host) = CString::new(host) else {
+                            return 0;
+                        };
+
+                        webkit_network_session_allow_tls_certificate_for_host(
+                            webkit_web_view_get_network_session(view),
+                            certificate,
+                            host.as_ptr(),
+                        );
+                        webkit_web_view_load_uri(view, uri);
+                        1
+                    }
+                }
+            }
+        })
+    }
+
+    webkitgtk::connect(
+        webview,
+        c"load-failed-with-tls-errors",
+        on_tls_errors as *const c_void,
+    )
+}
+
+#[cfg(any(target_os = "macos", feature = "qt"))]
+pub(crate) fn install(_: &Webview) -> crate::error::Result<()> {
+    Err(crate::error::Error::unsupported("certificate errors", None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pem_is_decoded_into_der() {
+        let pem = "-----BEGIN CERTIFICATE-----\r\nMIIB\r\nAAE=\r\n-----END \
+                   CERTIFICATE-----\r\n-----BEGIN CERTIFICATE-----\r\nAAAA\r\n-----END \
+                   CERTIFICATE-----\r\n";
+        assert_eq!(pem_to_der(pem), Some(vec![0x30, 0x82, 0x01, 0x00, 0x01]));
+        assert_eq!(pem_to_der("-----BEGIN CERTIFICATE-----\n*\n"), None);
+        assert_eq!(pem_to_der(""), None);
+    }
+}
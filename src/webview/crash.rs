@@ -0,0 +1,148 @@
+//! Renderer crash module.
+//!
+//! See [`crate::webview::RenderProcessTerminatedEvent`] for details.
+use crate::util::MutexExt;
+use crate::webview::RenderProcessTerminatedEvent;
+use crate::webview::TerminationReason;
+use crate::webview::Webview;
+
+impl Webview {
+    /// Fires [`RenderProcessTerminatedEvent`], which only happens once until
+    /// the page loads again or gets recovered.
+    pub(crate) fn on_render_process_terminated(&self, reason: TerminationReason) {
+        // The watchdog would report the page again otherwise
+        self.0.watchdog.lock_unpoisoned().on_terminated();
+
+        self.event_listener_data()
+            .emit(self, || RenderProcessTerminatedEvent { reason });
+    }
+}
+
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+pub(crate) fn install(webview: &Webview) -> crate::error::Result<()> {
+    use crate::webview::webview2;
+    use crate::webview::webview2::S_OK;
+
+    // ICoreWebView2ProcessFailedEventArgs
+    const GET_PROCESS_FAILED_KIND: usize = 3;
+    // ICoreWebView2ProcessFailedEventArgs2
+    const GET_REASON: usize = 4;
+    // COREWEBVIEW2_PROCESS_FAILED_KIND
+    const RENDER_PROCESS_EXITED: i32 = 1;
+    const RENDER_PROCESS_UNRESPONSIVE: i32 = 2;
+    // COREWEBVIEW2_PROCESS_FAILED_REASON
+    const OUT_OF_MEMORY: i32 = 5;
+
+    let core = webview2::core_webview(webview)?;
+    let weak = webview.downgrade();
+
+    // SAFETY: The slot is `add_ProcessFailed`, whose arguments are borrowed
+    // for the call
+    unsafe {
+        webview2::add_event_handler(
+            &core,
+            webview2::slot::ADD_PROCESS_FAILED,
+            "webview process failed event",
+            move |_, args| {
+                let (Some(w), Some(args)) = (weak.upgrade(), webview2::ComPtr::from_borrowed(args))
+                else {
+                    return S_OK;
+                };
+
+                // Failures of other processes (e.g. the GPU process) are
+                // recovered by the engine
+                let reason = match args.get_value::<i32>(GET_PROCESS_FAILED_KIND) {
+                    Some(RENDER_PROCESS_EXITED) => {
+                        let out_of_memory = args
+                            .cast(&webview2::IID_ICOREWEBVIEW2_PROCESS_FAILED_EVENT_ARGS2)
+                            .and_then(|a| a.get_value::<i32>(GET_REASON))
+                            == Some(OUT_OF_MEMORY);
+
+                        if out_of_memory {
+                            TerminationReason::OutOfMemory
+                        } else {
+                            TerminationReason::Crashed
+                        }
+                    }
+                    Some(RENDER_PROCESS_UNRESPONSIVE) => TerminationReason::Unresponsive,
+                    _ => return S_OK,
+                };
+
+                w.on_render_process_terminated(reason);
+                S_OK
+            },
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(all(target_os = "macos", not(feature = "qt")))]
+pub(crate) fn install(webview: &Webview) -> crate::error::Result<()> {
+    use std::ffi::c_void;
+
+    use objc2::runtime::AnyObject;
+    use objc2::runtime::Sel;
+    use objc2::sel;
+
+    use crate::webview::wkwebview;
+
+    extern "C" fn did_terminate(_: *mut AnyObject, _: Sel, view: *mut AnyObject) {
+        crate::util::ffi_callback("webview web content process terminated", (), || {
+            // WebKit doesn't tell why the process is gone
+            if let Some(w) = wkwebview::find(view) {
+                w.on_render_process_terminated(TerminationReason::Crashed);
+            }
+        })
+    }
+
+    wkwebview::add_delegate_method(
+        webview,
+        sel!(webViewWebContentProcessDidTerminate:),
+        did_terminate as *const c_void,
+        c"v@:@",
+    )
+}
+
+#[cfg(all(
+    not(any(target_os = "windows", target_os = "macos")),
+    not(feature = "qt")
+))]
+pub(crate) fn install(webview: &Webview) -> crate::error::Result<()> {
+    use std::ffi::c_void;
+
+    use crate::webview::webkitgtk;
+
+    // WebKitWebProcessTerminationReason
+    const EXCEEDED_MEMORY_LIMIT: u32 = 1;
+
+    extern "C" fn on_terminated(_: *mut c_void, reason: u32, data: *mut c_void) {
+        crate::util::ffi_callback("webview web process terminated signal", (), || {
+            // SAFETY: The data is passed by the signal
+            let Some(w) = (unsafe { webkitgtk::webview(data) }) else {
+                return;
+            };
+
+            let reason = match reason {
+                EXCEEDED_MEMORY_LIMIT => TerminationReason::OutOfMemory,
+                _ => TerminationReason::Crashed,
+            };
+
+            w.on_render_process_terminated(reason);
+        })
+    }
+
+    webkitgtk::connect(
+        webview,
+        c"web-process-terminated",
+        on_terminated as *const c_void,
+    )
+}
+
+#[cfg(feature = "qt")]
+pub(crate) fn install(_: &Webview) -> crate::error::Result<()> {
+    Err(crate::error::Error::unsupported(
+        "renderer crash reports",
+        Some("detect hangs with Webview::set_render_watchdog"),
+    ))
+}
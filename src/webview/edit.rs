@@ -0,0 +1,25 @@
+/// An editing command run in the focused web content, see
+/// [`crate::webview::Webview::exec_command`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EditCommand {
+    Cut,
+    Copy,
+    Paste,
+    SelectAll,
+    Undo,
+    Redo,
+}
+
+impl EditCommand {
+    /// Gets the name of the command as known by `document.execCommand`.
+    pub(crate) fn js_name(self) -> &'static str {
+        match self {
+            Self::Cut => "cut",
+            Self::Copy => "copy",
+            Self::Paste => "paste",
+            Self::SelectAll => "selectAll",
+            Self::Undo => "undo",
+            Self::Redo => "redo",
+        }
+    }
+}
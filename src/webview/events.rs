@@ -1,7 +1,9 @@
 use std::borrow::Cow;
 use std::panic::RefUnwindSafe;
+use std::time::Duration;
 
 use crate::icon::Icon;
+use crate::js::JsValue;
 use crate::navigation::Navigation;
 use crate::permission::PermissionRequest;
 use crate::policy::Policy;
@@ -11,6 +13,8 @@ use crate::scheme::SchemeError;
 use crate::state::LoadState;
 use crate::status::HandleStatus;
 use crate::url::Url;
+use crate::webview::InputGesture;
+use crate::webview::LoadFailure;
 use crate::webview::Webview;
 
 /// A trait containing webview events.
@@ -26,7 +30,13 @@ pub trait WebviewEventListener: RefUnwindSafe {
         HandleStatus::Unhandled
     }
 
-    /// Fired when the webview enters or leaves fullscreen.
+    /// Fired when the page enters or leaves element fullscreen (e.g. via
+    /// `Element.requestFullscreen`).
+    ///
+    /// Returning [`Policy::Allow`] lets the window follow the page into (or out
+    /// of) fullscreen. Returning [`Policy::Block`] keeps the window as is, but
+    /// the page still considers the element fullscreen until it exits, which
+    /// can be done with [`Webview::exit_content_fullscreen`].
     fn on_fullscreen(&self, webview: Webview, is_fullscreen: bool) -> Policy { Policy::Allow }
 
     /// Fired when the DOM is ready.
@@ -71,3 +81,381 @@ pub trait WebviewSchemeHandler: RefUnwindSafe {
         exc.reject(SchemeError::NotFound)
     }
 }
+
+/// Fired when the webview requests a permission. See
+/// [`WebviewEventListener::on_permission`].
+pub struct PermissionEvent {
+    pub request: PermissionRequest,
+}
+
+/// Fired when the webview enters or leaves fullscreen. See
+/// [`WebviewEventListener::on_fullscreen`].
+pub struct FullscreenEvent {
+    pub is_fullscreen: bool,
+}
+
+/// Fired when the DOM is ready. See [`WebviewEventListener::on_dom_ready`].
+pub struct DomReadyEvent;
+
+/// Fired when the webview has changed its href. See
+/// [`WebviewEventListener::on_navigated`].
+pub struct NavigatedEvent {
+    pub url: Url,
+}
+
+/// Fired when the webview is about to navigate to a new URL. See
+/// [`WebviewEventListener::on_navigate`].
+///
+/// Unlike [`Navigation`], this event is a snapshot of the navigation and can be
+/// used outside of the handler.
+pub struct NavigateEvent {
+    pub url: Url,
+    pub is_new_window: bool,
+    pub is_redirection: bool,
+    pub is_user_initiated: bool,
+    /// The user gesture that likely triggered the navigation. See
+    /// [`InputGesture`].
+    pub gesture: Option<InputGesture>,
+}
+
+impl NavigateEvent {
+    pub(crate) fn new(nav: &Navigation, gesture: Option<InputGesture>) -> Self {
+        Self {
+            url: nav.url(),
+            is_new_window: nav.is_new_window(),
+            is_redirection: nav.is_redirection(),
+            is_user_initiated: nav.is_user_initiated(),
+            gesture,
+        }
+    }
+}
+
+/// Fired when the webview sends a message. See
+/// [`WebviewEventListener::on_message`].
+pub struct MessageEvent {
+    pub message: String,
+}
+
+impl MessageEvent {
+    /// Parses the message as JSON.
+    pub fn json(&self) -> crate::error::Result<JsValue> { JsValue::parse(&self.message) }
+}
+
+/// Fired when the webview starts a network request. See
+/// [`WebviewEventListener::on_request`].
+pub struct RequestEvent {
+    pub url: Url,
+}
+
+/// Fired when the webview loads a favicon. See
+/// [`WebviewEventListener::on_favicon`].
+pub struct FaviconEvent {
+    pub icon: Icon,
+}
+
+/// Fired when the webview title changes. See
+/// [`WebviewEventListener::on_title`].
+pub struct TitleEvent {
+    pub title: String,
+}
+
+/// Fired when the webview page is loaded. See
+/// [`WebviewEventListener::on_load`].
+pub struct LoadEvent {
+    pub state: LoadState,
+}
+
+/// Fired when a failed load is about to be retried, or when the retry policy is
+/// exhausted. See [`crate::webview::Retry`].
+pub struct RetryEvent {
+    pub failure: LoadFailure,
+    /// The attempt number, starting from 1.
+    pub attempt: u32,
+    /// The delay before the attempt, or [`None`] if no more attempts will be
+    /// made.
+    pub delay: Option<Duration>,
+}
+
+/// Fired when the page starts or stops playing media. See
+/// [`crate::webview::Webview::is_audio_playing`].
+///
+/// The state is reported by the page, which tracks `<audio>` and `<video>`
+/// elements. Sounds played via Web Audio are not tracked.
+pub struct AudioStateEvent {
+    pub is_playing: bool,
+}
+
+/// Fired when the user interacts with the page in ways useful for session
+/// analytics or suspending idle pages. See [`UserActivity`].
+///
+/// Activity is reported by the page, thus it restarts when a new page is
+/// loaded.
+pub struct UserActivityEvent {
+    pub activity: UserActivity,
+}
+
+/// The kind of a [`UserActivityEvent`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UserActivity {
+    /// The user has interacted with the page (pointer, keyboard, wheel or
+    /// touch) for the first time since it's loaded.
+    FirstInput,
+    /// The page has become visible.
+    Visible,
+    /// The page has become hidden (e.g. the window is minimized).
+    Hidden,
+    /// The user hasn't interacted with the page for the duration set with
+    /// [`crate::webview::Webview::set_user_idle_timeout`].
+    Idle,
+    /// The user has interacted with the page after it became idle.
+    Active,
+}
+
+impl UserActivity {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "first-input" => Self::FirstInput,
+            "visible" => Self::Visible,
+            "hidden" => Self::Hidden,
+            "idle" => Self::Idle,
+            "active" => Self::Active,
+            _ => return None,
+        })
+    }
+}
+
+/// Fired when the page logs a message via `console`, or when an uncaught error
+/// occurs in the page.
+///
+/// Messages are reported by the page, which forwards `console.debug`, `log`,
+/// `info`, `warn` and `error` calls. Arguments are converted to text, with
+/// objects serialized as JSON where possible. The source and line are taken
+/// from the call stack on a best-effort basis.
+pub struct ConsoleMessageEvent {
+    pub level: ConsoleLevel,
+    pub text: String,
+    /// The URL of the script logging the message, if known.
+    pub source: Option<String>,
+    /// The line number in the source, if known.
+    pub line: Option<u32>,
+}
+
+impl ConsoleMessageEvent {
+    /// Parses the message reported by the page.
+    pub(crate) fn parse(msg: &str) -> Option<Self> {
+        let value = JsValue::parse(msg).ok()?;
+        let [level, text, source, line] = value.as_array()? else {
+            return None;
+        };
+
+        Some(Self {
+            level: ConsoleLevel::from_name(level.as_str()?)?,
+            text: text.as_str()?.to_owned(),
+            source: source.as_str().map(ToOwned::to_owned),
+            line: line.as_f64().map(|l| l as u32),
+        })
+    }
+}
+
+/// The level of a [`ConsoleMessageEvent`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConsoleLevel {
+    Debug,
+    Log,
+    Info,
+    Warn,
+    Error,
+}
+
+impl ConsoleLevel {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "debug" => Self::Debug,
+            "log" => Self::Log,
+            "info" => Self::Info,
+            "warn" => Self::Warn,
+            "error" => Self::Error,
+            _ => return None,
+        })
+    }
+}
+
+/// Fired when scripts queued with
+/// [`crate::webview::Webview::execute_on_ready`] are dropped, because another
+/// navigation started before the page they were queued for was ready.
+pub struct ScriptsDroppedEvent {
+    /// The dropped scripts, in the order they were queued.
+    pub scripts: Vec<String>,
+}
+
+/// Fired periodically with statistics sampled from the page. See
+/// [`crate::webview::Webview::set_page_stats_sampling`].
+///
+/// Comparing samples over time helps detecting pages that leak memory in
+/// long-running sessions, which handlers can then reload (e.g. with
+/// [`crate::webview::Webview::recover`]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PageStatsEvent {
+    /// The size of the JS heap in use, in bytes.
+    ///
+    /// Only Chromium-based engines (WebView2 and Qt) report heap sizes, thus
+    /// this is [`None`] elsewhere.
+    pub js_heap_used: Option<u64>,
+    /// The size of the JS heap allocated, in bytes. See [`Self::js_heap_used`].
+    pub js_heap_total: Option<u64>,
+    /// The number of elements in the document.
+    pub dom_nodes: u64,
+}
+
+impl PageStatsEvent {
+    /// Parses the stats reported by the page.
+    pub(crate) fn parse(msg: &str) -> Option<Self> {
+        let value = JsValue::parse(msg).ok()?;
+        let [used, total, nodes] = value.as_array()? else {
+            return None;
+        };
+
+        Some(Self {
+            js_heap_used: used.as_f64().map(|v| v as u64),
+            js_heap_total: total.as_f64().map(|v| v as u64),
+            dom_nodes: nodes.as_f64()? as u64,
+        })
+    }
+}
+
+/// Fired when the renderer process of the page is gone or stops responding.
+///
+/// Crashes are reported by WebView2, WKWebView and WebKitGTK. Hangs are
+/// reported by WebView2, and by the watchdog if enabled (see
+/// [`crate::webview::Webview::set_render_watchdog`]), which is also the only
+/// source of the event with Qt.
+///
+/// The page is left blank or frozen after this. Handlers can show an error
+/// page or call [`crate::webview::Webview::recover`].
+pub struct RenderProcessTerminatedEvent {
+    pub reason: TerminationReason,
+}
+
+/// The reason of a [`RenderProcessTerminatedEvent`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TerminationReason {
+    /// The renderer process exited unexpectedly, e.g. crashed or got killed.
+    /// WKWebView reports all exits as such.
+    Crashed,
+    /// The renderer process ran out of memory.
+    OutOfMemory,
+    /// The page stopped responding, as reported by WebView2 or the watchdog.
+    Unresponsive,
+}
+
+/// Fired when a server or proxy asks for credentials, before the engine shows
+/// its own dialog (if any).
+///
+/// Handlers can answer with credentials, cancel the request, or leave it to
+/// the engine. When the same challenge is asked again (e.g. the credentials
+/// were rejected), the event fires again with [`AuthChallengeEvent::is_retry`]
+/// set, and after three attempts in a row further ones are cancelled.
+///
+/// Challenges are reported by WebView2, WKWebView and WebKitGTK. The event
+/// never fires with Qt, which doesn't expose them to the bindings.
+pub struct AuthChallengeEvent {
+    /// The URL of the challenged request on WebView2, or only its origin on
+    /// WebKit backends.
+    pub url: Url,
+    /// The realm of the challenge, or empty if none is given.
+    pub realm: String,
+    /// Whether the challenge came from a proxy. WebView2 doesn't tell, thus
+    /// this is always `false` there.
+    pub is_proxy: bool,
+    /// Whether the same challenge has been answered before.
+    pub is_retry: bool,
+}
+
+/// The action taken for an [`AuthChallengeEvent`].
+#[derive(Default)]
+pub enum AuthAction {
+    /// Leaves the challenge to the engine, which may show a dialog.
+    #[default]
+    Default,
+    /// Cancels the request, which then shows the error response.
+    Cancel,
+    /// Answers the challenge with the given credentials.
+    Credentials { user: String, password: String },
+}
+
+/// Fired when the TLS certificate of a server fails verification, before the
+/// engine shows its error page.
+///
+/// Handlers can let the load proceed (e.g. after asking the user about the
+/// self-signed certificate of an internal server), which trusts the
+/// certificate for the host until the app exits, or block it.
+///
+/// Certificate errors are reported by WebView2 and WebKitGTK. The event never
+/// fires with WKWebView and Qt, which don't expose them to the bindings.
+pub struct CertificateErrorEvent {
+    /// The URL of the failing request.
+    pub url: Url,
+    /// The host the certificate was presented by.
+    pub host: String,
+    pub error: CertificateError,
+    /// The certificate presented by the server, DER-encoded, or empty if the
+    /// engine doesn't provide it.
+    pub certificate: Vec<u8>,
+}
+
+/// The reason a certificate fails verification. Engines report different
+/// details, thus some kinds may be reported as [`CertificateError::Other`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CertificateError {
+    /// The certificate doesn't match the host.
+    HostMismatch,
+    /// The certificate has expired or is not yet valid.
+    Expired,
+    /// The certificate has been revoked.
+    Revoked,
+    /// The certificate is not signed by a trusted authority, e.g. self-signed.
+    /// WebView2 reports this as [`CertificateError::Invalid`].
+    UntrustedIssuer,
+    /// The certificate is malformed or otherwise invalid.
+    Invalid,
+    Other,
+}
+
+/// The action taken for a [`CertificateErrorEvent`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CertificateAction {
+    /// Leaves the error to the engine, which fails the load.
+    #[default]
+    Default,
+    /// Continues the load, trusting the certificate for the host.
+    Proceed,
+    /// Fails the load without showing the engine's error page.
+    Block,
+}
+
+/// Fired when the page requests a new window (e.g. via `window.open` or links
+/// with `target="_blank"`), before the navigate event.
+///
+/// Handlers can host the popup in a webview of their own with
+/// [`NewWindowAction::Host`]. The backend doesn't support adopting popups, thus
+/// the hosting webview simply navigates to the requested URL and is not
+/// connected to the opener (`window.opener` is `null` and `window.open` doesn't
+/// return a usable handle).
+pub struct NewWindowRequestedEvent {
+    pub url: Url,
+    /// The user gesture that likely triggered the request. See
+    /// [`InputGesture`].
+    pub gesture: Option<InputGesture>,
+}
+
+/// The action taken for a [`NewWindowRequestedEvent`].
+#[derive(Default)]
+pub enum NewWindowAction {
+    /// Continues with the navigate event as if there were no handlers.
+    #[default]
+    Default,
+    /// Ignores the request.
+    Deny,
+    /// Navigates the given webview to the requested URL.
+    Host(Webview),
+}
@@ -0,0 +1,143 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// A browser flag understood by Chromium-based backends (WebView2 and Qt
+/// WebEngine). See [`crate::webview::WebviewOptions::set_flag`].
+///
+/// Other backends don't take browser flags. Flags not covered here can still
+/// be passed with [`BrowserFlag::Raw`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum BrowserFlag {
+    /// Disables GPU hardware acceleration.
+    DisableGpu,
+    /// Sets when media is allowed to play automatically.
+    AutoplayPolicy(AutoplayPolicy),
+    /// Disables the same-origin policy. Only use this for trusted content.
+    DisableWebSecurity,
+    /// Overrides the device scale factor.
+    ForceDeviceScaleFactor(f64),
+    /// Sets the UI language (e.g. `en-US`).
+    Lang(String),
+    /// Enables the given engine features.
+    EnableFeatures(Vec<String>),
+    /// Disables the given engine features.
+    DisableFeatures(Vec<String>),
+    /// Passes the given string as-is.
+    Raw(String),
+}
+
+/// The autoplay policy of [`BrowserFlag::AutoplayPolicy`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AutoplayPolicy {
+    /// Media can play without user gestures.
+    NoUserGestureRequired,
+    /// Media can only play after a user gesture in the frame.
+    UserGestureRequired,
+    /// Media can only play after the document has been activated by the user.
+    DocumentUserActivationRequired,
+}
+
+impl AutoplayPolicy {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::NoUserGestureRequired => "no-user-gesture-required",
+            Self::UserGestureRequired => "user-gesture-required",
+            Self::DocumentUserActivationRequired => "document-user-activation-required",
+        }
+    }
+}
+
+impl Display for BrowserFlag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DisableGpu => write!(f, "--disable-gpu"),
+            Self::AutoplayPolicy(p) => write!(f, "--autoplay-policy={}", p.name()),
+            Self::DisableWebSecurity => write!(f, "--disable-web-security"),
+            Self::ForceDeviceScaleFactor(s) => write!(f, "--force-device-scale-factor={s}"),
+            Self::Lang(l) => write!(f, "--lang={l}"),
+            Self::EnableFeatures(fs) => write!(f, "--enable-features={}", fs.join(",")),
+            Self::DisableFeatures(fs) => write!(f, "--disable-features={}", fs.join(",")),
+            Self::Raw(r) => write!(f, "{r}"),
+        }
+    }
+}
+
+/// A problem found in browser flags. See
+/// [`crate::webview::WebviewOptions::browser_flag_issues`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BrowserFlagIssue {
+    /// The flag is not known to the bindings. It may still be valid.
+    Unknown(String),
+    /// The flag is given more than once with different values, in which case
+    /// only one of them takes effect.
+    Conflicting(String),
+}
+
+/// Names of flags known to the bindings, including those derived from other
+/// options.
+const KNOWN_FLAGS: &[&str] = &[
+    "--allow-file-access-from-files",
+    "--autoplay-policy",
+    "--disable-features",
+    "--disable-gpu",
+    "--disable-gpu-compositing",
+    "--disable-web-security",
+    "--enable-features",
+    "--enable-logging",
+    "--force-device-scale-factor",
+    "--ignore-certificate-errors-spki-list",
+    "--lang",
+    "--no-proxy-server",
+    "--proxy-server",
+    "--remote-debugging-port",
+    "--use-fake-ui-for-media-stream",
+    "--v",
+];
+
+/// Gets the name part (before `=`) of a flag.
+pub(crate) fn flag_name(flag: &str) -> &str { flag.split_once('=').map_or(flag, |(n, _)| n) }
+
+/// Finds unknown and conflicting flags.
+pub(crate) fn find_flag_issues(flags: &[String]) -> Vec<BrowserFlagIssue> {
+    let mut issues = Vec::new();
+
+    for (i, flag) in flags.iter().enumerate() {
+        let name = flag_name(flag);
+
+        if !KNOWN_FLAGS.contains(&name) {
+            issues.push(BrowserFlagIssue::Unknown(flag.clone()));
+        }
+
+        let conflicting = flags[..i].iter().any(|f| flag_name(f) == name && f != flag);
+
+        if conflicting && !issues.contains(&BrowserFlagIssue::Conflicting(name.to_owned())) {
+            issues.push(BrowserFlagIssue::Conflicting(name.to_owned()));
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AutoplayPolicy;
+    use super::BrowserFlag;
+    use super::BrowserFlagIssue;
+    use super::find_flag_issues;
+
+    #[test]
+    fn flag_issues_are_found() {
+        let flags = [
+            BrowserFlag::DisableGpu.to_string(),
+            BrowserFlag::AutoplayPolicy(AutoplayPolicy::NoUserGestureRequired).to_string(),
+            "--disable-gpuu".to_owned(),
+            "--lang=en-US".to_owned(),
+            "--lang=fr".to_owned(),
+        ];
+
+        assert_eq!(find_flag_issues(&flags), vec![
+            BrowserFlagIssue::Unknown("--disable-gpuu".to_owned()),
+            BrowserFlagIssue::Conflicting("--lang".to_owned()),
+        ]);
+    }
+}
@@ -0,0 +1,146 @@
+//! Foreign window embedding module.
+//!
+//! See [`crate::webview::Webview::new_in_parent`] for details.
+#![cfg(feature = "raw-window-handle")]
+use raw_window_handle::HasWindowHandle;
+use raw_window_handle::RawWindowHandle;
+
+use crate::app::App;
+use crate::geometry::Rect;
+use crate::webview::Webview;
+use crate::webview::WebviewEventListener;
+use crate::webview::WebviewOptions;
+use crate::webview::WebviewSchemeHandler;
+use crate::window::Window;
+use crate::window::WindowDecoration;
+
+impl Webview {
+    /// Creates a webview as a child of a native window created outside of
+    /// this crate (e.g. by a Win32, Qt or GTK app), covering the given bounds
+    /// of its client area. This allows adopting webviews in an existing app
+    /// one view at a time.
+    ///
+    /// The webview lives in a borderless [`Window`] of its own, which is
+    /// turned into a child of the parent. The window is available via
+    /// [`Self::window`] for moving or resizing the webview (e.g.
+    /// [`Window::set_bounds`], relative to the parent) and showing or hiding
+    /// it. It's closed along with the parent.
+    ///
+    /// Only Win32 parents are supported on WebView2 for now. Other backends
+    /// and parents fail with [`crate::error::Error::Unsupported`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, unless allowed by the
+    /// [`crate::thread::ThreadViolationPolicy`].
+    pub fn new_in_parent(
+        opt: WebviewOptions,
+        app: &App,
+        parent: &impl HasWindowHandle,
+        bounds: Rect,
+        event_listener: impl WebviewEventListener + 'static,
+        scheme_handler: impl WebviewSchemeHandler + 'static,
+    ) -> crate::error::Result<Self> {
+        let unsupported = || {
+            crate::error::Error::unsupported(
+                "embedding webviews into foreign windows",
+                Some("create a Window and place it next to the foreign one"),
+            )
+        };
+
+        let parent = parent.window_handle().map_err(|_| unsupported())?.as_raw();
+        if !can_adopt(parent) {
+            return Err(unsupported());
+        }
+
+        let window = Window::new(app, ())?;
+        window.set_decorations(WindowDecoration::None);
+        adopt(&window, parent, bounds)?;
+
+        let webview = Self::new(opt, window.clone(), event_listener, scheme_handler)?;
+        window.show();
+        Ok(webview)
+    }
+}
+
+#[cfg(all(
+    target_os = "windows",
+    target_pointer_width = "64",
+    not(feature = "qt")
+))]
+fn can_adopt(parent: RawWindowHandle) -> bool { matches!(parent, RawWindowHandle::Win32(_)) }
+
+/// Makes the window a child of the parent.
+#[cfg(all(
+    target_os = "windows",
+    target_pointer_width = "64",
+    not(feature = "qt")
+))]
+fn adopt(window: &Window, parent: RawWindowHandle, bounds: Rect) -> crate::error::Result<()> {
+    const GWL_STYLE: i32 = -16;
+    const WS_CHILD: isize = 0x4000_0000;
+    const WS_VISIBLE: isize = 0x1000_0000;
+    const WS_CLIPSIBLINGS: isize = 0x0400_0000;
+    const SWP_NOZORDER: u32 = 0x0004;
+    const SWP_FRAMECHANGED: u32 = 0x0020;
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn SetParent(child: isize, parent: isize) -> isize;
+        fn SetWindowLongPtrW(hwnd: isize, index: i32, value: isize) -> isize;
+        fn SetWindowPos(
+            hwnd: isize,
+            after: isize,
+            x: i32,
+            y: i32,
+            cx: i32,
+            cy: i32,
+            flags: u32,
+        ) -> i32;
+    }
+
+    let RawWindowHandle::Win32(parent) = parent else {
+        unreachable!("checked by can_adopt");
+    };
+
+    let hwnd = window.query_native_handle()?.as_ptr() as isize;
+    let parent = parent.hwnd.get();
+
+    // SAFETY: Both windows are alive, and we're on the event thread, which
+    // owns the child
+    unsafe {
+        SetWindowLongPtrW(hwnd, GWL_STYLE, WS_CHILD | WS_VISIBLE | WS_CLIPSIBLINGS);
+
+        if SetParent(hwnd, parent) == 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        SetWindowPos(
+            hwnd,
+            0,
+            bounds.pos.x,
+            bounds.pos.y,
+            bounds.size.width,
+            bounds.size.height,
+            SWP_NOZORDER | SWP_FRAMECHANGED,
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(not(all(
+    target_os = "windows",
+    target_pointer_width = "64",
+    not(feature = "qt")
+)))]
+fn can_adopt(_: RawWindowHandle) -> bool { false }
+
+#[cfg(not(all(
+    target_os = "windows",
+    target_pointer_width = "64",
+    not(feature = "qt")
+)))]
+fn adopt(_: &Window, _: RawWindowHandle, _: Rect) -> crate::error::Result<()> {
+    unreachable!("checked by can_adopt")
+}
@@ -0,0 +1,82 @@
+use std::ops::BitOr;
+
+/// The kind of input device of a user gesture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputKind {
+    Mouse,
+    Keyboard,
+    Touch,
+    Pen,
+}
+
+impl InputKind {
+    fn from_name(name: &str) -> Option<Self> {
+        let kind = match name {
+            "mouse" => Self::Mouse,
+            "keyboard" => Self::Keyboard,
+            "touch" => Self::Touch,
+            "pen" => Self::Pen,
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+/// A set of modifier keys held during a user gesture.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers(u8);
+
+impl Modifiers {
+    pub const ALT: Self = Self(1 << 2);
+    pub const CONTROL: Self = Self(1 << 1);
+    /// The Command key on macOS, or the Windows key on Windows.
+    pub const META: Self = Self(1 << 3);
+    pub const SHIFT: Self = Self(1 << 0);
+
+    /// Gets an empty set.
+    pub const fn empty() -> Self { Self(0) }
+
+    /// Checks whether the set is empty.
+    pub const fn is_empty(&self) -> bool { self.0 == 0 }
+
+    /// Checks whether all keys in `other` are contained in this set.
+    pub const fn contains(&self, other: Self) -> bool { self.0 & other.0 == other.0 }
+}
+
+impl BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self { Self(self.0 | rhs.0) }
+}
+
+/// Describes the user gesture that likely triggered an event.
+///
+/// Backends don't report input details for navigations, thus gestures are
+/// recorded by a script observing clicks and key presses in the page, and
+/// attached to user-initiated navigations that follow shortly after. This is
+/// best-effort: gestures outside the page (e.g. context menus) are not seen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InputGesture {
+    pub kind: InputKind,
+    pub modifiers: Modifiers,
+    /// The mouse button (`0` for the primary button, `1` for the middle
+    /// button, etc.), if the gesture is a click.
+    pub button: Option<u16>,
+}
+
+impl InputGesture {
+    /// Parses a gesture reported by the page, in the form of
+    /// `<kind>:<modifiers>:<button>`.
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.split(':');
+        let kind = InputKind::from_name(parts.next()?)?;
+        let modifiers = Modifiers(parts.next()?.parse().ok()?);
+        let button = parts.next()?.parse().ok();
+
+        Some(Self {
+            kind,
+            modifiers,
+            button,
+        })
+    }
+}
@@ -0,0 +1,269 @@
+use std::any::Any;
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::panic::RefUnwindSafe;
+use std::panic::UnwindSafe;
+use std::rc::Rc;
+
+use crate::policy::Policy;
+use crate::status::HandleStatus;
+use crate::webview::*;
+
+/// A webview event that can be subscribed with [`Webview::on`].
+///
+/// Handlers receive the event by reference and return [`Self::Output`]. When
+/// multiple handlers (and the [`WebviewEventListener`]) are invoked for the
+/// same event, their outputs are combined with [`EventOutput::merge`].
+pub trait WebviewEvent: 'static {
+    type Output: EventOutput;
+}
+
+/// The output of an event handler.
+pub trait EventOutput: Default {
+    /// Combines outputs of two handlers of the same event.
+    fn merge(self, other: Self) -> Self;
+}
+
+impl EventOutput for () {
+    fn merge(self, _other: Self) -> Self {}
+}
+
+/// Blocks if any of the handlers blocks.
+impl EventOutput for Policy {
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (Policy::Allow, Policy::Allow) => Policy::Allow,
+            _ => Policy::Block,
+        }
+    }
+}
+
+/// Handled if any of the handlers has handled the event.
+impl EventOutput for HandleStatus {
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (HandleStatus::Unhandled, HandleStatus::Unhandled) => HandleStatus::Unhandled,
+            _ => HandleStatus::Handled,
+        }
+    }
+}
+
+/// The first handler that doesn't return [`NewWindowAction::Default`] decides.
+impl EventOutput for NewWindowAction {
+    fn merge(self, other: Self) -> Self {
+        match self {
+            NewWindowAction::Default => other,
+            _ => self,
+        }
+    }
+}
+
+/// The first handler that doesn't return [`AuthAction::Default`] decides.
+impl EventOutput for AuthAction {
+    fn merge(self, other: Self) -> Self {
+        match self {
+            AuthAction::Default => other,
+            _ => self,
+        }
+    }
+}
+
+/// The first handler that doesn't return [`CertificateAction::Default`]
+/// decides.
+impl EventOutput for CertificateAction {
+    fn merge(self, other: Self) -> Self {
+        match self {
+            CertificateAction::Default => other,
+            _ => self,
+        }
+    }
+}
+
+/// Visits event types, see [`visit_events`].
+pub(crate) trait EventVisitor {
+    fn visit<E: WebviewEvent>(&mut self);
+}
+
+macro_rules! impl_event {
+    ($($ev:ty => $out:ty),* $(,)?) => {
+        $(impl WebviewEvent for $ev { type Output = $out; })*
+
+        /// Invokes the visitor with each event type fired by webviews.
+        pub(crate) fn visit_events(visitor: &mut impl EventVisitor) {
+            $(visitor.visit::<$ev>();)*
+        }
+    };
+}
+
+impl_event! {
+    PermissionEvent => HandleStatus,
+    FullscreenEvent => Policy,
+    DomReadyEvent => (),
+    NavigatedEvent => (),
+    NavigateEvent => Policy,
+    MessageEvent => HandleStatus,
+    RequestEvent => (),
+    FaviconEvent => (),
+    TitleEvent => (),
+    LoadEvent => (),
+    RetryEvent => (),
+    AudioStateEvent => (),
+    UserActivityEvent => (),
+    ConsoleMessageEvent => (),
+    RenderProcessTerminatedEvent => (),
+    PageStatsEvent => (),
+    ScriptsDroppedEvent => (),
+    AuthChallengeEvent => AuthAction,
+    CertificateErrorEvent => CertificateAction,
+    NewWindowRequestedEvent => NewWindowAction,
+}
+
+/// An identifier of a handler registered with [`Webview::on`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HandlerId(u64);
+
+pub(crate) type Handler<E> = Rc<dyn Fn(Webview, &E) -> <E as WebviewEvent>::Output>;
+
+/// A type-erased [`Handler`].
+type ErasedHandler = (HandlerId, Box<dyn Any>);
+
+/// Stores handlers registered with [`Webview::on`], keyed by event type, and
+/// listeners added with [`Webview::add_listener`].
+#[derive(Default)]
+pub(crate) struct HandlerRegistry {
+    next_id: u64,
+    handlers: HashMap<TypeId, Vec<ErasedHandler>>,
+    listeners: Vec<(HandlerId, Rc<dyn WebviewEventListener>)>,
+}
+
+impl HandlerRegistry {
+    fn next_id(&mut self) -> HandlerId {
+        self.next_id += 1;
+        HandlerId(self.next_id)
+    }
+
+    pub(crate) fn add<E: WebviewEvent>(&mut self, handler: Handler<E>) -> HandlerId {
+        let id = self.next_id();
+
+        self.handlers
+            .entry(TypeId::of::<E>())
+            .or_default()
+            .push((id, Box::new(handler)));
+
+        id
+    }
+
+    pub(crate) fn add_listener(&mut self, listener: Rc<dyn WebviewEventListener>) -> HandlerId {
+        let id = self.next_id();
+        self.listeners.push((id, listener));
+        id
+    }
+
+    pub(crate) fn remove(&mut self, id: HandlerId) -> bool {
+        for hs in self.handlers.values_mut() {
+            if let Some(i) = hs.iter().position(|(h, _)| *h == id) {
+                hs.remove(i);
+                return true;
+            }
+        }
+
+        if let Some(i) = self.listeners.iter().position(|(l, _)| *l == id) {
+            self.listeners.remove(i);
+            return true;
+        }
+
+        false
+    }
+
+    pub(crate) fn clear<E: WebviewEvent>(&mut self) -> usize {
+        self.handlers
+            .remove(&TypeId::of::<E>())
+            .map_or(0, |hs| hs.len())
+    }
+
+    /// Clones the added listeners, like [`Self::get`].
+    pub(crate) fn listeners(&self) -> Vec<Rc<dyn WebviewEventListener>> {
+        self.listeners.iter().map(|(_, l)| l.clone()).collect()
+    }
+
+    /// Clones the handlers of the given event, so that they can be invoked
+    /// without borrowing the registry.
+    pub(crate) fn get<E: WebviewEvent>(&self) -> Vec<Handler<E>> {
+        let Some(hs) = self.handlers.get(&TypeId::of::<E>()) else {
+            return Vec::new();
+        };
+
+        hs.iter()
+            .filter_map(|(_, h)| h.downcast_ref::<Handler<E>>().cloned())
+            .collect()
+    }
+}
+
+/// A scope that removes all handlers registered through it when dropped.
+///
+/// This is useful for plugins that register many handlers and want to clean
+/// them up at once. A scope can be created with [`Webview::scope`] and is
+/// confined to the event thread.
+pub struct RegistrationScope {
+    webview: WebviewRef,
+    ids: RefCell<Vec<HandlerId>>,
+    _marker: PhantomData<*const ()>, // !Send + !Sync as handlers are removed on drop
+}
+
+impl Drop for RegistrationScope {
+    fn drop(&mut self) {
+        if let Some(w) = self.webview.upgrade() {
+            for id in self.ids.get_mut().drain(..) {
+                let _ = w.off(id); // Scopes are confined to the event thread
+            }
+        }
+    }
+}
+
+impl RegistrationScope {
+    pub(crate) fn new(webview: WebviewRef) -> Self {
+        Self {
+            webview,
+            ids: RefCell::new(Vec::new()),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Registers a handler like [`Webview::on`]. The handler is removed when
+    /// this scope is dropped.
+    ///
+    /// Returns [`None`] if the webview has been dropped.
+    pub fn on<E: WebviewEvent>(
+        &self,
+        handler: impl Fn(Webview, &E) -> E::Output + RefUnwindSafe + 'static,
+    ) -> Option<HandlerId> {
+        let id = self.webview.upgrade()?.on(handler);
+        self.ids.borrow_mut().push(id);
+        Some(id)
+    }
+
+    /// Registers a handler like [`Webview::once`]. The handler is removed when
+    /// this scope is dropped, if it hasn't been invoked.
+    ///
+    /// Returns [`None`] if the webview has been dropped.
+    pub fn once<E: WebviewEvent>(
+        &self,
+        handler: impl FnOnce(Webview, &E) -> E::Output + UnwindSafe + 'static,
+    ) -> Option<HandlerId> {
+        let id = self.webview.upgrade()?.once(handler);
+        self.ids.borrow_mut().push(id);
+        Some(id)
+    }
+
+    /// Adds a listener like [`Webview::add_listener`]. The listener is removed
+    /// when this scope is dropped.
+    ///
+    /// Returns [`None`] if the webview has been dropped.
+    pub fn add_listener(&self, listener: impl WebviewEventListener + 'static) -> Option<HandlerId> {
+        let id = self.webview.upgrade()?.add_listener(listener);
+        self.ids.borrow_mut().push(id);
+        Some(id)
+    }
+}
@@ -0,0 +1,129 @@
+//! Webview layout module.
+//!
+//! See [`crate::webview::Webview::set_bounds`] for details.
+use saucer_sys::*;
+
+use crate::util::MutexExt;
+use crate::webview::Webview;
+
+/// The layout of a webview in its window.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct WebviewLayout {
+    /// The bounds set with [`Webview::set_bounds`], or [`None`] if the webview
+    /// fills the window.
+    bounds: Option<(i32, i32, i32, i32)>,
+    visible: bool,
+}
+
+impl Default for WebviewLayout {
+    fn default() -> Self {
+        Self {
+            bounds: None,
+            visible: true,
+        }
+    }
+}
+
+impl Webview {
+    /// Sets the bounds of the webview in the window, in the order of x, y,
+    /// width and height. The webview no longer follows the size of the window
+    /// until [`Self::reset_bounds`] is called.
+    ///
+    /// A window can hold multiple webviews (e.g. for split panes), each
+    /// created with [`Webview::new`] on the same window and placed with this
+    /// method. Webviews created later are stacked above earlier ones.
+    pub fn set_bounds(&self, x: i32, y: i32, w: i32, h: i32) {
+        let visible = {
+            let mut layout = self.0.layout.lock_unpoisoned();
+            layout.bounds = Some((x, y, w, h));
+            layout.visible
+        };
+
+        if visible {
+            unsafe { saucer_webview_set_bounds(self.as_ptr(), x, y, w, h) }
+        }
+    }
+
+    /// Makes the webview fill the window again. See [`Self::set_bounds`].
+    pub fn reset_bounds(&self) {
+        let visible = {
+            let mut layout = self.0.layout.lock_unpoisoned();
+            layout.bounds = None;
+            layout.visible
+        };
+
+        if visible {
+            unsafe { saucer_webview_reset_bounds(self.as_ptr()) }
+        }
+    }
+
+    /// Gets the webview bounds in the window. For hidden webviews, these are
+    /// the bounds to restore when shown.
+    pub fn bounds(&self) -> crate::error::Result<(i32, i32, i32, i32)> {
+        self.ensure_alive()?;
+
+        if let Some(b) = self.0.layout.lock_unpoisoned().bounds {
+            return Ok(b);
+        }
+
+        let mut x = 0;
+        let mut y = 0;
+        let mut w = 0;
+        let mut h = 0;
+
+        unsafe {
+            saucer_webview_bounds(
+                self.as_ptr(),
+                &raw mut x,
+                &raw mut y,
+                &raw mut w,
+                &raw mut h,
+            )
+        }
+
+        Ok((x, y, w, h))
+    }
+
+    /// Shows or hides the webview, keeping its bounds and page. Other webviews
+    /// of the window are not affected, thus panes and tabs can be switched by
+    /// hiding all but one of them.
+    ///
+    /// Backends can't hide webviews, thus hidden webviews are shrunk to an
+    /// empty size. The page keeps running meanwhile.
+    pub fn set_visible(&self, visible: bool) {
+        let bounds = {
+            let mut layout = self.0.layout.lock_unpoisoned();
+            if layout.visible == visible {
+                return;
+            }
+
+            layout.visible = visible;
+            layout.bounds
+        };
+
+        unsafe {
+            match (visible, bounds) {
+                (false, _) => saucer_webview_set_bounds(self.as_ptr(), 0, 0, 0, 0),
+                (true, Some((x, y, w, h))) => saucer_webview_set_bounds(self.as_ptr(), x, y, w, h),
+                (true, None) => saucer_webview_reset_bounds(self.as_ptr()),
+            }
+        }
+    }
+
+    /// Checks whether the webview is shown. See [`Self::set_visible`].
+    pub fn is_visible(&self) -> bool { self.0.layout.lock_unpoisoned().visible }
+
+    /// Moves the webview above other webviews of the window.
+    ///
+    /// Backends don't expose the stacking order of webviews yet, thus this
+    /// always fails with [`crate::error::Error::Unsupported`] for now, and
+    /// webviews stay stacked in the order they're created.
+    pub fn bring_to_front(&self) -> crate::error::Result<()> {
+        self.ensure_alive()?;
+
+        Err(crate::error::Error::unsupported(
+            "reordering webviews",
+            Some("avoid overlapping webviews, or hide covered ones with Webview::set_visible"),
+        ))
+    }
+}
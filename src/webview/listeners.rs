@@ -0,0 +1,185 @@
+use std::borrow::Cow;
+use std::panic::RefUnwindSafe;
+
+use crate::icon::Icon;
+use crate::navigation::Navigation;
+use crate::permission::PermissionRequest;
+use crate::policy::Policy;
+use crate::state::LoadState;
+use crate::status::HandleStatus;
+use crate::url::Url;
+use crate::webview::Webview;
+use crate::webview::WebviewEventListener;
+
+type Listener<A, R = ()> = Option<Box<dyn Fn(Webview, A) -> R + RefUnwindSafe + 'static>>;
+// Borrowed arguments can't go through the generic alias
+type NavigateListener = Option<Box<dyn Fn(Webview, &Navigation) -> Policy + RefUnwindSafe>>;
+type MessageListener = Option<Box<dyn Fn(Webview, Cow<str>) -> HandleStatus + RefUnwindSafe>>;
+
+/// A [`WebviewEventListener`] built from closures.
+///
+/// Events without a closure behave as the defaults of the trait, so only the
+/// events of interest need to be set:
+///
+/// ```no_run
+/// use saucers::webview::WebviewListeners;
+///
+/// let listeners = WebviewListeners::new()
+///     .on_title(|w, title| w.window().set_title(title))
+///     .on_dom_ready(|_| println!("DOM ready"));
+/// ```
+///
+/// The same caveat about captured handles as for [`WebviewEventListener`]
+/// applies to the closures.
+#[derive(Default)]
+pub struct WebviewListeners {
+    permission: Listener<PermissionRequest, HandleStatus>,
+    fullscreen: Listener<bool, Policy>,
+    dom_ready: Listener<()>,
+    navigated: Listener<Url>,
+    navigate: NavigateListener,
+    message: MessageListener,
+    request: Listener<Url>,
+    favicon: Listener<Icon>,
+    title: Listener<String>,
+    load: Listener<LoadState>,
+}
+
+impl WebviewListeners {
+    /// Creates a set of listeners doing nothing.
+    pub fn new() -> Self { Self::default() }
+
+    /// Sets the closure for [`WebviewEventListener::on_permission`].
+    pub fn on_permission(
+        mut self,
+        f: impl Fn(Webview, PermissionRequest) -> HandleStatus + RefUnwindSafe + 'static,
+    ) -> Self {
+        self.permission = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the closure for [`WebviewEventListener::on_fullscreen`].
+    pub fn on_fullscreen(
+        mut self,
+        f: impl Fn(Webview, bool) -> Policy + RefUnwindSafe + 'static,
+    ) -> Self {
+        self.fullscreen = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the closure for [`WebviewEventListener::on_dom_ready`].
+    pub fn on_dom_ready(mut self, f: impl Fn(Webview) + RefUnwindSafe + 'static) -> Self {
+        self.dom_ready = Some(Box::new(move |w, ()| f(w)));
+        self
+    }
+
+    /// Sets the closure for [`WebviewEventListener::on_navigated`].
+    pub fn on_navigated(mut self, f: impl Fn(Webview, Url) + RefUnwindSafe + 'static) -> Self {
+        self.navigated = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the closure for [`WebviewEventListener::on_navigate`].
+    pub fn on_navigate(
+        mut self,
+        f: impl Fn(Webview, &Navigation) -> Policy + RefUnwindSafe + 'static,
+    ) -> Self {
+        self.navigate = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the closure for [`WebviewEventListener::on_message`].
+    pub fn on_message(
+        mut self,
+        f: impl Fn(Webview, Cow<str>) -> HandleStatus + RefUnwindSafe + 'static,
+    ) -> Self {
+        self.message = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the closure for [`WebviewEventListener::on_request`].
+    pub fn on_request(mut self, f: impl Fn(Webview, Url) + RefUnwindSafe + 'static) -> Self {
+        self.request = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the closure for [`WebviewEventListener::on_favicon`].
+    pub fn on_favicon(mut self, f: impl Fn(Webview, Icon) + RefUnwindSafe + 'static) -> Self {
+        self.favicon = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the closure for [`WebviewEventListener::on_title`].
+    pub fn on_title(mut self, f: impl Fn(Webview, String) + RefUnwindSafe + 'static) -> Self {
+        self.title = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the closure for [`WebviewEventListener::on_load`].
+    pub fn on_load(mut self, f: impl Fn(Webview, LoadState) + RefUnwindSafe + 'static) -> Self {
+        self.load = Some(Box::new(f));
+        self
+    }
+}
+
+impl WebviewEventListener for WebviewListeners {
+    fn on_permission(&self, webview: Webview, req: PermissionRequest) -> HandleStatus {
+        self.permission
+            .as_ref()
+            .map_or(HandleStatus::Unhandled, |f| f(webview, req))
+    }
+
+    fn on_fullscreen(&self, webview: Webview, is_fullscreen: bool) -> Policy {
+        self.fullscreen
+            .as_ref()
+            .map_or(Policy::Allow, |f| f(webview, is_fullscreen))
+    }
+
+    fn on_dom_ready(&self, webview: Webview) {
+        if let Some(f) = &self.dom_ready {
+            f(webview, ())
+        }
+    }
+
+    fn on_navigated(&self, webview: Webview, url: Url) {
+        if let Some(f) = &self.navigated {
+            f(webview, url)
+        }
+    }
+
+    fn on_navigate(&self, webview: Webview, nav: &Navigation) -> Policy {
+        self.navigate
+            .as_ref()
+            .map_or(Policy::Allow, |f| f(webview, nav))
+    }
+
+    fn on_message(&self, webview: Webview, msg: Cow<str>) -> HandleStatus {
+        self.message
+            .as_ref()
+            .map_or(HandleStatus::Unhandled, |f| f(webview, msg))
+    }
+
+    fn on_request(&self, webview: Webview, url: Url) {
+        if let Some(f) = &self.request {
+            f(webview, url)
+        }
+    }
+
+    fn on_favicon(&self, webview: Webview, icon: Icon) {
+        if let Some(f) = &self.favicon {
+            f(webview, icon)
+        }
+    }
+
+    fn on_title(&self, webview: Webview, title: String) {
+        if let Some(f) = &self.title {
+            f(webview, title)
+        }
+    }
+
+    fn on_load(&self, webview: Webview, state: LoadState) {
+        if let Some(f) = &self.load {
+            f(webview, state)
+        }
+    }
+}
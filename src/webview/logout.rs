@@ -0,0 +1,390 @@
+//! Logout module.
+//!
+//! See [`crate::webview::Webview::logout`] for details.
+use crate::url::Url;
+use crate::util::js_string;
+use crate::webview::Webview;
+
+type DoneCallback = Box<dyn FnOnce(crate::error::Result<()>)>;
+
+impl Webview {
+    /// Logs out of a web app by clearing data of the origin (e.g.
+    /// `https://example.com`), and then navigating to the redirect URL. The
+    /// callback receives the outcome on the event thread.
+    ///
+    /// Cookies sent to the host of the origin, including `HttpOnly` ones, are
+    /// deleted through the cookie store of the engine first. If the page is on
+    /// the origin, loading is then stopped, and cache storage, IndexedDB
+    /// databases, service workers, session storage and local storage are
+    /// cleared by the page, with the navigation starting right after in the
+    /// same task, so that page scripts can't restore any data in between.
+    /// Requests still in flight are aborted by the navigation. Storage of
+    /// origins the page is not on is left as is.
+    ///
+    /// Only WebView2 and WebKitGTK expose their cookie stores. Elsewhere this
+    /// fails with [`crate::error::Error::Unsupported`] without clearing
+    /// anything, as `HttpOnly` cookies would remain. Use a dedicated
+    /// [`crate::webview::Profile`] and delete it there.
+    pub fn logout(
+        &self,
+        origin: &str,
+        redirect: &str,
+        callback: impl FnOnce(crate::error::Result<()>) + Send + 'static,
+    ) {
+        let (origin, host) = match Url::new_parse(origin) {
+            Ok(o) if !o.host().is_empty() => (o.origin(), o.host()),
+            Ok(_) => return callback(Err(crate::error::Error::InvalidUrl(origin.to_owned()))),
+            Err(e) => return callback(Err(e)),
+        };
+
+        let weak = self.downgrade();
+        let redirect = redirect.to_owned();
+
+        clear_cookies(
+            self,
+            &host,
+            Box::new(move |res| {
+                let Some(w) = weak.upgrade() else {
+                    return callback(Err(crate::error::Error::Closed));
+                };
+
+                if let Err(e) = res {
+                    return callback(Err(e));
+                }
+
+                let same_origin = w.url().is_ok_and(|u| u.origin() == origin);
+                if !same_origin {
+                    w.set_url_str(redirect);
+                    return callback(Ok(()));
+                }
+
+                let js = include_str!("scripts/logout.js")
+                    .replace("__REDIRECT__", &js_string(&redirect));
+                w.evaluate(&js, |res| callback(res.map(|_| ())));
+            }),
+        );
+    }
+}
+
+/// Checks whether a cookie of the domain (e.g. `.example.com`) is sent to the
+/// host.
+#[allow(unused)] // Not used by all backends
+fn domain_matches(domain: &str, host: &str) -> bool {
+    let domain = domain.trim_start_matches('.').to_ascii_lowercase();
+    let host = host.to_ascii_lowercase();
+
+    !domain.is_empty()
+        && (host == domain || host.strip_suffix(&domain).is_some_and(|h| h.ends_with('.')))
+}
+
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+fn clear_cookies(webview: &Webview, host: &str, done: DoneCallback) {
+    use std::cell::Cell;
+    use std::ffi::c_void;
+    use std::rc::Rc;
+
+    use crate::webview::webview2;
+    use crate::webview::webview2::ComPtr;
+    use crate::webview::webview2::HResult;
+    use crate::webview::webview2::S_OK;
+
+    // ICoreWebView2CookieManager
+    const GET_COOKIES: usize = 5;
+    const DELETE_COOKIE: usize = 7;
+    // ICoreWebView2CookieList
+    const GET_COUNT: usize = 3;
+    const GET_VALUE_AT_INDEX: usize = 4;
+    // ICoreWebView2Cookie
+    const GET_DOMAIN: usize = 6;
+
+    let manager = webview2::core_webview(webview).and_then(|core| {
+        core.cast(&webview2::IID_ICOREWEBVIEW2_2)
+            // SAFETY: The slot is `get_CookieManager`
+            .and_then(|core| unsafe { core.get(webview2::slot::GET_COOKIE_MANAGER) })
+            .ok_or_else(|| crate::error::Error::unsupported("clearing cookies", None))
+    });
+
+    let manager = match manager {
+        Ok(m) => Rc::new(m),
+        Err(e) => return done(Err(e)),
+    };
+
+    let failed = || {
+        Err(crate::error::Error::Io(std::io::Error::other(
+            "failed to get cookies",
+        )))
+    };
+
+    let done = Rc::new(Cell::new(Some(done)));
+    let handler = {
+        let (done, manager, host) = (done.clone(), manager.clone(), host.to_owned());
+
+        webview2::Handler::<HResult, *mut c_void, _>::create(
+            "webview cookie list",
+            move |res, list| {
+                let Some(done) = done.take() else {
+                    return S_OK;
+                };
+
+                // SAFETY: The list is borrowed for the call
+                let list = unsafe { ComPtr::from_borrowed(list) };
+                let Some(list) = list.filter(|_| res >= 0) else {
+                    done(failed());
+                    return S_OK;
+                };
+
+                // SAFETY: The slots match `ICoreWebView2CookieList`,
+                // `ICoreWebView2Cookie` and `ICoreWebView2CookieManager`
+                unsafe {
+                    let count = list.get_value::<u32>(GET_COUNT).unwrap_or(0);
+                    let get_value: unsafe extern "system" fn(
+                        *mut c_void,
+                        u32,
+                        *mut *mut c_void,
+                    ) -> HResult = list.method(GET_VALUE_AT_INDEX);
+
+                    for i in 0..count {
+                        let mut cookie = std::ptr::null_mut();
+                        if get_value(list.as_ptr(), i, &raw mut cookie) < 0 {
+                            continue;
+                        }
+
+                        let Some(cookie) = ComPtr::from_raw(cookie) else {
+                            continue;
+                        };
+
+                        let domain = cookie.get_string(GET_DOMAIN).unwrap_or_default();
+                        if domain_matches(&domain, &host) {
+                            // `DeleteCookie` takes the cookie as its only argument
+                            manager.put(DELETE_COOKIE, cookie.as_ptr());
+                        }
+                    }
+                }
+
+                done(Ok(()));
+                S_OK
+            },
+        )
+    };
+
+    // An empty URI gets cookies of all paths, which are filtered by domain
+    let uri = webview2::wide("");
+
+    // SAFETY: The slot is `GetCookies`, which takes a completion handler
+    let res = unsafe {
+        let f: unsafe extern "system" fn(*mut c_void, *const u16, *mut c_void) -> HResult =
+            manager.method(GET_COOKIES);
+        f(manager.as_ptr(), uri.as_ptr(), handler.as_ptr())
+    };
+
+    // The handler is never invoked then
+    if res < 0
+        && let Some(done) = done.take()
+    {
+        done(failed());
+    }
+}
+
+#[cfg(all(
+    not(any(target_os = "windows", target_os = "macos")),
+    not(feature = "qt")
+))]
+fn clear_cookies(webview: &Webview, host: &str, done: DoneCallback) {
+    use std::ffi::c_char;
+    use std::ffi::c_void;
+    use std::panic::AssertUnwindSafe;
+
+    use crate::webview::webkitgtk;
+
+    // WebKitWebsiteDataTypes
+    const COOKIES: u32 = 1 << 8;
+
+    #[repr(C)]
+    struct GList {
+        data: *mut c_void,
+        next: *mut GList,
+        prev: *mut GList,
+    }
+
+    type ReadyCallback = unsafe extern "C" fn(*mut c_void, *mut c_void, *mut c_void);
+
+    unsafe extern "C" {
+        fn webkit_web_view_get_network_session(view: *mut c_void) -> *mut c_void;
+        fn webkit_network_session_get_website_data_manager(session: *mut c_void) -> *mut c_void;
+        fn webkit_website_data_manager_fetch(
+            manager: *mut c_void,
+            types: u32,
+            cancellable: *mut c_void,
+            callback: ReadyCallback,
+            data: *mut c_void,
+        );
+        fn webkit_website_data_manager_fetch_finish(
+            manager: *mut c_void,
+            result: *mut c_void,
+            error: *mut *mut c_void,
+        ) -> *mut GList;
+        fn webkit_website_data_manager_remove(
+            manager: *mut c_void,
+            types: u32,
+            records: *mut GList,
+            cancellable: *mut c_void,
+            callback: ReadyCallback,
+            data: *mut c_void,
+        );
+        fn webkit_website_data_manager_remove_finish(
+            manager: *mut c_void,
+            result: *mut c_void,
+            error: *mut *mut c_void,
+        ) -> i32;
+        fn webkit_website_data_get_name(record: *mut c_void) -> *const c_char;
+        fn webkit_website_data_unref(record: *mut c_void);
+        fn g_list_prepend(list: *mut GList, data: *mut c_void) -> *mut GList;
+        fn g_list_free(list: *mut GList);
+        fn g_list_free_full(list: *mut GList, free: unsafe extern "C" fn(*mut c_void));
+        fn g_error_free(error: *mut c_void);
+    }
+
+    /// Passed through the callbacks of the data manager.
+    struct Pending {
+        done: DoneCallback,
+        host: String,
+        records: *mut GList,
+    }
+
+    fn failed(what: &str) -> crate::error::Result<()> {
+        Err(crate::error::Error::Io(std::io::Error::other(format!(
+            "failed to {what} website data"
+        ))))
+    }
+
+    unsafe extern "C" fn on_fetched(manager: *mut c_void, result: *mut c_void, data: *mut c_void) {
+        let mut pending = unsafe { Box::from_raw(data as *mut Pending) };
+
+        let mut error = std::ptr::null_mut();
+        let records =
+            unsafe { webkit_website_data_manager_fetch_finish(manager, result, &raw mut error) };
+
+        if !error.is_null() {
+            unsafe { g_error_free(error) };
+            let done = pending.done;
+            crate::util::ffi_callback(
+                "webview logout",
+                (),
+                AssertUnwindSafe(|| done(failed("fetch"))),
+            );
+            return;
+        }
+
+        let mut matching = std::ptr::null_mut();
+        let mut node = records;
+
+        // SAFETY: The list and its records are owned by us until freed
+        unsafe {
+            while !node.is_null() {
+                let record = (*node).data;
+                let name = webkitgtk::string(webkit_website_data_get_name(record));
+
+                if name.is_some_and(|n| domain_matches(&n, &pending.host)) {
+                    matching = g_list_prepend(matching, record);
+                }
+
+                node = (*node).next;
+            }
+        }
+
+        if matching.is_null() {
+            unsafe { g_list_free_full(records, webkit_website_data_unref) };
+            let done = pending.done;
+            crate::util::ffi_callback("webview logout", (), AssertUnwindSafe(|| done(Ok(()))));
+            return;
+        }
+
+        pending.records = records;
+
+        // SAFETY: The records stay alive until the removal finishes
+        unsafe {
+            webkit_website_data_manager_remove(
+                manager,
+                COOKIES,
+                matching,
+                std::ptr::null_mut(),
+                on_removed,
+                Box::into_raw(pending).cast(),
+            );
+            g_list_free(matching);
+        }
+    }
+
+    unsafe extern "C" fn on_removed(manager: *mut c_void, result: *mut c_void, data: *mut c_void) {
+        let pending = unsafe { Box::from_raw(data as *mut Pending) };
+
+        let mut error = std::ptr::null_mut();
+        let removed =
+            unsafe { webkit_website_data_manager_remove_finish(manager, result, &raw mut error) };
+
+        unsafe {
+            if !error.is_null() {
+                g_error_free(error);
+            }
+
+            g_list_free_full(pending.records, webkit_website_data_unref);
+        }
+
+        let res = if removed != 0 {
+            Ok(())
+        } else {
+            failed("remove")
+        };
+        let done = pending.done;
+        crate::util::ffi_callback("webview logout", (), AssertUnwindSafe(|| done(res)));
+    }
+
+    let view = match webview.query_native_controller() {
+        Ok(v) => v,
+        Err(e) => return done(Err(e)),
+    };
+
+    let pending = Box::new(Pending {
+        done,
+        host: host.to_owned(),
+        records: std::ptr::null_mut(),
+    });
+
+    // SAFETY: On the event thread while the webview is alive, and the pending
+    // state is freed by the callback
+    unsafe {
+        let manager = webkit_network_session_get_website_data_manager(
+            webkit_web_view_get_network_session(view.as_ptr()),
+        );
+
+        webkit_website_data_manager_fetch(
+            manager,
+            COOKIES,
+            std::ptr::null_mut(),
+            on_fetched,
+            Box::into_raw(pending).cast(),
+        );
+    }
+}
+
+#[cfg(any(target_os = "macos", feature = "qt"))]
+fn clear_cookies(_: &Webview, _: &str, done: DoneCallback) {
+    done(Err(crate::error::Error::unsupported(
+        "clearing HttpOnly cookies",
+        Some("use a dedicated Profile and delete it"),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cookie_domains_match_hosts() {
+        assert!(domain_matches("example.com", "example.com"));
+        assert!(domain_matches(".Example.com", "app.example.com"));
+        assert!(!domain_matches("example.com", "badexample.com"));
+        assert!(!domain_matches("app.example.com", "example.com"));
+        assert!(!domain_matches(".", "example.com"));
+    }
+}
@@ -0,0 +1,232 @@
+//! Memory pressure module.
+//!
+//! See [`crate::webview::Webview::send_memory_pressure`] and
+//! [`crate::webview::Webview::try_suspend`] for details.
+use crate::thread::check_event_thread;
+use crate::webview::Webview;
+
+/// The level of a memory-pressure hint. See [`Webview::send_memory_pressure`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MemoryPressure {
+    /// The app would like memory back, e.g. when minimized to the tray. Pages
+    /// should drop caches they can rebuild cheaply.
+    #[default]
+    Moderate,
+    /// The app is short of memory. Pages should drop everything they can.
+    Critical,
+}
+
+impl MemoryPressure {
+    /// Gets the name of the level as sent to the page.
+    pub(crate) fn js_name(self) -> &'static str {
+        match self {
+            Self::Moderate => "moderate",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+impl Webview {
+    /// Tries to suspend a hidden webview, which pauses its scripts and timers
+    /// and lets the engine free memory, until [`Self::resume`] is called. The
+    /// callback receives whether the webview got suspended, and may be invoked
+    /// before this returns.
+    ///
+    /// Only webviews hidden with [`Self::set_visible`], or whose window is
+    /// hidden, are suspended. Only WebView2 can suspend webviews, elsewhere
+    /// this does nothing and the callback receives `false`. Consider
+    /// [`Self::send_memory_pressure`] as well.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, unless allowed by the
+    /// [`crate::thread::ThreadViolationPolicy`].
+    pub fn try_suspend(&self, callback: impl FnOnce(bool) + 'static) -> crate::error::Result<()> {
+        if check_event_thread(self.0.is_thread_safe(), "suspending webviews").is_some() {
+            return Err(crate::error::Error::WrongThread);
+        }
+
+        self.ensure_alive()?;
+
+        let hidden = !self.is_visible() || !self.window().is_visible().unwrap_or(true);
+        if !hidden {
+            callback(false);
+            return Ok(());
+        }
+
+        try_suspend(self, Box::new(callback))
+    }
+
+    /// Resumes a webview suspended with [`Self::try_suspend`], and lifts the
+    /// memory target lowered by [`Self::send_memory_pressure`]. Does nothing
+    /// if neither happened, and on backends other than WebView2.
+    ///
+    /// Webviews are also resumed by the engine when they're shown, but pages
+    /// may stay frozen until this is called.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, unless allowed by the
+    /// [`crate::thread::ThreadViolationPolicy`].
+    pub fn resume(&self) -> crate::error::Result<()> {
+        if check_event_thread(self.0.is_thread_safe(), "resuming webviews").is_some() {
+            return Err(crate::error::Error::WrongThread);
+        }
+
+        self.ensure_alive()?;
+        resume(self)
+    }
+
+    /// Asks the page to free memory, e.g. when the window is hidden to the
+    /// tray. A `saucer:memory-pressure` `CustomEvent` is dispatched on
+    /// `window`, with the level (`"moderate"` or `"critical"`) as its
+    /// `detail`, for the page to drop caches, pause animations and so on.
+    ///
+    /// On WebView2, the memory target of the engine is also lowered, which
+    /// trims the memory of the renderer until [`Self::resume`] is called.
+    /// Elsewhere, this is only a hint for the page, and does nothing unless
+    /// the page listens to the event. See
+    /// [`crate::window::Window::send_memory_pressure`] for all webviews in a
+    /// window.
+    pub fn send_memory_pressure(&self, level: MemoryPressure) {
+        let detail = level.js_name();
+        self.execute(format!(
+            r#"window.dispatchEvent(new CustomEvent("saucer:memory-pressure", {{ detail: "{detail}" }}));"#
+        ));
+
+        if self.0.is_thread_safe() {
+            let _ = set_low_memory_target(self, true);
+        }
+    }
+}
+
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+fn try_suspend(webview: &Webview, callback: Box<dyn FnOnce(bool)>) -> crate::error::Result<()> {
+    use std::cell::Cell;
+    use std::ffi::c_void;
+    use std::rc::Rc;
+
+    use crate::webview::webview2;
+    use crate::webview::webview2::HResult;
+    use crate::webview::webview2::S_OK;
+
+    let Some(core) = webview2::core_webview(webview)?.cast(&webview2::IID_ICOREWEBVIEW2_3) else {
+        callback(false);
+        return Ok(());
+    };
+
+    // The engine only suspends webviews it considers hidden
+    set_controller_visible(webview, false)?;
+
+    let callback = Rc::new(Cell::new(Some(callback)));
+    let done = {
+        let callback = callback.clone();
+        let weak = webview.downgrade();
+
+        move |suspended: bool| {
+            if !suspended && let Some(w) = weak.upgrade() {
+                let _ = set_controller_visible(&w, true);
+            }
+
+            if let Some(cb) = callback.take() {
+                cb(suspended);
+            }
+        }
+    };
+
+    let handler = {
+        let done = done.clone();
+        webview2::Handler::<HResult, i32, _>::create(
+            "webview suspend completion",
+            move |res, suspended| {
+                done(res >= 0 && suspended != 0);
+                S_OK
+            },
+        )
+    };
+
+    // SAFETY: The slot is `TrySuspend`, which takes a completion handler
+    let res = unsafe {
+        let f: unsafe extern "system" fn(*mut c_void, *mut c_void) -> HResult =
+            core.method(webview2::slot::TRY_SUSPEND);
+        f(core.as_ptr(), handler.as_ptr())
+    };
+
+    if res < 0 {
+        done(false);
+    }
+
+    Ok(())
+}
+
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+fn resume(webview: &Webview) -> crate::error::Result<()> {
+    use std::ffi::c_void;
+
+    use crate::webview::webview2;
+    use crate::webview::webview2::HResult;
+
+    set_low_memory_target(webview, false)?;
+
+    if let Some(core) = webview2::core_webview(webview)?.cast(&webview2::IID_ICOREWEBVIEW2_3) {
+        // SAFETY: The slot is `Resume`, which takes no arguments
+        unsafe {
+            let f: unsafe extern "system" fn(*mut c_void) -> HResult =
+                core.method(webview2::slot::RESUME);
+            f(core.as_ptr());
+        }
+    }
+
+    set_controller_visible(webview, true)
+}
+
+/// Shows or hides the controller, which the engine checks for suspending.
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+fn set_controller_visible(webview: &Webview, visible: bool) -> crate::error::Result<()> {
+    use crate::webview::webview2;
+
+    let controller = webview.query_native_controller()?;
+
+    // SAFETY: The native webview is an `ICoreWebView2Controller`, which is
+    // borrowed from saucer
+    unsafe {
+        let controller = webview2::ComPtr::from_borrowed(controller.as_ptr())
+            .ok_or(crate::error::Error::Closed)?;
+        controller.put(webview2::slot::CONTROLLER_PUT_IS_VISIBLE, visible as i32);
+    }
+
+    Ok(())
+}
+
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+fn set_low_memory_target(webview: &Webview, low: bool) -> crate::error::Result<()> {
+    use crate::webview::webview2;
+
+    // COREWEBVIEW2_MEMORY_USAGE_TARGET_LEVEL
+    const NORMAL: i32 = 0;
+    const LOW: i32 = 1;
+
+    if let Some(core) = webview2::core_webview(webview)?.cast(&webview2::IID_ICOREWEBVIEW2_19) {
+        // SAFETY: The slot is `put_MemoryUsageTargetLevel`
+        unsafe {
+            core.put(
+                webview2::slot::PUT_MEMORY_USAGE_TARGET_LEVEL,
+                if low { LOW } else { NORMAL },
+            )
+        };
+    }
+
+    Ok(())
+}
+
+#[cfg(not(all(target_os = "windows", not(feature = "qt"))))]
+fn try_suspend(_: &Webview, callback: Box<dyn FnOnce(bool)>) -> crate::error::Result<()> {
+    callback(false);
+    Ok(())
+}
+
+#[cfg(not(all(target_os = "windows", not(feature = "qt"))))]
+fn resume(_: &Webview) -> crate::error::Result<()> { Ok(()) }
+
+#[cfg(not(all(target_os = "windows", not(feature = "qt"))))]
+fn set_low_memory_target(_: &Webview, _: bool) -> crate::error::Result<()> { Ok(()) }
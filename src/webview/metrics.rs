@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use crate::js::JsValue;
+
+/// Performance metrics of a page, taken with
+/// [`crate::webview::Webview::metrics`].
+///
+/// Backends don't expose the memory of renderer processes, thus the memory is
+/// the JS heap as seen by the page. See
+/// [`crate::webview::Webview::set_page_stats_sampling`] for sampling it
+/// periodically.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PageMetrics {
+    /// The size of the JS heap in use, in bytes. Only Chromium-based engines
+    /// (WebView2 and Qt) report heap sizes, thus this is [`None`] elsewhere.
+    pub js_heap_used: Option<u64>,
+    /// The size of the JS heap allocated, in bytes. See
+    /// [`Self::js_heap_used`].
+    pub js_heap_total: Option<u64>,
+    /// The number of elements in the document.
+    pub dom_nodes: u64,
+    /// The frames rendered per second, measured over a short period. This is
+    /// a hint rather than an exact rate, and is [`None`] if the page is hidden
+    /// and doesn't render.
+    pub frame_rate: Option<f64>,
+    /// The time from the start of the navigation until `DOMContentLoaded`
+    /// handlers finished, or [`None`] if that hasn't happened yet.
+    pub dom_content_loaded: Option<Duration>,
+    /// The time from the start of the navigation until `load` handlers
+    /// finished, or [`None`] if that hasn't happened yet.
+    pub load: Option<Duration>,
+}
+
+impl PageMetrics {
+    /// Converts the metrics reported by the page.
+    pub(crate) fn from_js(value: &JsValue) -> Option<Self> {
+        let num = |key: &str| value.get(key).and_then(JsValue::as_f64);
+        let millis = |key: &str| {
+            num(key)
+                .filter(|v| *v > 0.0)
+                .map(|v| Duration::from_secs_f64(v / 1000.0))
+        };
+
+        Some(Self {
+            js_heap_used: num("heapUsed").map(|v| v as u64),
+            js_heap_total: num("heapTotal").map(|v| v as u64),
+            dom_nodes: num("domNodes")? as u64,
+            frame_rate: num("frameRate"),
+            dom_content_loaded: millis("domContentLoaded"),
+            load: millis("load"),
+        })
+    }
+}
@@ -1,46 +1,247 @@
+mod accessibility;
+mod auth;
+mod bridge;
+mod certificate;
+mod crash;
+mod edit;
 mod events;
+mod flags;
+mod foreign;
+mod gesture;
+mod handlers;
+mod layout;
+mod listeners;
+mod logout;
+mod memory;
+mod metrics;
+mod navigate;
 mod options;
+mod pool;
+mod profile;
+mod queue;
+mod replies;
+mod retry;
 mod script;
-
+mod spellcheck;
+mod stream;
+mod temp_scheme;
+mod user_agent;
+mod watchdog;
+#[cfg(all(
+    not(any(target_os = "windows", target_os = "macos")),
+    not(feature = "qt")
+))]
+mod webkitgtk;
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+mod webview2;
+#[cfg(all(target_os = "macos", not(feature = "qt")))]
+mod wkwebview;
+
+use std::any::TypeId;
 use std::borrow::Cow;
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ffi::c_char;
 use std::ffi::c_void;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::panic::AssertUnwindSafe;
+use std::panic::RefUnwindSafe;
+use std::panic::UnwindSafe;
 use std::ptr::NonNull;
+use std::rc::Rc;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::sync::Weak;
-use std::sync::mpsc::Sender;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::thread::ThreadId;
+use std::time::Duration;
+use std::time::Instant;
 
+pub use accessibility::*;
+pub use bridge::*;
+pub use edit::*;
 pub use events::*;
+pub use flags::*;
+pub use gesture::*;
+pub use handlers::*;
+use layout::WebviewLayout;
+pub use listeners::*;
+pub use memory::*;
+pub use metrics::*;
+pub use navigate::*;
 pub use options::*;
+pub use pool::*;
+pub use profile::*;
+use queue::ScriptQueue;
+use replies::PendingReplies;
+pub use retry::*;
 use saucer_sys::*;
 pub use script::*;
-
+pub use stream::STREAM_BUFFER_SIZE;
+pub(crate) use stream::STREAM_SCHEME;
+use stream::StreamShared;
+pub use stream::StreamWriter;
+pub use temp_scheme::TemporaryScheme;
+use temp_scheme::TemporarySchemeEntry;
+use watchdog::Watchdog;
+use watchdog::WatchdogTick;
+
+use crate::app::HandleKind;
 use crate::cleanup::CleanUpHolder;
+use crate::cleanup::DropSender;
+use crate::companion::Companion;
+use crate::companion::CompanionRef;
 use crate::icon::Icon;
+use crate::js::JsValue;
 use crate::macros::ffi_forward;
 use crate::macros::load_range;
+use crate::macros::trace_event;
 use crate::macros::use_string;
+use crate::menu::MenuItemId;
 use crate::navigation::Navigation;
+use crate::navigation::NavigationRules;
 use crate::permission::PermissionRequest;
+use crate::permission::PermissionRules;
+use crate::permission::PermissionStore;
+use crate::permission::PermissionType;
+use crate::policy::ContentRules;
 use crate::policy::Policy;
 use crate::scheme::Executor;
 use crate::scheme::Request;
+use crate::scheme::SchemeError;
+use crate::scheme::Vfs;
+use crate::scheme::serve_vfs;
 use crate::stash::Stash;
+use crate::state::LoadState;
 use crate::status::HandleStatus;
+use crate::thread::ThreadViolationPolicy;
+use crate::thread::check_event_thread;
 use crate::url::Url;
+use crate::util::MutexExt;
 use crate::util::ffi_callback;
+use crate::util::js_string;
 use crate::window::Window;
+use crate::window::WindowEdge;
+use crate::window::WindowEffect;
+use crate::window::WindowSignal;
+
+/// The version of the scripts injected by the bindings. See
+/// [`Webview::internal_scripts`].
+///
+/// The scripts only change with releases of this crate, thus this is the crate
+/// version.
+pub const INTERNAL_SCRIPTS_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// How long a recorded gesture is considered the cause of a navigation.
+const GESTURE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long the user must not interact with a page before it's idle, by
+/// default.
+const DEFAULT_USER_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Time to wait for the page to report its status after a load finishes.
+const RETRY_REPORT_GRACE: Duration = Duration::from_millis(300);
 
 /// An unprotected raw webview handle.
 struct RawWebview {
     inner: NonNull<saucer_webview>,
-    drop_sender: Sender<CleanUpHolder>,
+    id: u64,
+    drop_sender: DropSender,
     host_tid: ThreadId,
     event_listener_data: *mut EventListenerData,
     scheme_handler_data: *mut SchemeHandlerData,
     schemes: Vec<Cow<'static, str>>,
     window: Window, // Keep the window alive
+    /// Scripts injected by the bindings, keyed by the feature using them.
+    managed_scripts: Mutex<HashMap<&'static str, (ScriptId, String)>>,
+    script_namespace: String,
+    /// Prefix of messages sent by scripts managed by the bindings, made of a
+    /// random token which tells them apart from messages of the page. These
+    /// messages are consumed before reaching handlers and listeners. Scripts
+    /// carrying it only run in the top-level document, thus frames can't send
+    /// them, but it doesn't authenticate anything against the page itself.
+    message_prefix: String,
+    user_agent: Mutex<Option<String>>,
+    permission_rules: Mutex<PermissionRules>,
+    permission_store: Mutex<Option<Box<dyn PermissionStore>>>,
+    retry: Mutex<RetryState>,
+    /// Whether pages report their load status regardless of the retry policy,
+    /// for [`Webview::navigate`].
+    tracks_load_status: AtomicBool,
+    navigation: Mutex<Option<TrackedNavigation>>,
+    navigation_rules: Mutex<Option<NavigationRules>>,
+    content_fullscreen: AtomicBool,
+    muted: AtomicBool,
+    audio_playing: AtomicBool,
+    last_gesture: Mutex<Option<(InputGesture, Instant)>>,
+    /// When the page last started speech recognition.
+    speech_started: Mutex<Option<Instant>>,
+    pinch_zoom: AtomicBool,
+    timezone: Mutex<Option<String>>,
+    dark_fallback: AtomicBool,
+    force_dark: AtomicBool,
+    watchdog: Mutex<Watchdog>,
+    /// The interval and generation of page stats sampling.
+    page_stats: Mutex<(Option<Duration>, u64)>,
+    /// Whether the context menu was enabled before kiosk mode disabled it.
+    kiosk_context_menu: Mutex<Option<bool>>,
+    layout: Mutex<WebviewLayout>,
+    page_cache: Mutex<PageCache>,
+    script_queue: Mutex<ScriptQueue>,
+    streams: Mutex<HashMap<String, Arc<StreamShared>>>,
+    companion_bridges: Mutex<HashMap<String, CompanionBridge>>,
+    replies: Mutex<PendingReplies>,
+    /// The last authentication challenge (URL and realm), and how many times
+    /// in a row it has been passed to handlers.
+    auth_attempts: Mutex<Option<(String, u32)>>,
+    /// Handlers registered with [`Webview::with_temporary_scheme`].
+    temp_schemes: Mutex<HashMap<String, TemporarySchemeEntry>>,
+    /// File systems mounted with [`Webview::mount`], keyed by scheme.
+    mounts: Mutex<HashMap<String, Arc<dyn Vfs>>>,
+    next_temp_scheme: AtomicU64,
+    /// Updates of [`Webview::render_region`] yet to be flushed.
+    pending_regions: Mutex<Vec<(String, String)>>,
+    /// Bits of the [`WindowEventBridge`] in effect.
+    window_bridge: AtomicU32,
+    window_observed: AtomicBool,
+    dev_tools_policy: DevToolsPolicy,
+}
+
+type BoxedCompanionVetter = Box<dyn Fn(&str) -> Option<String> + Send + Sync + 'static>;
+
+/// A companion the page can send messages to, through a vetting handler.
+struct CompanionBridge {
+    companion: CompanionRef,
+    vet: BoxedCompanionVetter,
+}
+
+/// A snapshot of page states, maintained from events. See
+/// [`Webview::cached`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CachedWebviewState {
+    /// The URL as last reported by [`NavigatedEvent`].
+    pub url: Option<Arc<str>>,
+    /// The title as last reported by [`TitleEvent`].
+    pub title: Option<Arc<str>>,
+    /// Whether a load has started and not yet finished, as reported by
+    /// [`LoadEvent`].
+    pub is_loading: bool,
+    /// See [`Webview::is_audio_playing`].
+    pub is_audio_playing: bool,
+}
+
+/// The last known page states, as reported by events.
+#[derive(Default)]
+struct PageCache {
+    title: Option<Arc<str>>,
+    url: Option<Arc<str>>,
+    is_loading: bool,
+    changed: bool,
 }
 
 unsafe impl Send for RawWebview {}
@@ -48,18 +249,43 @@ unsafe impl Sync for RawWebview {}
 
 impl Drop for RawWebview {
     fn drop(&mut self) {
+        for s in self.streams.get_mut().unwrap().values() {
+            s.close(); // Pending executors must be dropped before the webview
+        }
+
+        for cb in self.replies.get_mut().unwrap().drain() {
+            cb(Err(crate::error::Error::Closed));
+        }
+
         let cleanup = CleanUpHolder::Webview {
             ptr: self.inner,
-            schemes: self.schemes.drain(..).collect(),
+            schemes: self
+                .schemes
+                .drain(..)
+                .chain(
+                    self.temp_schemes
+                        .get_mut()
+                        .unwrap()
+                        .drain()
+                        .map(|(name, _)| Cow::Owned(name)),
+                )
+                .chain(
+                    self.mounts
+                        .get_mut()
+                        .unwrap()
+                        .drain()
+                        .map(|(name, _)| Cow::Owned(name)),
+                )
+                .collect(),
             event_listener_data: self.event_listener_data,
             scheme_handler_data: self.scheme_handler_data,
         };
 
         if self.is_thread_safe() {
-            unsafe { cleanup.discard() }; // SAFETY: On the event thread
+            unsafe { self.drop_sender.discard(self.id, cleanup) }; // SAFETY: On the event thread
         } else {
             self.drop_sender
-                .send(cleanup)
+                .send(self.id, cleanup)
                 .expect("failed to post webview destruction");
         }
     }
@@ -80,29 +306,45 @@ impl RawWebview {
 #[derive(Clone)]
 pub struct Webview(Arc<RawWebview>);
 
+/// Handles are equal if they refer to the same webview.
+impl PartialEq for Webview {
+    fn eq(&self, other: &Self) -> bool { Arc::ptr_eq(&self.0, &other.0) }
+}
+
+impl Eq for Webview {}
+
+impl Hash for Webview {
+    fn hash<H: Hasher>(&self, state: &mut H) { self.id().hash(state) }
+}
+
+/// An identifier of a webview, obtained with [`Webview::id`].
+///
+/// IDs are unique in the process and never reused. They're the same as the
+/// IDs listed by [`crate::app::App::live_handles`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WebviewId(u64);
+
+impl WebviewId {
+    /// Gets the raw value of the ID.
+    pub fn get(self) -> u64 { self.0 }
+}
+
 impl Webview {
     ffi_forward! {
         /// Checks whether devtools is open.
-        pub fn has_dev_tools(&Self) -> bool => saucer_webview_dev_tools;
+        pub fn has_dev_tools(&Self) -> crate::error::Result<bool> => saucer_webview_dev_tools;
         /// Checks whether context menu is enabled.
-        pub fn has_context_menu(&Self) -> bool => saucer_webview_context_menu;
+        pub fn has_context_menu(&Self) -> crate::error::Result<bool> => saucer_webview_context_menu;
         /// Checks whether dark mode is enforced.
-        pub fn is_force_dark(&Self) -> bool => saucer_webview_force_dark;
+        pub fn is_force_dark(&Self) -> crate::error::Result<bool> => saucer_webview_force_dark;
     }
 
     ffi_forward! {
-        /// Toggles devtools.
-        pub fn set_dev_tools(&Self, enabled: bool) => saucer_webview_set_dev_tools;
+        @alive
         /// Sets whether to enable context menu.
         pub fn set_context_menu(&Self, enabled: bool) => saucer_webview_set_context_menu;
-        /// Sets whether to enforce dark mode.
-        pub fn set_force_dark(&Self, enabled: bool) => saucer_webview_set_force_dark;
         /// Sets the background color.
         pub fn set_background(&Self, r: u8, g: u8, b: u8, a: u8) => saucer_webview_set_background;
-        /// Reset webview bounds.
-        pub fn reset_bounds(&Self) => saucer_webview_reset_bounds;
-        /// Sets the webview bounds in the window.
-        pub fn set_bounds(&Self, x: i32, y: i32, w: i32, h: i32) => saucer_webview_set_bounds;
         /// Navigates back.
         pub fn back(&Self) => saucer_webview_back;
         /// Navigates forward.
@@ -112,11 +354,13 @@ impl Webview {
     }
 
     ffi_forward! {
+        @alive
         /// Removes all embedded items.
         pub fn unembed_all(&Self) => saucer_webview_unembed_all;
     }
 
     ffi_forward! {
+        @alive
         /// Removes all injected scripts.
         pub fn uninject_all(&Self) => saucer_webview_uninject_all;
     }
@@ -125,29 +369,57 @@ impl Webview {
     /// [`WebviewEventListener`], [`WebviewSchemeHandler`], and a list of
     /// schemes that this webview intend to handle. The scheme must be
     /// registered via [`crate::scheme::register_scheme`] before being used.
+    /// The `saucers` scheme is reserved for [`Webview::open_stream`] and must
+    /// not be claimed when [`crate::app::AppOptions::streams`] is enabled.
+    ///
+    /// The webview fills the window by default. A window can hold multiple
+    /// webviews, see [`Self::set_bounds`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, unless allowed by the
+    /// [`crate::thread::ThreadViolationPolicy`].
     pub fn new(
-        opt: WebviewOptions,
+        mut opt: WebviewOptions,
         window: Window,
         event_listener: impl WebviewEventListener + 'static,
         scheme_handler: impl WebviewSchemeHandler + 'static,
     ) -> crate::error::Result<Self> {
-        if !window.is_thread_safe() {
-            panic!("webviews must be created on the event thread");
+        if check_event_thread(window.is_thread_safe(), "creating webviews").is_some() {
+            return Err(crate::error::Error::WrongThread);
         }
 
         let ds = window.drop_sender();
         let w = window.clone();
-        let schemes = scheme_handler.schemes();
+        let mut schemes = scheme_handler.schemes();
+        let streams = window.app().is_some_and(|a| a.has_streams());
+        if streams {
+            schemes.push(STREAM_SCHEME.into());
+        }
+        let user_agent = opt.user_agent.clone();
+        let spellcheck = opt.spellcheck;
+        let spellcheck_languages = opt.spellcheck_languages.clone();
+        let transparent = opt.transparent;
+        let timezone = opt.timezone.take();
+        let dev_tools_policy = opt.dev_tools_policy;
+        let internal_scripts = opt.internal_scripts.clone();
+        let profile = opt.profile.clone();
+        opt.apply_profile();
+        opt.claim_browser_flags()?;
+        let runtime = opt.override_runtime()?;
         let mut ex = -1;
         let opt = RawWebviewOptions::new(opt, window);
         let ptr = unsafe { saucer_webview_new(opt.as_ptr(), &raw mut ex) };
+        drop(runtime); // The environment is created with the webview
         let wv = NonNull::new(ptr).ok_or(crate::error::Error::Saucer(ex))?;
+        let id = ds.track(HandleKind::Webview);
 
         let wv = Self(Arc::new_cyclic(|weak| {
             let webview = WebviewRef(weak.clone());
 
             RawWebview {
                 inner: wv,
+                id,
                 drop_sender: ds,
                 host_tid: std::thread::current().id(),
                 event_listener_data: Box::into_raw(Box::new(EventListenerData::new(
@@ -160,14 +432,56 @@ impl Webview {
                 ))),
                 schemes,
                 window: w,
+                managed_scripts: Mutex::new(HashMap::new()),
+                script_namespace: internal_scripts.namespace.clone(),
+                message_prefix: format!("__saucers_{}__:", crate::util::random_token()),
+                user_agent: Mutex::new(user_agent),
+                permission_rules: Mutex::new(PermissionRules::new()),
+                permission_store: Mutex::new(None),
+                retry: Mutex::new(RetryState::default()),
+                tracks_load_status: AtomicBool::new(false),
+                navigation: Mutex::new(None),
+                navigation_rules: Mutex::new(None),
+                content_fullscreen: AtomicBool::new(false),
+                muted: AtomicBool::new(false),
+                audio_playing: AtomicBool::new(false),
+                last_gesture: Mutex::new(None),
+                speech_started: Mutex::new(None),
+                pinch_zoom: AtomicBool::new(true),
+                timezone: Mutex::new(None),
+                dark_fallback: AtomicBool::new(false),
+                force_dark: AtomicBool::new(false),
+                watchdog: Mutex::new(Watchdog::default()),
+                page_stats: Mutex::new((None, 0)),
+                kiosk_context_menu: Mutex::new(None),
+                layout: Mutex::new(WebviewLayout::default()),
+                page_cache: Mutex::new(PageCache::default()),
+                script_queue: Mutex::new(ScriptQueue::default()),
+                streams: Mutex::new(HashMap::new()),
+                companion_bridges: Mutex::new(HashMap::new()),
+                replies: Mutex::default(),
+                auth_attempts: Mutex::new(None),
+                temp_schemes: Mutex::new(HashMap::new()),
+                mounts: Mutex::new(HashMap::new()),
+                next_temp_scheme: AtomicU64::new(0),
+                pending_regions: Mutex::new(Vec::new()),
+                window_bridge: AtomicU32::new(0),
+                window_observed: AtomicBool::new(false),
+                dev_tools_policy,
             }
         }));
         let data = wv.0.event_listener_data;
         let scheme_data = wv.0.scheme_handler_data;
 
         for s in &wv.0.schemes {
+            let tp = if s == STREAM_SCHEME {
+                handle_stream_scheme_tp
+            } else {
+                handle_scheme_tp
+            };
+
             use_string!(s: s.as_ref(); unsafe {
-               saucer_webview_handle_scheme(ptr, s, Some(handle_scheme_tp), scheme_data as *mut c_void)
+               saucer_webview_handle_scheme(ptr, s, Some(tp), scheme_data as *mut c_void)
             });
         }
 
@@ -190,33 +504,176 @@ impl Webview {
         bind_event!(SAUCER_WEBVIEW_EVENT_TITLE, ev_on_title_tp);
         bind_event!(SAUCER_WEBVIEW_EVENT_LOAD, ev_on_load_tp);
 
+        let _ = auth::install(&wv); // Challenges are left to the engine otherwise
+        let _ = certificate::install(&wv); // Loads fail with the engine's error page otherwise
+        let _ = crash::install(&wv); // Crashes are only seen by the watchdog otherwise
+        retry::install(&wv);
+
+        wv.set_managed_script("guard", Some(include_str!("scripts/guard.js").to_owned()));
+
+        let eager = [
+            ("audio", internal_scripts.audio),
+            ("input", internal_scripts.input),
+            ("speech", internal_scripts.speech),
+            ("activity", internal_scripts.activity),
+            ("streams", internal_scripts.streams),
+            ("console", internal_scripts.console),
+        ];
+
+        for (key, _) in eager.into_iter().filter(|(_, on)| *on) {
+            wv.enable_internal_script(key);
+        }
+
+        if let Some(profile) = profile {
+            wv.set_permission_store(Some(profile.permission_store()));
+        }
+
+        if transparent {
+            wv.set_background(0, 0, 0, 0);
+            wv.window().set_effect(WindowEffect::Transparent)?;
+        }
+
+        if let Some(enabled) = spellcheck {
+            wv.set_spellcheck(enabled);
+        }
+
+        if let Some(languages) = spellcheck_languages {
+            wv.set_spellcheck_languages(&languages)?;
+        }
+
+        if !dev_tools_policy.is_allowed() {
+            unsafe { saucer_webview_set_dev_tools(ptr, false) };
+        }
+
+        if let Some(zone) = timezone {
+            wv.set_timezone_override(Some(&zone));
+        }
+
+        if wv.window().cursor_override().is_some() {
+            wv.apply_window_cursor();
+        }
+
+        if wv.window().kiosk().is_some() {
+            wv.apply_window_kiosk();
+        }
+
+        if wv.window().menu().is_some() {
+            wv.apply_window_menu();
+        }
+
+        if let Some(app) = wv.window().app() {
+            let current = app.current_policies();
+            if current.policies.content.is_some() {
+                wv.apply_content_rules(current.policies.content.as_ref());
+            }
+
+            let webview = wv.downgrade();
+            app.subscribe_policies(move |p| {
+                let Some(w) = webview.upgrade() else {
+                    return false;
+                };
+
+                w.apply_content_rules(p.policies.content.as_ref());
+                true
+            });
+        }
+
+        wv.window().attach_webview(wv.downgrade());
+
+        let webview = wv.downgrade();
+        wv.window().observe(move |signal| {
+            let Some(w) = webview.upgrade() else {
+                return false;
+            };
+
+            match signal {
+                WindowSignal::Cursor => w.apply_window_cursor(),
+                WindowSignal::Progress => w.show_window_progress(),
+                WindowSignal::Kiosk => w.apply_window_kiosk(),
+                WindowSignal::Menu => w.apply_window_menu(),
+                WindowSignal::Edit(cmd) => w.run_edit_command(cmd, true),
+                _ => {}
+            }
+
+            true
+        });
+
         Ok(wv)
     }
 
+    /// Checks whether the webview is still alive, i.e. its window has not been
+    /// closed.
+    ///
+    /// State getters return [`crate::error::Error::Closed`] once the window is
+    /// closed, as the native state may have been freed. Navigating (e.g.
+    /// [`Self::set_url`], [`Self::reload`]) and [`Self::execute`] do nothing
+    /// then.
+    pub fn is_alive(&self) -> bool { self.0.window.is_alive() }
+
+    pub(crate) fn ensure_alive(&self) -> crate::error::Result<()> { self.0.window.ensure_alive() }
+
     /// Gets the URL.
     pub fn url(&self) -> crate::error::Result<Url> {
+        self.ensure_alive()?;
         let mut ex = -1;
         let ptr = unsafe { saucer_webview_url(self.as_ptr(), &raw mut ex) };
 
         unsafe { Url::from_ptr(ptr, ex) }
     }
 
+    /// Gets the page title as last reported by [`TitleEvent`], or [`None`] if
+    /// no title has been reported yet.
+    ///
+    /// Unlike [`Self::page_title`], this doesn't query the backend and only
+    /// clones a shared string, thus it's cheap enough to be polled (e.g. for
+    /// rendering tab strips).
+    pub fn cached_title(&self) -> Option<Arc<str>> {
+        self.0.page_cache.lock_unpoisoned().title.clone()
+    }
+
+    /// Gets the page URL as last reported by [`NavigatedEvent`], or [`None`]
+    /// if the webview hasn't navigated yet. Like [`Self::cached_title`], this
+    /// doesn't query the backend.
+    pub fn cached_url(&self) -> Option<Arc<str>> { self.0.page_cache.lock_unpoisoned().url.clone() }
+
+    /// Gets a snapshot of the page states as last reported by events. Like
+    /// [`Self::cached_title`], this doesn't query the backend, thus it's cheap
+    /// and safe to call from any thread.
+    pub fn cached(&self) -> CachedWebviewState {
+        let cache = self.0.page_cache.lock_unpoisoned();
+        CachedWebviewState {
+            url: cache.url.clone(),
+            title: cache.title.clone(),
+            is_loading: cache.is_loading,
+            is_audio_playing: self.is_audio_playing(),
+        }
+    }
+
+    /// Checks whether the cached title or URL has changed since the last call,
+    /// and clears the flag.
+    pub fn take_page_changed(&self) -> bool {
+        std::mem::take(&mut self.0.page_cache.lock_unpoisoned().changed)
+    }
+
     /// Gets the favicon.
-    pub fn favicon(&self) -> Icon {
-        unsafe { Icon::from_ptr(saucer_webview_favicon(self.as_ptr())) }
+    pub fn favicon(&self) -> crate::error::Result<Icon> {
+        self.ensure_alive()?;
+        Ok(unsafe { Icon::from_ptr(saucer_webview_favicon(self.as_ptr())) })
     }
 
     /// Gets the page title.
-    pub fn page_title(&self) -> String {
+    pub fn page_title(&self) -> crate::error::Result<String> {
+        self.ensure_alive()?;
         let buf = load_range!(ptr[size] = 0u8; {
             unsafe { saucer_webview_page_title(self.as_ptr(), ptr as *mut c_char, size) }
         });
 
-        String::from_utf8_lossy(&buf).into_owned()
+        Ok(String::from_utf8_lossy(&buf).into_owned())
     }
 
     /// Gets the background color.
-    pub fn background(&self) -> (u8, u8, u8, u8) {
+    pub fn background(&self) -> crate::error::Result<(u8, u8, u8, u8)> {
+        self.ensure_alive()?;
         let mut r = 0;
         let mut g = 0;
         let mut b = 0;
@@ -232,46 +689,68 @@ impl Webview {
             )
         }
 
-        (r, g, b, a)
-    }
-
-    /// Sets the webview bounds in the window.
-    pub fn bounds(&self) -> (i32, i32, i32, i32) {
-        let mut x = 0;
-        let mut y = 0;
-        let mut w = 0;
-        let mut h = 0;
-
-        unsafe {
-            saucer_webview_bounds(
-                self.as_ptr(),
-                &raw mut x,
-                &raw mut y,
-                &raw mut w,
-                &raw mut h,
-            )
-        }
-
-        (x, y, w, h)
+        Ok((r, g, b, a))
     }
 
     /// Navigates to the given URL.
     pub fn set_url(&self, url: impl AsRef<Url>) {
+        if !self.is_alive() {
+            return;
+        }
+
         unsafe { saucer_webview_set_url(self.as_ptr(), url.as_ref().as_ptr()) } // Value copied
     }
 
     /// Navigates to the given URL.
     pub fn set_url_str(&self, url: impl Into<Vec<u8>>) {
+        if !self.is_alive() {
+            return;
+        }
+
         use_string!(url; unsafe { saucer_webview_set_url_str(self.as_ptr(), url) });
     }
 
+    /// Validates the given URL and navigates to it.
+    ///
+    /// Unlike [`Self::set_url_str`], which passes the input to the backend
+    /// as-is, this method rejects input that can't be parsed, that has no
+    /// scheme, or that has no host when the scheme requires one (`http`,
+    /// `https`). Use this for addresses entered by users.
+    pub fn try_set_url(&self, url: &str) -> crate::error::Result<()> {
+        let invalid = || crate::error::Error::InvalidUrl(url.to_owned());
+
+        if url.contains('\0') {
+            return Err(invalid());
+        }
+
+        let parsed = Url::new_parse(url).map_err(|_| invalid())?;
+        let scheme = parsed.scheme();
+
+        if scheme.is_empty()
+            || (matches!(scheme.as_str(), "http" | "https") && parsed.host().is_empty())
+        {
+            return Err(invalid());
+        }
+
+        self.set_url(parsed);
+        Ok(())
+    }
+
     /// Sets the HTML content.
     pub fn set_html(&self, html: impl Into<Vec<u8>>) {
+        if !self.is_alive() {
+            return;
+        }
+
         use_string!(html; unsafe { saucer_webview_set_html(self.as_ptr(), html) });
     }
 
     /// Navigates to the embedded content specified by the path.
     pub fn serve(&self, path: impl Into<Vec<u8>>) {
+        if !self.is_alive() {
+            return;
+        }
+
         use_string!(path; unsafe { saucer_webview_serve(self.as_ptr(), path) });
     }
 
@@ -292,11 +771,80 @@ impl Webview {
         use_string!(path; unsafe { saucer_webview_unembed(self.as_ptr(), path) });
     }
 
-    /// Executes JavaScript code.
+    /// Executes JavaScript code. If the DOM of the document is not ready yet,
+    /// the bindings defer the script until it is, thus it may run in the page
+    /// navigated to next. See [`Self::execute_now`] and
+    /// [`Self::execute_on_ready`] for explicit control.
     pub fn execute(&self, js: impl Into<Vec<u8>>) {
+        if !self.is_alive() {
+            return;
+        }
+
         use_string!(js; unsafe { saucer_webview_execute(self.as_ptr(), js) });
     }
 
+    /// Gets the native webview of the backend, for implementing platform
+    /// features the bindings don't cover:
+    ///
+    /// | Backend | Type |
+    /// |---|---|
+    /// | WebView2 | `ICoreWebView2Controller *` |
+    /// | WebKit | `WKWebView *` |
+    /// | WebKitGTK | `WebKitWebView *` |
+    /// | Qt | `QWebEngineView *` |
+    ///
+    /// See [`Window::native_handle`] for the window.
+    ///
+    /// # Safety
+    ///
+    /// The webview is owned by the backend. Callers must not free it, and
+    /// must only use it on the event thread while the webview is alive.
+    /// Replacing delegates or event handlers of the backend breaks the events
+    /// of the bindings, and changing states they keep track of (e.g. the user
+    /// agent) leaves them out of sync.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, unless allowed by the
+    /// [`crate::thread::ThreadViolationPolicy`].
+    #[cfg(feature = "native-handles")]
+    pub unsafe fn native_controller(&self) -> crate::error::Result<NonNull<c_void>> {
+        if check_event_thread(self.0.is_thread_safe(), "getting native handles").is_some() {
+            return Err(crate::error::Error::WrongThread);
+        }
+
+        self.query_native_controller()
+    }
+
+    /// Gets the native webview without checking the thread.
+    #[allow(unused)] // Not used by all backends
+    pub(crate) fn query_native_controller(&self) -> crate::error::Result<NonNull<c_void>> {
+        self.ensure_alive()?;
+
+        crate::window::read_native(|r, s| unsafe { saucer_webview_native(self.as_ptr(), 0, r, s) })
+            .ok_or_else(|| crate::error::Error::unsupported("native webview handles", None))
+    }
+
+    /// Runs an editing command in the focused element of the page, as the
+    /// matching keyboard shortcut would, e.g. to drive the page from menu
+    /// items (see [`crate::menu::MenuBuilder::edit_item`]). Focused frames of
+    /// the same origin are descended into.
+    ///
+    /// Commands go through `document.execCommand`, thus they work in input
+    /// fields and editable content. Engines may refuse to paste from it, in
+    /// which case the clipboard is read with `navigator.clipboard`, which may
+    /// require the [`crate::permission::PermissionType::Clipboard`] permission.
+    /// Nothing happens if the command can't be run.
+    pub fn exec_command(&self, command: EditCommand) { self.run_edit_command(command, false); }
+
+    fn run_edit_command(&self, command: EditCommand, focused_only: bool) {
+        let js = include_str!("scripts/edit.js")
+            .replace("__COMMAND__", &js_string(command.js_name()))
+            .replace("__FOCUSED_ONLY__", &focused_only.to_string());
+
+        self.execute(js);
+    }
+
     /// Schedules specified JavaScript code to be executed when the page loads.
     pub fn inject(
         &self,
@@ -317,203 +865,2081 @@ impl Webview {
         unsafe { saucer_webview_uninject(self.as_ptr(), id.as_usize()) }
     }
 
-    /// Gets the parent window.
-    pub fn window(&self) -> Window { self.0.window.clone() }
-
-    /// Gets a weak [`WebviewRef`].
-    pub fn downgrade(&self) -> WebviewRef { WebviewRef(Arc::downgrade(&self.0)) }
+    /// Sets the policy of the given permission type for all origins. See
+    /// [`PermissionRules`] for details.
+    pub fn set_permission_policy(&self, kind: PermissionType, policy: Policy) {
+        self.0.permission_rules.lock_unpoisoned().set(kind, policy);
+    }
 
-    pub(crate) fn as_ptr(&self) -> *mut saucer_webview { self.0.inner.as_ptr() }
-}
+    /// Replaces the permission rules consulted before firing permission events.
+    /// The permission table of the app (see
+    /// [`crate::app::App::apply_policies`]) takes precedence.
+    pub fn set_permission_rules(&self, rules: PermissionRules) {
+        *self.0.permission_rules.lock_unpoisoned() = rules;
+    }
 
-/// A weak webview handle.
-///
-/// Like [`crate::app::AppRef`], this handle does not prevent deallocation and
-/// can be used in various listeners.
-#[derive(Clone)]
-pub struct WebviewRef(Weak<RawWebview>);
+    /// Gets a copy of the permission rules.
+    pub fn permission_rules(&self) -> PermissionRules {
+        self.0.permission_rules.lock_unpoisoned().clone()
+    }
 
-impl WebviewRef {
-    /// Tries to upgrade to a strong handle.
-    pub fn upgrade(&self) -> Option<Webview> { Some(Webview(self.0.upgrade()?)) }
-}
+    /// Checks whether the page has an element in fullscreen, regardless of
+    /// whether the window followed it. See
+    /// [`WebviewEventListener::on_fullscreen`].
+    pub fn is_content_fullscreen(&self) -> bool {
+        self.0.content_fullscreen.load(Ordering::Relaxed)
+    }
 
-pub(crate) struct SchemeHandlerData {
-    handler: Box<dyn WebviewSchemeHandler + 'static>,
-    webview: WebviewRef,
-}
+    /// Makes the page exit element fullscreen, if any. The fullscreen event is
+    /// fired as the page exits.
+    pub fn exit_content_fullscreen(&self) {
+        self.execute("if (document.fullscreenElement) void document.exitFullscreen();");
+    }
 
-impl SchemeHandlerData {
-    fn new(handler: impl WebviewSchemeHandler + 'static, webview: WebviewRef) -> Self {
-        Self {
-            handler: Box::new(handler),
-            webview,
-        }
+    /// Sets whether users can zoom the page visually with pinch gestures or
+    /// Ctrl + mouse wheel. Enabled by default.
+    ///
+    /// Both are handled together, as engines report touchpad pinches as
+    /// Ctrl + wheel events. Zooming via keyboard shortcuts and the page's own
+    /// zoom (e.g. CSS `zoom`) are not affected.
+    pub fn set_pinch_zoom(&self, enabled: bool) {
+        self.0.pinch_zoom.store(enabled, Ordering::Relaxed);
+
+        let js = format!(
+            r#"(() => {{
+                const zoom = (window.__saucersZoom ??= (() => {{
+                    const self = {{ enabled: true }};
+                    const block = (e) => {{
+                        if (!self.enabled) e.preventDefault();
+                    }};
+                    window.addEventListener("wheel", (e) => e.ctrlKey && block(e), {{ passive: false }});
+                    window.addEventListener("touchmove", (e) => e.touches.length > 1 && block(e), {{ passive: false }});
+                    window.addEventListener("gesturestart", block, {{ passive: false }});
+                    return self;
+                }})());
+                zoom.enabled = {enabled};
+            }})();"#
+        );
+
+        self.set_managed_script("pinch-zoom", Some(js));
     }
-}
 
-pub(crate) struct EventListenerData {
-    listener: Box<dyn WebviewEventListener + 'static>,
-    webview: WebviewRef,
-}
+    /// Checks whether pinch zooming is enabled. See [`Self::set_pinch_zoom`].
+    pub fn is_pinch_zoom_enabled(&self) -> bool { self.0.pinch_zoom.load(Ordering::Relaxed) }
 
-impl EventListenerData {
-    fn new(listener: impl WebviewEventListener + 'static, webview: WebviewRef) -> Self {
-        Self {
-            listener: Box::new(listener),
-            webview,
+    /// Toggles devtools. Enabling them does nothing if they're not allowed by
+    /// [`WebviewOptions::dev_tools_policy`].
+    pub fn set_dev_tools(&self, enabled: bool) {
+        if enabled && !self.0.dev_tools_policy.is_allowed() {
+            return;
         }
+
+        unsafe { saucer_webview_set_dev_tools(self.as_ptr(), enabled) };
     }
-}
 
-extern "C" fn ev_on_permission_tp(
-    _: *mut saucer_webview,
-    req: *mut saucer_permission_request,
-    data: *mut c_void,
-) -> saucer_status {
-    let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback(HandleStatus::Unhandled.into(), || {
-        let req = unsafe { PermissionRequest::from_ptr(saucer_permission_request_copy(req)) };
+    /// Gets the devtools policy the webview is created with.
+    pub fn dev_tools_policy(&self) -> DevToolsPolicy { self.0.dev_tools_policy }
 
-        let ret = if let Some(w) = data.webview.upgrade() {
-            data.listener.on_permission(w.clone(), req)
-        } else {
-            HandleStatus::Unhandled
-        };
+    /// Opens the devtools panel. Same as `set_dev_tools(true)`.
+    pub fn open_dev_tools(&self) { self.set_dev_tools(true) }
 
-        ret.into()
-    })
-}
+    /// Closes the devtools panel. Same as `set_dev_tools(false)`.
+    pub fn close_dev_tools(&self) { self.set_dev_tools(false) }
 
-extern "C" fn ev_on_fullscreen_tp(
-    _: *mut saucer_webview,
-    is_fullscreen: bool,
-    data: *mut c_void,
-) -> saucer_policy {
-    let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback(Policy::Allow.into(), || {
-        let ret = if let Some(w) = data.webview.upgrade() {
-            data.listener.on_fullscreen(w.clone(), is_fullscreen)
-        } else {
-            Policy::Allow
-        };
+    /// Sets whether to enforce dark mode.
+    ///
+    /// Some backends can't force dark mode and ignore this silently, unless
+    /// the fallback is enabled via [`Self::set_dark_fallback`].
+    pub fn set_force_dark(&self, enabled: bool) {
+        unsafe { saucer_webview_set_force_dark(self.as_ptr(), enabled) }
+        self.0.force_dark.store(enabled, Ordering::Relaxed);
+        self.update_dark_fallback();
+    }
 
-        ret.into()
-    })
-}
+    /// Sets whether [`Self::set_force_dark`] falls back to emulating dark mode
+    /// when the backend can't force it natively. Disabled by default.
+    ///
+    /// The backend is considered incapable if [`Self::is_force_dark`] doesn't
+    /// report the enforced state. The emulation reports a dark
+    /// `prefers-color-scheme` to `matchMedia` and inverts the page colors
+    /// (except media) with a CSS filter, unless the page declares itself as
+    /// dark-only. Styles under `prefers-color-scheme` media queries are not
+    /// switched, as they can't be overridden from scripts.
+    pub fn set_dark_fallback(&self, enabled: bool) {
+        self.0.dark_fallback.store(enabled, Ordering::Relaxed);
+        self.update_dark_fallback();
+    }
 
-extern "C" fn ev_on_dom_ready_tp(_: *mut saucer_webview, data: *mut c_void) {
-    let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback((), || {
-        if let Some(w) = data.webview.upgrade() {
-            data.listener.on_dom_ready(w.clone());
+    /// Checks whether the dark mode fallback is enabled. See
+    /// [`Self::set_dark_fallback`].
+    pub fn has_dark_fallback(&self) -> bool { self.0.dark_fallback.load(Ordering::Relaxed) }
+
+    /// Enables the emulated dark mode if dark mode is requested but not
+    /// enforced by the backend, or disables it otherwise.
+    fn update_dark_fallback(&self) {
+        let emulate = self.0.dark_fallback.load(Ordering::Relaxed)
+            && self.0.force_dark.load(Ordering::Relaxed)
+            && !self.is_force_dark().unwrap_or(false);
+
+        let installed = self
+            .0
+            .managed_scripts
+            .lock_unpoisoned()
+            .contains_key("dark");
+        if !emulate && !installed {
+            return;
         }
-    });
-}
 
-extern "C" fn ev_on_navigated_tp(_: *mut saucer_webview, url: *mut saucer_url, data: *mut c_void) {
-    let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback((), || {
-        let url = unsafe {
-            Url::from_ptr(saucer_url_copy(url), -1).expect("navigation target URL should exist")
-        };
+        let js = include_str!("scripts/dark.js").replace("__ENABLED__", &emulate.to_string());
+        self.set_managed_script("dark", Some(js));
+    }
 
-        if let Some(w) = data.webview.upgrade() {
-            data.listener.on_navigated(w.clone(), url);
-        }
-    });
-}
+    /// Overrides the timezone seen by the page, given as an IANA name (e.g.
+    /// `"UTC"` or `"Asia/Tokyo"`). Passing [`None`] restores the system
+    /// timezone.
+    ///
+    /// None of the backends expose a native override, so this is done by
+    /// patching `Intl.DateTimeFormat`, `Date#getTimezoneOffset`, the local
+    /// `Date` getters (e.g. `getHours`) and `Date#toLocale*String` in the main
+    /// world. Parsing and constructing dates from local components, the local
+    /// setters and `Date#toString` still use the system timezone. Unknown
+    /// names are ignored by the page.
+    pub fn set_timezone_override(&self, zone: Option<&str>) {
+        *self.0.timezone.lock_unpoisoned() = zone.map(ToOwned::to_owned);
+
+        let zone = zone.map_or_else(|| "null".to_owned(), js_string);
+        let js = include_str!("scripts/timezone.js").replace("__ZONE__", &zone);
+
+        self.set_managed_script("timezone", Some(js));
+    }
 
-extern "C" fn ev_on_navigate_tp(
-    _: *mut saucer_webview,
-    nav: *mut saucer_navigation,
-    data: *mut c_void,
-) -> saucer_policy {
-    let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback(Policy::Allow.into(), || {
-        let nav = unsafe { Navigation::from_ptr(nav) }; // SAFETY: It can't be moved out
+    /// Gets the timezone set via [`Self::set_timezone_override`].
+    pub fn timezone_override(&self) -> Option<String> { self.0.timezone.lock_unpoisoned().clone() }
 
-        let ret = if let Some(w) = data.webview.upgrade() {
-            data.listener.on_navigate(w.clone(), &nav)
-        } else {
-            Policy::Allow
-        };
+    /// Sets whether the page is muted.
+    ///
+    /// Muting is done in the page by muting media elements and suspending
+    /// `AudioContext`s, and is restored when unmuting. Media muted by the page
+    /// itself stays muted.
+    pub fn set_muted(&self, muted: bool) {
+        self.0.muted.store(muted, Ordering::Relaxed);
 
-        let _ = nav; // Ensure it's not moved
-        ret.into()
-    })
-}
+        let js = include_str!("scripts/audio.js")
+            .replace("__PREFIX__", &self.internal_prefix("audio-state:"))
+            .replace("__MUTED__", if muted { "true" } else { "false" });
 
-extern "C" fn ev_on_message_tp(
-    _: *mut saucer_webview,
-    msg: *mut c_char,
-    size: usize,
-    data: *mut c_void,
-) -> saucer_status {
-    let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback(HandleStatus::Unhandled.into(), || {
-        let s = unsafe { std::slice::from_raw_parts_mut(msg as *mut u8, size) };
-        let s = String::from_utf8_lossy(s);
+        self.set_managed_script("audio", Some(js));
+    }
 
-        let ret = if let Some(w) = data.webview.upgrade() {
-            data.listener.on_message(w.clone(), s)
-        } else {
-            HandleStatus::Unhandled
-        };
+    /// Sets how long the user must not interact with the page before
+    /// [`UserActivity::Idle`] is reported. Defaults to one minute.
+    pub fn set_user_idle_timeout(&self, timeout: Duration) {
+        let js = include_str!("scripts/activity.js")
+            .replace("__PREFIX__", &self.internal_prefix("activity:"))
+            .replace("__IDLE_MS__", &timeout.as_millis().to_string());
 
-        ret.into()
-    })
-}
+        self.set_managed_script("activity", Some(js));
+    }
 
-extern "C" fn ev_on_request_tp(_: *mut saucer_webview, req: *mut saucer_url, data: *mut c_void) {
-    let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback((), || {
-        let url =
-            unsafe { Url::from_ptr(saucer_url_copy(req), -1).expect("request URL should exist") };
+    /// Checks whether the page is muted via [`Self::set_muted`].
+    pub fn is_muted(&self) -> bool { self.0.muted.load(Ordering::Relaxed) }
 
-        if let Some(w) = data.webview.upgrade() {
-            data.listener.on_request(w.clone(), url);
+    /// Checks whether the page is playing media, as last reported by it. See
+    /// [`AudioStateEvent`].
+    pub fn is_audio_playing(&self) -> bool { self.0.audio_playing.load(Ordering::Relaxed) }
+
+    /// Sets the store of remembered permission decisions. Passing [`None`]
+    /// removes the store.
+    pub fn set_permission_store(&self, store: Option<Box<dyn PermissionStore>>) {
+        if store.is_some() {
+            self.enable_internal_script("speech");
         }
-    });
-}
 
-extern "C" fn ev_on_favicon_tp(
-    _: *mut saucer_webview,
-    favicon: *mut saucer_icon,
-    data: *mut c_void,
-) {
-    let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback((), || {
-        let icon = unsafe { Icon::from_ptr(saucer_icon_copy(favicon)) };
+        *self.0.permission_store.lock_unpoisoned() = store;
+    }
 
-        if let Some(w) = data.webview.upgrade() {
-            data.listener.on_favicon(w.clone(), icon);
+    /// Invokes the callback with the permission store, if any.
+    pub fn with_permission_store<R>(
+        &self,
+        f: impl FnOnce(&mut dyn PermissionStore) -> R,
+    ) -> Option<R> {
+        let mut store = self.0.permission_store.lock_unpoisoned();
+        Some(f(store.as_deref_mut()?))
+    }
+
+    /// Resolves the permission request with the given policy and remembers the
+    /// decision in the permission store, if any. Handlers calling this should
+    /// return [`HandleStatus::Handled`].
+    pub fn remember_permission(
+        &self,
+        req: &PermissionRequest,
+        policy: Policy,
+    ) -> crate::error::Result<()> {
+        req.clone().accept(policy == Policy::Allow);
+        self.with_permission_store(|s| s.set(&req.url().origin(), req.kind(), policy))
+            .unwrap_or(Ok(()))
+    }
+
+    /// Makes elements matching the given CSS selector drag the window, which is
+    /// useful for custom titlebars. Double-clicking them toggles maximization.
+    /// Passing [`None`] disables dragging.
+    ///
+    /// Interactive elements (buttons, inputs, links, etc.) and elements with
+    /// the `data-saucers-no-drag` attribute inside drag regions don't drag.
+    /// Elements with the `data-saucers-resize` attribute resize the window from
+    /// the edge given as its value (`top`, `bottom-left`, etc.), regardless of
+    /// the selector, until dragging is disabled.
+    ///
+    /// Only pointer input of the user drags or resizes the window, and the
+    /// page can't move the window on its own while dragging is disabled.
+    pub fn set_drag_regions(&self, selector: Option<&str>) {
+        let js = include_str!("scripts/drag.js")
+            .replace("__PREFIX__", &self.internal_prefix("window:"))
+            .replace(
+                "__SELECTOR__",
+                &selector.map_or("null".to_owned(), js_string),
+            );
+
+        self.set_managed_script("drag", Some(js));
+
+        // The script clears the selector on the current page, but future pages
+        // don't need it
+        if selector.is_none() {
+            self.set_managed_script("drag", None);
         }
-    });
-}
+    }
 
-extern "C" fn ev_on_title_tp(
-    _: *mut saucer_webview,
-    title: *mut c_char,
-    size: usize,
+    /// Replaces the content of the element matching the CSS selector with the
+    /// HTML, e.g. for updating parts of a page driven by Rust templates.
+    ///
+    /// Rather than replacing the whole content, the existing nodes are patched
+    /// to match the HTML, which keeps focus, text selection and user input of
+    /// unchanged parts. Updates made in the same event loop iteration are
+    /// coalesced into one script, and only the last update of each region
+    /// applies. Regions not found in the page are skipped.
+    ///
+    /// The HTML is inserted as is, thus it must be escaped properly if it
+    /// contains untrusted data. Scripts in it are not run.
+    pub fn render_region(&self, selector: &str, html: impl Into<String>) {
+        let html = html.into();
+        let schedule = {
+            let mut pending = self.0.pending_regions.lock_unpoisoned();
+            let schedule = pending.is_empty();
+
+            match pending.iter_mut().find(|(s, _)| s == selector) {
+                Some((_, h)) => *h = html,
+                None => pending.push((selector.to_owned(), html)),
+            }
+
+            schedule
+        };
+
+        if !schedule {
+            return;
+        }
+
+        if let Some(app) = self.window().app() {
+            let webview = self.downgrade();
+            app.post(move |_| {
+                if let Some(w) = webview.upgrade() {
+                    w.flush_regions();
+                }
+            });
+        }
+    }
+
+    /// Applies updates made with [`Self::render_region`].
+    fn flush_regions(&self) {
+        let pending = std::mem::take(&mut *self.0.pending_regions.lock_unpoisoned());
+        if pending.is_empty() {
+            return;
+        }
+
+        if !self
+            .0
+            .managed_scripts
+            .lock_unpoisoned()
+            .contains_key("regions")
+        {
+            self.set_managed_script(
+                "regions",
+                Some(include_str!("scripts/regions.js").to_owned()),
+            );
+        }
+
+        let updates = pending
+            .iter()
+            .map(|(s, h)| format!("[{},{}]", js_string(s), js_string(h)))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let js = format!(
+            "window.{}Regions.render([{updates}]);",
+            self.0.script_namespace
+        );
+        self.execute(js.as_str());
+    }
+
+    /// Confines keyboard focus to the element matching the CSS selector, e.g.
+    /// a dialog or a kiosk overlay. Passing [`None`] releases focus.
+    ///
+    /// Tab and Shift+Tab cycle through focusable elements inside the element,
+    /// and focus moving out of it (e.g. by clicking elsewhere) is moved back.
+    /// Tab order within the element follows the document and `tabindex` as
+    /// usual. This only affects the page, see
+    /// [`crate::window::Window::set_focus_trap`] for keeping the window
+    /// focused.
+    pub fn set_focus_trap(&self, selector: Option<&str>) {
+        let js = include_str!("scripts/focus.js").replace(
+            "__SELECTOR__",
+            &selector.map_or("null".to_owned(), js_string),
+        );
+
+        self.set_managed_script("focus", Some(js));
+    }
+
+    /// Mirrors the given window events into the page as DOM
+    /// `CustomEvent`s, so that frontend code can react to native window
+    /// states. Passing an empty set stops mirroring. See
+    /// [`WindowEventBridge`] for the events dispatched.
+    ///
+    /// Events only report changes. Pages loaded later don't receive the
+    /// current states.
+    pub fn set_window_event_bridge(&self, events: WindowEventBridge) {
+        self.0.window_bridge.store(events.bits(), Ordering::Relaxed);
+
+        let js = (!events.is_empty()).then(|| {
+            include_str!("scripts/window.js").replace(
+                "__COLOR_SCHEME__",
+                &events.contains(WindowEventBridge::COLOR_SCHEME).to_string(),
+            )
+        });
+
+        self.set_managed_script("window", js);
+
+        if events.is_empty() || self.0.window_observed.swap(true, Ordering::Relaxed) {
+            return;
+        }
+
+        let webview = self.downgrade();
+        self.window().observe(move |signal| {
+            let Some(w) = webview.upgrade() else {
+                return false;
+            };
+
+            w.bridge_window_signal(signal);
+            true
+        });
+    }
+
+    /// Applies the cursor set with [`Window::set_cursor`] to the page.
+    fn apply_window_cursor(&self) {
+        let cursor = self.window().cursor_override();
+        let js = include_str!("scripts/cursor.js").replace(
+            "__CURSOR__",
+            &cursor.as_deref().map_or("null".to_owned(), js_string),
+        );
+
+        self.set_managed_script("cursor", Some(js));
+
+        // The script clears the override on the current page, but future pages
+        // don't need it
+        if cursor.is_none() {
+            self.set_managed_script("cursor", None);
+        }
+    }
+
+    /// Applies accelerators of the menu of the window to the page. See
+    /// [`Window::set_menu_accelerators`].
+    fn apply_window_menu(&self) {
+        let accelerators = self
+            .window()
+            .menu()
+            .map(|m| {
+                // Editing shortcuts are left to the page, which handles them natively
+                m.actions()
+                    .filter(|a| a.edit_command().is_none())
+                    .filter_map(|a| {
+                        let acc = a.accelerator()?;
+                        let m = acc.modifiers;
+                        Some(format!(
+                            "[{},{},{},{},{},{}]",
+                            a.id().get(),
+                            js_string(&acc.key),
+                            m.ctrl,
+                            m.alt,
+                            m.shift,
+                            m.meta
+                        ))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let js = include_str!("scripts/menu.js")
+            .replace("__ACCELERATORS__", &format!("[{}]", accelerators.join(",")))
+            .replace("__PREFIX__", &self.internal_prefix("menu:"));
+
+        self.set_managed_script("menu", Some(js));
+
+        // The script clears accelerators of the current page, but future pages
+        // don't need it
+        if accelerators.is_empty() {
+            self.set_managed_script("menu", None);
+        }
+    }
+
+    /// Applies the kiosk mode of the window. See [`Window::set_kiosk_with`].
+    fn apply_window_kiosk(&self) {
+        let options = self.window().kiosk();
+
+        {
+            let mut saved = self.0.kiosk_context_menu.lock_unpoisoned();
+            if options.is_some_and(|o| o.disable_context_menu) {
+                if saved.is_none() {
+                    *saved = Some(self.has_context_menu().unwrap_or(true));
+                }
+
+                self.set_context_menu(false);
+            } else if let Some(enabled) = saved.take() {
+                self.set_context_menu(enabled);
+            }
+        }
+
+        if options.is_some_and(|o| o.disable_dev_tools) {
+            self.set_dev_tools(false);
+        }
+
+        let locked = options.is_some_and(|o| !o.allow_escape);
+        let js = include_str!("scripts/kiosk.js").replace("__LOCKED__", &locked.to_string());
+        self.set_managed_script("kiosk", Some(js));
+
+        // The script unlocks the current page, but future pages don't need it
+        if !locked {
+            self.set_managed_script("kiosk", None);
+        }
+    }
+
+    /// Updates the progress toast of the page. See
+    /// [`Window::set_progress_toast`].
+    fn show_window_progress(&self) {
+        let window = self.window();
+        let progress = window
+            .progress_state()
+            .filter(|p| window.has_progress_toast() && !p.finished)
+            .map_or("null".to_owned(), |p| {
+                format!(
+                    "{{label:{},value:{}}}",
+                    js_string(&p.label),
+                    p.value.map_or("null".to_owned(), |v| v.to_string())
+                )
+            });
+
+        let js = include_str!("scripts/progress.js").replace("__PROGRESS__", &progress);
+        self.execute(js.replace(
+            "window.__saucers",
+            &format!("window.{}", self.0.script_namespace),
+        ));
+    }
+
+    /// Applies content rules of the app to the page.
+    fn apply_content_rules(&self, rules: Option<&ContentRules>) {
+        let rules = rules.filter(|r| !r.is_empty());
+        let js = include_str!("scripts/content.js").replace(
+            "__RULES__",
+            &rules.map_or("[]".to_owned(), ContentRules::to_js),
+        );
+
+        self.set_managed_script("content", Some(js));
+
+        // The script clears the rules on the current page, but future pages
+        // don't need it
+        if rules.is_none() {
+            self.set_managed_script("content", None);
+        }
+    }
+
+    /// Gets the window events mirrored into the page. See
+    /// [`Self::set_window_event_bridge`].
+    pub fn window_event_bridge(&self) -> WindowEventBridge {
+        WindowEventBridge::from_bits_truncate(self.0.window_bridge.load(Ordering::Relaxed))
+    }
+
+    fn bridge_window_signal(&self, signal: WindowSignal) {
+        let events = self.window_event_bridge();
+
+        let (name, detail) = match signal {
+            WindowSignal::Focus(f) if events.contains(WindowEventBridge::FOCUS) => {
+                ("focus", format!("{{focused:{f}}}"))
+            }
+            WindowSignal::Resize(s) if events.contains(WindowEventBridge::RESIZE) => (
+                "resize",
+                format!("{{width:{},height:{}}}", s.width, s.height),
+            ),
+            WindowSignal::Maximize(m) if events.contains(WindowEventBridge::MAXIMIZE) => {
+                ("maximize", format!("{{maximized:{m}}}"))
+            }
+            WindowSignal::Minimize(m) if events.contains(WindowEventBridge::MINIMIZE) => {
+                ("minimize", format!("{{minimized:{m}}}"))
+            }
+            _ => return,
+        };
+
+        self.execute(format!(
+            "window.{}Window?.dispatch({},{detail});",
+            self.0.script_namespace,
+            js_string(name)
+        ));
+    }
+
+    /// Sets the rules filtering navigations. Passing [`None`] removes the
+    /// rules. See [`NavigationRules`] for details.
+    ///
+    /// Navigations blocked by the rules of the app (see
+    /// [`crate::app::App::apply_policies`]) are blocked regardless.
+    pub fn set_navigation_rules(&self, rules: Option<NavigationRules>) {
+        *self.0.navigation_rules.lock_unpoisoned() = rules;
+    }
+
+    /// Sets the policy for retrying failed loads. Passing [`None`] disables
+    /// retrying. See [`Retry`] for how failures are detected.
+    pub fn set_retry_policy(&self, policy: Option<Retry>) {
+        self.0.retry.lock_unpoisoned().policy = policy;
+        self.update_load_status_script();
+    }
+
+    /// Installs the script reporting the load status if the retry policy or
+    /// [`Self::navigate`] needs it, or removes it otherwise.
+    fn update_load_status_script(&self) {
+        let enabled = self.0.retry.lock_unpoisoned().policy.is_some()
+            || self.0.tracks_load_status.load(Ordering::Relaxed);
+
+        let js = enabled.then(|| {
+            format!(
+                r#"(() => {{
+                    if (window.top !== window) return;
+                    document.addEventListener("DOMContentLoaded", () => {{
+                        const nav = performance.getEntriesByType("navigation")[0];
+                        const status = (nav && nav.responseStatus) || 0;
+                        window.saucer.internal.message({} + status);
+                    }});
+                }})();"#,
+                self.internal_prefix("load-status:")
+            )
+        });
+
+        self.set_managed_script("retry", js);
+    }
+
+    /// Gets the policy for retrying failed loads.
+    pub fn retry_policy(&self) -> Option<Retry> { self.0.retry.lock_unpoisoned().policy.clone() }
+
+    /// Checks the finished load of the given generation and schedules a retry
+    /// if needed.
+    fn check_retry(&self, generation: u64) {
+        let Some((failure, attempt, delay)) = self.0.retry.lock_unpoisoned().check(generation)
+        else {
+            return;
+        };
+
+        self.event_listener_data().emit(self, || RetryEvent {
+            failure,
+            attempt,
+            delay,
+        });
+
+        if let Some(delay) = delay {
+            self.post_delayed(delay, move |w| {
+                let target = w.0.retry.lock_unpoisoned().begin_retry(generation);
+                match target {
+                    Some(Some(url)) => w.set_url(url),
+                    Some(None) => w.reload(),
+                    None => {}
+                }
+            });
+        }
+    }
+
+    /// Sets up a watchdog that pings the page at the given interval, firing a
+    /// [`RenderProcessTerminatedEvent`] with
+    /// [`TerminationReason::Unresponsive`] if a ping isn't answered before
+    /// the next one. Passing [`None`] disables the watchdog, which is the
+    /// default.
+    ///
+    /// Crashes are reported by the engines without the watchdog (except with
+    /// Qt), which detects hangs instead: a page stuck in a long-running script
+    /// is reported, thus the interval should be well above the longest task
+    /// the page may run. Pages are only pinged after they finish loading, and
+    /// the event fires once until the page loads again or [`Self::recover`] is
+    /// called.
+    pub fn set_render_watchdog(&self, interval: Option<Duration>) {
+        let generation = self.0.watchdog.lock_unpoisoned().set_interval(interval);
+
+        if let (Some(interval), Some(generation)) = (interval, generation) {
+            self.post_delayed(interval, move |w| w.watchdog_tick(generation));
+        }
+    }
+
+    /// Gets the interval of the render watchdog. See
+    /// [`Self::set_render_watchdog`].
+    pub fn render_watchdog(&self) -> Option<Duration> {
+        self.0.watchdog.lock_unpoisoned().interval()
+    }
+
+    /// Reloads the page after its renderer is gone, e.g. in a handler of
+    /// [`RenderProcessTerminatedEvent`].
+    ///
+    /// The engines start a new renderer process when reloading a crashed page.
+    /// A page that was only unresponsive is reloaded in place.
+    pub fn recover(&self) {
+        self.0.watchdog.lock_unpoisoned().reset();
+        self.reload();
+    }
+
+    /// Samples statistics of the page at the given interval, firing a
+    /// [`PageStatsEvent`] with each sample. Passing [`None`] stops sampling,
+    /// which is the default.
+    ///
+    /// Stats are collected by a script executed in the page, thus pages busy
+    /// running scripts report late or not at all (see
+    /// [`Self::set_render_watchdog`] for detecting those).
+    pub fn set_page_stats_sampling(&self, interval: Option<Duration>) {
+        let generation = {
+            let mut stats = self.0.page_stats.lock_unpoisoned();
+            stats.0 = interval;
+            stats.1 += 1;
+            stats.1
+        };
+
+        if let Some(interval) = interval {
+            self.post_delayed(interval, move |w| w.page_stats_tick(generation));
+        }
+    }
+
+    /// Gets the interval of page stats sampling. See
+    /// [`Self::set_page_stats_sampling`].
+    pub fn page_stats_sampling(&self) -> Option<Duration> { self.0.page_stats.lock_unpoisoned().0 }
+
+    /// Takes performance metrics of the page and passes them to the callback
+    /// on the event thread. See [`PageMetrics`] for what's measured.
+    ///
+    /// Measuring the frame rate takes about half a second, after which the
+    /// callback is invoked. See [`Self::evaluate`] for when the callback may
+    /// not be invoked.
+    pub fn metrics(
+        &self,
+        callback: impl FnOnce(crate::error::Result<PageMetrics>) + Send + 'static,
+    ) {
+        self.evaluate(include_str!("scripts/metrics.js"), |res| {
+            let metrics = res.and_then(|v| {
+                PageMetrics::from_js(&v)
+                    .ok_or_else(|| crate::error::Error::Script("malformed page metrics".into()))
+            });
+            callback(metrics)
+        });
+    }
+
+    fn page_stats_tick(&self, generation: u64) {
+        let (interval, current) = *self.0.page_stats.lock_unpoisoned();
+        if current != generation {
+            return;
+        }
+
+        self.execute(format!(
+            r#"(() => {{
+                const memory = performance.memory;
+                const stats = [
+                    memory?.usedJSHeapSize ?? null,
+                    memory?.totalJSHeapSize ?? null,
+                    document.getElementsByTagName("*").length,
+                ];
+                window.saucer.internal.message({} + JSON.stringify(stats));
+            }})();"#,
+            self.internal_prefix("page-stats:")
+        ));
+
+        if let Some(interval) = interval {
+            self.post_delayed(interval, move |w| w.page_stats_tick(generation));
+        }
+    }
+
+    fn watchdog_tick(&self, generation: u64) {
+        let (tick, interval) = {
+            let mut watchdog = self.0.watchdog.lock_unpoisoned();
+            (watchdog.tick(generation), watchdog.interval())
+        };
+
+        match tick {
+            WatchdogTick::Stop => return,
+            WatchdogTick::Wait => {}
+            WatchdogTick::Ping(seq) => self.execute(format!(
+                "window.saucer.internal.message({})",
+                self.internal_prefix(&format!("pong:{seq}"))
+            )),
+            WatchdogTick::Terminated => {
+                self.on_render_process_terminated(TerminationReason::Unresponsive)
+            }
+        }
+
+        if let Some(interval) = interval {
+            self.post_delayed(interval, move |w| w.watchdog_tick(generation));
+        }
+    }
+
+    /// Takes the recently recorded gesture, if any.
+    fn take_gesture(&self) -> Option<InputGesture> {
+        self.0
+            .last_gesture
+            .lock_unpoisoned()
+            .take()
+            .filter(|(_, at)| at.elapsed() < GESTURE_TIMEOUT)
+            .map(|(g, _)| g)
+    }
+
+    /// Checks whether the page has just started speech recognition, which is
+    /// then considered the cause of a permission request.
+    fn take_speech_started(&self) -> bool {
+        self.0
+            .speech_started
+            .lock_unpoisoned()
+            .take()
+            .is_some_and(|at| at.elapsed() < GESTURE_TIMEOUT)
+    }
+
+    /// Gets the prefix of internal messages of the given kind, quoted as a
+    /// JavaScript string literal for managed scripts.
+    ///
+    /// The literal must only be embedded in closures that pages can't reach,
+    /// as the source of functions exposed to pages can be read with
+    /// `toString`.
+    fn internal_prefix(&self, kind: &str) -> String {
+        js_string(&format!("{}{kind}", self.0.message_prefix))
+    }
+
+    /// Handles a message sent by managed scripts.
+    fn handle_internal_message(&self, msg: &str) {
+        if let Some(status) = msg.strip_prefix("load-status:")
+            && let Ok(status) = status.parse()
+        {
+            self.0.retry.lock_unpoisoned().on_report(status);
+        } else if let Some(gesture) = msg.strip_prefix("input:").and_then(InputGesture::parse) {
+            *self.0.last_gesture.lock_unpoisoned() = Some((gesture, Instant::now()));
+        } else if let Some(id) = msg.strip_prefix("menu:")
+            && let Ok(id) = id.parse()
+        {
+            self.window().activate_accelerator(MenuItemId::from_raw(id));
+        } else if let Some(cmd) = msg.strip_prefix("window:") {
+            if !self
+                .0
+                .managed_scripts
+                .lock_unpoisoned()
+                .contains_key("drag")
+            {
+                return; // Left over on a page after dragging is disabled
+            }
+
+            let window = self.window();
+            match cmd {
+                "drag" => window.start_drag(),
+                "maximize" => window.set_maximized(!window.is_maximized().unwrap_or(false)),
+                _ => {
+                    if let Some(edge) = cmd.strip_prefix("resize:").and_then(WindowEdge::from_name)
+                    {
+                        window.start_resize(edge);
+                    }
+                }
+            }
+        } else if let Some(state) = msg.strip_prefix("audio-state:") {
+            let is_playing = state == "1";
+            if self.0.audio_playing.swap(is_playing, Ordering::Relaxed) != is_playing {
+                self.event_listener_data()
+                    .emit(self, || AudioStateEvent { is_playing });
+            }
+        } else if let Some(seq) = msg.strip_prefix("pong:")
+            && let Ok(seq) = seq.parse()
+        {
+            self.0.watchdog.lock_unpoisoned().on_pong(seq);
+        } else if msg == "speech:start" {
+            *self.0.speech_started.lock_unpoisoned() = Some(Instant::now());
+        } else if let Some(activity) = msg
+            .strip_prefix("activity:")
+            .and_then(UserActivity::from_name)
+        {
+            self.event_listener_data()
+                .emit(self, || UserActivityEvent { activity });
+        } else if let Some(msg) = msg.strip_prefix("reply:") {
+            self.handle_reply(msg);
+        } else if let Some(msg) = msg.strip_prefix("companion:") {
+            self.send_to_companion(msg);
+        } else if let Some(ev) = msg
+            .strip_prefix("page-stats:")
+            .and_then(PageStatsEvent::parse)
+        {
+            self.event_listener_data().emit(self, || ev);
+        } else if let Some(ev) = msg
+            .strip_prefix("console:")
+            .and_then(ConsoleMessageEvent::parse)
+        {
+            self.event_listener_data().emit(self, || ev);
+        }
+    }
+
+    /// Bridges the page to the companion process, replacing the bridge to the
+    /// companion of the same name, if any.
+    ///
+    /// The page sends messages to the companion by its name:
+    ///
+    /// ```js
+    /// window.__saucersCompanions.send("encoder", "start");
+    /// ```
+    ///
+    /// Each message is passed to `vet`, which returns the line to write to the
+    /// stdin of the process, or [`None`] to drop it. As pages may run untrusted
+    /// content, the handler should only let through what the companion is
+    /// meant to receive. Output of the companion is dispatched to the page as
+    /// `saucers-companion` events on `window`, whose `detail` carries `name`,
+    /// `stream` (`stdout` or `stderr`) and `line`.
+    ///
+    /// The global is renamed along with [`InternalScripts::namespace`]. The
+    /// bridge doesn't keep the companion alive.
+    pub fn bridge_companion(
+        &self,
+        companion: &Companion,
+        vet: impl Fn(&str) -> Option<String> + Send + Sync + 'static,
+    ) {
+        companion.add_bridge(self.downgrade());
+        self.0.companion_bridges.lock_unpoisoned().insert(
+            companion.name().to_owned(),
+            CompanionBridge {
+                companion: companion.downgrade(),
+                vet: Box::new(vet),
+            },
+        );
+
+        let js = format!(
+            r#"(() => {{
+                const prefix = {};
+                window.__saucersCompanions ??= {{
+                    send: (name, data) =>
+                        void window.saucer.internal.message(prefix + JSON.stringify([String(name), String(data)])),
+                }};
+            }})();"#,
+            self.internal_prefix("companion:")
+        );
+        self.set_managed_script("companion", Some(js));
+    }
+
+    /// Takes a snapshot of the accessibility tree of the page and passes it to
+    /// the callback on the event thread. See [`AxNode`] for how the tree is
+    /// built.
+    ///
+    /// This is meant for accessibility assertions in tests. Backends don't
+    /// expose their native accessibility trees, thus the tree is computed
+    /// from the DOM by the page and may differ from what assistive
+    /// technologies see in corner cases. See [`Self::evaluate`] for when the
+    /// callback may not be invoked.
+    pub fn accessibility_snapshot(
+        &self,
+        callback: impl FnOnce(crate::error::Result<AxNode>) + Send + 'static,
+    ) {
+        self.evaluate(include_str!("scripts/accessibility.js"), |res| {
+            let node = res.and_then(|v| {
+                AxNode::from_js(&v).ok_or_else(|| {
+                    crate::error::Error::Script("malformed accessibility tree".into())
+                })
+            });
+            callback(node)
+        });
+    }
+
+    /// Evaluates the JavaScript expression in the page and passes its result
+    /// to the callback on the event thread. Promises are awaited.
+    ///
+    /// The result is converted with `JSON.stringify`, thus values that can't
+    /// be represented in JSON are lost (e.g. functions become `null`). Errors
+    /// thrown are converted to [`crate::error::Error::Script`] with their
+    /// messages.
+    ///
+    /// If another page starts loading or the webview is dropped before the
+    /// result is available, the callback receives
+    /// [`crate::error::Error::Closed`] instead, in the latter case on the
+    /// thread dropping the last handle. Only the main frame is evaluated in.
+    pub fn evaluate(
+        &self,
+        expr: &str,
+        callback: impl FnOnce(crate::error::Result<JsValue>) + Send + 'static,
+    ) {
+        let id = self.0.replies.lock_unpoisoned().add(Box::new(callback));
+
+        let js = format!(
+            r#"(async () => {{
+                let res;
+                try {{
+                    res = JSON.stringify([true, await ({expr}
+                    )]);
+                }} catch (e) {{
+                    res = JSON.stringify([false, String(e)]);
+                }}
+                window.saucer.internal.message({} + res);
+            }})();"#,
+            self.internal_prefix(&format!("reply:{id}:"))
+        );
+        self.execute(js);
+    }
+
+    /// Passes the result reported by the page to the waiting callback.
+    fn handle_reply(&self, msg: &str) {
+        let Some((id, res)) = msg.split_once(':') else {
+            return;
+        };
+
+        let Some(callback) = id
+            .parse()
+            .ok()
+            .and_then(|id| self.0.replies.lock_unpoisoned().take(id))
+        else {
+            return;
+        };
+
+        let res = match JsValue::parse(res) {
+            Ok(JsValue::Array(mut a)) if a.len() == 2 => {
+                let value = a.pop().unwrap();
+                match a[0] {
+                    JsValue::Bool(true) => Ok(value),
+                    _ => Err(crate::error::Error::Script(
+                        value.as_str().unwrap_or_default().to_owned(),
+                    )),
+                }
+            }
+            Ok(_) => Err(crate::error::Error::Script("malformed result".into())),
+            Err(e) => Err(e),
+        };
+
+        callback(res);
+    }
+
+    /// Passes a message from the page to the bridged companion.
+    fn send_to_companion(&self, msg: &str) {
+        let Ok(msg) = JsValue::parse(msg) else {
+            return;
+        };
+
+        let (Some(name), Some(data)) = (
+            msg.as_array()
+                .and_then(|a| a.first())
+                .and_then(JsValue::as_str),
+            msg.as_array()
+                .and_then(|a| a.get(1))
+                .and_then(JsValue::as_str),
+        ) else {
+            return;
+        };
+
+        let line = {
+            let bridges = self.0.companion_bridges.lock_unpoisoned();
+            let Some(bridge) = bridges.get(name) else {
+                return;
+            };
+
+            let Some(companion) = bridge.companion.upgrade() else {
+                return;
+            };
+
+            (bridge.vet)(data).map(|l| (companion, l))
+        };
+
+        if let Some((companion, line)) = line {
+            let _ = companion.write_line(&line); // Dropped when stuck, the page can't be told anyway
+        }
+    }
+
+    /// Opens a stream for sending binary data to the page in chunks, replacing
+    /// the open stream of the same name, if any.
+    ///
+    /// Chunks are served by a scheme internal to the bindings, thus they don't
+    /// need to be encoded as text like messages do. The page reads the stream
+    /// as a `ReadableStream` of `Uint8Array` chunks:
+    ///
+    /// ```js
+    /// const reader = window.__saucersStreams.open("frames").getReader();
+    /// ```
+    ///
+    /// The global is renamed along with [`InternalScripts::namespace`], and
+    /// learns the URL of the stream when it's opened, thus streams must be
+    /// opened after the page has loaded. Pages can also fetch
+    /// [`StreamWriter::url`] directly, which answers with the next chunk, or
+    /// status 204 when the stream has ended. The URL carries a random token,
+    /// and responses may only be read by the origin of the current page. See
+    /// [`StreamWriter`] for flow control.
+    ///
+    /// Streams must be enabled with [`crate::app::AppOptions::streams`],
+    /// otherwise this fails with [`crate::error::Error::StreamsDisabled`].
+    pub fn open_stream(&self, name: &str) -> crate::error::Result<StreamWriter> {
+        if !self.0.schemes.iter().any(|s| s == STREAM_SCHEME) {
+            return Err(crate::error::Error::StreamsDisabled);
+        }
+
+        let shared = Arc::new(StreamShared::new());
+        let url = shared.url(name);
+        let mut streams = self.0.streams.lock_unpoisoned();
+
+        streams.retain(|_, s| !s.is_done());
+        if let Some(old) = streams.insert(name.to_owned(), shared.clone()) {
+            old.close();
+        }
+
+        drop(streams);
+
+        self.enable_internal_script("streams");
+        self.execute(format!(
+            "window.{}Streams?.register({}, {});",
+            self.0.script_namespace,
+            js_string(name),
+            js_string(&url)
+        ));
+
+        Ok(StreamWriter::new(shared, url))
+    }
+
+    /// Runs the query on the event thread and waits for its result for up to
+    /// the given duration. Can be called on any thread. If called on the event
+    /// thread, the query runs immediately.
+    ///
+    /// Returns [`crate::error::Error::Timeout`] if the result isn't available
+    /// in time (the query may still run later), or
+    /// [`crate::error::Error::Closed`] if the webview or app is gone before
+    /// the query runs.
+    pub fn query_with_timeout<T: Send + 'static>(
+        &self,
+        query: impl FnOnce(Webview) -> T + Send + UnwindSafe + 'static,
+        timeout: Duration,
+    ) -> crate::error::Result<T> {
+        if self.0.is_thread_safe() {
+            return Ok(query(self.clone()));
+        }
+
+        let app = self.window().app().ok_or(crate::error::Error::Closed)?;
+        let webview = self.downgrade();
+        let (tx, rx) = std::sync::mpsc::sync_channel(1);
+
+        app.post(move |_| {
+            if let Some(w) = webview.upgrade() {
+                let _ = tx.send(query(w));
+            }
+        });
+
+        rx.recv_timeout(timeout).map_err(|e| match e {
+            std::sync::mpsc::RecvTimeoutError::Timeout => crate::error::Error::Timeout,
+            std::sync::mpsc::RecvTimeoutError::Disconnected => crate::error::Error::Closed,
+        })
+    }
+
+    /// Invokes the callback on the event thread after the given delay, if the
+    /// webview is still alive by then.
+    fn post_delayed(
+        &self,
+        delay: Duration,
+        callback: impl FnOnce(Webview) + Send + UnwindSafe + 'static,
+    ) {
+        let Some(app) = self.window().app() else {
+            return;
+        };
+
+        let app = app.downgrade();
+        let webview = self.downgrade();
+
+        crate::app::schedule_delayed(delay, move || {
+            if let Some(app) = app.upgrade() {
+                app.post(move |_| {
+                    if let Some(w) = webview.upgrade() {
+                        callback(w);
+                    }
+                });
+            }
+        });
+    }
+
+    /// Replaces the script managed by the bindings under the given key. The
+    /// script is executed on the current page and injected into future pages.
+    /// Passing [`None`] removes the script.
+    pub(crate) fn set_managed_script(&self, key: &'static str, js: Option<String>) {
+        let mut scripts = self.0.managed_scripts.lock_unpoisoned();
+
+        if let Some((id, _)) = scripts.remove(key) {
+            self.uninject(id);
+        }
+
+        if let Some(js) = js {
+            let js = js.replace(
+                "window.__saucers",
+                &format!("window.{}", self.0.script_namespace),
+            );
+
+            // Frames must not see the message token, while other scripts also
+            // apply to the content of frames
+            let no_frames = js.contains(&self.0.message_prefix);
+
+            self.execute(js.as_str());
+            let id = self.inject(js.as_str(), ScriptTime::Creation, no_frames, false);
+            scripts.insert(key, (id, js));
+        }
+    }
+
+    /// Installs the internal script of the given key (see [`InternalScripts`]),
+    /// unless it's already installed.
+    fn enable_internal_script(&self, key: &'static str) {
+        if self.0.managed_scripts.lock_unpoisoned().contains_key(key) {
+            return;
+        }
+
+        match key {
+            "audio" => self.set_muted(self.is_muted()),
+            "activity" => self.set_user_idle_timeout(DEFAULT_USER_IDLE_TIMEOUT),
+            "input" => {
+                let input_js = include_str!("scripts/input.js")
+                    .replace("__PREFIX__", &self.internal_prefix("input:"));
+                self.set_managed_script("input", Some(input_js));
+            }
+            "speech" => {
+                let speech_js = format!(
+                    r#"(() => {{
+                        const Recognition = window.SpeechRecognition ?? window.webkitSpeechRecognition;
+                        if (!Recognition || window.__saucersSpeech) return;
+                        window.__saucersSpeech = true;
+                        const start = Recognition.prototype.start;
+                        const message = {};
+                        Recognition.prototype.start = function (...args) {{
+                            window.saucer.internal.message(message);
+                            return start.apply(this, args);
+                        }};
+                    }})();"#,
+                    self.internal_prefix("speech:start")
+                );
+                self.set_managed_script("speech", Some(speech_js));
+            }
+            "streams" if self.0.schemes.iter().any(|s| s == STREAM_SCHEME) => {
+                let streams_js = r#"(() => {
+                    const urls = new Map();
+                    window.__saucersStreams ??= {
+                        register: (name, url) => urls.set(name, url),
+                        open: (name) => {
+                            const url = urls.get(name);
+                            if (!url) throw new Error("unknown stream: " + name);
+                            return new ReadableStream({
+                                async pull(controller) {
+                                    const res = await fetch(url);
+                                    if (!res.ok) throw new Error("stream failed: " + res.status);
+                                    if (res.status === 204) controller.close();
+                                    else controller.enqueue(new Uint8Array(await res.arrayBuffer()));
+                                },
+                            });
+                        },
+                    };
+                })();"#
+                    .to_owned();
+                self.set_managed_script("streams", Some(streams_js));
+            }
+            "console" => {
+                let console_js = include_str!("scripts/console.js")
+                    .replace("__PREFIX__", &self.internal_prefix("console:"));
+                self.set_managed_script("console", Some(console_js));
+            }
+            _ => {}
+        }
+    }
+
+    /// Installs the internal script reporting the event, if any, when a
+    /// handler of it is registered.
+    fn on_handler_added<E: WebviewEvent>(&self) {
+        let ty = TypeId::of::<E>();
+        let key = if ty == TypeId::of::<NavigateEvent>() {
+            "input"
+        } else if ty == TypeId::of::<PermissionEvent>() {
+            "speech"
+        } else if ty == TypeId::of::<AudioStateEvent>() {
+            "audio"
+        } else if ty == TypeId::of::<UserActivityEvent>() {
+            "activity"
+        } else if ty == TypeId::of::<ConsoleMessageEvent>() {
+            "console"
+        } else {
+            return;
+        };
+
+        self.enable_internal_script(key);
+    }
+
+    /// Gets the scripts currently injected by the bindings, as pairs of names
+    /// and sources, sorted by name. See [`InternalScripts`].
+    pub fn internal_scripts(&self) -> Vec<(&'static str, String)> {
+        let mut scripts = self
+            .0
+            .managed_scripts
+            .lock_unpoisoned()
+            .iter()
+            .map(|(k, (_, js))| (*k, js.clone()))
+            .collect::<Vec<_>>();
+
+        scripts.sort_by_key(|(k, _)| *k);
+        scripts
+    }
+
+    /// Gets the parent window.
+    pub fn window(&self) -> Window { self.0.window.clone() }
+
+    /// Registers a handler for the scheme which only lives for one navigation,
+    /// e.g. for serving the callback page of an OAuth flow.
+    ///
+    /// The handler is removed once the page finishes loading after it has
+    /// served a request, or when the returned guard is dropped, whichever
+    /// comes first. Like other schemes, the scheme must be registered with
+    /// [`crate::scheme::register_scheme`] before the app is created. Fails with
+    /// [`crate::error::Error::SchemeInUse`] if the scheme already has a handler
+    /// in this webview.
+    ///
+    /// When called on other threads, this method fails with
+    /// [`crate::error::Error::WrongThread`] (or panics, depending on the
+    /// [`crate::thread::ThreadViolationPolicy`]).
+    pub fn with_temporary_scheme(
+        &self,
+        name: impl Into<String>,
+        handler: impl Fn(Webview, Request, Executor) + RefUnwindSafe + 'static,
+    ) -> crate::error::Result<TemporaryScheme> {
+        if check_event_thread(self.0.is_thread_safe(), "registering scheme handlers").is_some() {
+            return Err(crate::error::Error::WrongThread);
+        }
+
+        let name = name.into();
+        let mut schemes = self.0.temp_schemes.lock_unpoisoned();
+
+        if self.has_scheme_handler(&name) || schemes.contains_key(&name) {
+            return Err(crate::error::Error::SchemeInUse(name));
+        }
+
+        let generation = self.0.next_temp_scheme.fetch_add(1, Ordering::Relaxed);
+        schemes.insert(name.clone(), TemporarySchemeEntry {
+            handler: Rc::new(handler),
+            generation,
+            served: false,
+        });
+
+        use_string!(s: name.as_str(); unsafe {
+            saucer_webview_handle_scheme(
+                self.as_ptr(),
+                s,
+                Some(handle_temporary_scheme_tp),
+                self.0.scheme_handler_data as *mut c_void,
+            )
+        });
+
+        Ok(TemporaryScheme::new(self.downgrade(), name, generation))
+    }
+
+    /// Removes the temporary scheme handler, if it's of the given generation.
+    pub(crate) fn remove_temporary_scheme(&self, name: &str, generation: Option<u64>) {
+        if !self.0.is_thread_safe() {
+            let webview = self.downgrade();
+            let name = name.to_owned();
+
+            if let Some(app) = self.window().app() {
+                app.post(move |_| {
+                    if let Some(w) = webview.upgrade() {
+                        w.remove_temporary_scheme(&name, generation);
+                    }
+                });
+            }
+
+            return;
+        }
+
+        let removed = {
+            let mut schemes = self.0.temp_schemes.lock_unpoisoned();
+            match schemes.get(name) {
+                Some(e) if generation.is_none_or(|g| g == e.generation) => schemes.remove(name),
+                _ => None,
+            }
+        };
+
+        if removed.is_some() {
+            use_string!(s: name; unsafe { saucer_webview_remove_scheme(self.as_ptr(), s) });
+        }
+    }
+
+    /// Serves the file system under the given scheme, e.g. mounting at
+    /// `assets` serves `/img/logo.png` of the file system at
+    /// `assets://app/img/logo.png` (the host is ignored). Directories are
+    /// served with their `index.html`. Like other schemes, the scheme must be
+    /// registered with [`crate::scheme::register_scheme`] before the app is
+    /// created.
+    ///
+    /// Fails with [`crate::error::Error::SchemeInUse`] if the scheme already
+    /// has a handler in this webview, or [`crate::error::Error::WrongThread`]
+    /// (or panics, depending on the [`crate::thread::ThreadViolationPolicy`])
+    /// when called on other threads.
+    pub fn mount(&self, scheme: impl Into<String>, vfs: impl Vfs) -> crate::error::Result<()> {
+        if check_event_thread(self.0.is_thread_safe(), "mounting file systems").is_some() {
+            return Err(crate::error::Error::WrongThread);
+        }
+
+        let scheme = scheme.into();
+
+        if self.has_scheme_handler(&scheme)
+            || self.0.temp_schemes.lock_unpoisoned().contains_key(&scheme)
+        {
+            return Err(crate::error::Error::SchemeInUse(scheme));
+        }
+
+        self.0
+            .mounts
+            .lock_unpoisoned()
+            .insert(scheme.clone(), Arc::new(vfs));
+
+        use_string!(s: scheme.as_str(); unsafe {
+            saucer_webview_handle_scheme(
+                self.as_ptr(),
+                s,
+                Some(handle_mount_scheme_tp),
+                self.0.scheme_handler_data as *mut c_void,
+            )
+        });
+
+        Ok(())
+    }
+
+    /// Removes the file system mounted under the given scheme. Returns whether
+    /// it was mounted. Requests in flight are still served.
+    ///
+    /// When called on other threads, this method fails with
+    /// [`crate::error::Error::WrongThread`] (or panics, depending on the
+    /// [`crate::thread::ThreadViolationPolicy`]).
+    pub fn unmount(&self, scheme: &str) -> crate::error::Result<bool> {
+        if check_event_thread(self.0.is_thread_safe(), "unmounting file systems").is_some() {
+            return Err(crate::error::Error::WrongThread);
+        }
+
+        if self.0.mounts.lock_unpoisoned().remove(scheme).is_none() {
+            return Ok(false);
+        }
+
+        use_string!(s: scheme; unsafe { saucer_webview_remove_scheme(self.as_ptr(), s) });
+        Ok(true)
+    }
+
+    /// Checks whether the scheme is handled by the scheme handler or a mounted
+    /// file system.
+    fn has_scheme_handler(&self, name: &str) -> bool {
+        self.0.schemes.iter().any(|s| s == name)
+            || self.0.mounts.lock_unpoisoned().contains_key(name)
+    }
+
+    pub(crate) fn has_temporary_scheme(&self, name: &str, generation: u64) -> bool {
+        self.0
+            .temp_schemes
+            .lock_unpoisoned()
+            .get(name)
+            .is_some_and(|e| e.generation == generation)
+    }
+
+    /// Registers a handler for the given event type. Returns an ID that can be
+    /// used to remove the handler with [`Self::off`].
+    ///
+    /// Handlers are invoked before the [`WebviewEventListener`] in the order
+    /// they're registered, and their outputs are combined (see
+    /// [`EventOutput`]). Like the listener, capturing strong handles in the
+    /// handler forms circular references, which [`crate::weak_clone`] helps
+    /// to avoid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread.
+    pub fn on<E: WebviewEvent>(
+        &self,
+        handler: impl Fn(Webview, &E) -> E::Output + RefUnwindSafe + 'static,
+    ) -> HandlerId {
+        let id = self
+            .event_listener_data()
+            .handlers
+            .borrow_mut()
+            .add::<E>(Rc::new(handler));
+
+        self.on_handler_added::<E>();
+        id
+    }
+
+    /// Like [`Self::on`], but doesn't install the internal script reporting
+    /// the event, for observers that must not change how pages behave.
+    pub(crate) fn observe<E: WebviewEvent>(
+        &self,
+        handler: impl Fn(Webview, &E) -> E::Output + RefUnwindSafe + 'static,
+    ) -> HandlerId {
+        self.event_listener_data()
+            .handlers
+            .borrow_mut()
+            .add::<E>(Rc::new(handler))
+    }
+
+    /// Like [`Self::on`], but the handler is removed after it's invoked once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread.
+    pub fn once<E: WebviewEvent>(
+        &self,
+        handler: impl FnOnce(Webview, &E) -> E::Output + UnwindSafe + 'static,
+    ) -> HandlerId {
+        let slot = Cell::new(Some(handler));
+        let id = Rc::new(Cell::new(None));
+        let handler_id = id.clone();
+
+        let registered = self
+            .event_listener_data()
+            .handlers
+            .borrow_mut()
+            .add::<E>(Rc::new(move |w: Webview, ev: &E| {
+                let Some(handler) = slot.take() else {
+                    return E::Output::default();
+                };
+
+                if let Some(id) = handler_id.get() {
+                    let _ = w.off(id);
+                }
+
+                handler(w, ev)
+            }));
+
+        id.set(Some(registered));
+        self.on_handler_added::<E>();
+        registered
+    }
+
+    /// Adds a [`WebviewEventListener`] in addition to the one given at
+    /// creation. Returns an ID that can be used to remove it with
+    /// [`Self::off`], like handlers registered with [`Self::on`].
+    ///
+    /// Added listeners are invoked after handlers of the corresponding events,
+    /// in the order they're added, and before the listener given at creation.
+    /// Their outputs are combined in the same way (see [`EventOutput`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread.
+    pub fn add_listener(&self, listener: impl WebviewEventListener + 'static) -> HandlerId {
+        self.event_listener_data()
+            .handlers
+            .borrow_mut()
+            .add_listener(Rc::new(listener))
+    }
+
+    /// Removes all handlers of the given event type registered with
+    /// [`Self::on`] or [`Self::once`]. Listeners are not affected. Returns
+    /// how many handlers have been removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread.
+    pub fn clear<E: WebviewEvent>(&self) -> usize {
+        self.event_listener_data()
+            .handlers
+            .borrow_mut()
+            .clear::<E>()
+    }
+
+    /// Removes a handler registered with [`Self::on`] or [`Self::once`], or a
+    /// listener added with [`Self::add_listener`]. Returns whether the handler
+    /// has been found.
+    ///
+    /// When called on other threads and queued by the
+    /// [`crate::thread::ThreadViolationPolicy`], the handler is removed later
+    /// on the event thread and `false` is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, unless allowed by the
+    /// [`crate::thread::ThreadViolationPolicy`].
+    pub fn off(&self, id: HandlerId) -> crate::error::Result<bool> {
+        match check_event_thread(self.0.is_thread_safe(), "removing event handlers") {
+            None => Ok(self.event_listener_data().handlers.borrow_mut().remove(id)),
+            Some(ThreadViolationPolicy::Queue) => {
+                let app = self.window().app().ok_or(crate::error::Error::Closed)?;
+                let webview = self.downgrade();
+
+                app.post(move |_| {
+                    if let Some(w) = webview.upgrade() {
+                        let _ = w.off(id);
+                    }
+                });
+
+                Ok(false)
+            }
+            Some(_) => Err(crate::error::Error::WrongThread),
+        }
+    }
+
+    /// Creates a [`RegistrationScope`] which removes all handlers registered
+    /// through it when dropped.
+    pub fn scope(&self) -> RegistrationScope { RegistrationScope::new(self.downgrade()) }
+
+    /// Gets the event listener data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, as the data is only accessed
+    /// there.
+    fn event_listener_data(&self) -> &EventListenerData {
+        if !self.0.is_thread_safe() {
+            panic!("event handlers must be managed on the event thread");
+        }
+
+        // SAFETY: The data is freed after the raw handle is dropped.
+        unsafe { &*self.0.event_listener_data }
+    }
+
+    /// Gets a weak [`WebviewRef`].
+    pub fn downgrade(&self) -> WebviewRef { WebviewRef(Arc::downgrade(&self.0)) }
+
+    /// Gets the ID of the webview, which identifies it for its whole lifetime.
+    pub fn id(&self) -> WebviewId { WebviewId(self.0.id) }
+
+    pub(crate) fn as_ptr(&self) -> *mut saucer_webview { self.0.inner.as_ptr() }
+}
+
+/// A weak webview handle.
+///
+/// Like [`crate::app::AppRef`], this handle does not prevent deallocation and
+/// can be used in various listeners.
+#[derive(Clone)]
+pub struct WebviewRef(Weak<RawWebview>);
+
+/// Refs are equal if they refer to the same webview, even after it's dropped.
+impl PartialEq for WebviewRef {
+    fn eq(&self, other: &Self) -> bool { Weak::ptr_eq(&self.0, &other.0) }
+}
+
+impl Eq for WebviewRef {}
+
+impl Hash for WebviewRef {
+    // The allocation is kept by the weak pointer, thus the address is not reused
+    fn hash<H: Hasher>(&self, state: &mut H) { self.0.as_ptr().hash(state) }
+}
+
+impl WebviewRef {
+    /// Tries to upgrade to a strong handle.
+    pub fn upgrade(&self) -> Option<Webview> { Some(Webview(self.0.upgrade()?)) }
+}
+
+pub(crate) struct SchemeHandlerData {
+    handler: Box<dyn WebviewSchemeHandler + 'static>,
+    webview: WebviewRef,
+}
+
+impl SchemeHandlerData {
+    fn new(handler: impl WebviewSchemeHandler + 'static, webview: WebviewRef) -> Self {
+        Self {
+            handler: Box::new(handler),
+            webview,
+        }
+    }
+}
+
+pub(crate) struct EventListenerData {
+    listener: Box<dyn WebviewEventListener + 'static>,
+    // Only accessed on the event thread. Handlers are cloned out before invoking, so a panicking
+    // handler can't leave the registry borrowed.
+    handlers: AssertUnwindSafe<RefCell<HandlerRegistry>>,
+    webview: WebviewRef,
+}
+
+impl EventListenerData {
+    fn new(listener: impl WebviewEventListener + 'static, webview: WebviewRef) -> Self {
+        Self {
+            listener: Box::new(listener),
+            handlers: AssertUnwindSafe(RefCell::new(HandlerRegistry::default())),
+            webview,
+        }
+    }
+
+    /// Invokes handlers registered for the given event and combines their
+    /// outputs. The event is only created when there are handlers.
+    fn emit<E: WebviewEvent>(&self, webview: &Webview, event: impl FnOnce() -> E) -> E::Output {
+        let handlers = self.handlers.borrow().get::<E>();
+
+        trace_event!(
+            target: "saucers::event",
+            event = std::any::type_name::<E>(),
+            handlers = handlers.len(),
+            "dispatching webview event"
+        );
+
+        if handlers.is_empty() {
+            return E::Output::default();
+        }
+
+        let event = event();
+
+        handlers.into_iter().fold(E::Output::default(), |out, h| {
+            out.merge(h(webview.clone(), &event))
+        })
+    }
+
+    /// Invokes listeners added with [`Webview::add_listener`], then the one
+    /// given at creation, and combines their outputs.
+    fn dispatch<R: EventOutput>(&self, f: impl Fn(&dyn WebviewEventListener) -> R) -> R {
+        let listeners = self.handlers.borrow().listeners();
+
+        listeners
+            .iter()
+            .fold(R::default(), |out, l| out.merge(f(l.as_ref())))
+            .merge(f(self.listener.as_ref()))
+    }
+}
+
+extern "C" fn ev_on_permission_tp(
+    _: *mut saucer_webview,
+    req: *mut saucer_permission_request,
+    data: *mut c_void,
+) -> saucer_status {
+    let data = unsafe { &*(data as *const EventListenerData) };
+    ffi_callback(
+        "webview permission event",
+        HandleStatus::Unhandled.into(),
+        || {
+            let req = unsafe { PermissionRequest::from_ptr(saucer_permission_request_copy(req)) };
+
+            let ret = if let Some(w) = data.webview.upgrade() {
+                let req = match req.kind() {
+                    PermissionType::AudioMedia | PermissionType::Unknown
+                        if w.take_speech_started() =>
+                    {
+                        req.with_kind(PermissionType::SpeechRecognition)
+                    }
+                    _ => req,
+                };
+
+                let rule = {
+                    let policies = w.window().app().map(|app| app.current_policies());
+                    let app_rules = policies
+                        .as_ref()
+                        .map(|p| &p.policies.permissions)
+                        .filter(|r| !r.is_empty());
+                    let rules = w.0.permission_rules.lock_unpoisoned();
+                    let store = w.0.permission_store.lock_unpoisoned();
+
+                    if app_rules.is_none() && rules.is_empty() && store.is_none() {
+                        None
+                    } else {
+                        let origin = req.url().origin();
+                        app_rules
+                            .and_then(|r| r.lookup(&origin, req.kind()))
+                            .or_else(|| rules.lookup(&origin, req.kind()))
+                            .or_else(|| store.as_ref()?.get(&origin, req.kind()))
+                    }
+                };
+
+                if let Some(policy) = rule {
+                    req.accept(policy == Policy::Allow);
+                    return HandleStatus::Handled.into();
+                }
+
+                let out = data.emit(&w, || PermissionEvent {
+                    request: req.clone(),
+                });
+                out.merge(data.dispatch(|l| l.on_permission(w.clone(), req.clone())))
+            } else {
+                HandleStatus::Unhandled
+            };
+
+            ret.into()
+        },
+    )
+}
+
+extern "C" fn ev_on_fullscreen_tp(
+    _: *mut saucer_webview,
+    is_fullscreen: bool,
+    data: *mut c_void,
+) -> saucer_policy {
+    let data = unsafe { &*(data as *const EventListenerData) };
+    ffi_callback("webview fullscreen event", Policy::Allow.into(), || {
+        let ret = if let Some(w) = data.webview.upgrade() {
+            w.0.content_fullscreen
+                .store(is_fullscreen, Ordering::Relaxed);
+            let out = data.emit(&w, || FullscreenEvent { is_fullscreen });
+            out.merge(data.dispatch(|l| l.on_fullscreen(w.clone(), is_fullscreen)))
+        } else {
+            Policy::Allow
+        };
+
+        ret.into()
+    })
+}
+
+extern "C" fn ev_on_dom_ready_tp(_: *mut saucer_webview, data: *mut c_void) {
+    let data = unsafe { &*(data as *const EventListenerData) };
+    ffi_callback("webview dom ready event", (), || {
+        if let Some(w) = data.webview.upgrade() {
+            w.on_queue_dom_ready();
+            data.emit(&w, || DomReadyEvent);
+            data.dispatch(|l| l.on_dom_ready(w.clone()));
+        }
+    });
+}
+
+extern "C" fn ev_on_navigated_tp(_: *mut saucer_webview, url: *mut saucer_url, data: *mut c_void) {
+    let data = unsafe { &*(data as *const EventListenerData) };
+    ffi_callback("webview navigated event", (), || {
+        let url = unsafe {
+            Url::from_ptr(saucer_url_copy(url), -1).expect("navigation target URL should exist")
+        };
+
+        if let Some(w) = data.webview.upgrade() {
+            {
+                let mut cache = w.0.page_cache.lock_unpoisoned();
+                let content = url.content();
+                if cache.url.as_deref() != Some(content.as_str()) {
+                    cache.url = Some(content.into());
+                    cache.changed = true;
+                }
+            }
+
+            w.on_tracked_navigated(&url);
+            data.emit(&w, || NavigatedEvent { url: url.clone() });
+            data.dispatch(|l| l.on_navigated(w.clone(), url.clone()));
+        }
+    });
+}
+
+extern "C" fn ev_on_navigate_tp(
+    _: *mut saucer_webview,
+    nav: *mut saucer_navigation,
+    data: *mut c_void,
+) -> saucer_policy {
+    let data = unsafe { &*(data as *const EventListenerData) };
+    ffi_callback("webview navigate event", Policy::Allow.into(), || {
+        let nav = unsafe { Navigation::from_ptr(nav) }; // SAFETY: It can't be moved out
+
+        let ret = if let Some(w) = data.webview.upgrade() {
+            let url = nav.url().content();
+            let app_rule = w.window().app().and_then(|app| {
+                let current = app.current_policies();
+                Some(current.policies.navigation.as_ref()?.evaluate(&url))
+            });
+
+            let rule =
+                w.0.navigation_rules
+                    .lock_unpoisoned()
+                    .as_ref()
+                    .map(|r| r.evaluate(&url));
+
+            if app_rule == Some(Policy::Block) || rule == Some(Policy::Block) {
+                return Policy::Block.into();
+            }
+
+            if !w.0.dev_tools_policy.is_allowed() && nav.url().scheme() == "view-source" {
+                return Policy::Block.into();
+            }
+
+            let gesture = if nav.is_user_initiated() {
+                w.take_gesture()
+            } else {
+                None
+            };
+
+            if nav.is_new_window() {
+                match data.emit(&w, || NewWindowRequestedEvent {
+                    url: nav.url(),
+                    gesture,
+                }) {
+                    NewWindowAction::Default => {}
+                    NewWindowAction::Deny => return Policy::Block.into(),
+                    NewWindowAction::Host(host) => {
+                        host.set_url(nav.url());
+                        return Policy::Block.into();
+                    }
+                }
+            }
+
+            let out = data.emit(&w, || NavigateEvent::new(&nav, gesture));
+            let out = out.merge(data.dispatch(|l| l.on_navigate(w.clone(), &nav)));
+
+            if out == Policy::Allow && !nav.is_new_window() {
+                w.0.retry.lock_unpoisoned().on_navigate(nav.url());
+            }
+
+            if !nav.is_new_window() {
+                w.on_tracked_navigate(&nav, out);
+            }
+
+            out
+        } else {
+            Policy::Allow
+        };
+
+        let _ = nav; // Ensure it's not moved
+        ret.into()
+    })
+}
+
+extern "C" fn ev_on_message_tp(
+    _: *mut saucer_webview,
+    msg: *mut c_char,
+    size: usize,
+    data: *mut c_void,
+) -> saucer_status {
+    let data = unsafe { &*(data as *const EventListenerData) };
+    ffi_callback(
+        "webview message event",
+        HandleStatus::Unhandled.into(),
+        || {
+            let s = unsafe { std::slice::from_raw_parts_mut(msg as *mut u8, size) };
+            let s = String::from_utf8_lossy(s);
+
+            let ret = if let Some(w) = data.webview.upgrade() {
+                if let Some(msg) = s.strip_prefix(w.0.message_prefix.as_str()) {
+                    w.handle_internal_message(msg);
+                    return HandleStatus::Handled.into();
+                }
+
+                let out = data.emit(&w, || MessageEvent {
+                    message: s.clone().into_owned(),
+                });
+                out.merge(data.dispatch(|l| l.on_message(w.clone(), s.clone())))
+            } else {
+                HandleStatus::Unhandled
+            };
+
+            ret.into()
+        },
+    )
+}
+
+extern "C" fn ev_on_request_tp(_: *mut saucer_webview, req: *mut saucer_url, data: *mut c_void) {
+    let data = unsafe { &*(data as *const EventListenerData) };
+    ffi_callback("webview request event", (), || {
+        let url =
+            unsafe { Url::from_ptr(saucer_url_copy(req), -1).expect("request URL should exist") };
+
+        if let Some(w) = data.webview.upgrade() {
+            data.emit(&w, || RequestEvent { url: url.clone() });
+            data.dispatch(|l| l.on_request(w.clone(), url.clone()));
+        }
+    });
+}
+
+extern "C" fn ev_on_favicon_tp(
+    _: *mut saucer_webview,
+    favicon: *mut saucer_icon,
+    data: *mut c_void,
+) {
+    let data = unsafe { &*(data as *const EventListenerData) };
+    ffi_callback("webview favicon event", (), || {
+        let icon = unsafe { Icon::from_ptr(saucer_icon_copy(favicon)) };
+
+        if let Some(w) = data.webview.upgrade() {
+            data.emit(&w, || FaviconEvent { icon: icon.clone() });
+            data.dispatch(|l| l.on_favicon(w.clone(), icon.clone()));
+        }
+    });
+}
+
+extern "C" fn ev_on_title_tp(
+    _: *mut saucer_webview,
+    title: *mut c_char,
+    size: usize,
     data: *mut c_void,
 ) {
     let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback((), || {
+    ffi_callback("webview title event", (), || {
         let s = unsafe { std::slice::from_raw_parts_mut(title as *mut u8, size) };
         let s = String::from_utf8_lossy(s).into_owned();
 
         if let Some(w) = data.webview.upgrade() {
-            data.listener.on_title(w.clone(), s);
+            {
+                let mut cache = w.0.page_cache.lock_unpoisoned();
+                if cache.title.as_deref() != Some(s.as_str()) {
+                    cache.title = Some(s.as_str().into());
+                    cache.changed = true;
+                }
+            }
+
+            data.emit(&w, || TitleEvent { title: s.clone() });
+            data.dispatch(|l| l.on_title(w.clone(), s.clone()));
         }
     });
 }
 
 extern "C" fn ev_on_load_tp(_: *mut saucer_webview, state: saucer_state, data: *mut c_void) {
     let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback((), || {
+    ffi_callback("webview load event", (), || {
         if let Some(w) = data.webview.upgrade() {
-            data.listener.on_load(w.clone(), state.into());
+            let state = LoadState::from(state);
+
+            let generation = {
+                let mut retry = w.0.retry.lock_unpoisoned();
+                match state {
+                    LoadState::Started => {
+                        retry.on_started();
+                        None
+                    }
+                    LoadState::Finished => retry.on_finished(),
+                }
+            };
+
+            w.0.page_cache.lock_unpoisoned().is_loading = state == LoadState::Started;
+
+            if state == LoadState::Started {
+                w.on_queue_load_started();
+
+                // Results of the previous page will never arrive
+                let replies = w.0.replies.lock_unpoisoned().drain();
+                for cb in replies {
+                    cb(Err(crate::error::Error::Closed));
+                }
+            }
+
+            if state == LoadState::Finished {
+                w.reset_auth_attempts();
+
+                let served =
+                    w.0.temp_schemes
+                        .lock_unpoisoned()
+                        .iter()
+                        .filter(|(_, e)| e.served)
+                        .map(|(n, _)| n.clone())
+                        .collect::<Vec<_>>();
+
+                for name in served {
+                    w.remove_temporary_scheme(&name, None);
+                }
+            }
+
+            {
+                let mut watchdog = w.0.watchdog.lock_unpoisoned();
+                match state {
+                    LoadState::Started => watchdog.on_started(),
+                    LoadState::Finished => watchdog.on_finished(),
+                }
+            }
+
+            if let Some(generation) = generation {
+                w.post_delayed(RETRY_REPORT_GRACE, move |w| w.check_retry(generation));
+            }
+
+            if state == LoadState::Finished {
+                w.on_tracked_load_finished();
+            }
+
+            data.emit(&w, || LoadEvent { state });
+            data.dispatch(|l| l.on_load(w.clone(), state));
+        }
+    });
+}
+
+extern "C" fn handle_stream_scheme_tp(
+    req: *mut saucer_scheme_request,
+    exc: *mut saucer_scheme_executor,
+    data: *mut c_void,
+) {
+    let data = unsafe { &*(data as *const SchemeHandlerData) };
+    ffi_callback("stream scheme handler", (), || {
+        let req = unsafe { Request::from_ptr(saucer_scheme_request_copy(req)) };
+        let exc = unsafe { Executor::from_ptr(saucer_scheme_executor_copy(exc)) };
+
+        let Some(w) = data.webview.upgrade() else {
+            return;
+        };
+
+        let url = req.url();
+        let path = url.path();
+        let Some((token, name)) = path.trim_start_matches('/').split_once('/') else {
+            exc.reject(SchemeError::NotFound);
+            return;
+        };
+
+        let name = crate::util::percent_decode(name);
+        let stream = (url.host() == "stream")
+            .then(|| w.0.streams.lock_unpoisoned().get(&name).cloned())
+            .flatten()
+            .filter(|s| s.accepts(token)); // Leave the held read alone
+
+        match stream {
+            Some(s) => {
+                let origin = w.url().ok().map(|u| u.origin());
+                if s.read(exc, origin) {
+                    w.0.streams.lock_unpoisoned().remove(&name);
+                }
+            }
+            None => exc.reject(SchemeError::NotFound),
+        }
+    });
+}
+
+extern "C" fn handle_temporary_scheme_tp(
+    req: *mut saucer_scheme_request,
+    exc: *mut saucer_scheme_executor,
+    data: *mut c_void,
+) {
+    let data = unsafe { &*(data as *const SchemeHandlerData) };
+    ffi_callback("temporary scheme handler", (), || {
+        let req = unsafe { Request::from_ptr(saucer_scheme_request_copy(req)) };
+        let exc = unsafe { Executor::from_ptr(saucer_scheme_executor_copy(exc)) };
+
+        let Some(w) = data.webview.upgrade() else {
+            return;
+        };
+
+        let handler = {
+            let mut schemes = w.0.temp_schemes.lock_unpoisoned();
+            schemes.get_mut(req.url().scheme().as_str()).map(|e| {
+                e.served = true;
+                e.handler.clone()
+            })
+        };
+
+        let Some(handler) = handler else {
+            exc.reject(SchemeError::NotFound);
+            return;
+        };
+
+        let exc = match w.window().app() {
+            Some(app) => app.scheme_tracker().track(exc),
+            None => Some(exc),
+        };
+
+        if let Some(exc) = exc {
+            handler(w, req, exc)
+        }
+    });
+}
+
+extern "C" fn handle_mount_scheme_tp(
+    req: *mut saucer_scheme_request,
+    exc: *mut saucer_scheme_executor,
+    data: *mut c_void,
+) {
+    let data = unsafe { &*(data as *const SchemeHandlerData) };
+    ffi_callback("mounted file system", (), || {
+        let req = unsafe { Request::from_ptr(saucer_scheme_request_copy(req)) };
+        let exc = unsafe { Executor::from_ptr(saucer_scheme_executor_copy(exc)) };
+
+        let Some(w) = data.webview.upgrade() else {
+            return;
+        };
+
+        let url = req.url();
+        let vfs = w.0.mounts.lock_unpoisoned().get(&url.scheme()).cloned();
+
+        let Some(vfs) = vfs else {
+            exc.reject(SchemeError::NotFound);
+            return;
+        };
+
+        let exc = match w.window().app() {
+            Some(app) => app.scheme_tracker().track(exc),
+            None => Some(exc),
+        };
+
+        if let Some(exc) = exc {
+            serve_vfs(vfs, url.path(), exc);
         }
     });
 }
@@ -524,13 +2950,22 @@ extern "C" fn handle_scheme_tp(
     data: *mut c_void,
 ) {
     let data = unsafe { &*(data as *const SchemeHandlerData) };
-    ffi_callback((), || {
+    ffi_callback("scheme handler", (), || {
         // Both the request and the executor are borrowed (via auto conversion in C++)
 
         let req = unsafe { Request::from_ptr(saucer_scheme_request_copy(req)) };
         let exc = unsafe { Executor::from_ptr(saucer_scheme_executor_copy(exc)) };
 
-        if let Some(w) = data.webview.upgrade() {
+        let Some(w) = data.webview.upgrade() else {
+            return;
+        };
+
+        let exc = match w.window().app() {
+            Some(app) => app.scheme_tracker().track(exc),
+            None => Some(exc),
+        };
+
+        if let Some(exc) = exc {
             data.handler.handle_scheme(w.clone(), req, exc)
         }
     });
@@ -0,0 +1,284 @@
+//! Navigation handle module.
+//!
+//! See [`crate::webview::Webview::navigate`] for details.
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::Ordering;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Waker;
+
+use crate::navigation::Navigation;
+use crate::policy::Policy;
+use crate::url::Url;
+use crate::util::MutexExt;
+use crate::webview::LoadFailure;
+use crate::webview::RETRY_REPORT_GRACE;
+use crate::webview::Webview;
+
+/// How a navigation started with [`Webview::navigate`] concluded.
+#[derive(Clone)]
+pub enum NavigationOutcome {
+    /// The page has finished loading, at the given URL after redirects.
+    Finished(Url),
+    /// The page failed to load. The URL is the last one navigated to. See
+    /// [`crate::webview::Retry`] for how failures are detected.
+    Failed { url: Url, failure: LoadFailure },
+    /// The navigation (or one of its redirects) to the given URL was blocked,
+    /// e.g. by [`crate::navigation::NavigationRules`] or a handler of
+    /// [`crate::webview::NavigateEvent`].
+    Blocked(Url),
+    /// Another navigation took over before the page finished loading, e.g.
+    /// when the user clicks a link or [`Webview::navigate`] is called again.
+    Superseded,
+    /// The webview has been dropped.
+    Closed,
+}
+
+type BoxedCommitCallback = Box<dyn FnOnce(Url) + Send + 'static>;
+
+type BoxedCompleteCallback = Box<dyn FnOnce(NavigationOutcome) + Send + 'static>;
+
+#[derive(Default)]
+struct HandleState {
+    committed: Option<Url>,
+    outcome: Option<NavigationOutcome>,
+    on_commit: Vec<BoxedCommitCallback>,
+    on_complete: Vec<BoxedCompleteCallback>,
+    waker: Option<Waker>,
+}
+
+/// A handle of a navigation started with [`Webview::navigate`], which
+/// resolves exactly once when the navigation concludes. Completion can be
+/// observed with [`Self::on_complete`], or by awaiting the handle.
+///
+/// Dropping the handle doesn't cancel the navigation.
+#[derive(Clone)]
+pub struct NavigationHandle(Arc<Mutex<HandleState>>);
+
+impl NavigationHandle {
+    /// Invokes the callback with the URL once the navigation commits, i.e.
+    /// the new page starts replacing the old one. It's invoked immediately if
+    /// already committed, and never if the navigation concludes before
+    /// committing (e.g. when blocked).
+    pub fn on_commit(&self, callback: impl FnOnce(Url) + Send + 'static) {
+        let committed = {
+            let mut state = self.0.lock_unpoisoned();
+            match &state.committed {
+                Some(url) => url.clone(),
+                None if state.outcome.is_none() => {
+                    state.on_commit.push(Box::new(callback));
+                    return;
+                }
+                None => return,
+            }
+        };
+
+        callback(committed);
+    }
+
+    /// Invokes the callback with the outcome once the navigation concludes,
+    /// or immediately if it already has.
+    pub fn on_complete(&self, callback: impl FnOnce(NavigationOutcome) + Send + 'static) {
+        let outcome = {
+            let mut state = self.0.lock_unpoisoned();
+            match &state.outcome {
+                Some(o) => o.clone(),
+                None => {
+                    state.on_complete.push(Box::new(callback));
+                    return;
+                }
+            }
+        };
+
+        callback(outcome);
+    }
+
+    /// Gets the URL the navigation has committed to, if it has.
+    pub fn committed_url(&self) -> Option<Url> { self.0.lock_unpoisoned().committed.clone() }
+
+    /// Gets the outcome of the navigation, or [`None`] if it's still going on.
+    pub fn outcome(&self) -> Option<NavigationOutcome> { self.0.lock_unpoisoned().outcome.clone() }
+
+    fn commit(&self, url: &Url) {
+        let callbacks = {
+            let mut state = self.0.lock_unpoisoned();
+            if state.outcome.is_some() {
+                return;
+            }
+
+            state.committed = Some(url.clone());
+            std::mem::take(&mut state.on_commit)
+        };
+
+        for cb in callbacks {
+            cb(url.clone());
+        }
+    }
+
+    fn resolve(&self, outcome: NavigationOutcome) {
+        let (callbacks, waker) = {
+            let mut state = self.0.lock_unpoisoned();
+            if state.outcome.is_some() {
+                return;
+            }
+
+            state.outcome = Some(outcome.clone());
+            state.on_commit.clear();
+            (std::mem::take(&mut state.on_complete), state.waker.take())
+        };
+
+        for cb in callbacks {
+            cb(outcome.clone());
+        }
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for NavigationHandle {
+    type Output = NavigationOutcome;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.0.lock_unpoisoned();
+
+        match &state.outcome {
+            Some(o) => Poll::Ready(o.clone()),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// The navigation followed by a webview. See [`Webview::navigate`].
+pub(crate) struct TrackedNavigation {
+    handle: NavigationHandle,
+    /// The URL last navigated to or committed, following redirects.
+    target: Url,
+    /// Whether the navigation has been seen by the navigate event.
+    started: bool,
+}
+
+impl Drop for TrackedNavigation {
+    fn drop(&mut self) { self.handle.resolve(NavigationOutcome::Closed) }
+}
+
+impl Webview {
+    /// Navigates to the given URL, returning a handle that resolves when the
+    /// navigation concludes, with the final URL after redirects. See
+    /// [`NavigationOutcome`] for the outcomes.
+    ///
+    /// Redirects by the server or by scripts of the loading page are followed
+    /// until the page finishes loading. Navigations initiated by the user, or
+    /// by calling this method again, supersede the navigation instead. Those
+    /// started by the page after it has finished loading are not followed,
+    /// thus flows ending with such redirects (e.g. some login pages) should
+    /// check the URL and navigate again if needed.
+    ///
+    /// Load failures are detected the same way as for
+    /// [`crate::webview::Retry`], thus completion is reported slightly after
+    /// the page finishes loading.
+    pub fn navigate(&self, url: impl AsRef<Url>) -> NavigationHandle {
+        let url = url.as_ref();
+        let handle = NavigationHandle(Arc::default());
+
+        if self.ensure_alive().is_err() {
+            handle.resolve(NavigationOutcome::Closed);
+            return handle;
+        }
+
+        self.0.tracks_load_status.store(true, Ordering::Relaxed);
+        self.update_load_status_script();
+
+        let prev = self
+            .0
+            .navigation
+            .lock_unpoisoned()
+            .replace(TrackedNavigation {
+                handle: handle.clone(),
+                target: url.clone(),
+                started: false,
+            });
+
+        if let Some(prev) = prev {
+            prev.handle.resolve(NavigationOutcome::Superseded);
+        }
+
+        self.set_url(url);
+        handle
+    }
+
+    /// Updates the followed navigation when a navigation is about to happen.
+    pub(crate) fn on_tracked_navigate(&self, nav: &Navigation, policy: Policy) {
+        let mut tracked = self.0.navigation.lock_unpoisoned();
+        let Some(t) = tracked.as_mut() else {
+            return;
+        };
+
+        let outcome = if !t.started || !nav.is_user_initiated() {
+            t.started = true;
+            t.target = nav.url();
+            (policy == Policy::Block).then(|| NavigationOutcome::Blocked(nav.url()))
+        } else {
+            Some(NavigationOutcome::Superseded)
+        };
+
+        if let Some(outcome) = outcome {
+            let t = tracked.take().unwrap();
+            drop(tracked);
+            t.handle.resolve(outcome);
+        }
+    }
+
+    /// Updates the followed navigation when it commits.
+    pub(crate) fn on_tracked_navigated(&self, url: &Url) {
+        let handle = {
+            let mut tracked = self.0.navigation.lock_unpoisoned();
+            let Some(t) = tracked.as_mut().filter(|t| t.started) else {
+                return;
+            };
+
+            t.target = url.clone();
+            t.handle.clone()
+        };
+
+        handle.commit(url);
+    }
+
+    /// Checks the followed navigation after a load finishes, once the page
+    /// has had time to report its status.
+    pub(crate) fn on_tracked_load_finished(&self) {
+        let generation = self.0.retry.lock_unpoisoned().generation();
+        let started = self
+            .0
+            .navigation
+            .lock_unpoisoned()
+            .as_ref()
+            .map(|t| t.started);
+
+        if started != Some(true) {
+            return;
+        }
+
+        self.post_delayed(RETRY_REPORT_GRACE, move |w| {
+            let Some(failure) = w.0.retry.lock_unpoisoned().outcome(generation) else {
+                return; // Another load has started since
+            };
+
+            let Some(t) = w.0.navigation.lock_unpoisoned().take_if(|t| t.started) else {
+                return;
+            };
+
+            let url = t.target.clone();
+            t.handle.resolve(match failure {
+                Some(failure) => NavigationOutcome::Failed { url, failure },
+                None => NavigationOutcome::Finished(url),
+            });
+        });
+    }
+}
@@ -1,8 +1,20 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::PathBuf;
 use std::ptr::NonNull;
+use std::sync::LazyLock;
+use std::sync::Mutex;
 
 use saucer_sys::*;
 
+use crate::backend::Backend;
 use crate::macros::use_string;
+use crate::util::MutexExt;
+use crate::webview::BrowserFlag;
+use crate::webview::BrowserFlagIssue;
+use crate::webview::Profile;
+use crate::webview::flags::find_flag_issues;
+use crate::webview::flags::flag_name;
 use crate::window::Window;
 
 /// Options for configuring webview creation.
@@ -16,7 +28,372 @@ pub struct WebviewOptions {
     pub hardware_acceleration: Option<bool>,
     pub storage_path: Option<String>,
     pub user_agent: Option<String>,
+    /// Browser flags passed to the underlying engine. See
+    /// [`WebviewOptions::isolate_browser_flags`] for how they are shared
+    /// between webviews. Prefer [`WebviewOptions::set_flag`] for known flags.
+    ///
+    /// On WebView2, these are the additional browser arguments of the
+    /// environment.
     pub browser_flags: Vec<String>,
+    /// Whether to check browser flags when creating the webview in debug
+    /// builds, panicking if [`WebviewOptions::browser_flag_issues`] finds any.
+    pub strict_browser_flags: bool,
+    /// Whether to fail creating the webview if its browser flags may affect,
+    /// or be affected by, other webviews in the process.
+    ///
+    /// Browser flags are applied to the browser process, not to individual
+    /// webviews:
+    ///
+    /// - On WebView2, webviews sharing the same storage path (including the
+    ///   default one) share a browser process, thus they must be given the same
+    ///   flags. Use distinct [`WebviewOptions::storage_path`] values to isolate
+    ///   them.
+    /// - On Qt WebEngine, flags are taken once for the whole process when the
+    ///   first webview is created and can't be isolated.
+    /// - WebKit-based backends don't take browser flags.
+    ///
+    /// When set, creating a webview fails with
+    /// [`crate::error::Error::BrowserFlagsNotIsolated`] if an existing webview
+    /// shares its browser process but uses different flags (including those
+    /// derived from [`WebviewOptions::proxy`] and
+    /// [`WebviewOptions::remote_debugging_port`]). When unset, the flags are
+    /// passed as-is and may be silently ignored in such cases.
+    pub isolate_browser_flags: bool,
+    pub proxy: Option<ProxyConfig>,
+    /// The port to serve the Chrome DevTools Protocol on, for attaching
+    /// external debuggers and automation clients.
+    ///
+    /// This is passed as a browser flag, thus it only takes effect on
+    /// Chromium-based backends (WebView2 and Qt WebEngine), and is shared by
+    /// all webviews in the same browser process (see
+    /// [`WebviewOptions::isolate_browser_flags`]). Creating webviews with a
+    /// port on other backends fails with
+    /// [`crate::error::Error::Unsupported`].
+    pub remote_debugging_port: Option<u16>,
+    /// The profile whose storage and permission store are used. When set, it
+    /// overrides [`WebviewOptions::storage_path`], and cookies are persistent
+    /// unless [`WebviewOptions::persistent_cookies`] says otherwise.
+    pub profile: Option<Profile>,
+    /// Whether to make the webview and its window background transparent. See
+    /// [`crate::window::Window::set_effect`].
+    pub transparent: bool,
+    /// Whether to enable spellchecking. See
+    /// [`crate::webview::Webview::set_spellcheck`].
+    pub spellcheck: Option<bool>,
+    /// The dictionaries used for spellchecking. See
+    /// [`crate::webview::Webview::set_spellcheck_languages`]. Creating
+    /// webviews with languages fails with [`crate::error::Error::Unsupported`]
+    /// on backends that can't choose them.
+    pub spellcheck_languages: Option<Vec<String>>,
+    /// The timezone seen by the page. See
+    /// [`crate::webview::Webview::set_timezone_override`].
+    pub timezone: Option<String>,
+    /// The scripts injected by the bindings. See [`InternalScripts`].
+    pub internal_scripts: InternalScripts,
+    /// Whether devtools are allowed. See [`DevToolsPolicy`].
+    pub dev_tools_policy: DevToolsPolicy,
+    /// The UI language of the engine (e.g. `de-DE`), which also decides the
+    /// default `Accept-Language` header and `navigator.language`.
+    ///
+    /// This is passed as a browser flag (see [`BrowserFlag::Lang`]), thus it
+    /// only takes effect on Chromium-based backends (WebView2 and Qt
+    /// WebEngine). Other backends follow the system language.
+    pub language: Option<String>,
+    /// The folder of a fixed-version WebView2 Runtime to use instead of the
+    /// Evergreen one installed on the system, i.e. the folder containing
+    /// `msedgewebview2.exe`. This allows shipping a pinned engine with the
+    /// app.
+    ///
+    /// Webviews sharing a browser process (see
+    /// [`WebviewOptions::isolate_browser_flags`]) must use the same runtime.
+    /// Creating webviews fails with [`crate::error::Error::Io`] if the folder
+    /// doesn't contain the runtime, or with
+    /// [`crate::error::Error::Unsupported`] on other backends.
+    pub browser_executable_folder: Option<PathBuf>,
+}
+
+/// Decides whether devtools are available in a webview.
+///
+/// When devtools are not allowed, they are kept closed regardless of
+/// [`crate::webview::Webview::set_dev_tools`], `view-source:` URLs are
+/// blocked, and remote debugging is disabled (including
+/// [`WebviewOptions::remote_debugging_port`] and remote debugging flags in
+/// [`WebviewOptions::browser_flags`]). The policy can't be changed after the
+/// webview is created, thus devtools can't be enabled accidentally.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DevToolsPolicy {
+    /// Devtools are allowed.
+    #[default]
+    Allow,
+    /// Devtools are only allowed in builds with debug assertions, i.e. they
+    /// are disabled in release builds.
+    DebugOnly,
+    /// Devtools are never allowed.
+    Deny,
+}
+
+impl DevToolsPolicy {
+    /// Checks whether devtools are allowed in this build.
+    pub const fn is_allowed(&self) -> bool {
+        match self {
+            Self::Allow => true,
+            Self::DebugOnly => cfg!(debug_assertions),
+            Self::Deny => false,
+        }
+    }
+}
+
+/// Controls the scripts injected into pages by the bindings, which back
+/// features such as [`crate::webview::InputGesture`] and
+/// [`crate::webview::ConsoleMessageEvent`].
+///
+/// Each script is only installed once its feature is first used, e.g. when a
+/// handler of its event is registered, as most of them wrap page APIs. The
+/// flags below install them up front instead, which covers pages loaded before
+/// then. All of them default to `false`.
+///
+/// The scripts run in the main world and keep their state in globals under a
+/// common prefix. The exact injected sources can be inspected with
+/// [`crate::webview::Webview::internal_scripts`]. Scripts injected by saucer
+/// itself (e.g. `window.saucer`) are not covered.
+///
+/// The scripts report to the bindings with messages carrying a random token of
+/// the webview, which tells them apart from messages of the page. Scripts
+/// carrying the token are only injected into the top-level document, thus
+/// messages from frames never pass as internal ones. The token is not a means
+/// of authentication: the page shares the world of the scripts and may observe
+/// it, so reports of a hostile page can't be trusted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InternalScripts {
+    /// The prefix of globals defined by the scripts, which must be a valid
+    /// JavaScript identifier. Defaults to `__saucers`.
+    pub namespace: String,
+    /// Whether to track user gestures for
+    /// [`crate::webview::NavigateEvent::gesture`] up front. Otherwise it's
+    /// tracked once a handler of [`crate::webview::NavigateEvent`] is
+    /// registered.
+    pub input: bool,
+    /// Whether to forward console messages as
+    /// [`crate::webview::ConsoleMessageEvent`] up front. Otherwise they're
+    /// forwarded once a handler of the event is registered.
+    pub console: bool,
+    /// Whether to track media playback for
+    /// [`crate::webview::AudioStateEvent`] up front. Otherwise it's tracked
+    /// once a handler of the event is registered or
+    /// [`crate::webview::Webview::set_muted`] is called.
+    pub audio: bool,
+    /// Whether to track speech recognition started by the page up front, which
+    /// tells [`crate::permission::PermissionType::SpeechRecognition`] requests
+    /// apart from others. Otherwise it's tracked once a handler of
+    /// [`crate::webview::PermissionEvent`] is registered or a permission
+    /// store is set.
+    pub speech: bool,
+    /// Whether to report user activity as
+    /// [`crate::webview::UserActivityEvent`] up front. Otherwise it's
+    /// reported once a handler of the event is registered or
+    /// [`crate::webview::Webview::set_user_idle_timeout`] is called.
+    pub activity: bool,
+    /// Whether to define the page-side helper of
+    /// [`crate::webview::Webview::open_stream`] up front, if streams are
+    /// enabled (see [`crate::app::AppOptions::streams`]). Otherwise it's
+    /// defined once a stream is opened.
+    pub streams: bool,
+}
+
+impl Default for InternalScripts {
+    fn default() -> Self {
+        Self {
+            namespace: "__saucers".to_owned(),
+            input: false,
+            console: false,
+            audio: false,
+            speech: false,
+            activity: false,
+            streams: false,
+        }
+    }
+}
+
+/// Proxy settings of a webview.
+///
+/// Proxies are configured via browser flags, thus they only take effect on
+/// Chromium-based backends (WebView2 and Qt WebEngine). Other backends follow
+/// the system settings. Proxies requiring authentication are not yet
+/// supported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProxyConfig {
+    /// Follows the system proxy settings.
+    System,
+    /// Connects directly without any proxy.
+    Direct,
+    /// Uses an HTTP proxy.
+    Http { host: String, port: u16 },
+    /// Uses a SOCKS5 proxy.
+    Socks5 { host: String, port: u16 },
+}
+
+impl ProxyConfig {
+    /// Gets the browser flag applying this config, if any.
+    fn browser_flag(&self) -> Option<String> {
+        match self {
+            Self::System => None,
+            Self::Direct => Some("--no-proxy-server".to_owned()),
+            Self::Http { host, port } => Some(format!("--proxy-server=http://{host}:{port}")),
+            Self::Socks5 { host, port } => Some(format!("--proxy-server=socks5://{host}:{port}")),
+        }
+    }
+}
+
+impl WebviewOptions {
+    /// Adds a typed browser flag, replacing existing flags of the same name.
+    pub fn set_flag(&mut self, flag: BrowserFlag) {
+        let flag = flag.to_string();
+        let name = flag_name(&flag).to_owned();
+
+        self.browser_flags.retain(|f| flag_name(f) != name);
+        self.browser_flags.push(flag);
+    }
+
+    /// Checks the browser flags to be passed (including derived ones) for
+    /// unknown and conflicting ones.
+    pub fn browser_flag_issues(&self) -> Vec<BrowserFlagIssue> {
+        find_flag_issues(&self.effective_browser_flags())
+    }
+
+    /// Gets the browser flags to be passed, including derived ones.
+    fn effective_browser_flags(&self) -> Vec<String> {
+        let proxy_flag = self.proxy.as_ref().and_then(ProxyConfig::browser_flag);
+        let debugging_flag = self
+            .remote_debugging_port
+            .map(|p| format!("--remote-debugging-port={p}"));
+        let lang_flag = self
+            .language
+            .as_ref()
+            .map(|l| BrowserFlag::Lang(l.clone()).to_string());
+
+        let debugging_allowed = self.dev_tools_policy.is_allowed();
+
+        self.browser_flags
+            .iter()
+            .filter(|f| debugging_allowed || !flag_name(f).starts_with("--remote-debugging"))
+            .cloned()
+            .chain(proxy_flag)
+            .chain(debugging_flag.filter(|_| debugging_allowed))
+            .chain(lang_flag)
+            .collect()
+    }
+
+    /// Gets the key of the browser process the webview will live in, or
+    /// [`None`] if the backend doesn't take browser flags.
+    fn flag_profile(&self) -> Option<Option<String>> {
+        match Backend::current() {
+            Backend::Qt => Some(None),
+            Backend::WebView2 => Some(self.storage_path.clone()),
+            Backend::WebKit | Backend::WebKitGtk => None,
+        }
+    }
+
+    /// Applies the storage settings of the profile, if any.
+    pub(crate) fn apply_profile(&mut self) {
+        if let Some(profile) = &self.profile {
+            self.storage_path = Some(profile.storage_path().to_string_lossy().into_owned());
+            self.persistent_cookies.get_or_insert(true);
+        }
+    }
+
+    /// Records the browser flags of a webview to be created, checking that they
+    /// are consistent with existing webviews if isolation is requested.
+    pub(crate) fn claim_browser_flags(&self) -> crate::error::Result<()> {
+        static PROFILES: LazyLock<Mutex<HashMap<Option<String>, Vec<String>>>> =
+            LazyLock::new(Default::default);
+
+        if self.strict_browser_flags {
+            let issues = self.browser_flag_issues();
+            debug_assert!(issues.is_empty(), "invalid browser flags: {issues:?}");
+        }
+
+        if self.remote_debugging_port.is_some()
+            && self.dev_tools_policy.is_allowed()
+            && !Backend::current().capabilities().remote_debugging
+        {
+            return Err(crate::error::Error::unsupported(
+                "remote debugging ports",
+                Some("open the built-in inspector with Webview::open_dev_tools"),
+            ));
+        }
+
+        let Some(profile) = self.flag_profile() else {
+            return Ok(());
+        };
+
+        let flags = self.effective_browser_flags();
+        let mut profiles = PROFILES.lock_unpoisoned();
+
+        match profiles.get(&profile) {
+            Some(existing) if *existing != flags && self.isolate_browser_flags => {
+                Err(crate::error::Error::BrowserFlagsNotIsolated)
+            }
+            Some(_) => Ok(()),
+            None => {
+                profiles.insert(profile, flags);
+                Ok(())
+            }
+        }
+    }
+
+    /// Points WebView2 at the fixed-version runtime of the options, if any,
+    /// until the returned guard is dropped. The webview must be created in
+    /// between.
+    pub(crate) fn override_runtime(&self) -> crate::error::Result<Option<RuntimeOverride>> {
+        // Read by WebView2 when creating the environment of a webview
+        const VAR: &str = "WEBVIEW2_BROWSER_EXECUTABLE_FOLDER";
+
+        let Some(folder) = &self.browser_executable_folder else {
+            return Ok(None);
+        };
+
+        if Backend::current() != Backend::WebView2 {
+            return Err(crate::error::Error::unsupported(
+                "fixed-version engine runtimes",
+                None,
+            ));
+        }
+
+        if !folder.join("msedgewebview2.exe").is_file() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no WebView2 Runtime in {}", folder.display()),
+            )
+            .into());
+        }
+
+        let prev = std::env::var_os(VAR);
+
+        // SAFETY: Only reached on Windows, where the environment is guarded by
+        // the OS and can be modified concurrently
+        unsafe { std::env::set_var(VAR, folder) };
+
+        Ok(Some(RuntimeOverride { var: VAR, prev }))
+    }
+}
+
+/// Restores the environment changed by
+/// [`WebviewOptions::override_runtime`] when dropped.
+pub(crate) struct RuntimeOverride {
+    var: &'static str,
+    prev: Option<OsString>,
+}
+
+impl Drop for RuntimeOverride {
+    fn drop(&mut self) {
+        // SAFETY: See `WebviewOptions::override_runtime`
+        unsafe {
+            match self.prev.take() {
+                Some(v) => std::env::set_var(self.var, v),
+                None => std::env::remove_var(self.var),
+            }
+        }
+    }
 }
 
 pub(crate) struct RawWebviewOptions {
@@ -31,6 +408,7 @@ impl RawWebviewOptions {
     pub(crate) fn new(opt: WebviewOptions, window: Window) -> Self {
         let ptr = unsafe { saucer_webview_options_new(window.as_ptr()) };
         let inner = NonNull::new(ptr).expect("invalid webview options ptr");
+        let flags = opt.effective_browser_flags();
 
         unsafe {
             if let Some(t) = opt.allow_attributes {
@@ -53,7 +431,7 @@ impl RawWebviewOptions {
                 use_string!(p; saucer_webview_options_set_user_agent(ptr, p));
             }
 
-            for f in opt.browser_flags {
+            for f in flags {
                 use_string!(f; saucer_webview_options_append_browser_flag(ptr, f));
                 // Value copied
             }
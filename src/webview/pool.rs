@@ -0,0 +1,105 @@
+use std::collections::VecDeque;
+use std::panic::UnwindSafe;
+
+use crate::app::App;
+use crate::app::FinishRoutine;
+use crate::thread::check_event_thread;
+use crate::webview::Webview;
+
+type BoxedWebviewFactory = Box<dyn Fn(&App) -> crate::error::Result<Webview> + UnwindSafe>;
+
+/// A pool of pre-created webviews.
+///
+/// Creating a webview can take noticeable time on some backends (WebView2 in
+/// particular). A pool creates webviews ahead of time using the given factory
+/// and keeps them hidden, so that [`WebviewPool::take`] only needs to navigate
+/// and show one.
+///
+/// The factory is expected to create a hidden window (windows are hidden until
+/// [`crate::window::Window::show`] is called) and a webview in it. Pooled
+/// webviews are ordinary handles and are collected like any other when the
+/// pool is dropped. Like other handles, the pool must be dropped no later than
+/// the finish routine, which can be done by returning it from the start
+/// callback directly.
+///
+/// Hidden windows are still open windows, thus they prevent the app from
+/// quitting when `quit_on_last_window_closed` is set. Call
+/// [`WebviewPool::clear`] when the last visible window closes if needed.
+pub struct WebviewPool {
+    app: App,
+    factory: BoxedWebviewFactory,
+    idle: VecDeque<Webview>,
+    capacity: usize,
+}
+
+impl WebviewPool {
+    /// Creates a pool that keeps up to `capacity` idle webviews created by the
+    /// given factory, and fills it immediately.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, unless allowed by the
+    /// [`crate::thread::ThreadViolationPolicy`].
+    pub fn new(
+        app: &App,
+        capacity: usize,
+        factory: impl Fn(&App) -> crate::error::Result<Webview> + UnwindSafe + 'static,
+    ) -> crate::error::Result<Self> {
+        if check_event_thread(app.is_thread_safe(), "creating webview pools").is_some() {
+            return Err(crate::error::Error::WrongThread);
+        }
+
+        let mut pool = Self {
+            app: app.clone(),
+            factory: Box::new(factory),
+            idle: VecDeque::with_capacity(capacity),
+            capacity,
+        };
+
+        pool.fill()?;
+        Ok(pool)
+    }
+
+    /// Creates webviews until the pool is full.
+    ///
+    /// [`WebviewPool::take`] does not refill the pool, as creating webviews
+    /// right after taking one would delay showing it. Call this method later
+    /// (e.g. in a callback passed to [`App::post`]) to keep the pool warm.
+    pub fn fill(&mut self) -> crate::error::Result<()> {
+        while self.idle.len() < self.capacity {
+            self.idle.push_back((self.factory)(&self.app)?);
+        }
+
+        Ok(())
+    }
+
+    /// Takes a webview from the pool, navigates it to the given URL and shows
+    /// its window. A new webview is created if the pool is empty.
+    pub fn take(&mut self, url: impl Into<Vec<u8>>) -> crate::error::Result<Webview> {
+        let webview = match self.idle.pop_front() {
+            Some(w) => w,
+            None => (self.factory)(&self.app)?,
+        };
+
+        webview.set_url_str(url);
+        webview.window().show();
+
+        Ok(webview)
+    }
+
+    /// Drops all idle webviews.
+    pub fn clear(&mut self) { self.idle.clear(); }
+
+    /// Gets the number of idle webviews.
+    pub fn len(&self) -> usize { self.idle.len() }
+
+    /// Checks whether the pool has no idle webviews.
+    pub fn is_empty(&self) -> bool { self.idle.is_empty() }
+
+    /// Gets the number of idle webviews this pool keeps.
+    pub fn capacity(&self) -> usize { self.capacity }
+}
+
+/// Allows you to return a [`WebviewPool`] directly from start callback to keep
+/// it.
+impl FinishRoutine for WebviewPool {}
@@ -0,0 +1,148 @@
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use crate::permission::FilePermissionStore;
+use crate::permission::PermissionStore;
+use crate::permission::PermissionType;
+use crate::policy::Policy;
+use crate::util::MutexExt;
+
+struct ProfileInner {
+    name: String,
+    path: PathBuf,
+    permissions: Mutex<FilePermissionStore>,
+}
+
+/// A named browsing profile, holding storage (cookies, cache, local storage,
+/// etc.) and remembered permissions on disk.
+///
+/// Profiles are directories under a root directory chosen by the app. Webviews
+/// created with the same profile (see
+/// [`crate::webview::WebviewOptions::profile`]) share its storage and
+/// permission store, while different profiles are isolated from each other.
+/// This makes it easy to implement multiple accounts.
+///
+/// Handles are cheap to clone.
+#[derive(Clone)]
+pub struct Profile(Arc<ProfileInner>);
+
+impl Profile {
+    /// Opens the profile with the given name under the root directory, creating
+    /// it if needed. Names must be a single plain path component, and can't
+    /// contain path separators or `:`.
+    pub fn open(root: impl AsRef<Path>, name: &str) -> crate::error::Result<Self> {
+        let path = Self::profile_path(root.as_ref(), name)?;
+        std::fs::create_dir_all(path.join("storage"))?;
+        let permissions = FilePermissionStore::open(path.join("permissions"))?;
+
+        Ok(Self(Arc::new(ProfileInner {
+            name: name.to_owned(),
+            path,
+            permissions: Mutex::new(permissions),
+        })))
+    }
+
+    /// Lists the names of profiles under the root directory.
+    pub fn list(root: impl AsRef<Path>) -> crate::error::Result<Vec<String>> {
+        let mut names = Vec::new();
+
+        for entry in std::fs::read_dir(root)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir()
+                && let Some(name) = entry.file_name().to_str()
+            {
+                names.push(name.to_owned());
+            }
+        }
+
+        names.sort();
+        Ok(names)
+    }
+
+    /// Deletes the profile with the given name under the root directory,
+    /// including all its data. Profiles must not be deleted while being used
+    /// by webviews.
+    pub fn delete(root: impl AsRef<Path>, name: &str) -> crate::error::Result<()> {
+        std::fs::remove_dir_all(Self::profile_path(root.as_ref(), name)?)?;
+        Ok(())
+    }
+
+    /// Gets the name of the profile.
+    pub fn name(&self) -> &str { &self.0.name }
+
+    /// Gets the directory of the profile.
+    pub fn path(&self) -> &Path { &self.0.path }
+
+    /// Gets the storage path used by webviews of this profile.
+    pub fn storage_path(&self) -> PathBuf { self.0.path.join("storage") }
+
+    /// Gets a permission store backed by this profile. Stores of the same
+    /// profile share their content.
+    pub fn permission_store(&self) -> Box<dyn PermissionStore> { Box::new(self.clone()) }
+
+    fn profile_path(root: &Path, name: &str) -> crate::error::Result<PathBuf> {
+        // A single plain component, as others (e.g. `C:x` on Windows) resolve
+        // outside the root when joined
+        let mut components = Path::new(name).components();
+        let is_plain = matches!(components.next(), Some(Component::Normal(_)))
+            && components.next().is_none()
+            && !name.contains(['/', '\\', ':', '\0']);
+
+        if !is_plain {
+            return Err(crate::error::Error::InvalidProfileName(name.to_owned()));
+        }
+
+        Ok(root.join(name))
+    }
+}
+
+impl PermissionStore for Profile {
+    fn get(&self, origin: &str, kind: PermissionType) -> Option<Policy> {
+        self.0.permissions.lock_unpoisoned().get(origin, kind)
+    }
+
+    fn set(
+        &mut self,
+        origin: &str,
+        kind: PermissionType,
+        policy: Policy,
+    ) -> crate::error::Result<()> {
+        self.0
+            .permissions
+            .lock_unpoisoned()
+            .set(origin, kind, policy)
+    }
+
+    fn remove(&mut self, origin: &str, kind: PermissionType) -> crate::error::Result<()> {
+        self.0.permissions.lock_unpoisoned().remove(origin, kind)
+    }
+
+    fn clear(&mut self) -> crate::error::Result<()> { self.0.permissions.lock_unpoisoned().clear() }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::Profile;
+
+    #[test]
+    fn profile_names_stay_under_the_root() {
+        let root = Path::new("profiles");
+
+        assert_eq!(
+            Profile::profile_path(root, "default").unwrap(),
+            root.join("default")
+        );
+
+        for name in ["../x", "a/b", "a\\b", "C:x", "a:b", ".", "..", ""] {
+            assert!(
+                Profile::profile_path(root, name).is_err(),
+                "{name:?} should be rejected"
+            );
+        }
+    }
+}
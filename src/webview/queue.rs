@@ -0,0 +1,147 @@
+//! Script queue module.
+//!
+//! See [`crate::webview::Webview::execute_on_ready`] for details.
+use crate::util::MutexExt;
+use crate::webview::ScriptsDroppedEvent;
+use crate::webview::Webview;
+
+/// Scripts waiting for the document to be ready.
+#[derive(Debug, Default)]
+pub(crate) struct ScriptQueue {
+    /// Whether the DOM of the current document is ready.
+    ready: bool,
+    /// Whether a load has started and its DOM is not ready yet.
+    loading: bool,
+    /// Queued scripts, and whether they were queued during a load, which they
+    /// are then bound to.
+    pending: Vec<(bool, String)>,
+}
+
+impl ScriptQueue {
+    /// Queues the script, or gives it back if the document is ready.
+    fn push(&mut self, js: String) -> Option<String> {
+        if self.ready {
+            return Some(js);
+        }
+
+        self.pending.push((self.loading, js));
+        None
+    }
+
+    /// Takes all queued scripts.
+    fn take(&mut self) -> Vec<String> { self.pending.drain(..).map(|(_, js)| js).collect() }
+
+    /// Marks a load as started, binding queued scripts to it. Returns scripts
+    /// bound to an earlier load, which is abandoned.
+    fn on_started(&mut self) -> Vec<String> {
+        self.ready = false;
+        self.loading = true;
+
+        let (dropped, kept) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(bound, _)| *bound);
+
+        self.pending = kept.into_iter().map(|(_, js)| (true, js)).collect();
+        dropped.into_iter().map(|(_, js)| js).collect()
+    }
+
+    /// Marks the document as ready, returning the scripts to run.
+    fn on_dom_ready(&mut self) -> Vec<String> {
+        self.ready = true;
+        self.loading = false;
+        self.take()
+    }
+}
+
+impl Webview {
+    /// Executes JavaScript code in the current document, failing with
+    /// [`crate::error::Error::NoDocument`] if its DOM is not ready yet (e.g.
+    /// during navigations), instead of deferring it like [`Self::execute`].
+    pub fn execute_now(&self, js: impl Into<String>) -> crate::error::Result<()> {
+        self.ensure_alive()?;
+
+        if !self.0.script_queue.lock_unpoisoned().ready {
+            return Err(crate::error::Error::NoDocument);
+        }
+
+        self.execute(js.into());
+        Ok(())
+    }
+
+    /// Executes JavaScript code once the DOM of the document is ready, or
+    /// immediately if it already is. Scripts run in the order they're queued.
+    ///
+    /// Scripts queued while a page loads are bound to that page. If another
+    /// navigation starts before its DOM is ready, they're dropped and a
+    /// [`ScriptsDroppedEvent`] is fired, rather than running in a page they
+    /// were not meant for. Scripts queued with no load in progress run in the
+    /// next page that becomes ready.
+    pub fn execute_on_ready(&self, js: impl Into<String>) {
+        let js = self.0.script_queue.lock_unpoisoned().push(js.into());
+
+        if let Some(js) = js {
+            self.execute(js);
+        }
+    }
+
+    /// Hands scripts queued with [`Self::execute_on_ready`] to the engine now,
+    /// returning how many there were. Scripts are still deferred by the
+    /// bindings until a document is ready, see [`Self::execute`], but are no
+    /// longer dropped on navigations.
+    pub fn flush_pending_scripts(&self) -> usize {
+        let scripts = self.0.script_queue.lock_unpoisoned().take();
+        let count = scripts.len();
+
+        for js in scripts {
+            self.execute(js);
+        }
+
+        count
+    }
+
+    /// Updates the queue when a load starts.
+    pub(crate) fn on_queue_load_started(&self) {
+        let dropped = self.0.script_queue.lock_unpoisoned().on_started();
+
+        if !dropped.is_empty() {
+            self.event_listener_data()
+                .emit(self, || ScriptsDroppedEvent { scripts: dropped });
+        }
+    }
+
+    /// Runs queued scripts when the DOM is ready.
+    pub(crate) fn on_queue_dom_ready(&self) {
+        let scripts = self.0.script_queue.lock_unpoisoned().on_dom_ready();
+
+        for js in scripts {
+            self.execute(js);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_queue() {
+        let mut queue = ScriptQueue::default();
+
+        // Queued before any load, kept for the first one
+        assert!(queue.push("a".to_owned()).is_none());
+        assert!(queue.on_started().is_empty());
+
+        // Bound to the load in progress, dropped when it's abandoned
+        assert!(queue.push("b".to_owned()).is_none());
+        assert_eq!(queue.on_started(), ["a", "b"]);
+
+        assert!(queue.push("c".to_owned()).is_none());
+        assert_eq!(queue.on_dom_ready(), ["c"]);
+        assert_eq!(queue.push("d".to_owned()).as_deref(), Some("d"));
+
+        assert!(queue.on_started().is_empty());
+        assert!(queue.push("e".to_owned()).is_none());
+        assert_eq!(queue.take(), ["e"]);
+        assert!(queue.on_dom_ready().is_empty());
+    }
+}
@@ -0,0 +1,73 @@
+//! Pending replies module.
+//!
+//! See [`crate::webview::Webview::evaluate`] for details.
+use std::collections::HashMap;
+
+use crate::js::JsValue;
+
+pub(crate) type BoxedReplyCallback =
+    Box<dyn FnOnce(crate::error::Result<JsValue>) + Send + 'static>;
+
+/// Callbacks waiting for results of scripts evaluated by the bindings.
+#[derive(Default)]
+pub(crate) struct PendingReplies {
+    next: u64,
+    callbacks: HashMap<u64, BoxedReplyCallback>,
+}
+
+impl PendingReplies {
+    /// Adds a callback, returning the ID the page reports its result with.
+    pub(crate) fn add(&mut self, callback: BoxedReplyCallback) -> u64 {
+        let id = self.next;
+        self.next += 1;
+        self.callbacks.insert(id, callback);
+        id
+    }
+
+    /// Takes the callback of the given ID.
+    pub(crate) fn take(&mut self, id: u64) -> Option<BoxedReplyCallback> {
+        self.callbacks.remove(&id)
+    }
+
+    /// Takes all callbacks, whose results will never arrive. They should be
+    /// invoked with [`crate::error::Error::Closed`] after releasing the lock.
+    pub(crate) fn drain(&mut self) -> Vec<BoxedReplyCallback> {
+        self.callbacks.drain().map(|(_, cb)| cb).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn drained_replies_fail_with_closed() {
+        let results = Arc::new(Mutex::new(Vec::new()));
+        let mut replies = PendingReplies::default();
+
+        let ids = (0..2)
+            .map(|_| {
+                let results = results.clone();
+                replies.add(Box::new(move |res| results.lock().unwrap().push(res)))
+            })
+            .collect::<Vec<_>>();
+
+        for cb in replies.drain() {
+            cb(Err(crate::error::Error::Closed));
+        }
+
+        let results = results.lock().unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(
+            results
+                .iter()
+                .all(|r| matches!(r, Err(crate::error::Error::Closed)))
+        );
+
+        // Late results of the page are ignored
+        assert!(ids.into_iter().all(|id| replies.take(id).is_none()));
+    }
+}
@@ -0,0 +1,438 @@
+use std::time::Duration;
+
+use crate::url::Url;
+use crate::util::MutexExt;
+use crate::webview::Webview;
+
+/// A policy for retrying failed main-frame loads, set via
+/// [`crate::webview::Webview::set_retry_policy`].
+///
+/// A load is considered failed if:
+///
+/// - The engine reports that the server can't be reached, which is treated as a
+///   [`LoadFailure::Network`] failure. Qt doesn't report load errors, thus
+///   there an HTTP(S) page that never reports its status (see below) is
+///   considered unreachable instead, as the error pages of the engine don't run
+///   scripts. Non-HTML documents (e.g. PDFs) are also affected by this.
+/// - The page reports an HTTP status of 400 or above, via a small script run in
+///   HTML documents. This relies on
+///   `PerformanceNavigationTiming.responseStatus`, which is not available on
+///   all engines, in which case HTTP errors are not detected.
+///
+/// Each retry fires a [`crate::webview::RetryEvent`]. The attempt counter is
+/// reset when a page loads successfully or when navigating elsewhere.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Retry {
+    max_attempts: u32,
+    delay: Duration,
+    exponential: bool,
+    on: RetryOn,
+}
+
+impl Retry {
+    /// Creates a policy that retries up to `max_attempts` times, doubling the
+    /// delay after each attempt, starting from `base_delay`.
+    pub fn exponential(max_attempts: u32, base_delay: Duration) -> Self {
+        Self {
+            max_attempts,
+            delay: base_delay,
+            exponential: true,
+            on: RetryOn::NetworkErrors,
+        }
+    }
+
+    /// Creates a policy that retries up to `max_attempts` times with the same
+    /// delay.
+    pub fn fixed(max_attempts: u32, delay: Duration) -> Self {
+        Self {
+            max_attempts,
+            delay,
+            exponential: false,
+            on: RetryOn::NetworkErrors,
+        }
+    }
+
+    /// Sets the kind of failures to retry. Defaults to
+    /// [`RetryOn::NetworkErrors`].
+    pub fn only_for(mut self, on: RetryOn) -> Self {
+        self.on = on;
+        self
+    }
+
+    /// Gets the maximum number of attempts.
+    pub fn max_attempts(&self) -> u32 { self.max_attempts }
+
+    /// Gets the delay before the given attempt (starting from 1), or [`None`]
+    /// if the policy has been exhausted.
+    pub fn delay(&self, attempt: u32) -> Option<Duration> {
+        if attempt == 0 || attempt > self.max_attempts {
+            return None;
+        }
+
+        if self.exponential {
+            let factor = 1u32.checked_shl(attempt - 1).unwrap_or(u32::MAX);
+            Some(self.delay.saturating_mul(factor))
+        } else {
+            Some(self.delay)
+        }
+    }
+
+    /// Checks whether the given failure should be retried.
+    pub fn matches(&self, failure: LoadFailure) -> bool {
+        match self.on {
+            RetryOn::NetworkErrors => failure == LoadFailure::Network,
+            RetryOn::HttpErrors => matches!(failure, LoadFailure::Http(_)),
+            RetryOn::AllErrors => true,
+        }
+    }
+}
+
+/// The kind of failures retried by a [`Retry`] policy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RetryOn {
+    /// Retries when the server can't be reached.
+    NetworkErrors,
+    /// Retries when the server responds with an HTTP error status.
+    HttpErrors,
+    /// Retries on all failures above.
+    AllErrors,
+}
+
+/// Describes why a load is considered failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LoadFailure {
+    /// The page didn't load, likely because the server can't be reached.
+    Network,
+    /// The server responded with the given HTTP error status.
+    Http(u16),
+}
+
+/// Tracks loads of a webview for its retry policy.
+#[derive(Default)]
+pub(crate) struct RetryState {
+    pub(crate) policy: Option<Retry>,
+    attempts: u32,
+    generation: u64,
+    status: Option<u16>,
+    target: Option<Url>,
+    retrying: bool,
+    /// Whether the engine has reported the current load as failed.
+    engine_failed: bool,
+    /// Whether the engine reports load failures at all.
+    engine_reports_failures: bool,
+}
+
+impl RetryState {
+    /// Records a main-frame navigation that's about to happen.
+    pub(crate) fn on_navigate(&mut self, url: Url) {
+        if !std::mem::take(&mut self.retrying) {
+            self.attempts = 0;
+        }
+        self.target = Some(url);
+    }
+
+    /// Records the start of a load.
+    pub(crate) fn on_started(&mut self) {
+        self.generation += 1;
+        self.status = None;
+        self.engine_failed = false;
+    }
+
+    /// Records that the engine has failed to load the page, e.g. because the
+    /// server can't be reached.
+    pub(crate) fn on_engine_failure(&mut self) { self.engine_failed = true; }
+
+    /// Records the status reported by the page.
+    pub(crate) fn on_report(&mut self, status: u16) { self.status = Some(status); }
+
+    /// Gets the generation of the finished load if it should be checked.
+    pub(crate) fn on_finished(&self) -> Option<u64> {
+        self.policy.as_ref().map(|_| self.generation)
+    }
+
+    /// Gets the generation of the current load.
+    pub(crate) fn generation(&self) -> u64 { self.generation }
+
+    /// Checks the load of the given generation by the failure reported by the
+    /// engine and the status reported by the page. Returns the failure if
+    /// any, or nothing if another load has started since.
+    pub(crate) fn outcome(&self, generation: u64) -> Option<Option<LoadFailure>> {
+        if generation != self.generation {
+            return None;
+        }
+
+        if self.engine_failed {
+            return Some(Some(LoadFailure::Network));
+        }
+
+        Some(match self.status {
+            Some(s) if s >= 400 => Some(LoadFailure::Http(s)),
+            Some(_) => None,
+            // Pages of other schemes (e.g. `view-source:` and engine pages)
+            // may not run scripts at all
+            None if !self.engine_reports_failures && self.target.as_ref().is_some_and(is_http) => {
+                Some(LoadFailure::Network)
+            }
+            None => None,
+        })
+    }
+
+    /// Checks the load of the given generation. Returns the failure, the
+    /// attempt number and the delay before it ([`None`] when exhausted) if a
+    /// retry applies.
+    pub(crate) fn check(
+        &mut self,
+        generation: u64,
+    ) -> Option<(LoadFailure, u32, Option<Duration>)> {
+        let failure = self.outcome(generation)?;
+        let policy = self.policy.as_ref()?;
+        let Some(failure) = failure else {
+            self.attempts = 0;
+            return None;
+        };
+
+        if !policy.matches(failure) {
+            return None;
+        }
+
+        let delay = policy.delay(self.attempts + 1);
+        if delay.is_some() {
+            self.attempts += 1;
+        }
+
+        Some((failure, self.attempts, delay))
+    }
+
+    /// Prepares a retry of the load of the given generation. Returns the URL to
+    /// navigate to (or [`None`] to reload), or nothing if the webview has
+    /// navigated elsewhere.
+    pub(crate) fn begin_retry(&mut self, generation: u64) -> Option<Option<Url>> {
+        if generation != self.generation || self.policy.is_none() {
+            return None;
+        }
+
+        self.retrying = true;
+        Some(self.target.clone())
+    }
+}
+
+fn is_http(url: &Url) -> bool { matches!(url.scheme().as_str(), "http" | "https") }
+
+impl Webview {
+    /// Records that the engine has failed the current load.
+    #[allow(unused)] // Not used by all backends
+    fn on_load_failed(&self) { self.0.retry.lock_unpoisoned().on_engine_failure(); }
+}
+
+/// Hooks the load failures reported by the engine, if it reports them.
+pub(crate) fn install(webview: &Webview) {
+    let installed = install_native(webview).is_ok();
+    webview.0.retry.lock_unpoisoned().engine_reports_failures = installed;
+}
+
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+fn install_native(webview: &Webview) -> crate::error::Result<()> {
+    use crate::webview::webview2;
+    use crate::webview::webview2::S_OK;
+
+    // ICoreWebView2NavigationCompletedEventArgs
+    const GET_IS_SUCCESS: usize = 3;
+    const GET_WEB_ERROR_STATUS: usize = 4;
+
+    let core = webview2::core_webview(webview)?;
+    let weak = webview.downgrade();
+
+    // SAFETY: The slot is `add_NavigationCompleted`, whose arguments are
+    // borrowed for the call
+    unsafe {
+        webview2::add_event_handler(
+            &core,
+            webview2::slot::ADD_NAVIGATION_COMPLETED,
+            "webview navigation completed event",
+            move |_, args| {
+                let (Some(w), Some(args)) = (weak.upgrade(), webview2::ComPtr::from_borrowed(args))
+                else {
+                    return S_OK;
+                };
+
+                if args.get_value::<i32>(GET_IS_SUCCESS) != Some(0) {
+                    return S_OK;
+                }
+
+                // COREWEBVIEW2_WEB_ERROR_STATUS, from `SERVER_UNREACHABLE` to
+                // `HOST_NAME_NOT_RESOLVED`, and `REDIRECT_FAILED` and
+                // `UNEXPECTED_ERROR`. Others are about certificates,
+                // cancellation and authentication.
+                if matches!(
+                    args.get_value::<i32>(GET_WEB_ERROR_STATUS),
+                    Some(6..=13 | 15 | 16)
+                ) {
+                    w.on_load_failed();
+                }
+
+                S_OK
+            },
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(all(target_os = "macos", not(feature = "qt")))]
+fn install_native(webview: &Webview) -> crate::error::Result<()> {
+    use std::ffi::c_void;
+
+    use objc2::msg_send;
+    use objc2::runtime::AnyObject;
+    use objc2::runtime::Sel;
+    use objc2::sel;
+
+    use crate::webview::wkwebview;
+
+    // NSURLErrorCancelled
+    const CANCELLED: isize = -999;
+    // WebKitErrorFrameLoadInterruptedByPolicyChange, e.g. for downloads
+    const INTERRUPTED_BY_POLICY: isize = 102;
+
+    extern "C" fn did_fail(
+        _: *mut AnyObject,
+        _: Sel,
+        view: *mut AnyObject,
+        _: *mut AnyObject,
+        error: *mut AnyObject,
+    ) {
+        crate::util::ffi_callback("webview navigation failed", (), || {
+            // SAFETY: On the main thread, and the error is borrowed for the
+            // call
+            let code: isize = unsafe { msg_send![error, code] };
+
+            if code != CANCELLED
+                && code != INTERRUPTED_BY_POLICY
+                && let Some(w) = wkwebview::find(view)
+            {
+                w.on_load_failed();
+            }
+        })
+    }
+
+    wkwebview::add_delegate_method(
+        webview,
+        sel!(webView:didFailProvisionalNavigation:withError:),
+        did_fail as *const c_void,
+        c"v@:@@@",
+    )?;
+
+    wkwebview::add_delegate_method(
+        webview,
+        sel!(webView:didFailNavigation:withError:),
+        did_fail as *const c_void,
+        c"v@:@@@",
+    )
+}
+
+#[cfg(all(
+    not(any(target_os = "windows", target_os = "macos")),
+    not(feature = "qt")
+))]
+fn install_native(webview: &Webview) -> crate::error::Result<()> {
+    use std::ffi::c_char;
+    use std::ffi::c_void;
+
+    use crate::webview::webkitgtk;
+
+    // WEBKIT_NETWORK_ERROR_CANCELLED
+    const CANCELLED: i32 = 302;
+
+    #[repr(C)]
+    struct GError {
+        domain: u32,
+        code: i32,
+        message: *const c_char,
+    }
+
+    unsafe extern "C" {
+        fn webkit_network_error_quark() -> u32;
+        fn webkit_policy_error_quark() -> u32;
+    }
+
+    extern "C" fn on_load_failed(
+        _: *mut c_void,
+        _: u32,
+        _: *const c_char,
+        error: *const GError,
+        data: *mut c_void,
+    ) -> i32 {
+        crate::util::ffi_callback("webview load failed signal", 0, || {
+            // SAFETY: On the event thread, and the arguments are borrowed for
+            // the signal
+            unsafe {
+                let Some(w) = webkitgtk::webview(data) else {
+                    return 0;
+                };
+
+                // Policy errors are raised for downloads and blocked loads
+                let error = &*error;
+                let ignored = error.domain == webkit_policy_error_quark()
+                    || (error.domain == webkit_network_error_quark() && error.code == CANCELLED);
+
+                if !ignored {
+                    w.on_load_failed();
+                }
+
+                0 // The engine shows its error page
+            }
+        })
+    }
+
+    webkitgtk::connect(webview, c"load-failed", on_load_failed as *const c_void)
+}
+
+#[cfg(feature = "qt")]
+fn install_native(_: &Webview) -> crate::error::Result<()> {
+    Err(crate::error::Error::unsupported("load failures", None))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::LoadFailure;
+    use super::Retry;
+    use super::RetryState;
+
+    #[test]
+    fn exponential_retry_delays() {
+        let retry = Retry::exponential(3, Duration::from_millis(500));
+
+        assert_eq!(retry.delay(0), None);
+        assert_eq!(retry.delay(1), Some(Duration::from_millis(500)));
+        assert_eq!(retry.delay(2), Some(Duration::from_millis(1000)));
+        assert_eq!(retry.delay(3), Some(Duration::from_millis(2000)));
+        assert_eq!(retry.delay(4), None);
+    }
+
+    #[test]
+    fn missing_reports_fail_only_reported_loads() {
+        let mut state = RetryState {
+            engine_reports_failures: true,
+            ..Default::default()
+        };
+
+        state.on_started();
+        assert_eq!(state.outcome(state.generation()), Some(None));
+
+        state.on_engine_failure();
+        assert_eq!(
+            state.outcome(state.generation()),
+            Some(Some(LoadFailure::Network))
+        );
+
+        state.on_started();
+        state.on_report(503);
+        assert_eq!(
+            state.outcome(state.generation()),
+            Some(Some(LoadFailure::Http(503)))
+        );
+        assert_eq!(state.outcome(state.generation() - 1), None);
+    }
+}
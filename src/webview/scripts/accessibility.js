@@ -0,0 +1,160 @@
+// Builds a pruned accessibility tree of the document. Evaluates to the root node.
+(() => {
+    const IMPLICIT_ROLES = {
+        A: (e) => (e.hasAttribute("href") ? "link" : null),
+        ARTICLE: () => "article",
+        ASIDE: () => "complementary",
+        BUTTON: () => "button",
+        DETAILS: () => "group",
+        DIALOG: () => "dialog",
+        FOOTER: () => "contentinfo",
+        FORM: () => "form",
+        H1: () => "heading",
+        H2: () => "heading",
+        H3: () => "heading",
+        H4: () => "heading",
+        H5: () => "heading",
+        H6: () => "heading",
+        HEADER: () => "banner",
+        HR: () => "separator",
+        IMG: (e) => (e.getAttribute("alt") === "" ? "presentation" : "img"),
+        INPUT: (e) =>
+            ({
+                button: "button",
+                checkbox: "checkbox",
+                email: "textbox",
+                image: "button",
+                number: "spinbutton",
+                password: "textbox",
+                radio: "radio",
+                range: "slider",
+                reset: "button",
+                search: "searchbox",
+                submit: "button",
+                tel: "textbox",
+                text: "textbox",
+                url: "textbox",
+            })[e.type] ?? null,
+        LI: () => "listitem",
+        MAIN: () => "main",
+        NAV: () => "navigation",
+        OL: () => "list",
+        OPTION: () => "option",
+        P: () => "paragraph",
+        PROGRESS: () => "progressbar",
+        SECTION: (e) => (e.hasAttribute("aria-label") || e.hasAttribute("aria-labelledby") ? "region" : null),
+        SELECT: (e) => (e.multiple ? "listbox" : "combobox"),
+        SUMMARY: () => "button",
+        TABLE: () => "table",
+        TD: () => "cell",
+        TEXTAREA: () => "textbox",
+        TH: () => "columnheader",
+        TR: () => "row",
+        UL: () => "list",
+    };
+
+    // Roles whose name comes from their content
+    const NAME_FROM_CONTENT = new Set([
+        "button", "cell", "checkbox", "columnheader", "heading", "link", "listitem",
+        "menuitem", "option", "radio", "row", "switch", "tab", "tooltip", "treeitem",
+    ]);
+
+    const SKIPPED = new Set(["SCRIPT", "STYLE", "NOSCRIPT", "TEMPLATE", "HEAD"]);
+
+    const collapse = (s) => (s ?? "").replace(/\s+/g, " ").trim();
+
+    const isHidden = (e) => {
+        if (e.hidden || e.getAttribute("aria-hidden") === "true") return true;
+        const style = getComputedStyle(e);
+        return style.display === "none" || style.visibility === "hidden";
+    };
+
+    const roleOf = (e) => {
+        const explicit = e.getAttribute("role");
+        if (explicit) return explicit.split(/\s+/)[0];
+        return IMPLICIT_ROLES[e.tagName]?.(e) ?? null;
+    };
+
+    const nameOf = (e, role) => {
+        const labelledBy = e.getAttribute("aria-labelledby");
+        if (labelledBy) {
+            const text = labelledBy
+                .split(/\s+/)
+                .map((id) => document.getElementById(id)?.textContent)
+                .filter(Boolean)
+                .join(" ");
+            if (collapse(text)) return collapse(text);
+        }
+
+        const label = e.getAttribute("aria-label");
+        if (collapse(label)) return collapse(label);
+
+        if (e.labels?.length) return collapse([...e.labels].map((l) => l.textContent).join(" "));
+        if (e.tagName === "IMG") return collapse(e.getAttribute("alt"));
+        if (e.tagName === "INPUT" && ["button", "submit", "reset"].includes(e.type)) return collapse(e.value);
+        if (NAME_FROM_CONTENT.has(role)) return collapse(e.innerText ?? e.textContent);
+        if (e.placeholder) return collapse(e.placeholder);
+        return collapse(e.getAttribute("title"));
+    };
+
+    const statesOf = (e, role) => {
+        const states = [];
+        const aria = (n) => e.getAttribute("aria-" + n);
+
+        if (e.disabled || aria("disabled") === "true") states.push("disabled");
+        if (aria("checked") === "mixed" || e.indeterminate) states.push("mixed");
+        else if (e.checked || aria("checked") === "true" || aria("pressed") === "true") states.push("checked");
+        if (aria("expanded") === "true" || (e.tagName === "DETAILS" && e.open)) states.push("expanded");
+        if (aria("expanded") === "false" || (e.tagName === "DETAILS" && !e.open)) states.push("collapsed");
+        if (e.selected || aria("selected") === "true") states.push("selected");
+        if (document.activeElement === e) states.push("focused");
+        if (e.required || aria("required") === "true") states.push("required");
+        if (e.readOnly || aria("readonly") === "true") states.push("readonly");
+
+        return states;
+    };
+
+    const valueOf = (e, role) => {
+        if (e.type === "password") return null;
+        if (["textbox", "searchbox", "spinbutton", "slider", "combobox", "progressbar"].includes(role)) {
+            return e.value !== undefined ? String(e.value) : e.getAttribute("aria-valuenow");
+        }
+        return null;
+    };
+
+    const levelOf = (e, role) =>
+        role === "heading" ? Number(e.getAttribute("aria-level")) || Number(e.tagName.slice(1)) || 2 : null;
+
+    // Returns a list of nodes, as generic elements are flattened into their parents
+    const visit = (n, parentNamed) => {
+        if (n.nodeType === Node.TEXT_NODE) {
+            const text = collapse(n.textContent);
+            return text && !parentNamed ? [{ role: "text", name: text, children: [] }] : [];
+        }
+
+        if (n.nodeType !== Node.ELEMENT_NODE || SKIPPED.has(n.tagName) || isHidden(n)) return [];
+
+        const role = roleOf(n);
+        const named = parentNamed || NAME_FROM_CONTENT.has(role);
+        const children = [...(n.shadowRoot ?? n).childNodes].flatMap((c) => visit(c, named));
+
+        if (!role || role === "presentation" || role === "none" || role === "generic") return children;
+
+        return [
+            {
+                role,
+                name: nameOf(n, role),
+                value: valueOf(n, role),
+                level: levelOf(n, role),
+                states: statesOf(n, role),
+                children,
+            },
+        ];
+    };
+
+    return {
+        role: "document",
+        name: document.title,
+        children: document.body ? visit(document.body, false) : [],
+    };
+})()
@@ -0,0 +1,44 @@
+// Reports user activity of the page. Placeholders are replaced before injection.
+(() => {
+    const state = (window.__saucersActivity ??= { idleMs: 0, timer: null, idle: false, installed: false });
+    state.idleMs = __IDLE_MS__;
+
+    const prefix = __PREFIX__;
+    const send = (kind) => void window.saucer.internal.message(prefix + kind);
+
+    const arm = () => {
+        clearTimeout(state.timer);
+        state.timer = setTimeout(() => {
+            state.idle = true;
+            send("idle");
+        }, state.idleMs);
+    };
+
+    if (state.installed) {
+        arm(); // Apply the new timeout
+        return;
+    }
+    state.installed = true;
+
+    let seenInput = false;
+    const onInput = () => {
+        if (!seenInput) {
+            seenInput = true;
+            send("first-input");
+        }
+
+        if (state.idle) {
+            state.idle = false;
+            send("active");
+        }
+
+        arm();
+    };
+
+    for (const type of ["pointerdown", "keydown", "wheel", "touchstart"]) {
+        window.addEventListener(type, onInput, { capture: true, passive: true });
+    }
+
+    document.addEventListener("visibilitychange", () => send(document.hidden ? "hidden" : "visible"));
+    arm();
+})();
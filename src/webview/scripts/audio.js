@@ -0,0 +1,95 @@
+// Tracks media playback and applies muting. Placeholders are replaced before injection.
+(() => {
+    const prefix = __PREFIX__;
+
+    const audio = (window.__saucersAudio ??= (() => {
+        const self = {
+            muted: false,
+            playing: false,
+            media: new Set(),
+            mutedByUs: new WeakSet(),
+            contexts: new Set(),
+            suspendedByUs: new WeakSet(),
+        };
+
+        const report = () => {
+            const playing = [...self.media].some((m) => !m.paused && !m.ended);
+            if (playing !== self.playing) {
+                self.playing = playing;
+                void window.saucer.internal.message(prefix + (playing ? "1" : "0"));
+            }
+        };
+
+        const mute = (m) => {
+            if (self.muted && !m.muted) {
+                m.muted = true;
+                self.mutedByUs.add(m);
+            }
+        };
+
+        const track = (m) => {
+            if (!self.media.has(m)) {
+                self.media.add(m);
+                for (const e of ["play", "pause", "ended", "emptied"]) m.addEventListener(e, report);
+            }
+            mute(m);
+        };
+
+        const play = HTMLMediaElement.prototype.play;
+        HTMLMediaElement.prototype.play = function (...args) {
+            track(this);
+            return play.apply(this, args);
+        };
+
+        document.addEventListener(
+            "play",
+            (e) => {
+                if (e.target instanceof HTMLMediaElement) {
+                    track(e.target);
+                    report();
+                }
+            },
+            true,
+        );
+
+        const Context = window.AudioContext;
+        if (Context) {
+            window.AudioContext = class extends Context {
+                constructor(...args) {
+                    super(...args);
+                    self.contexts.add(this);
+                    if (self.muted) {
+                        self.suspendedByUs.add(this);
+                        void this.suspend();
+                    }
+                }
+            };
+        }
+
+        self.apply = () => {
+            for (const m of self.media) {
+                if (self.muted) {
+                    mute(m);
+                } else if (self.mutedByUs.has(m)) {
+                    self.mutedByUs.delete(m);
+                    m.muted = false;
+                }
+            }
+
+            for (const c of self.contexts) {
+                if (self.muted && c.state === "running") {
+                    self.suspendedByUs.add(c);
+                    void c.suspend();
+                } else if (!self.muted && self.suspendedByUs.has(c)) {
+                    self.suspendedByUs.delete(c);
+                    void c.resume();
+                }
+            }
+        };
+
+        return self;
+    })());
+
+    audio.muted = __MUTED__;
+    audio.apply();
+})();
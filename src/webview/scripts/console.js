@@ -0,0 +1,45 @@
+// Forwards console messages and uncaught errors. Placeholders are replaced before injection.
+(() => {
+    if (window.__saucersConsole) return;
+    window.__saucersConsole = true;
+
+    const prefix = __PREFIX__;
+
+    const format = (arg) => {
+        if (typeof arg === "string") return arg;
+        if (arg instanceof Error) return arg.stack || String(arg);
+        try {
+            return typeof arg === "object" && arg !== null ? JSON.stringify(arg) : String(arg);
+        } catch {
+            return String(arg);
+        }
+    };
+
+    // Finds the caller location from the stack, skipping this script
+    const location = () => {
+        const lines = (new Error().stack || "").split("\n").filter((l) => !/^Error/.test(l));
+        for (const l of lines.slice(2)) {
+            const m = /([^\s(]+):(\d+):\d+\)?$/.exec(l.trim());
+            if (m) return [m[1].replace(/^.*@/, ""), Number(m[2])];
+        }
+        return [null, null];
+    };
+
+    const send = (level, text, source, line) =>
+        void window.saucer.internal.message(prefix + JSON.stringify([level, text, source, line]));
+
+    for (const level of ["debug", "log", "info", "warn", "error"]) {
+        const original = console[level];
+        console[level] = function (...args) {
+            try {
+                send(level, args.map(format).join(" "), ...location());
+            } catch {
+                // Logging must never break the page
+            }
+            return original.apply(this, args);
+        };
+    }
+
+    window.addEventListener("error", (e) => send("error", format(e.error ?? e.message), e.filename || null, e.lineno || null));
+    window.addEventListener("unhandledrejection", (e) => send("error", "Uncaught (in promise) " + format(e.reason), null, null));
+})();
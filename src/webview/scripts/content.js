@@ -0,0 +1,71 @@
+// Blocks subresources matching the content rules of the app. Placeholders are replaced before
+// injection.
+(() => {
+    const state = (window.__saucersContent ??= { rules: [], installed: false });
+
+    const compile = (pattern) =>
+        new RegExp(
+            "^" +
+                pattern
+                    .replace(/[.+^${}()|[\]\\]/g, "\\$&")
+                    .replace(/\*/g, ".*")
+                    .replace(/\?/g, ".") +
+                "$",
+        );
+
+    state.rules = __RULES__.map(([pattern, allowed]) => [compile(pattern), allowed]);
+
+    if (state.installed) return;
+    state.installed = true;
+
+    const blocked = (url) => {
+        try {
+            url = new URL(url, location.href).href;
+        } catch {
+            return false;
+        }
+
+        const rule = state.rules.find(([re]) => re.test(url));
+        return rule ? !rule[1] : false;
+    };
+
+    const fetch = window.fetch;
+    window.fetch = function (input, init) {
+        const url = input instanceof Request ? input.url : String(input);
+        if (blocked(url)) return Promise.reject(new TypeError("blocked by content rules"));
+        return fetch.call(this, input, init);
+    };
+
+    const open = XMLHttpRequest.prototype.open;
+    XMLHttpRequest.prototype.open = function (method, url, ...rest) {
+        if (blocked(String(url))) throw new DOMException("blocked by content rules", "NetworkError");
+        return open.call(this, method, url, ...rest);
+    };
+
+    const attrs = ["src", "href", "data", "poster"];
+    const tags = new Set(["SCRIPT", "IMG", "IFRAME", "LINK", "VIDEO", "AUDIO", "SOURCE", "EMBED", "OBJECT", "TRACK"]);
+
+    const check = (el) => {
+        if (!tags.has(el.tagName)) return;
+
+        for (const attr of attrs) {
+            const value = el.getAttribute(attr);
+            if (value && blocked(value)) el.removeAttribute(attr);
+        }
+    };
+
+    new MutationObserver((records) => {
+        for (const r of records) {
+            if (r.type === "attributes") {
+                check(r.target);
+                continue;
+            }
+
+            for (const node of r.addedNodes) {
+                if (node.nodeType !== Node.ELEMENT_NODE) continue;
+                check(node);
+                node.querySelectorAll("*").forEach(check);
+            }
+        }
+    }).observe(document, { childList: true, subtree: true, attributes: true, attributeFilter: attrs });
+})();
@@ -0,0 +1,17 @@
+// Overrides cursors of the page with the one set on the window. Placeholders are replaced before
+// injection.
+(() => {
+    const state = (window.__saucersCursor ??= { style: null });
+    const cursor = __CURSOR__;
+
+    state.style ??= document.createElement("style");
+    state.style.textContent = cursor ? `html, html * { cursor: ${cursor} !important; }` : "";
+
+    const attach = () => document.documentElement.appendChild(state.style);
+
+    if (document.documentElement) {
+        attach();
+    } else {
+        document.addEventListener("DOMContentLoaded", attach, { once: true });
+    }
+})();
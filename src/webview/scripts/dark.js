@@ -0,0 +1,53 @@
+// Emulates forced dark mode with CSS. Placeholders are replaced before injection.
+(() => {
+    const dark = (window.__saucersDark ??= (() => {
+        const self = { enabled: false };
+
+        const style = document.createElement("style");
+        style.textContent = `
+            :root { color-scheme: dark; }
+            html { filter: invert(0.9) hue-rotate(180deg); background: #fff; }
+            img, video, picture, canvas, iframe, embed, object { filter: invert(1) hue-rotate(180deg); }
+        `;
+
+        // Pages only declaring dark support are left unfiltered
+        const darkOnly = () => {
+            const meta = document.querySelector('meta[name="color-scheme"]');
+            const schemes = meta?.content.split(/\s+/) ?? [];
+            return schemes.includes("dark") && !schemes.includes("light");
+        };
+
+        const matchMedia = window.matchMedia;
+        window.matchMedia = function (query) {
+            const list = matchMedia.call(this, query);
+            if (!self.enabled || !/prefers-color-scheme/.test(query)) return list;
+
+            const matches = /prefers-color-scheme:\s*dark/.test(query);
+            return new Proxy(list, {
+                get: (target, key) => {
+                    if (key === "matches") return matches;
+                    const value = Reflect.get(target, key);
+                    return typeof value === "function" ? value.bind(target) : value;
+                },
+            });
+        };
+
+        self.apply = () => {
+            if (self.enabled && !darkOnly()) {
+                document.documentElement?.appendChild(style);
+            } else {
+                style.remove();
+            }
+        };
+
+        // Applied again once the color scheme meta tag is parsed
+        if (document.readyState === "loading") {
+            document.addEventListener("DOMContentLoaded", () => self.apply(), { once: true });
+        }
+
+        return self;
+    })());
+
+    dark.enabled = __ENABLED__;
+    dark.apply();
+})();
@@ -0,0 +1,35 @@
+// Translates pointer events on drag and resize regions into native calls. Placeholders are
+// replaced before injection.
+(() => {
+    const prefix = __PREFIX__;
+    const state = (window.__saucersDrag ??= { selector: null, installed: false });
+    state.selector = __SELECTOR__;
+
+    if (state.installed) return;
+    state.installed = true;
+
+    const send = (cmd) => void window.saucer.internal.message(prefix + cmd);
+    const interactive = "button, input, select, textarea, a, [contenteditable], [data-saucers-no-drag]";
+
+    window.addEventListener(
+        "mousedown",
+        (e) => {
+            if (!e.isTrusted || e.button !== 0 || !(e.target instanceof Element)) return;
+
+            const resize = e.target.closest("[data-saucers-resize]");
+            if (resize) {
+                e.preventDefault();
+                send("resize:" + resize.getAttribute("data-saucers-resize"));
+                return;
+            }
+
+            if (!state.selector || e.target.closest(interactive) || !e.target.closest(state.selector)) {
+                return;
+            }
+
+            e.preventDefault();
+            send(e.detail === 2 ? "maximize" : "drag");
+        },
+        true,
+    );
+})();
@@ -0,0 +1,27 @@
+// Runs an editing command in the focused document, descending into focused same-origin frames.
+// Placeholders are replaced before injection.
+(async () => {
+    const command = __COMMAND__;
+    if (__FOCUSED_ONLY__ && !document.hasFocus()) return;
+
+    let doc = document;
+    for (;;) {
+        let inner = null;
+        try {
+            inner = doc.activeElement?.contentDocument;
+        } catch {
+            // Cross-origin frames can't be reached
+        }
+
+        if (!inner) break;
+        doc = inner;
+    }
+
+    if (doc.execCommand(command)) return;
+
+    // Engines refuse to read the clipboard with `execCommand`, try the async API instead
+    if (command === "paste" && navigator.clipboard?.readText) {
+        const text = await navigator.clipboard.readText();
+        doc.execCommand("insertText", false, text);
+    }
+})();
@@ -0,0 +1,61 @@
+// Confines keyboard focus to the element matching a selector. Placeholders are replaced before
+// injection.
+(() => {
+    const state = (window.__saucersFocus ??= { selector: null, installed: false });
+    state.selector = __SELECTOR__;
+
+    if (state.installed) return;
+    state.installed = true;
+
+    const focusable =
+        "a[href], area[href], button, input, select, textarea, iframe, summary, [contenteditable], [tabindex]";
+
+    const trap = () => (state.selector ? document.querySelector(state.selector) : null);
+
+    const candidates = (root) =>
+        [...root.querySelectorAll(focusable)].filter(
+            (e) => !e.disabled && e.tabIndex >= 0 && e.getClientRects().length > 0,
+        );
+
+    document.addEventListener(
+        "keydown",
+        (e) => {
+            const root = trap();
+            if (e.key !== "Tab" || !root) return;
+
+            const items = candidates(root);
+            if (items.length === 0) {
+                e.preventDefault();
+                return;
+            }
+
+            const first = items[0];
+            const last = items[items.length - 1];
+            const active = document.activeElement;
+
+            if (!root.contains(active)) {
+                e.preventDefault();
+                (e.shiftKey ? last : first).focus();
+            } else if (e.shiftKey && active === first) {
+                e.preventDefault();
+                last.focus();
+            } else if (!e.shiftKey && active === last) {
+                e.preventDefault();
+                first.focus();
+            }
+        },
+        true,
+    );
+
+    document.addEventListener(
+        "focusin",
+        (e) => {
+            const root = trap();
+            if (!root || root.contains(e.target)) return;
+
+            const [first] = candidates(root);
+            (first ?? root).focus();
+        },
+        true,
+    );
+})();
@@ -0,0 +1,18 @@
+// Keeps pages from replacing the function managed scripts send internal messages with, which would
+// expose the token of those messages. Runs before page scripts, as it's injected first.
+(() => {
+    const saucer = window.saucer;
+    if (typeof saucer?.internal?.message !== "function") return;
+
+    for (const [target, key] of [
+        [window, "saucer"],
+        [saucer, "internal"],
+        [saucer.internal, "message"],
+    ]) {
+        try {
+            Object.defineProperty(target, key, { value: target[key], writable: false, configurable: false });
+        } catch {
+            // Already locked
+        }
+    }
+})();
@@ -0,0 +1,26 @@
+// Reports user gestures so that they can be attached to navigations. Placeholders are replaced
+// before injection.
+(() => {
+    if (window.__saucersInput) return;
+    window.__saucersInput = true;
+
+    const prefix = __PREFIX__;
+    let pointerType = "mouse";
+
+    const modifiers = (e) =>
+        (e.shiftKey ? 1 : 0) | (e.ctrlKey ? 2 : 0) | (e.altKey ? 4 : 0) | (e.metaKey ? 8 : 0);
+
+    const send = (kind, e, button) =>
+        void window.saucer.internal.message(prefix + kind + ":" + modifiers(e) + ":" + button);
+
+    window.addEventListener("pointerdown", (e) => (pointerType = e.pointerType || "mouse"), true);
+    window.addEventListener("click", (e) => send(e.detail === 0 ? "keyboard" : pointerType, e, e.detail === 0 ? "" : e.button), true);
+    window.addEventListener("auxclick", (e) => send(pointerType, e, e.button), true);
+    window.addEventListener(
+        "keydown",
+        (e) => {
+            if (e.key === "Enter") send("keyboard", e, "");
+        },
+        true,
+    );
+})();
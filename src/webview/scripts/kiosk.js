@@ -0,0 +1,19 @@
+// Keeps keys leaving fullscreen from the page in kiosk mode. Placeholders are replaced before
+// injection.
+(() => {
+    const state = (window.__saucersKiosk ??= { locked: false, installed: false });
+    state.locked = __LOCKED__;
+
+    if (state.installed) return;
+    state.installed = true;
+
+    const block = (e) => {
+        if (state.locked && (e.key === "Escape" || e.key === "F11")) {
+            e.preventDefault();
+            e.stopImmediatePropagation();
+        }
+    };
+
+    window.addEventListener("keydown", block, true);
+    window.addEventListener("keyup", block, true);
+})();
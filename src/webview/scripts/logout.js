@@ -0,0 +1,57 @@
+// Clears storage of the current origin, then navigates away in the same task, so that page scripts
+// can't write anything in between. Placeholders are replaced before injection.
+(async () => {
+    const redirect = __REDIRECT__;
+
+    window.stop();
+    window.onbeforeunload = null;
+
+    const settle = async (f) => {
+        try {
+            await f();
+        } catch {
+            // Best-effort, the storage may be unavailable
+        }
+    };
+
+    await settle(async () => {
+        for (const key of await caches.keys()) await caches.delete(key);
+    });
+
+    await settle(async () => {
+        for (const { name } of await indexedDB.databases()) indexedDB.deleteDatabase(name);
+    });
+
+    await settle(async () => {
+        for (const reg of await navigator.serviceWorker.getRegistrations()) await reg.unregister();
+    });
+
+    // Cookies can only be removed with the same domain and path they were set with
+    const host = location.hostname;
+    const labels = host.split(".");
+    const domains = [null, ...labels.slice(0, -1).map((_, i) => labels.slice(i).join("."))];
+    const segments = location.pathname.split("/").filter(Boolean);
+    const paths = ["/", ...segments.map((_, i) => "/" + segments.slice(0, i + 1).join("/"))];
+    const expiry = "=; expires=Thu, 01 Jan 1970 00:00:00 GMT; max-age=0";
+
+    for (const cookie of document.cookie.split(";")) {
+        const name = cookie.split("=")[0].trim();
+        if (!name) continue;
+
+        for (const domain of domains) {
+            for (const path of paths) {
+                document.cookie = name + expiry + "; path=" + path + (domain ? "; domain=" + domain : "");
+            }
+        }
+    }
+
+    for (const storage of ["sessionStorage", "localStorage"]) {
+        try {
+            window[storage].clear();
+        } catch {
+            // Unavailable in sandboxed documents
+        }
+    }
+
+    location.replace(redirect);
+})()
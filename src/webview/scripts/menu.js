@@ -0,0 +1,30 @@
+// Activates menu items with their accelerators. Only key presses of the user are handled, and the
+// bindings ignore items without accelerators. Placeholders are replaced before injection.
+(() => {
+    const state = (window.__saucersMenu ??= { accelerators: [], installed: false });
+    state.accelerators = __ACCELERATORS__;
+
+    if (state.installed) return;
+    state.installed = true;
+
+    window.addEventListener(
+        "keydown",
+        (e) => {
+            const key = e.key.length === 1 ? e.key.toLowerCase() : e.key;
+            const match = state.accelerators.find(
+                ([, k, ctrl, alt, shift, meta]) =>
+                    k === key &&
+                    ctrl === e.ctrlKey &&
+                    alt === e.altKey &&
+                    shift === e.shiftKey &&
+                    meta === e.metaKey,
+            );
+
+            if (!e.isTrusted || !match) return;
+            e.preventDefault();
+            e.stopPropagation();
+            window.saucer.internal.message(__PREFIX__ + match[0]);
+        },
+        true,
+    );
+})();
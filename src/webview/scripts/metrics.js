@@ -0,0 +1,38 @@
+// Collects performance metrics of the page.
+(async () => {
+    const memory = performance.memory;
+    const [nav] = performance.getEntriesByType("navigation");
+
+    // Frames are counted for a short period, rendering stops while the page is hidden
+    const frameRate = await new Promise((resolve) => {
+        if (document.visibilityState === "hidden") {
+            resolve(null);
+            return;
+        }
+
+        const period = 500;
+        let frames = 0;
+        const start = performance.now();
+        const count = (now) => {
+            if (now - start >= period) {
+                resolve((frames * 1000) / (now - start));
+                return;
+            }
+
+            frames++;
+            requestAnimationFrame(count);
+        };
+
+        requestAnimationFrame(count);
+        setTimeout(() => resolve(null), period * 4);
+    });
+
+    return {
+        heapUsed: memory?.usedJSHeapSize ?? null,
+        heapTotal: memory?.totalJSHeapSize ?? null,
+        domNodes: document.getElementsByTagName("*").length,
+        frameRate,
+        domContentLoaded: nav?.domContentLoadedEventEnd ?? null,
+        load: nav?.loadEventEnd ?? null,
+    };
+})()
@@ -0,0 +1,41 @@
+// Shows the progress of the window as a toast. Placeholders are replaced before injection.
+(() => {
+    const state = (window.__saucersProgress ??= { toast: null });
+    const progress = __PROGRESS__;
+
+    if (!progress) {
+        state.toast?.remove();
+        state.toast = null;
+        return;
+    }
+
+    if (!state.toast) {
+        const toast = document.createElement("div");
+        toast.setAttribute("role", "status");
+        toast.style.cssText =
+            "position:fixed;right:16px;bottom:16px;z-index:2147483647;min-width:220px;padding:10px 12px;" +
+            "border-radius:8px;background:rgba(32,32,32,.92);color:#fff;font:13px system-ui,sans-serif;" +
+            "box-shadow:0 4px 16px rgba(0,0,0,.3);pointer-events:none";
+
+        const label = document.createElement("div");
+        const bar = document.createElement("progress");
+        bar.max = 1;
+        bar.style.cssText = "display:block;width:100%;margin-top:6px";
+
+        toast.append(label, bar);
+        state.toast = toast;
+    }
+
+    const [label, bar] = state.toast.children;
+    label.textContent = progress.label;
+
+    if (progress.value === null) {
+        bar.removeAttribute("value");
+    } else {
+        bar.value = progress.value;
+    }
+
+    if (!state.toast.isConnected) {
+        (document.body ?? document.documentElement)?.appendChild(state.toast);
+    }
+})();
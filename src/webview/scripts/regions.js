@@ -0,0 +1,68 @@
+// Patches regions of the document with HTML rendered in Rust. Existing nodes are reused where
+// possible, so that focus, selection and scroll positions survive updates.
+(() => {
+    if (window.__saucersRegions) return;
+
+    const sameKind = (a, b) =>
+        a.nodeType === b.nodeType &&
+        (a.nodeType !== Node.ELEMENT_NODE ||
+            (a.tagName === b.tagName && a.getAttribute("id") === b.getAttribute("id")));
+
+    const patchAttributes = (from, to) => {
+        for (const { name } of [...from.attributes]) {
+            if (!to.hasAttribute(name)) from.removeAttribute(name);
+        }
+
+        for (const { name, value } of [...to.attributes]) {
+            if (from.getAttribute(name) !== value) from.setAttribute(name, value);
+        }
+
+        // Values typed by the user are kept unless the template changes them
+        if (from !== document.activeElement && "value" in to && from.value !== to.value) {
+            from.value = to.value;
+        }
+    };
+
+    const patchChildren = (from, to) => {
+        const next = [...to.childNodes];
+        let cur = from.firstChild;
+
+        for (const n of next) {
+            if (cur && sameKind(cur, n)) {
+                patchNode(cur, n);
+                cur = cur.nextSibling;
+            } else {
+                from.insertBefore(n, cur);
+            }
+        }
+
+        while (cur) {
+            const stale = cur;
+            cur = cur.nextSibling;
+            stale.remove();
+        }
+    };
+
+    const patchNode = (from, to) => {
+        if (from.nodeType === Node.ELEMENT_NODE) {
+            patchAttributes(from, to);
+            patchChildren(from, to);
+        } else if (from.nodeValue !== to.nodeValue) {
+            from.nodeValue = to.nodeValue;
+        }
+    };
+
+    window.__saucersRegions = {
+        // Takes a list of [selector, html] pairs. Regions not found are skipped.
+        render(updates) {
+            for (const [selector, html] of updates) {
+                const region = document.querySelector(selector);
+                if (!region) continue;
+
+                const template = document.createElement("template");
+                template.innerHTML = html;
+                patchChildren(region, template.content);
+            }
+        },
+    };
+})();
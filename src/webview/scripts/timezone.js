@@ -0,0 +1,73 @@
+// Overrides the timezone seen by `Intl` and `Date`. Placeholders are replaced before injection.
+(() => {
+    const tz = (window.__saucersTimezone ??= (() => {
+        const self = { zone: null, format: null };
+
+        const DateTimeFormat = Intl.DateTimeFormat;
+        const proto = Date.prototype;
+        const original = {};
+        for (const k of Object.getOwnPropertyNames(proto)) original[k] = proto[k];
+
+        // Offset of the zone at the given instant, in minutes east of UTC.
+        const offset = (t) => {
+            const p = {};
+            for (const { type, value } of self.format.formatToParts(new Date(t))) p[type] = Number(value);
+            const local = Date.UTC(p.year, p.month - 1, p.day, p.hour % 24, p.minute, p.second);
+            return (local - (t - (((t % 1000) + 1000) % 1000))) / 60000;
+        };
+
+        const shifted = (d) => {
+            const t = original.getTime.call(d);
+            return new Date(t + offset(t) * 60000);
+        };
+
+        const withZone = (options) => (self.zone && options?.timeZone === undefined ? { ...options, timeZone: self.zone } : options);
+
+        Intl.DateTimeFormat = new Proxy(DateTimeFormat, {
+            construct: (target, [locales, options]) => new target(locales, withZone(options)),
+            apply: (target, _, [locales, options]) => target(locales, withZone(options)),
+        });
+
+        for (const k of ["toLocaleString", "toLocaleDateString", "toLocaleTimeString"]) {
+            proto[k] = function (locales, options) {
+                return original[k].call(this, locales, withZone(options));
+            };
+        }
+
+        proto.getTimezoneOffset = function () {
+            if (!self.zone || Number.isNaN(original.getTime.call(this))) return original.getTimezoneOffset.call(this);
+            return -offset(original.getTime.call(this));
+        };
+
+        for (const k of ["FullYear", "Month", "Date", "Day", "Hours", "Minutes", "Seconds", "Milliseconds"]) {
+            proto["get" + k] = function () {
+                if (!self.zone || Number.isNaN(original.getTime.call(this))) return original["get" + k].call(this);
+                return original["getUTC" + k].call(shifted(this));
+            };
+        }
+
+        self.apply = (zone) => {
+            try {
+                self.format = zone && new DateTimeFormat("en-US", {
+                    timeZone: zone,
+                    hourCycle: "h23",
+                    year: "numeric",
+                    month: "numeric",
+                    day: "numeric",
+                    hour: "numeric",
+                    minute: "numeric",
+                    second: "numeric",
+                });
+                self.zone = zone;
+            } catch {
+                // Unknown zones are ignored
+                self.format = null;
+                self.zone = null;
+            }
+        };
+
+        return self;
+    })());
+
+    tz.apply(__ZONE__);
+})();
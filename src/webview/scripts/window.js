@@ -0,0 +1,15 @@
+// Dispatches window events as DOM CustomEvents named `saucer:window-*`. Events of the native window
+// are sent by the bindings through `dispatch`. Placeholders are replaced before injection.
+(() => {
+    const state = (window.__saucersWindow ??= { colorScheme: false, installed: false });
+    state.colorScheme = __COLOR_SCHEME__;
+    state.dispatch = (name, detail) =>
+        window.dispatchEvent(new CustomEvent("saucer:window-" + name, { detail }));
+
+    if (state.installed) return;
+    state.installed = true;
+
+    matchMedia("(prefers-color-scheme: dark)").addEventListener("change", (e) => {
+        if (state.colorScheme) state.dispatch("color-scheme", { dark: e.matches });
+    });
+})();
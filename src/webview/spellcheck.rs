@@ -0,0 +1,108 @@
+//! Spellcheck module.
+//!
+//! See [`crate::webview::Webview::set_spellcheck`] and
+//! [`crate::webview::Webview::set_spellcheck_languages`] for details.
+use crate::thread::check_event_thread;
+use crate::webview::Webview;
+
+impl Webview {
+    /// Sets whether spellchecking is enabled for editable content, taking
+    /// effect on the current and future pages.
+    ///
+    /// This sets the `spellcheck` attribute of the document element, which is
+    /// inherited by elements that don't specify their own. Dictionaries are
+    /// chosen by the backend (usually following the system language) unless
+    /// set with [`Self::set_spellcheck_languages`].
+    pub fn set_spellcheck(&self, enabled: bool) {
+        let js = format!(
+            r#"(() => {{
+                const apply = () => {{
+                    if (document.documentElement) document.documentElement.spellcheck = {enabled};
+                }};
+                apply();
+                document.addEventListener("DOMContentLoaded", apply);
+            }})();"#
+        );
+
+        self.set_managed_script("spellcheck", Some(js));
+    }
+
+    /// Sets the dictionaries used for spellchecking, as language tags (e.g.
+    /// `en_US`, `de_DE`). Passing an empty list turns the spellchecker of the
+    /// engine off.
+    ///
+    /// Only WebKitGTK lets apps choose dictionaries, where they're set on the
+    /// web context, thus shared by webviews of the same context. Other
+    /// backends follow the system settings, and this fails with
+    /// [`crate::error::Error::Unsupported`] there.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, unless allowed by the
+    /// [`crate::thread::ThreadViolationPolicy`].
+    pub fn set_spellcheck_languages(
+        &self,
+        languages: &[impl AsRef<str>],
+    ) -> crate::error::Result<()> {
+        if check_event_thread(self.0.is_thread_safe(), "setting spellcheck languages").is_some() {
+            return Err(crate::error::Error::WrongThread);
+        }
+
+        set_languages(self, languages)
+    }
+}
+
+#[cfg(all(
+    not(any(target_os = "windows", target_os = "macos")),
+    not(feature = "qt")
+))]
+fn set_languages(webview: &Webview, languages: &[impl AsRef<str>]) -> crate::error::Result<()> {
+    use std::ffi::CString;
+    use std::ffi::c_char;
+    use std::ffi::c_void;
+
+    unsafe extern "C" {
+        fn webkit_web_view_get_context(view: *mut c_void) -> *mut c_void;
+        fn webkit_web_context_set_spell_checking_enabled(context: *mut c_void, enabled: i32);
+        fn webkit_web_context_set_spell_checking_languages(
+            context: *mut c_void,
+            languages: *const *const c_char,
+        );
+    }
+
+    // Tags with NUL can't name dictionaries
+    let tags: Vec<CString> = languages
+        .iter()
+        .filter_map(|l| CString::new(l.as_ref()).ok())
+        .collect();
+
+    let mut ptrs: Vec<*const c_char> = tags.iter().map(|t| t.as_ptr()).collect();
+    ptrs.push(std::ptr::null());
+
+    let view = webview.query_native_controller()?;
+
+    // SAFETY: On the event thread while the webview is alive, and the tags
+    // are copied by WebKit
+    unsafe {
+        let context = webkit_web_view_get_context(view.as_ptr());
+
+        if !tags.is_empty() {
+            webkit_web_context_set_spell_checking_languages(context, ptrs.as_ptr());
+        }
+
+        webkit_web_context_set_spell_checking_enabled(context, !tags.is_empty() as i32);
+    }
+
+    Ok(())
+}
+
+#[cfg(not(all(
+    not(any(target_os = "windows", target_os = "macos")),
+    not(feature = "qt")
+)))]
+fn set_languages(_: &Webview, _: &[impl AsRef<str>]) -> crate::error::Result<()> {
+    Err(crate::error::Error::unsupported(
+        "spellcheck languages",
+        Some("the engine follows the languages of the system"),
+    ))
+}
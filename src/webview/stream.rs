@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use crate::scheme::Executor;
+use crate::scheme::Response;
+use crate::scheme::SchemeError;
+use crate::stash::Stash;
+use crate::util::MutexExt;
+
+/// The scheme serving streams to pages. It's registered by
+/// [`crate::app::AppManager::new`] if [`crate::app::AppOptions::streams`] is
+/// enabled.
+pub(crate) const STREAM_SCHEME: &str = "saucers";
+
+/// How many bytes a stream buffers before [`StreamWriter::write`] blocks.
+pub const STREAM_BUFFER_SIZE: usize = 4 * 1024 * 1024;
+
+#[derive(Default)]
+struct StreamState {
+    chunks: VecDeque<Vec<u8>>,
+    buffered: usize,
+    /// The held read, with the origin allowed to read the response.
+    pending: Option<(Executor, Option<String>)>,
+    finished: bool,
+    closed: bool,
+}
+
+/// The state of a stream shared by its writer and the webview.
+pub(crate) struct StreamShared {
+    /// Random token in the URL of the stream, which must be presented by
+    /// reads, so that other pages can't guess the URL and read the stream.
+    token: String,
+    state: Mutex<StreamState>,
+    cond: Condvar,
+}
+
+impl StreamShared {
+    pub(crate) fn new() -> Self {
+        Self {
+            token: crate::util::random_token(),
+            state: Mutex::default(),
+            cond: Condvar::new(),
+        }
+    }
+
+    /// Checks whether a read presents the token of this stream.
+    pub(crate) fn accepts(&self, token: &str) -> bool { self.token == token }
+
+    /// Gets the URL the page reads the stream of the given name from.
+    pub(crate) fn url(&self, name: &str) -> String {
+        format!(
+            "{STREAM_SCHEME}://stream/{}/{}",
+            self.token,
+            crate::util::percent_encode(name)
+        )
+    }
+
+    /// Replies a read of the page with the next chunk, or holds the executor
+    /// until one is written. Responses can only be read by the given origin,
+    /// which should be the one of the page. Returns whether the stream has
+    /// ended and can be forgotten.
+    pub(crate) fn read(&self, exc: Executor, origin: Option<String>) -> bool {
+        let mut state = self.state.lock_unpoisoned();
+
+        if let Some(chunk) = state.chunks.pop_front() {
+            state.buffered -= chunk.len();
+            self.cond.notify_all();
+            exc.accept(chunk_response(&chunk, origin.as_deref()));
+            false
+        } else if state.finished {
+            exc.accept(end_response(origin.as_deref()));
+            true
+        } else {
+            if let Some((old, _)) = state.pending.replace((exc, origin)) {
+                old.reject(SchemeError::Invalid); // Only one read at a time
+            }
+            false
+        }
+    }
+
+    /// Checks whether the writer has finished and all chunks have been read.
+    pub(crate) fn is_done(&self) -> bool {
+        let state = self.state.lock_unpoisoned();
+        state.finished && state.chunks.is_empty() && state.pending.is_none()
+    }
+
+    /// Closes the stream as the webview is going away. Pending reads must be
+    /// answered before the webview is freed.
+    pub(crate) fn close(&self) {
+        let mut state = self.state.lock_unpoisoned();
+        state.closed = true;
+        state.chunks.clear();
+
+        if let Some((exc, _)) = state.pending.take() {
+            exc.reject(SchemeError::Failed);
+        }
+
+        self.cond.notify_all();
+    }
+}
+
+fn chunk_response(chunk: &[u8], origin: Option<&str>) -> Response<'static> {
+    let mut res = Response::new(Stash::new_copy(chunk), "application/octet-stream");
+    allow_origin(&mut res, origin);
+    res
+}
+
+fn end_response(origin: Option<&str>) -> Response<'static> {
+    let mut res = Response::new(Stash::new_empty(), "application/octet-stream");
+    res.set_status(204);
+    allow_origin(&mut res, origin);
+    res
+}
+
+fn allow_origin(res: &mut Response, origin: Option<&str>) {
+    if let Some(origin) = origin {
+        res.add_header("Access-Control-Allow-Origin", origin);
+    }
+}
+
+/// The writing end of a binary stream to the page, created with
+/// [`crate::webview::Webview::open_stream`].
+///
+/// Chunks are buffered until the page reads them, and writing blocks once
+/// [`STREAM_BUFFER_SIZE`] bytes are buffered, so that a slow page slows down
+/// the writer instead of piling up memory. Dropping the writer ends the
+/// stream after buffered chunks are read.
+///
+/// Writers can be moved to other threads, which is the intended usage as
+/// writing may block.
+pub struct StreamWriter {
+    shared: Arc<StreamShared>,
+    url: String,
+}
+
+impl StreamWriter {
+    pub(crate) fn new(shared: Arc<StreamShared>, url: String) -> Self { Self { shared, url } }
+
+    /// Gets the URL the page reads the stream from, which carries a random
+    /// token of the stream. Reads from other URLs of the same name fail.
+    pub fn url(&self) -> &str { &self.url }
+
+    /// Writes a chunk, which the page receives as a whole. Empty chunks are
+    /// ignored.
+    ///
+    /// Blocks while the buffer is full. Fails with
+    /// [`crate::error::Error::Closed`] if the webview has been dropped or the
+    /// stream has been replaced.
+    pub fn write(&self, chunk: impl Into<Vec<u8>>) -> crate::error::Result<()> {
+        let chunk = chunk.into();
+        if chunk.is_empty() {
+            return Ok(());
+        }
+
+        let mut state = self.shared.state.lock_unpoisoned();
+
+        while !state.closed
+            && state.buffered > 0
+            && state.buffered + chunk.len() > STREAM_BUFFER_SIZE
+        {
+            state = self
+                .shared
+                .cond
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+
+        if state.closed {
+            return Err(crate::error::Error::Closed);
+        }
+
+        if let Some((exc, origin)) = state.pending.take() {
+            exc.accept(chunk_response(&chunk, origin.as_deref()));
+        } else {
+            state.buffered += chunk.len();
+            state.chunks.push_back(chunk);
+        }
+
+        Ok(())
+    }
+
+    /// Ends the stream. Same as dropping the writer.
+    pub fn finish(self) {}
+}
+
+impl Drop for StreamWriter {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock_unpoisoned();
+        state.finished = true;
+
+        if state.chunks.is_empty()
+            && let Some((exc, origin)) = state.pending.take()
+        {
+            exc.accept(end_response(origin.as_deref()));
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::rc::Rc;
+
+use crate::scheme::Executor;
+use crate::scheme::Request;
+use crate::webview::Webview;
+use crate::webview::WebviewRef;
+
+pub(crate) type TemporarySchemeHandler = Rc<dyn Fn(Webview, Request, Executor)>;
+
+/// A handler registered with [`Webview::with_temporary_scheme`].
+pub(crate) struct TemporarySchemeEntry {
+    pub(crate) handler: TemporarySchemeHandler,
+    /// Distinguishes re-registrations of the same scheme, so that stale
+    /// guards don't remove newer handlers.
+    pub(crate) generation: u64,
+    /// Whether the handler has served a request, after which it's removed
+    /// once the page finishes loading.
+    pub(crate) served: bool,
+}
+
+/// A guard of a temporary scheme handler. See
+/// [`Webview::with_temporary_scheme`].
+///
+/// Dropping the guard removes the handler if it's still registered. Use
+/// [`Self::detach`] to keep it until the navigation completes instead.
+#[must_use = "dropping the guard removes the handler immediately"]
+pub struct TemporaryScheme {
+    webview: WebviewRef,
+    name: String,
+    generation: u64,
+    detached: bool,
+}
+
+impl Drop for TemporaryScheme {
+    fn drop(&mut self) {
+        if !self.detached
+            && let Some(w) = self.webview.upgrade()
+        {
+            w.remove_temporary_scheme(&self.name, Some(self.generation));
+        }
+    }
+}
+
+impl TemporaryScheme {
+    pub(crate) fn new(webview: WebviewRef, name: String, generation: u64) -> Self {
+        Self {
+            webview,
+            name,
+            generation,
+            detached: false,
+        }
+    }
+
+    /// Gets the name of the scheme.
+    pub fn name(&self) -> &str { &self.name }
+
+    /// Checks whether the handler is still registered.
+    pub fn is_active(&self) -> bool {
+        self.webview
+            .upgrade()
+            .is_some_and(|w| w.has_temporary_scheme(&self.name, self.generation))
+    }
+
+    /// Releases the guard without removing the handler, which is then only
+    /// removed after serving a navigation, or when the webview is dropped.
+    pub fn detach(mut self) { self.detached = true; }
+}
@@ -0,0 +1,126 @@
+//! User agent module.
+//!
+//! See [`crate::webview::Webview::set_user_agent`] for details.
+use crate::thread::check_event_thread;
+use crate::util::MutexExt;
+use crate::webview::Webview;
+
+impl Webview {
+    /// Gets the user agent override, either specified in
+    /// [`crate::webview::WebviewOptions::user_agent`] or set via
+    /// [`Self::set_user_agent`]. Returns [`None`] if the backend default is
+    /// used.
+    pub fn user_agent(&self) -> Option<String> { self.0.user_agent.lock_unpoisoned().clone() }
+
+    /// Overrides the user agent of the webview, which is sent with requests
+    /// and reported to scripts, starting with the next navigation.
+    ///
+    /// The user agent is set on the settings of the engine, which WebView2,
+    /// WKWebView and WebKitGTK expose through the native webview. This fails
+    /// with [`crate::error::Error::Unsupported`] with Qt, where only
+    /// [`crate::webview::WebviewOptions::user_agent`] takes effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, unless allowed by the
+    /// [`crate::thread::ThreadViolationPolicy`].
+    pub fn set_user_agent(&self, ua: impl Into<String>) -> crate::error::Result<()> {
+        if check_event_thread(self.0.is_thread_safe(), "setting the user agent").is_some() {
+            return Err(crate::error::Error::WrongThread);
+        }
+
+        let ua = ua.into();
+        if ua.contains('\0') {
+            return Err(crate::error::Error::Io(
+                std::io::ErrorKind::InvalidInput.into(),
+            ));
+        }
+
+        self.ensure_alive()?;
+        set_user_agent(self, &ua)?;
+        *self.0.user_agent.lock_unpoisoned() = Some(ua);
+        Ok(())
+    }
+}
+
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+fn set_user_agent(webview: &Webview, ua: &str) -> crate::error::Result<()> {
+    use crate::webview::webview2;
+
+    // ICoreWebView2
+    const GET_SETTINGS: usize = 3;
+    // ICoreWebView2Settings2
+    const PUT_USER_AGENT: usize = 22;
+
+    // SAFETY: The slot is `get_Settings`
+    let settings = unsafe { webview2::core_webview(webview)?.get(GET_SETTINGS) }
+        .and_then(|s| s.cast(&webview2::IID_ICOREWEBVIEW2_SETTINGS2))
+        .ok_or_else(|| crate::error::Error::unsupported("setting the user agent", None))?;
+
+    // SAFETY: The slot is `put_UserAgent`
+    if unsafe { settings.put_string(PUT_USER_AGENT, ua) } < 0 {
+        return Err(crate::error::Error::unsupported(
+            "setting the user agent",
+            None,
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(all(target_os = "macos", not(feature = "qt")))]
+fn set_user_agent(webview: &Webview, ua: &str) -> crate::error::Result<()> {
+    use objc2::msg_send;
+    use objc2::runtime::AnyObject;
+
+    use crate::webview::wkwebview;
+
+    let view = webview
+        .query_native_controller()?
+        .as_ptr()
+        .cast::<AnyObject>();
+    let ua = wkwebview::ns_string(ua)
+        .ok_or_else(|| crate::error::Error::Io(std::io::ErrorKind::InvalidInput.into()))?;
+
+    // SAFETY: On the main thread, which is the event thread on macOS, and the
+    // string is copied by the call
+    unsafe {
+        let _: () = msg_send![view, setCustomUserAgent: &*ua];
+    }
+
+    Ok(())
+}
+
+#[cfg(all(
+    not(any(target_os = "windows", target_os = "macos")),
+    not(feature = "qt")
+))]
+fn set_user_agent(webview: &Webview, ua: &str) -> crate::error::Result<()> {
+    use std::ffi::CString;
+    use std::ffi::c_char;
+    use std::ffi::c_void;
+
+    unsafe extern "C" {
+        fn webkit_web_view_get_settings(view: *mut c_void) -> *mut c_void;
+        fn webkit_settings_set_user_agent(settings: *mut c_void, ua: *const c_char);
+    }
+
+    let ua = CString::new(ua).map_err(|e| crate::error::Error::Io(e.into()))?;
+    let view = webview.query_native_controller()?;
+
+    // SAFETY: On the event thread while the webview is alive, and the string
+    // is copied by WebKit
+    unsafe {
+        webkit_settings_set_user_agent(webkit_web_view_get_settings(view.as_ptr()), ua.as_ptr())
+    };
+
+    Ok(())
+}
+
+#[cfg(feature = "qt")]
+fn set_user_agent(_: &Webview, _: &str) -> crate::error::Result<()> {
+    Err(crate::error::Error::unsupported(
+        "setting the user agent",
+        Some("set WebviewOptions::user_agent when creating the webview"),
+    ))
+}
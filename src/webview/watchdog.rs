@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+/// Tracks whether the page answers pings, set up via
+/// [`crate::webview::Webview::set_render_watchdog`].
+///
+/// Each tick answers whether to ping the page. A page that hasn't answered the
+/// previous ping by the next tick is considered gone, until it loads again or
+/// gets recovered.
+#[derive(Default)]
+pub(crate) struct Watchdog {
+    interval: Option<Duration>,
+    generation: u64,
+    seq: u64,
+    pending: Option<u64>,
+    loaded: bool,
+    terminated: bool,
+}
+
+/// The action to take on a watchdog tick.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum WatchdogTick {
+    /// The watchdog has been reconfigured, stop ticking.
+    Stop,
+    /// Nothing to do until the next tick.
+    Wait,
+    /// Pings the page with the given sequence number.
+    Ping(u64),
+    /// The page didn't answer in time.
+    Terminated,
+}
+
+impl Watchdog {
+    pub(crate) fn interval(&self) -> Option<Duration> { self.interval }
+
+    /// Reconfigures the watchdog. Returns the generation to tick with, if
+    /// enabled.
+    pub(crate) fn set_interval(&mut self, interval: Option<Duration>) -> Option<u64> {
+        self.interval = interval;
+        self.generation += 1;
+        self.pending = None;
+        interval.map(|_| self.generation)
+    }
+
+    pub(crate) fn on_started(&mut self) {
+        self.loaded = false;
+        self.pending = None;
+    }
+
+    pub(crate) fn on_finished(&mut self) {
+        self.loaded = true;
+        self.terminated = false;
+    }
+
+    pub(crate) fn on_pong(&mut self, seq: u64) {
+        if self.pending == Some(seq) {
+            self.pending = None;
+        }
+    }
+
+    /// Stops pinging the page, whose renderer is reported gone otherwise.
+    pub(crate) fn on_terminated(&mut self) {
+        self.pending = None;
+        self.terminated = true;
+    }
+
+    /// Forgets about the terminated page, e.g. when it's being reloaded.
+    pub(crate) fn reset(&mut self) {
+        self.pending = None;
+        self.terminated = false;
+    }
+
+    pub(crate) fn tick(&mut self, generation: u64) -> WatchdogTick {
+        if generation != self.generation {
+            return WatchdogTick::Stop;
+        }
+
+        if !self.loaded || self.terminated {
+            return WatchdogTick::Wait;
+        }
+
+        if self.pending.take().is_some() {
+            self.terminated = true;
+            return WatchdogTick::Terminated;
+        }
+
+        self.seq += 1;
+        self.pending = Some(self.seq);
+        WatchdogTick::Ping(self.seq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Watchdog;
+    use super::WatchdogTick;
+
+    #[test]
+    fn unanswered_ping_terminates_once() {
+        let mut wd = Watchdog::default();
+        let generation = wd.set_interval(Some(Duration::from_secs(1))).unwrap();
+
+        assert_eq!(wd.tick(generation), WatchdogTick::Wait);
+        wd.on_finished();
+
+        assert_eq!(wd.tick(generation), WatchdogTick::Ping(1));
+        wd.on_pong(1);
+        assert_eq!(wd.tick(generation), WatchdogTick::Ping(2));
+        assert_eq!(wd.tick(generation), WatchdogTick::Terminated);
+        assert_eq!(wd.tick(generation), WatchdogTick::Wait);
+
+        wd.reset();
+        assert_eq!(wd.tick(generation), WatchdogTick::Ping(3));
+
+        wd.set_interval(None);
+        assert_eq!(wd.tick(generation), WatchdogTick::Stop);
+    }
+
+    #[test]
+    fn crashed_page_is_not_pinged() {
+        let mut wd = Watchdog::default();
+        let generation = wd.set_interval(Some(Duration::from_secs(1))).unwrap();
+        wd.on_finished();
+
+        assert_eq!(wd.tick(generation), WatchdogTick::Ping(1));
+        wd.on_terminated();
+        assert_eq!(wd.tick(generation), WatchdogTick::Wait);
+
+        wd.on_started();
+        wd.on_finished();
+        assert_eq!(wd.tick(generation), WatchdogTick::Ping(2));
+    }
+}
@@ -0,0 +1,71 @@
+//! WebKitGTK interop module.
+//!
+//! saucer doesn't expose some signals of WebKitGTK, which are connected on the
+//! native webview instead (see
+//! [`crate::webview::Webview::query_native_controller`]).
+use std::ffi::CStr;
+use std::ffi::c_char;
+use std::ffi::c_void;
+
+use crate::webview::Webview;
+use crate::webview::WebviewRef;
+
+unsafe extern "C" {
+    fn g_signal_connect_data(
+        instance: *mut c_void,
+        signal: *const c_char,
+        handler: *const c_void,
+        data: *mut c_void,
+        destroy: unsafe extern "C" fn(*mut c_void, *mut c_void),
+        flags: u32,
+    ) -> u64;
+}
+
+/// Connects a signal of the native webview. The handler must be an
+/// `extern "C"` function matching the signal, and receives a
+/// [`WebviewRef`] as its data, which can be upgraded with [`webview`].
+pub(crate) fn connect(
+    webview: &Webview,
+    signal: &CStr,
+    handler: *const c_void,
+) -> crate::error::Result<()> {
+    let view = webview.query_native_controller()?;
+    let data = Box::into_raw(Box::new(webview.downgrade()));
+
+    // SAFETY: The native webview is a `WebKitWebView`, and the data is freed
+    // by GLib when the handler is disconnected
+    unsafe {
+        g_signal_connect_data(
+            view.as_ptr(),
+            signal.as_ptr(),
+            handler,
+            data.cast(),
+            free_data,
+            0,
+        )
+    };
+
+    Ok(())
+}
+
+unsafe extern "C" fn free_data(data: *mut c_void, _: *mut c_void) {
+    drop(unsafe { Box::from_raw(data as *mut WebviewRef) });
+}
+
+/// Gets the webview from the data of a handler connected with [`connect`].
+///
+/// # Safety
+///
+/// The data must have been passed by a handler connected with [`connect`].
+pub(crate) unsafe fn webview(data: *mut c_void) -> Option<Webview> {
+    unsafe { &*(data as *const WebviewRef) }.upgrade()
+}
+
+/// Copies a string borrowed from GLib, which may be null.
+///
+/// # Safety
+///
+/// The pointer must be null or point to a null-terminated string.
+pub(crate) unsafe fn string(s: *const c_char) -> Option<String> {
+    (!s.is_null()).then(|| unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned())
+}
@@ -0,0 +1,360 @@
+//! WebView2 interop module.
+//!
+//! saucer doesn't expose some features of WebView2, which are reached through
+//! the COM interfaces of the native webview instead (see
+//! [`crate::webview::Webview::query_native_controller`]). Interfaces are called
+//! by their vtable slots, which are fixed by the ABI of WebView2, as newer
+//! interfaces only append methods to the ones they extend.
+use std::ffi::c_void;
+use std::panic::AssertUnwindSafe;
+use std::ptr::NonNull;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
+
+use crate::webview::Webview;
+
+pub(crate) type HResult = i32;
+
+pub(crate) const S_OK: HResult = 0;
+
+/// An interface ID.
+#[repr(C)]
+pub(crate) struct Guid(u32, u16, u16, [u8; 8]);
+
+pub(crate) const IID_ICOREWEBVIEW2_2: Guid = Guid(0x9e8f0cf8, 0xe670, 0x4b5e, [
+    0xb2, 0xbc, 0x73, 0xe0, 0x61, 0xe3, 0x18, 0x4c,
+]);
+
+pub(crate) const IID_ICOREWEBVIEW2_3: Guid = Guid(0xa0d6df20, 0x3b92, 0x416d, [
+    0xaa, 0x0c, 0x43, 0x7a, 0x9c, 0x72, 0x78, 0x57,
+]);
+
+pub(crate) const IID_ICOREWEBVIEW2_10: Guid = Guid(0xb1690564, 0x6f5a, 0x4983, [
+    0x8e, 0x48, 0x31, 0xd1, 0x14, 0x3f, 0xec, 0xdb,
+]);
+
+pub(crate) const IID_ICOREWEBVIEW2_14: Guid = Guid(0x6daa4f10, 0x4a90, 0x4753, [
+    0x88, 0x98, 0x77, 0xc5, 0xdf, 0x53, 0x41, 0x65,
+]);
+
+pub(crate) const IID_ICOREWEBVIEW2_19: Guid = Guid(0x6921f954, 0x79b0, 0x437f, [
+    0xa9, 0x97, 0xc8, 0x58, 0x11, 0x89, 0x7c, 0x68,
+]);
+
+pub(crate) const IID_ICOREWEBVIEW2_SETTINGS2: Guid = Guid(0xee9a0f68, 0xf46c, 0x4e32, [
+    0xac, 0x23, 0xef, 0x8c, 0xac, 0x22, 0x4d, 0x2a,
+]);
+
+pub(crate) const IID_ICOREWEBVIEW2_PROCESS_FAILED_EVENT_ARGS2: Guid =
+    Guid(0x4dab9422, 0x46fa, 0x4c3e, [
+        0xa5, 0xd2, 0x41, 0xd2, 0x07, 0x1d, 0x36, 0x80,
+    ]);
+
+/// Slots of `ICoreWebView2` and the interfaces extending it.
+pub(crate) mod slot {
+    /// `ICoreWebView2Controller::put_IsVisible`.
+    pub(crate) const CONTROLLER_PUT_IS_VISIBLE: usize = 4;
+    /// `ICoreWebView2Controller::get_CoreWebView2`.
+    pub(crate) const CONTROLLER_GET_CORE_WEBVIEW2: usize = 25;
+    /// `ICoreWebView2::add_NavigationCompleted`.
+    pub(crate) const ADD_NAVIGATION_COMPLETED: usize = 15;
+    /// `ICoreWebView2::add_ProcessFailed`.
+    pub(crate) const ADD_PROCESS_FAILED: usize = 25;
+    /// `ICoreWebView2_2::get_CookieManager`.
+    pub(crate) const GET_COOKIE_MANAGER: usize = 66;
+    /// `ICoreWebView2_3::TrySuspend`.
+    pub(crate) const TRY_SUSPEND: usize = 68;
+    /// `ICoreWebView2_3::Resume`.
+    pub(crate) const RESUME: usize = 69;
+    /// `ICoreWebView2_10::add_BasicAuthenticationRequested`.
+    pub(crate) const ADD_BASIC_AUTHENTICATION_REQUESTED: usize = 97;
+    /// `ICoreWebView2_14::add_ServerCertificateErrorDetected`.
+    pub(crate) const ADD_SERVER_CERTIFICATE_ERROR_DETECTED: usize = 106;
+    /// `ICoreWebView2_19::put_MemoryUsageTargetLevel`.
+    pub(crate) const PUT_MEMORY_USAGE_TARGET_LEVEL: usize = 120;
+}
+
+#[link(name = "ole32")]
+unsafe extern "system" {
+    fn CoTaskMemFree(ptr: *mut c_void);
+}
+
+/// An owned reference to a COM object, released when dropped.
+pub(crate) struct ComPtr(NonNull<c_void>);
+
+impl ComPtr {
+    /// Takes ownership of a reference, or returns [`None`] for null.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be null or point to a COM object whose reference is
+    /// owned by the caller.
+    pub(crate) unsafe fn from_raw(ptr: *mut c_void) -> Option<Self> { NonNull::new(ptr).map(Self) }
+
+    /// Takes a new reference of a borrowed object, e.g. the arguments passed
+    /// to an event handler, or returns [`None`] for null.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be null or point to a live COM object.
+    pub(crate) unsafe fn from_borrowed(ptr: *mut c_void) -> Option<Self> {
+        let this = NonNull::new(ptr).map(Self)?;
+
+        unsafe {
+            let f: unsafe extern "system" fn(*mut c_void) -> u32 = this.method(1);
+            f(this.as_ptr());
+        }
+
+        Some(this)
+    }
+
+    pub(crate) fn as_ptr(&self) -> *mut c_void { self.0.as_ptr() }
+
+    /// Gets the method at the given slot of the vtable.
+    ///
+    /// # Safety
+    ///
+    /// The interface must have a method of type `F` at the slot.
+    pub(crate) unsafe fn method<F: Copy>(&self, slot: usize) -> F {
+        unsafe { method(self.as_ptr(), slot) }
+    }
+
+    /// Calls a getter returning an interface, e.g. `get_Settings`.
+    ///
+    /// # Safety
+    ///
+    /// The interface must have such a getter at the slot.
+    pub(crate) unsafe fn get(&self, slot: usize) -> Option<ComPtr> {
+        unsafe { get(self.as_ptr(), slot) }
+    }
+
+    /// Calls a getter returning a value, e.g. `get_ErrorStatus`.
+    ///
+    /// # Safety
+    ///
+    /// The interface must have a getter of the value type at the slot.
+    pub(crate) unsafe fn get_value<T: Default>(&self, slot: usize) -> Option<T> {
+        let mut out = T::default();
+
+        unsafe {
+            let f: unsafe extern "system" fn(*mut c_void, *mut T) -> HResult = self.method(slot);
+            (f(self.as_ptr(), &raw mut out) >= 0).then_some(out)
+        }
+    }
+
+    /// Calls a getter returning a string, e.g. `get_Uri`.
+    ///
+    /// # Safety
+    ///
+    /// The interface must have such a getter at the slot.
+    pub(crate) unsafe fn get_string(&self, slot: usize) -> Option<String> {
+        let mut out = std::ptr::null_mut::<u16>();
+
+        unsafe {
+            let f: unsafe extern "system" fn(*mut c_void, *mut *mut u16) -> HResult =
+                self.method(slot);
+            if f(self.as_ptr(), &raw mut out) < 0 || out.is_null() {
+                return None;
+            }
+
+            let len = (0..).take_while(|&i| *out.add(i) != 0).count();
+            let s = String::from_utf16_lossy(std::slice::from_raw_parts(out, len));
+            CoTaskMemFree(out.cast());
+            Some(s)
+        }
+    }
+
+    /// Calls a setter taking a string, e.g. `put_UserAgent`.
+    ///
+    /// # Safety
+    ///
+    /// The interface must have such a setter at the slot.
+    pub(crate) unsafe fn put_string(&self, slot: usize, value: &str) -> HResult {
+        let value = wide(value);
+
+        unsafe {
+            let f: unsafe extern "system" fn(*mut c_void, *const u16) -> HResult =
+                self.method(slot);
+            f(self.as_ptr(), value.as_ptr())
+        }
+    }
+
+    /// Calls a setter taking a value, e.g. `put_Cancel`.
+    ///
+    /// # Safety
+    ///
+    /// The interface must have a setter of the value type at the slot.
+    pub(crate) unsafe fn put<T>(&self, slot: usize, value: T) -> HResult {
+        unsafe {
+            let f: unsafe extern "system" fn(*mut c_void, T) -> HResult = self.method(slot);
+            f(self.as_ptr(), value)
+        }
+    }
+
+    /// Queries another interface of the object.
+    pub(crate) fn cast(&self, iid: &Guid) -> Option<ComPtr> {
+        let mut out = std::ptr::null_mut();
+
+        // SAFETY: `QueryInterface` is the first method of all interfaces
+        unsafe {
+            let f: unsafe extern "system" fn(
+                *mut c_void,
+                *const Guid,
+                *mut *mut c_void,
+            ) -> HResult = self.method(0);
+            if f(self.as_ptr(), iid, &raw mut out) < 0 {
+                return None;
+            }
+
+            Self::from_raw(out)
+        }
+    }
+}
+
+impl Drop for ComPtr {
+    fn drop(&mut self) {
+        // SAFETY: `Release` is the third method of all interfaces, and we own
+        // a reference
+        unsafe {
+            let f: unsafe extern "system" fn(*mut c_void) -> u32 = self.method(2);
+            f(self.as_ptr());
+        }
+    }
+}
+
+unsafe fn method<F: Copy>(obj: *mut c_void, slot: usize) -> F {
+    unsafe {
+        let vtbl = *(obj as *const *const *const c_void);
+        std::mem::transmute_copy(&*vtbl.add(slot))
+    }
+}
+
+unsafe fn get(obj: *mut c_void, slot: usize) -> Option<ComPtr> {
+    let mut out = std::ptr::null_mut();
+
+    unsafe {
+        let f: unsafe extern "system" fn(*mut c_void, *mut *mut c_void) -> HResult =
+            method(obj, slot);
+        if f(obj, &raw mut out) < 0 {
+            return None;
+        }
+
+        ComPtr::from_raw(out)
+    }
+}
+
+/// Encodes a string as a null-terminated wide string.
+pub(crate) fn wide(s: &str) -> Vec<u16> { s.encode_utf16().chain([0]).collect() }
+
+/// Gets the `ICoreWebView2` of the webview.
+pub(crate) fn core_webview(webview: &Webview) -> crate::error::Result<ComPtr> {
+    let controller = webview.query_native_controller()?;
+
+    // SAFETY: The native webview is an `ICoreWebView2Controller`, which is
+    // borrowed from saucer, and the getter returns a new reference
+    unsafe { get(controller.as_ptr(), slot::CONTROLLER_GET_CORE_WEBVIEW2) }
+        .ok_or_else(|| crate::error::Error::unsupported("WebView2 interfaces", None))
+}
+
+/// Registers a handler for an event, with the `add_` method at the slot. The
+/// handler receives the sender and the arguments of the event, and lives as
+/// long as the sender keeps it.
+///
+/// # Safety
+///
+/// The interface must have an `add_` method taking a handler of the
+/// `(sender, args)` kind at the slot.
+pub(crate) unsafe fn add_event_handler(
+    obj: &ComPtr,
+    slot: usize,
+    context: &'static str,
+    handler: impl FnMut(*mut c_void, *mut c_void) -> HResult + 'static,
+) -> bool {
+    let handler = Handler::<*mut c_void, *mut c_void, _>::create(context, handler);
+    let mut token = 0i64;
+
+    unsafe {
+        let f: unsafe extern "system" fn(*mut c_void, *mut c_void, *mut i64) -> HResult =
+            obj.method(slot);
+        f(obj.as_ptr(), handler.as_ptr(), &raw mut token) >= 0
+    }
+}
+
+/// A COM object implementing a handler interface, which all have a single
+/// `Invoke` method after those of `IUnknown`.
+#[repr(C)]
+pub(crate) struct Handler<A: 'static, B: 'static, F> {
+    vtbl: &'static HandlerVtbl<A, B>,
+    refs: AtomicU32,
+    context: &'static str,
+    callback: F,
+}
+
+#[repr(C)]
+struct HandlerVtbl<A, B> {
+    query_interface:
+        unsafe extern "system" fn(*mut c_void, *const Guid, *mut *mut c_void) -> HResult,
+    add_ref: unsafe extern "system" fn(*mut c_void) -> u32,
+    release: unsafe extern "system" fn(*mut c_void) -> u32,
+    invoke: unsafe extern "system" fn(*mut c_void, A, B) -> HResult,
+}
+
+impl<A: 'static, B: 'static, F: FnMut(A, B) -> HResult + 'static> Handler<A, B, F> {
+    const VTBL: HandlerVtbl<A, B> = HandlerVtbl {
+        query_interface: Self::query_interface,
+        add_ref: Self::add_ref,
+        release: Self::release,
+        invoke: Self::invoke,
+    };
+
+    /// Creates a handler, returning the reference owned by the caller. Panics
+    /// in the callback are caught and reported with the context.
+    pub(crate) fn create(context: &'static str, callback: F) -> ComPtr {
+        let handler = Box::new(Self {
+            vtbl: &Self::VTBL,
+            refs: AtomicU32::new(1),
+            context,
+            callback,
+        });
+
+        ComPtr(NonNull::from(Box::leak(handler)).cast())
+    }
+
+    unsafe extern "system" fn query_interface(
+        this: *mut c_void,
+        _: *const Guid,
+        out: *mut *mut c_void,
+    ) -> HResult {
+        // Handlers are only queried for the interface they're passed as
+        unsafe {
+            Self::add_ref(this);
+            *out = this;
+        }
+
+        S_OK
+    }
+
+    unsafe extern "system" fn add_ref(this: *mut c_void) -> u32 {
+        let this = unsafe { &*(this as *const Self) };
+        this.refs.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    unsafe extern "system" fn release(this: *mut c_void) -> u32 {
+        let refs = unsafe { &*(this as *const Self) }
+            .refs
+            .fetch_sub(1, Ordering::AcqRel)
+            - 1;
+
+        if refs == 0 {
+            drop(unsafe { Box::from_raw(this as *mut Self) });
+        }
+
+        refs
+    }
+
+    unsafe extern "system" fn invoke(this: *mut c_void, a: A, b: B) -> HResult {
+        let this = unsafe { &mut *(this as *mut Self) };
+        let context = this.context;
+        crate::util::ffi_callback(context, S_OK, AssertUnwindSafe(|| (this.callback)(a, b)))
+    }
+}
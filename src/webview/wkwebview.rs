@@ -0,0 +1,118 @@
+//! WKWebView interop module.
+//!
+//! saucer doesn't forward some delegate methods of WKWebView, which are added
+//! to the class of its navigation delegate instead. As methods are shared by
+//! all instances of the class, implementations look up the webview from the
+//! `WKWebView` passed to them with [`find`].
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::ffi::c_char;
+use std::ffi::c_void;
+use std::sync::Mutex;
+
+use objc2::class;
+use objc2::msg_send;
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2::runtime::Bool;
+use objc2::runtime::Sel;
+
+use crate::util::MutexExt;
+use crate::webview::Webview;
+use crate::webview::WebviewRef;
+
+unsafe extern "C" {
+    fn object_getClass(obj: *const AnyObject) -> *mut c_void;
+    fn class_addMethod(
+        cls: *mut c_void,
+        name: Sel,
+        imp: *const c_void,
+        types: *const c_char,
+    ) -> Bool;
+}
+
+/// The `WKWebView`s of webviews with delegate methods added.
+static WEBVIEWS: Mutex<Vec<(usize, WebviewRef)>> = Mutex::new(Vec::new());
+
+/// The layout of a block, enough for calling it.
+#[allow(unused)] // Only the function is read
+#[repr(C)]
+pub(crate) struct Block<F> {
+    isa: *const c_void,
+    flags: i32,
+    reserved: i32,
+    pub(crate) invoke: F,
+}
+
+/// Adds a method to the class of the navigation delegate of the webview,
+/// unless the class has it already (e.g. added for another webview). `types`
+/// is the type encoding of the method.
+pub(crate) fn add_delegate_method(
+    webview: &Webview,
+    sel: Sel,
+    imp: *const c_void,
+    types: &CStr,
+) -> crate::error::Result<()> {
+    let view = webview
+        .query_native_controller()?
+        .as_ptr()
+        .cast::<AnyObject>();
+
+    {
+        let mut webviews = WEBVIEWS.lock_unpoisoned();
+        webviews.retain(|(_, w)| w.upgrade().is_some());
+
+        if !webviews.iter().any(|(v, _)| *v == view as usize) {
+            webviews.push((view as usize, webview.downgrade()));
+        }
+    }
+
+    // SAFETY: On the main thread, which is the event thread on macOS, and the
+    // implementation matches the type encoding
+    unsafe {
+        let delegate: *mut AnyObject = msg_send![view, navigationDelegate];
+        if delegate.is_null() {
+            return Err(crate::error::Error::unsupported(
+                "WKWebView delegates",
+                None,
+            ));
+        }
+
+        class_addMethod(object_getClass(delegate), sel, imp, types.as_ptr());
+    }
+
+    Ok(())
+}
+
+/// Finds the webview of a `WKWebView` passed to a delegate method.
+pub(crate) fn find(view: *mut AnyObject) -> Option<Webview> {
+    WEBVIEWS
+        .lock_unpoisoned()
+        .iter()
+        .find(|(v, _)| *v == view as usize)
+        .and_then(|(_, w)| w.upgrade())
+}
+
+/// Creates an `NSString`.
+pub(crate) fn ns_string(s: &str) -> Option<Retained<AnyObject>> {
+    let s = CString::new(s).ok()?;
+
+    // SAFETY: The string is null-terminated and copied by the call
+    unsafe { msg_send![class!(NSString), stringWithUTF8String: s.as_ptr()] }
+}
+
+/// Copies an `NSString`, which may be nil.
+///
+/// # Safety
+///
+/// The object must be nil or an `NSString`.
+pub(crate) unsafe fn string(s: *mut AnyObject) -> Option<String> {
+    if s.is_null() {
+        return None;
+    }
+
+    unsafe {
+        let ptr: *const c_char = msg_send![s, UTF8String];
+        (!ptr.is_null()).then(|| CStr::from_ptr(ptr).to_string_lossy().into_owned())
+    }
+}
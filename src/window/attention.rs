@@ -0,0 +1,112 @@
+//! Window attention module.
+//!
+//! See [`crate::window::Window::request_attention`] for details.
+use crate::thread::check_event_thread;
+use crate::window::Window;
+
+/// The urgency of an attention request. See [`Window::request_attention`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AttentionType {
+    /// Signals something worth a look, e.g. a finished job. The dock icon
+    /// bounces once on macOS, and the taskbar button flashes a few times on
+    /// Windows.
+    #[default]
+    Informational,
+    /// Signals something needing action. The dock icon bounces (macOS) or the
+    /// taskbar button flashes (Windows) until the window is activated.
+    Critical,
+}
+
+impl Window {
+    /// Asks the user to look at the window without focusing it, e.g. when a
+    /// job finishes in the background. The request ends once the app is
+    /// activated. Does nothing if the app is already active.
+    ///
+    /// The dock icon bounces on macOS, and the taskbar button flashes on
+    /// WebView2. This fails with [`crate::error::Error::Unsupported`]
+    /// elsewhere: GTK 4, which WebKitGTK is built on, has dropped urgency
+    /// hints, and the window APIs of Qt are not reachable from the bindings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, unless allowed by the
+    /// [`crate::thread::ThreadViolationPolicy`].
+    pub fn request_attention(&self, kind: AttentionType) -> crate::error::Result<()> {
+        if check_event_thread(self.is_thread_safe(), "requesting attention").is_some() {
+            return Err(crate::error::Error::WrongThread);
+        }
+
+        self.ensure_alive()?;
+        request_user_attention(self, kind)
+    }
+}
+
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+fn request_user_attention(window: &Window, kind: AttentionType) -> crate::error::Result<()> {
+    const FLASHW_TRAY: u32 = 0x0002;
+    const FLASHW_TIMERNOFG: u32 = 0x000c;
+
+    #[repr(C)]
+    struct FlashWindowInfo {
+        size: u32,
+        hwnd: isize,
+        flags: u32,
+        count: u32,
+        timeout: u32,
+    }
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn FlashWindowEx(info: *const FlashWindowInfo) -> i32;
+    }
+
+    let (flags, count) = match kind {
+        AttentionType::Informational => (FLASHW_TRAY, 3),
+        AttentionType::Critical => (FLASHW_TRAY | FLASHW_TIMERNOFG, 0),
+    };
+
+    let info = FlashWindowInfo {
+        size: size_of::<FlashWindowInfo>() as u32,
+        hwnd: window.query_native_handle()?.as_ptr() as isize,
+        flags,
+        count,
+        timeout: 0, // The default cursor blink rate
+    };
+
+    // SAFETY: The window is alive, and we're on the event thread, which owns
+    // it. The return value is the previous state, not an error.
+    unsafe { FlashWindowEx(&raw const info) };
+
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn request_user_attention(_: &Window, kind: AttentionType) -> crate::error::Result<()> {
+    use objc2::class;
+    use objc2::msg_send;
+    use objc2::runtime::AnyObject;
+
+    // NSRequestUserAttentionType
+    let kind: isize = match kind {
+        AttentionType::Critical => 0,
+        AttentionType::Informational => 10,
+    };
+
+    // SAFETY: On the main thread, which is the event thread on macOS
+    unsafe {
+        let app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+        if !app.is_null() {
+            let _: isize = msg_send![app, requestUserAttention: kind];
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(any(all(target_os = "windows", not(feature = "qt")), target_os = "macos")))]
+fn request_user_attention(_: &Window, _: AttentionType) -> crate::error::Result<()> {
+    Err(crate::error::Error::unsupported(
+        "attention requests",
+        Some("show a notification or a progress (see Window::progress)"),
+    ))
+}
@@ -0,0 +1,324 @@
+//! Resize constraints module.
+//!
+//! See [`crate::window::Window::set_aspect_ratio`] for details.
+use crate::geometry::Size;
+use crate::window::Window;
+
+/// Constraints on window sizes, enforced natively where possible and by the
+/// bindings otherwise.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct SizeConstraints {
+    pub(crate) aspect_ratio: Option<f64>,
+    pub(crate) increment: Size,
+    /// Whether the backend enforces the constraints while resizing.
+    pub(crate) native: bool,
+}
+
+impl SizeConstraints {
+    /// Checks whether there is nothing to enforce.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.aspect_ratio.is_none() && self.increment.width <= 1 && self.increment.height <= 1
+    }
+
+    /// Gets the closest size satisfying the constraints. The dimension that
+    /// changed more since `prev` is kept when correcting the aspect ratio, and
+    /// increments are counted from `base` (the minimum size).
+    pub(crate) fn apply(&self, prev: Size, size: Size, base: Size) -> Size {
+        let mut out = size;
+
+        if let Some(ratio) = self.aspect_ratio.filter(|r| r.is_finite() && *r > 0.0) {
+            let dw = (size.width - prev.width).abs();
+            let dh = (size.height - prev.height).abs();
+
+            if dw >= dh {
+                out.height = (size.width as f64 / ratio).round() as i32;
+            } else {
+                out.width = (size.height as f64 * ratio).round() as i32;
+            }
+        }
+
+        let snap = |v: i32, base: i32, inc: i32| {
+            if inc <= 1 || v <= base {
+                v
+            } else {
+                base + (v - base) / inc * inc
+            }
+        };
+
+        out.width = snap(out.width, base.width.max(0), self.increment.width);
+        out.height = snap(out.height, base.height.max(0), self.increment.height);
+        out
+    }
+}
+
+/// Passes the constraints to the native window. Returns whether the backend
+/// enforces them, otherwise the bindings correct the size after each resize.
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+pub(crate) fn apply_native(window: &Window, _: SizeConstraints) -> bool {
+    use crate::util::MutexExt;
+    use crate::window::WindowRef;
+
+    const WM_SIZING: u32 = 0x0214;
+    const WM_NCDESTROY: u32 = 0x0082;
+    // WMSZ_*, the edge being dragged
+    const LEFT: usize = 1;
+    const TOP: usize = 3;
+    const TOP_LEFT: usize = 4;
+    const TOP_RIGHT: usize = 5;
+    const BOTTOM_LEFT: usize = 7;
+    const SUBCLASS_ID: usize = 0x5a5c; // Any ID unique to the bindings
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct Rect {
+        left: i32,
+        top: i32,
+        right: i32,
+        bottom: i32,
+    }
+
+    type SubclassProc = unsafe extern "system" fn(isize, u32, usize, isize, usize, usize) -> isize;
+
+    #[link(name = "comctl32")]
+    unsafe extern "system" {
+        fn SetWindowSubclass(hwnd: isize, proc: SubclassProc, id: usize, data: usize) -> i32;
+        fn GetWindowSubclass(hwnd: isize, proc: SubclassProc, id: usize, data: *mut usize) -> i32;
+        fn RemoveWindowSubclass(hwnd: isize, proc: SubclassProc, id: usize) -> i32;
+        fn DefSubclassProc(hwnd: isize, msg: u32, wparam: usize, lparam: isize) -> isize;
+    }
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn GetClientRect(hwnd: isize, rect: *mut Rect) -> i32;
+        fn GetWindowRect(hwnd: isize, rect: *mut Rect) -> i32;
+    }
+
+    /// Adjusts the dragged rectangle, which is in physical pixels and includes
+    /// the frame, to the constraints.
+    fn constrain(hwnd: isize, window: &Window, edge: usize, rect: &mut Rect) {
+        let constraints = *window.0.constraints.lock_unpoisoned();
+        if constraints.is_empty() {
+            return;
+        }
+
+        let (mut client, mut outer) = (Rect::default(), Rect::default());
+
+        // SAFETY: The window is alive while its messages are processed
+        unsafe {
+            GetClientRect(hwnd, &raw mut client);
+            GetWindowRect(hwnd, &raw mut outer);
+        }
+
+        let frame = Size::new(
+            (outer.right - outer.left) - client.right,
+            (outer.bottom - outer.top) - client.bottom,
+        );
+
+        // Sizes of the bindings may be logical
+        let logical = window.cached().size;
+        let scale = if logical.width > 0 {
+            client.right as f64 / logical.width as f64
+        } else {
+            1.0
+        };
+
+        let scaled = |s: Size| {
+            Size::new(
+                (s.width as f64 * scale).round() as i32,
+                (s.height as f64 * scale).round() as i32,
+            )
+        };
+
+        let native = SizeConstraints {
+            increment: scaled(constraints.increment),
+            ..constraints
+        };
+
+        let prev = Size::new(client.right, client.bottom);
+        let size = Size::new(
+            (rect.right - rect.left) - frame.width,
+            (rect.bottom - rect.top) - frame.height,
+        );
+        let base = scaled(window.min_size().unwrap_or_default());
+        let fixed = native.apply(prev, size, base);
+
+        if matches!(edge, LEFT | TOP_LEFT | BOTTOM_LEFT) {
+            rect.left = rect.right - fixed.width - frame.width;
+        } else {
+            rect.right = rect.left + fixed.width + frame.width;
+        }
+
+        if matches!(edge, TOP | TOP_LEFT | TOP_RIGHT) {
+            rect.top = rect.bottom - fixed.height - frame.height;
+        } else {
+            rect.bottom = rect.top + fixed.height + frame.height;
+        }
+    }
+
+    unsafe extern "system" fn subclass_proc(
+        hwnd: isize,
+        msg: u32,
+        wparam: usize,
+        lparam: isize,
+        _: usize,
+        data: usize,
+    ) -> isize {
+        let window = data as *const WindowRef;
+
+        match msg {
+            WM_SIZING => {
+                crate::util::ffi_callback("window sizing message", (), || {
+                    // SAFETY: The data is freed only when the window is
+                    // destroyed, and the rectangle is passed by the message
+                    let (window, rect) = unsafe { (&*window, &mut *(lparam as *mut Rect)) };
+                    if let Some(w) = window.upgrade() {
+                        constrain(hwnd, &w, wparam, rect);
+                    }
+                });
+
+                // SAFETY: Forwarding the message of the window
+                unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) };
+                1
+            }
+            WM_NCDESTROY => {
+                // SAFETY: The window is being destroyed, thus the data is no
+                // longer used
+                unsafe {
+                    RemoveWindowSubclass(hwnd, subclass_proc, SUBCLASS_ID);
+                    drop(Box::from_raw(window.cast_mut()));
+                    DefSubclassProc(hwnd, msg, wparam, lparam)
+                }
+            }
+            // SAFETY: Forwarding the message of the window
+            _ => unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) },
+        }
+    }
+
+    let Ok(hwnd) = window.query_native_handle() else {
+        return false;
+    };
+    let hwnd = hwnd.as_ptr() as isize;
+
+    // SAFETY: The window is alive, and we're on the event thread, which owns
+    // it. The subclass reads the constraints when sizing, thus it's only
+    // installed once.
+    unsafe {
+        let mut data = 0;
+        if GetWindowSubclass(hwnd, subclass_proc, SUBCLASS_ID, &raw mut data) != 0 {
+            return true;
+        }
+
+        let data = Box::into_raw(Box::new(window.downgrade()));
+        if SetWindowSubclass(hwnd, subclass_proc, SUBCLASS_ID, data as usize) == 0 {
+            drop(Box::from_raw(data));
+            return false;
+        }
+    }
+
+    true
+}
+
+#[cfg(all(target_os = "macos", not(feature = "qt")))]
+pub(crate) fn apply_native(window: &Window, constraints: SizeConstraints) -> bool {
+    use objc2::Encode;
+    use objc2::Encoding;
+    use objc2::msg_send;
+    use objc2::runtime::AnyObject;
+
+    #[repr(C)]
+    struct NSSize {
+        width: f64,
+        height: f64,
+    }
+
+    // SAFETY: Matches the layout of `NSSize`
+    unsafe impl Encode for NSSize {
+        const ENCODING: Encoding = Encoding::Struct("CGSize", &[f64::ENCODING, f64::ENCODING]);
+    }
+
+    let Ok(ns_window) = window.query_native_handle() else {
+        return false;
+    };
+    let ns_window = ns_window.as_ptr().cast::<AnyObject>();
+
+    let ratio = constraints
+        .aspect_ratio
+        .filter(|r| r.is_finite() && *r > 0.0);
+    let increment = NSSize {
+        width: constraints.increment.width.max(1) as f64,
+        height: constraints.increment.height.max(1) as f64,
+    };
+    let has_increment = increment.width > 1.0 || increment.height > 1.0;
+
+    // SAFETY: On the main thread, which is the event thread on macOS, and the
+    // window is alive. Setting increments clears the aspect ratio, thus both
+    // can't be enforced at once.
+    unsafe {
+        match ratio {
+            Some(_) if has_increment => {
+                let none = NSSize {
+                    width: 1.0,
+                    height: 1.0,
+                };
+                let _: () = msg_send![ns_window, setContentResizeIncrements: none];
+                false
+            }
+            Some(ratio) => {
+                let ratio = NSSize {
+                    width: ratio,
+                    height: 1.0,
+                };
+                let _: () = msg_send![ns_window, setContentAspectRatio: ratio];
+                true
+            }
+            None => {
+                let _: () = msg_send![ns_window, setContentResizeIncrements: increment];
+                true
+            }
+        }
+    }
+}
+
+#[cfg(not(all(any(target_os = "windows", target_os = "macos"), not(feature = "qt"))))]
+pub(crate) fn apply_native(_: &Window, _: SizeConstraints) -> bool { false }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_constraints() {
+        let c = SizeConstraints {
+            aspect_ratio: Some(16.0 / 9.0),
+            increment: Size::default(),
+            native: false,
+        };
+
+        let prev = Size::new(1600, 900);
+        assert_eq!(
+            c.apply(prev, Size::new(1920, 920), Size::default()),
+            Size::new(1920, 1080)
+        );
+        assert_eq!(
+            c.apply(prev, Size::new(1610, 450), Size::default()),
+            Size::new(800, 450)
+        );
+
+        let c = SizeConstraints {
+            aspect_ratio: None,
+            increment: Size::new(8, 16),
+            native: false,
+        };
+
+        assert!(!c.is_empty());
+        assert_eq!(
+            c.apply(prev, Size::new(805, 470), Size::new(100, 100)),
+            Size::new(804, 468)
+        );
+        assert_eq!(
+            c.apply(prev, Size::new(50, 50), Size::new(100, 100)),
+            Size::new(50, 50)
+        );
+        assert!(SizeConstraints::default().is_empty());
+    }
+}
@@ -0,0 +1,403 @@
+//! Window cursor module.
+//!
+//! See [`crate::window::Window::set_cursor`] for details.
+use crate::geometry::Position;
+use crate::icon::Icon;
+use crate::util::MutexExt;
+use crate::util::percent_encode_bytes;
+use crate::window::Window;
+use crate::window::WindowSignal;
+
+/// Standard cursor shapes, named after their CSS `cursor` values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CursorIcon {
+    #[default]
+    Default,
+    ContextMenu,
+    Help,
+    Pointer,
+    Progress,
+    Wait,
+    Cell,
+    Crosshair,
+    Text,
+    VerticalText,
+    Alias,
+    Copy,
+    Move,
+    NoDrop,
+    NotAllowed,
+    Grab,
+    Grabbing,
+    AllScroll,
+    ColResize,
+    RowResize,
+    NResize,
+    EResize,
+    SResize,
+    WResize,
+    NeResize,
+    NwResize,
+    SeResize,
+    SwResize,
+    EwResize,
+    NsResize,
+    NeswResize,
+    NwseResize,
+    ZoomIn,
+    ZoomOut,
+}
+
+impl CursorIcon {
+    /// Gets the CSS name of the cursor.
+    pub fn css_name(&self) -> &'static str {
+        use CursorIcon::*;
+        match self {
+            Default => "default",
+            ContextMenu => "context-menu",
+            Help => "help",
+            Pointer => "pointer",
+            Progress => "progress",
+            Wait => "wait",
+            Cell => "cell",
+            Crosshair => "crosshair",
+            Text => "text",
+            VerticalText => "vertical-text",
+            Alias => "alias",
+            Copy => "copy",
+            Move => "move",
+            NoDrop => "no-drop",
+            NotAllowed => "not-allowed",
+            Grab => "grab",
+            Grabbing => "grabbing",
+            AllScroll => "all-scroll",
+            ColResize => "col-resize",
+            RowResize => "row-resize",
+            NResize => "n-resize",
+            EResize => "e-resize",
+            SResize => "s-resize",
+            WResize => "w-resize",
+            NeResize => "ne-resize",
+            NwResize => "nw-resize",
+            SeResize => "se-resize",
+            SwResize => "sw-resize",
+            EwResize => "ew-resize",
+            NsResize => "ns-resize",
+            NeswResize => "nesw-resize",
+            NwseResize => "nwse-resize",
+            ZoomIn => "zoom-in",
+            ZoomOut => "zoom-out",
+        }
+    }
+}
+
+/// The cursor set on a window.
+#[derive(Clone, Debug)]
+pub(crate) struct CursorState {
+    /// Whether the page decides the cursor, i.e. nothing has been set.
+    pub(crate) inherit: bool,
+    /// The CSS value of the cursor.
+    pub(crate) css: String,
+    /// The standard cursor, or [`None`] for custom images.
+    pub(crate) icon: Option<CursorIcon>,
+    pub(crate) visible: bool,
+}
+
+impl CursorState {
+    pub(crate) fn new() -> Self {
+        Self {
+            inherit: true,
+            css: CursorIcon::Default.css_name().to_owned(),
+            icon: Some(CursorIcon::Default),
+            visible: true,
+        }
+    }
+
+    /// Gets the CSS value overriding cursors of the page, or [`None`] if the
+    /// page decides.
+    pub(crate) fn override_css(&self) -> Option<&str> {
+        if !self.visible {
+            Some("none")
+        } else if self.inherit {
+            None
+        } else {
+            Some(&self.css)
+        }
+    }
+}
+
+impl Window {
+    /// Sets the cursor shown over the window, overriding cursors set by the
+    /// page. Passing [`CursorIcon::Default`] gives control back to the page.
+    ///
+    /// The cursor is set on the native window (via `WM_SETCURSOR` on
+    /// WebView2, `NSCursor` on macOS and the cursor of the `GtkWindow` on
+    /// GTK), which covers areas outside webviews and pages whose CSS can't be
+    /// touched. As engines set the cursor of the page over webviews, it's
+    /// also applied to them by injecting a style sheet, which covers pages
+    /// loaded later. Only the style sheet is used with Qt.
+    pub fn set_cursor(&self, icon: CursorIcon) {
+        self.update_cursor(|c| {
+            c.inherit = icon == CursorIcon::Default;
+            c.css = icon.css_name().to_owned();
+            c.icon = Some(icon);
+        });
+    }
+
+    /// Sets a custom cursor image, with the hotspot being the clicked point
+    /// relative to the top-left corner of the image. See [`Self::set_cursor`]
+    /// for how it's applied.
+    ///
+    /// Engines limit the size of cursor images (usually 128x128 or smaller)
+    /// and fall back to the default cursor for larger ones. Custom images are
+    /// only applied to webviews, the native cursor is reset to the default.
+    pub fn set_cursor_custom(&self, icon: &Icon, hotspot: impl Into<Position>) {
+        let hotspot = hotspot.into();
+        let data = icon.data();
+        let css = format!(
+            "url(\"data:image/png,{}\") {} {}, default",
+            percent_encode_bytes(data.data()),
+            hotspot.x.max(0),
+            hotspot.y.max(0)
+        );
+
+        self.update_cursor(|c| {
+            c.inherit = false;
+            c.css = css;
+            c.icon = None;
+        });
+    }
+
+    /// Shows or hides the cursor over the window. The cursor set with
+    /// [`Self::set_cursor`] is kept and shown again afterwards.
+    pub fn set_cursor_visible(&self, visible: bool) { self.update_cursor(|c| c.visible = visible); }
+
+    /// Checks whether the cursor is visible. See [`Self::set_cursor_visible`].
+    pub fn is_cursor_visible(&self) -> bool { self.0.cursor.lock_unpoisoned().visible }
+
+    /// Gets the CSS value overriding cursors of the page, or [`None`] if the
+    /// page decides.
+    pub(crate) fn cursor_override(&self) -> Option<String> {
+        self.0
+            .cursor
+            .lock_unpoisoned()
+            .override_css()
+            .map(str::to_owned)
+    }
+
+    fn update_cursor(&self, f: impl FnOnce(&mut CursorState)) {
+        let (prev, state) = {
+            let mut cursor = self.0.cursor.lock_unpoisoned();
+            let prev = cursor.clone();
+            f(&mut cursor);
+            (prev, cursor.clone())
+        };
+
+        let _ = apply_native(self, &prev, &state); // Only webviews get the cursor otherwise
+        self.notify(WindowSignal::Cursor);
+    }
+}
+
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+fn apply_native(window: &Window, _: &CursorState, _: &CursorState) -> crate::error::Result<()> {
+    use crate::window::WindowRef;
+
+    const WM_SETCURSOR: u32 = 0x0020;
+    const WM_NCDESTROY: u32 = 0x0082;
+    const HTCLIENT: isize = 1;
+    const SUBCLASS_ID: usize = 0x5a5d; // Any ID unique to the bindings
+
+    type SubclassProc = unsafe extern "system" fn(isize, u32, usize, isize, usize, usize) -> isize;
+
+    #[link(name = "comctl32")]
+    unsafe extern "system" {
+        fn SetWindowSubclass(hwnd: isize, proc: SubclassProc, id: usize, data: usize) -> i32;
+        fn GetWindowSubclass(hwnd: isize, proc: SubclassProc, id: usize, data: *mut usize) -> i32;
+        fn RemoveWindowSubclass(hwnd: isize, proc: SubclassProc, id: usize) -> i32;
+        fn DefSubclassProc(hwnd: isize, msg: u32, wparam: usize, lparam: isize) -> isize;
+    }
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn LoadCursorW(instance: isize, name: usize) -> isize;
+        fn SetCursor(cursor: isize) -> isize;
+    }
+
+    /// Gets the `IDC_*` resource of the cursor, if it overrides the default.
+    fn resource(state: &CursorState) -> Option<usize> {
+        use CursorIcon::*;
+
+        if !state.visible {
+            return Some(0);
+        }
+
+        Some(match state.icon? {
+            Default => return None,
+            Text | VerticalText => 32513,
+            Wait => 32514,
+            Crosshair | Cell => 32515,
+            NwseResize | NwResize | SeResize => 32642,
+            NeswResize | NeResize | SwResize => 32643,
+            EwResize | EResize | WResize | ColResize => 32644,
+            NsResize | NResize | SResize | RowResize => 32645,
+            Move | AllScroll | Grab | Grabbing => 32646,
+            NotAllowed | NoDrop => 32648,
+            Pointer => 32649,
+            Progress => 32650,
+            Help => 32651,
+            ContextMenu | Alias | Copy | ZoomIn | ZoomOut => 32512,
+        })
+    }
+
+    unsafe extern "system" fn subclass_proc(
+        hwnd: isize,
+        msg: u32,
+        wparam: usize,
+        lparam: isize,
+        _: usize,
+        data: usize,
+    ) -> isize {
+        let window = data as *const WindowRef;
+
+        match msg {
+            // Only the window itself, webviews set their own cursors
+            WM_SETCURSOR if wparam as isize == hwnd && (lparam & 0xffff) == HTCLIENT => {
+                // SAFETY: The data is freed only when the window is destroyed
+                let state = unsafe { &*window }
+                    .upgrade()
+                    .and_then(|w| resource(&w.0.cursor.lock_unpoisoned()));
+
+                match state {
+                    // SAFETY: Loading a system cursor, which is shared
+                    Some(id) => unsafe {
+                        SetCursor(if id == 0 { 0 } else { LoadCursorW(0, id) });
+                        1
+                    },
+                    // SAFETY: Forwarding the message of the window
+                    None => unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) },
+                }
+            }
+            WM_NCDESTROY => {
+                // SAFETY: The window is being destroyed, thus the data is no
+                // longer used
+                unsafe {
+                    RemoveWindowSubclass(hwnd, subclass_proc, SUBCLASS_ID);
+                    drop(Box::from_raw(window.cast_mut()));
+                    DefSubclassProc(hwnd, msg, wparam, lparam)
+                }
+            }
+            // SAFETY: Forwarding the message of the window
+            _ => unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) },
+        }
+    }
+
+    let hwnd = window.query_native_handle()?.as_ptr() as isize;
+
+    // SAFETY: The window is alive, and we're on the event thread, which owns
+    // it. The subclass reads the cursor when it's asked for, thus it's only
+    // installed once.
+    unsafe {
+        let mut data = 0;
+        if GetWindowSubclass(hwnd, subclass_proc, SUBCLASS_ID, &raw mut data) != 0 {
+            return Ok(());
+        }
+
+        let data = Box::into_raw(Box::new(window.downgrade()));
+        if SetWindowSubclass(hwnd, subclass_proc, SUBCLASS_ID, data as usize) == 0 {
+            drop(Box::from_raw(data));
+            return Err(crate::error::Error::unsupported("native cursors", None));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(all(target_os = "macos", not(feature = "qt")))]
+fn apply_native(
+    window: &Window,
+    prev: &CursorState,
+    state: &CursorState,
+) -> crate::error::Result<()> {
+    use objc2::class;
+    use objc2::msg_send;
+    use objc2::runtime::AnyObject;
+
+    window.ensure_alive()?;
+
+    let cls = class!(NSCursor);
+
+    // SAFETY: On the main thread, which is the event thread on macOS. Hiding
+    // is counted, thus it's only done when the visibility changes.
+    unsafe {
+        if prev.visible != state.visible {
+            if state.visible {
+                let _: () = msg_send![cls, unhide];
+            } else {
+                let _: () = msg_send![cls, hide];
+            }
+        }
+
+        use CursorIcon::*;
+        let cursor: *mut AnyObject = match state.icon.unwrap_or(Default) {
+            Text => msg_send![cls, IBeamCursor],
+            VerticalText => msg_send![cls, IBeamCursorForVerticalLayout],
+            Crosshair | Cell => msg_send![cls, crosshairCursor],
+            Pointer => msg_send![cls, pointingHandCursor],
+            Grab => msg_send![cls, openHandCursor],
+            Grabbing | Move | AllScroll => msg_send![cls, closedHandCursor],
+            NotAllowed | NoDrop => msg_send![cls, operationNotAllowedCursor],
+            EwResize | EResize | WResize | ColResize => msg_send![cls, resizeLeftRightCursor],
+            NsResize | NResize | SResize | RowResize => msg_send![cls, resizeUpDownCursor],
+            Copy => msg_send![cls, dragCopyCursor],
+            Alias => msg_send![cls, dragLinkCursor],
+            ContextMenu => msg_send![cls, contextualMenuCursor],
+            _ => msg_send![cls, arrowCursor],
+        };
+
+        // Lasts until the view under the mouse sets its own
+        let _: () = msg_send![cursor, set];
+    }
+
+    Ok(())
+}
+
+#[cfg(all(
+    not(any(target_os = "windows", target_os = "macos")),
+    not(feature = "qt")
+))]
+fn apply_native(window: &Window, _: &CursorState, state: &CursorState) -> crate::error::Result<()> {
+    use std::ffi::CString;
+    use std::ffi::c_char;
+    use std::ffi::c_void;
+
+    unsafe extern "C" {
+        fn gtk_widget_set_cursor_from_name(widget: *mut c_void, name: *const c_char);
+    }
+
+    let gtk_window = window.query_native_handle()?.as_ptr();
+
+    // Cursor names of GTK are the CSS ones, custom images are left to CSS
+    let name = if !state.visible {
+        Some(c"none".to_owned())
+    } else if state.inherit {
+        None
+    } else {
+        state.icon.and_then(|i| CString::new(i.css_name()).ok())
+    };
+
+    // SAFETY: The `GtkWindow` is alive, and we're on the event thread, which
+    // owns it
+    unsafe {
+        gtk_widget_set_cursor_from_name(
+            gtk_window,
+            name.as_ref().map_or(std::ptr::null(), |n| n.as_ptr()),
+        )
+    };
+
+    Ok(())
+}
+
+#[cfg(feature = "qt")]
+fn apply_native(_: &Window, _: &CursorState, _: &CursorState) -> crate::error::Result<()> {
+    Err(crate::error::Error::unsupported("native cursors", None))
+}
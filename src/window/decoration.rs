@@ -1,6 +1,14 @@
+use std::ops::BitAnd;
+use std::ops::BitOr;
+use std::ops::Not;
+use std::ops::Sub;
+
 use saucer_sys::*;
 
+use crate::window::Window;
+
 /// Describes the decoration status of a window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum WindowDecoration {
     None,
     Partial,
@@ -28,3 +36,330 @@ impl From<WindowDecoration> for saucer_window_decoration {
         }
     }
 }
+
+/// A set of window decoration parts, used for finer control than
+/// [`WindowDecoration`].
+///
+/// Backends only understand the three [`WindowDecoration`] levels, so a set of
+/// parts is mapped to the closest level when applied (see
+/// [`DecorationParts::closest`]). Titlebar buttons are then disabled through
+/// the native window on WebView2 and WebKit. Other backends can't disable
+/// them, thus sets with a titlebar but without all of its buttons are
+/// rejected there. Use the value returned by
+/// [`crate::window::Window::set_decoration_parts`] to find out what actually
+/// took effect.
+///
+/// Resize borders are toggled on all edges at once, as no backend can keep
+/// them on some edges only.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DecorationParts(u32);
+
+impl DecorationParts {
+    /// The close button on the titlebar.
+    pub const CLOSE_BUTTON: Self = Self(1 << 4);
+    /// The maximize button on the titlebar.
+    pub const MAXIMIZE_BUTTON: Self = Self(1 << 3);
+    /// The minimize button on the titlebar.
+    pub const MINIMIZE_BUTTON: Self = Self(1 << 2);
+    /// Borders that can be dragged to resize the window, on all edges.
+    pub const RESIZE_BORDERS: Self = Self(1 << 1);
+    /// The titlebar, including the window title.
+    pub const TITLEBAR: Self = Self(1 << 0);
+
+    /// Gets an empty set.
+    pub const fn empty() -> Self { Self(0) }
+
+    /// Gets a set containing all parts.
+    pub const fn all() -> Self { Self((1 << 5) - 1) }
+
+    /// Gets the raw bits.
+    pub const fn bits(&self) -> u32 { self.0 }
+
+    /// Checks whether the set is empty.
+    pub const fn is_empty(&self) -> bool { self.0 == 0 }
+
+    /// Checks whether all parts in `other` are contained in this set.
+    pub const fn contains(&self, other: Self) -> bool { self.0 & other.0 == other.0 }
+
+    /// Gets the closest [`WindowDecoration`] level that can represent this set.
+    ///
+    /// A set with a titlebar maps to [`WindowDecoration::Full`], whose buttons
+    /// are disabled separately. A set with only resize borders maps to
+    /// [`WindowDecoration::Partial`], and everything else maps to
+    /// [`WindowDecoration::None`].
+    pub fn closest(&self) -> WindowDecoration {
+        if self.contains(Self::TITLEBAR) {
+            WindowDecoration::Full
+        } else if self.contains(Self::RESIZE_BORDERS) {
+            WindowDecoration::Partial
+        } else {
+            WindowDecoration::None
+        }
+    }
+}
+
+impl From<WindowDecoration> for DecorationParts {
+    fn from(value: WindowDecoration) -> Self {
+        match value {
+            WindowDecoration::None => Self::empty(),
+            WindowDecoration::Partial => Self::RESIZE_BORDERS,
+            WindowDecoration::Full => Self::all(),
+        }
+    }
+}
+
+impl BitOr for DecorationParts {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self { Self(self.0 | rhs.0) }
+}
+
+impl BitAnd for DecorationParts {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self { Self(self.0 & rhs.0) }
+}
+
+impl Sub for DecorationParts {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self { Self(self.0 & !rhs.0) }
+}
+
+impl Not for DecorationParts {
+    type Output = Self;
+
+    fn not(self) -> Self { Self(!self.0 & Self::all().0) }
+}
+
+/// Enables the titlebar buttons in the set and disables the others.
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+pub(super) fn set_buttons(window: &Window, parts: DecorationParts) -> crate::error::Result<()> {
+    use win32::*;
+
+    let hwnd = window.query_native_handle()?.as_ptr() as isize;
+    let toggle =
+        |style: isize, flag: isize, on: bool| if on { style | flag } else { style & !flag };
+
+    // SAFETY: The window is alive, and we're on the event thread, which owns
+    // it
+    unsafe {
+        let mut style = GetWindowLongPtrW(hwnd, GWL_STYLE);
+        style = toggle(
+            style,
+            WS_MINIMIZEBOX,
+            parts.contains(DecorationParts::MINIMIZE_BUTTON),
+        );
+        style = toggle(
+            style,
+            WS_MAXIMIZEBOX,
+            parts.contains(DecorationParts::MAXIMIZE_BUTTON),
+        );
+        SetWindowLongPtrW(hwnd, GWL_STYLE, style);
+
+        let grayed = if parts.contains(DecorationParts::CLOSE_BUTTON) {
+            0
+        } else {
+            MF_GRAYED
+        };
+        EnableMenuItem(GetSystemMenu(hwnd, 0), SC_CLOSE, MF_BYCOMMAND | grayed);
+
+        SetWindowPos(
+            hwnd,
+            0,
+            0,
+            0,
+            0,
+            0,
+            SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_FRAMECHANGED,
+        );
+    }
+
+    Ok(())
+}
+
+/// Gets the titlebar buttons that are disabled.
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+pub(super) fn disabled_buttons(window: &Window) -> DecorationParts {
+    use win32::*;
+
+    let Ok(hwnd) = window.query_native_handle() else {
+        return DecorationParts::empty();
+    };
+
+    let hwnd = hwnd.as_ptr() as isize;
+    let mut disabled = DecorationParts::empty();
+
+    // SAFETY: The window is alive, and reading styles is thread-safe
+    unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_STYLE);
+
+        if style & WS_MINIMIZEBOX == 0 {
+            disabled = disabled | DecorationParts::MINIMIZE_BUTTON;
+        }
+
+        if style & WS_MAXIMIZEBOX == 0 {
+            disabled = disabled | DecorationParts::MAXIMIZE_BUTTON;
+        }
+
+        if GetMenuState(GetSystemMenu(hwnd, 0), SC_CLOSE, MF_BYCOMMAND) & MF_GRAYED != 0 {
+            disabled = disabled | DecorationParts::CLOSE_BUTTON;
+        }
+    }
+
+    disabled
+}
+
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+mod win32 {
+    pub const GWL_STYLE: i32 = -16;
+    pub const WS_MINIMIZEBOX: isize = 0x0002_0000;
+    pub const WS_MAXIMIZEBOX: isize = 0x0001_0000;
+    pub const SC_CLOSE: u32 = 0xf060;
+    pub const MF_BYCOMMAND: u32 = 0x0000;
+    pub const MF_GRAYED: u32 = 0x0001;
+    pub const SWP_NOSIZE: u32 = 0x0001;
+    pub const SWP_NOMOVE: u32 = 0x0002;
+    pub const SWP_NOZORDER: u32 = 0x0004;
+    pub const SWP_FRAMECHANGED: u32 = 0x0020;
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        pub fn GetWindowLongPtrW(hwnd: isize, index: i32) -> isize;
+        pub fn SetWindowLongPtrW(hwnd: isize, index: i32, value: isize) -> isize;
+        pub fn GetSystemMenu(hwnd: isize, revert: i32) -> isize;
+        pub fn EnableMenuItem(menu: isize, item: u32, flags: u32) -> i32;
+        pub fn GetMenuState(menu: isize, item: u32, flags: u32) -> u32;
+        pub fn SetWindowPos(
+            hwnd: isize,
+            after: isize,
+            x: i32,
+            y: i32,
+            cx: i32,
+            cy: i32,
+            flags: u32,
+        ) -> i32;
+    }
+}
+
+/// The `NSWindowButton` of each titlebar button.
+#[cfg(all(target_os = "macos", not(feature = "qt")))]
+const NS_WINDOW_BUTTONS: [(DecorationParts, usize); 3] = [
+    (DecorationParts::CLOSE_BUTTON, 0),
+    (DecorationParts::MINIMIZE_BUTTON, 1),
+    (DecorationParts::MAXIMIZE_BUTTON, 2),
+];
+
+/// Enables the titlebar buttons in the set and disables the others.
+#[cfg(all(target_os = "macos", not(feature = "qt")))]
+pub(super) fn set_buttons(window: &Window, parts: DecorationParts) -> crate::error::Result<()> {
+    use objc2::msg_send;
+    use objc2::runtime::AnyObject;
+
+    let ns_window = window.query_native_handle()?.as_ptr().cast::<AnyObject>();
+
+    for (part, kind) in NS_WINDOW_BUTTONS {
+        // SAFETY: On the main thread, which is the event thread on macOS, and
+        // the window is alive
+        unsafe {
+            let button: *mut AnyObject = msg_send![ns_window, standardWindowButton: kind];
+            if !button.is_null() {
+                let _: () = msg_send![button, setEnabled: parts.contains(part)];
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Gets the titlebar buttons that are disabled.
+#[cfg(all(target_os = "macos", not(feature = "qt")))]
+pub(super) fn disabled_buttons(window: &Window) -> DecorationParts {
+    use objc2::msg_send;
+    use objc2::runtime::AnyObject;
+
+    // AppKit must only be used on the main thread
+    let Some(ns_window) = window
+        .query_native_handle()
+        .ok()
+        .filter(|_| window.is_thread_safe())
+    else {
+        return DecorationParts::empty();
+    };
+
+    let ns_window = ns_window.as_ptr().cast::<AnyObject>();
+    let mut disabled = DecorationParts::empty();
+
+    for (part, kind) in NS_WINDOW_BUTTONS {
+        // SAFETY: See set_buttons
+        let enabled = unsafe {
+            let button: *mut AnyObject = msg_send![ns_window, standardWindowButton: kind];
+            button.is_null() || msg_send![button, isEnabled]
+        };
+
+        if !enabled {
+            disabled = disabled | part;
+        }
+    }
+
+    disabled
+}
+
+/// Checks whether the titlebar buttons in the set can be applied.
+#[cfg(all(any(target_os = "windows", target_os = "macos"), not(feature = "qt")))]
+pub(super) fn check_buttons(_: DecorationParts) -> crate::error::Result<()> { Ok(()) }
+
+/// Titlebar buttons can't be disabled, thus sets with a titlebar need all of
+/// them.
+#[cfg(not(all(any(target_os = "windows", target_os = "macos"), not(feature = "qt"))))]
+pub(super) fn check_buttons(parts: DecorationParts) -> crate::error::Result<()> {
+    let buttons = DecorationParts::CLOSE_BUTTON
+        | DecorationParts::MAXIMIZE_BUTTON
+        | DecorationParts::MINIMIZE_BUTTON;
+
+    if !parts.contains(DecorationParts::TITLEBAR) || parts.contains(buttons) {
+        Ok(())
+    } else {
+        Err(crate::error::Error::unsupported(
+            "disabling titlebar buttons",
+            Some("hide the titlebar and draw one in the page"),
+        ))
+    }
+}
+
+/// Titlebar buttons can't be toggled apart from the decoration level.
+#[cfg(not(all(any(target_os = "windows", target_os = "macos"), not(feature = "qt"))))]
+pub(super) fn set_buttons(_: &Window, _: DecorationParts) -> crate::error::Result<()> { Ok(()) }
+
+/// Titlebar buttons can't be toggled apart from the decoration level, thus
+/// they're all shown along with the titlebar.
+#[cfg(not(all(any(target_os = "windows", target_os = "macos"), not(feature = "qt"))))]
+pub(super) fn disabled_buttons(_: &Window) -> DecorationParts { DecorationParts::empty() }
+
+#[cfg(test)]
+mod tests {
+    use super::DecorationParts;
+    use super::WindowDecoration;
+
+    #[test]
+    fn decoration_parts_map_to_closest_level() {
+        let no_maximize = DecorationParts::all() - DecorationParts::MAXIMIZE_BUTTON;
+        assert_eq!(no_maximize.closest(), WindowDecoration::Full);
+        assert_eq!(
+            DecorationParts::RESIZE_BORDERS.closest(),
+            WindowDecoration::Partial
+        );
+        assert_eq!(
+            DecorationParts::CLOSE_BUTTON.closest(),
+            WindowDecoration::None
+        );
+
+        for dec in [
+            WindowDecoration::None,
+            WindowDecoration::Partial,
+            WindowDecoration::Full,
+        ] {
+            assert_eq!(DecorationParts::from(dec).closest(), dec);
+        }
+    }
+}
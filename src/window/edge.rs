@@ -1,6 +1,7 @@
 use saucer_sys::*;
 
 /// Window edge descriptor for specifying dragging operations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum WindowEdge {
     Top,
     Bottom,
@@ -27,3 +28,22 @@ impl From<WindowEdge> for saucer_window_edge {
         }
     }
 }
+
+impl WindowEdge {
+    /// Parses a kebab-case edge name (e.g. `top-left`).
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        use WindowEdge::*;
+        let edge = match name {
+            "top" => Top,
+            "bottom" => Bottom,
+            "left" => Left,
+            "right" => Right,
+            "bottom-left" => BottomLeft,
+            "bottom-right" => BottomRight,
+            "top-left" => TopLeft,
+            "top-right" => TopRight,
+            _ => return None,
+        };
+        Some(edge)
+    }
+}
@@ -0,0 +1,16 @@
+/// Visual effects applied to the window background, used with
+/// [`crate::window::Window::set_effect`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WindowEffect {
+    /// An opaque background.
+    None,
+    /// A fully transparent background, letting the desktop show through where
+    /// the page is transparent.
+    Transparent,
+    /// The acrylic material on Windows.
+    Acrylic,
+    /// The mica material on Windows.
+    Mica,
+    /// The vibrancy material on macOS.
+    Vibrancy,
+}
@@ -1,6 +1,8 @@
 use std::panic::RefUnwindSafe;
 
+use crate::geometry::Position;
 use crate::policy::Policy;
+use crate::window::ProgressState;
 use crate::window::Window;
 use crate::window::WindowDecoration;
 
@@ -27,6 +29,33 @@ pub trait WindowEventListener: RefUnwindSafe {
     /// Fired when the window size changes.
     fn on_resize(&self, window: Window, width: u32, height: u32) {}
 
+    /// Fired before the first of a series of resizes, e.g. when the user
+    /// starts dragging a border.
+    ///
+    /// Backends don't report interactive operations, thus the bindings infer
+    /// them from resize events. A series ends once the size has been stable
+    /// for a short while, which also applies to resizes done by the app.
+    fn on_resize_begin(&self, window: Window) {}
+
+    /// Fired after the last of a series of resizes. See
+    /// [`Self::on_resize_begin`].
+    fn on_resize_end(&self, window: Window) {}
+
+    /// Fired when the window position changes. Only fired while move tracking
+    /// is enabled, see [`Window::set_move_tracking`].
+    fn on_moved(&self, window: Window, position: Position) {}
+
+    /// Fired before the first of a series of moves. See [`Self::on_moved`].
+    fn on_move_begin(&self, window: Window) {}
+
+    /// Fired once the position stays the same for a tracking interval after
+    /// moving. See [`Self::on_moved`].
+    fn on_move_end(&self, window: Window) {}
+
+    /// Fired when the progress reported with [`Window::progress`] changes,
+    /// including when it finishes.
+    fn on_progress(&self, window: Window, progress: ProgressState) {}
+
     /// Fired when the window is focused or blurred.
     fn on_focus(&self, window: Window, focused: bool) {}
 
@@ -0,0 +1,106 @@
+//! Window fullscreen module.
+//!
+//! See [`crate::window::Window::set_fullscreen_on`] for details.
+use crate::geometry::Rect;
+use crate::screen::Screen;
+use crate::util::MutexExt;
+use crate::window::Window;
+use crate::window::WindowDecoration;
+
+/// How a window covers a screen. See [`Window::set_fullscreen_on`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FullscreenMode {
+    /// The native fullscreen of the platform, same as
+    /// [`Window::set_fullscreen`]. Depending on the platform, the window may
+    /// be moved to a separate space and animated.
+    #[default]
+    Exclusive,
+    /// A window without decorations covering the screen. It stays a normal
+    /// window, thus switching is instant and other windows can be shown above
+    /// it.
+    Borderless,
+}
+
+/// States to restore when leaving borderless fullscreen.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct BorderlessRestore {
+    bounds: Rect,
+    decorations: WindowDecoration,
+    maximized: bool,
+}
+
+impl Window {
+    /// Makes the window fullscreen on the given screen with the given mode.
+    /// The window leaves the current fullscreen mode first, if any.
+    ///
+    /// For [`FullscreenMode::Exclusive`], the window is moved onto the screen
+    /// before entering the native fullscreen, which uses the screen the window
+    /// is on.
+    pub fn set_fullscreen_on(
+        &self,
+        screen: &Screen,
+        mode: FullscreenMode,
+    ) -> crate::error::Result<()> {
+        self.exit_fullscreen()?;
+        let bounds = screen.bounds();
+
+        match mode {
+            FullscreenMode::Exclusive => {
+                let size = self.size()?.clamp(Default::default(), bounds.size);
+                self.set_position(bounds.centered(size).pos);
+                self.set_fullscreen(true);
+            }
+            FullscreenMode::Borderless => {
+                let maximized = self.is_maximized()?;
+                let restore = BorderlessRestore {
+                    bounds: self.bounds()?,
+                    decorations: self.decorations()?,
+                    maximized,
+                };
+
+                *self.0.borderless.lock_unpoisoned() = Some(restore);
+
+                if maximized {
+                    self.set_maximized(false);
+                }
+
+                self.set_decorations(WindowDecoration::None);
+                self.set_bounds(bounds);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Leaves fullscreen of either mode, restoring the window as it was before
+    /// entering. Does nothing if the window is not fullscreen.
+    pub fn exit_fullscreen(&self) -> crate::error::Result<()> {
+        if self.is_fullscreen()? {
+            self.set_fullscreen(false);
+        }
+
+        let restore = self.0.borderless.lock_unpoisoned().take();
+        if let Some(r) = restore {
+            self.set_decorations(r.decorations);
+            self.set_bounds(r.bounds);
+
+            if r.maximized {
+                self.set_maximized(true);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets the fullscreen mode of the window, or [`None`] if it's not
+    /// fullscreen.
+    pub fn fullscreen_mode(&self) -> crate::error::Result<Option<FullscreenMode>> {
+        if self.is_fullscreen()? {
+            Ok(Some(FullscreenMode::Exclusive))
+        } else if self.0.borderless.lock_unpoisoned().is_some() {
+            Ok(Some(FullscreenMode::Borderless))
+        } else {
+            Ok(None)
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Interactive move and resize module.
+//!
+//! Backends don't report moves, nor the start and end of interactive
+//! operations, thus they're inferred here. See
+//! [`crate::window::WindowEventListener::on_resize_begin`] and
+//! [`crate::window::Window::set_move_tracking`].
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::geometry::Position;
+use crate::util::MutexExt;
+use crate::window::Window;
+
+/// How long the size must stay the same to end a series of resizes.
+const RESIZE_SETTLE: Duration = Duration::from_millis(200);
+
+/// States of interactive operations of a window.
+#[derive(Default)]
+pub(crate) struct Interaction {
+    /// When the last resize of the current series happened.
+    last_resize: Option<Instant>,
+    /// The position seen by the last poll.
+    last_position: Option<Position>,
+    moving: bool,
+    /// Changed to stop the polling thread.
+    move_generation: u64,
+    move_interval: Option<Duration>,
+}
+
+impl Window {
+    /// Enables firing [`crate::window::WindowEventListener::on_moved`] and
+    /// related events, by polling the window position at the given interval.
+    /// Passing [`None`] disables tracking.
+    ///
+    /// Backends don't report window moves, thus the bindings poll the position
+    /// on a background thread, which posts a check to the event thread at
+    /// each interval. Shorter intervals report moves more smoothly at a higher
+    /// cost, e.g. 100 milliseconds is enough for persisting positions.
+    pub fn set_move_tracking(&self, interval: Option<Duration>) {
+        let generation = {
+            let mut state = self.0.interaction.lock_unpoisoned();
+            state.move_generation += 1;
+            state.move_interval = interval;
+            state.last_position = None;
+            state.moving = false;
+            state.move_generation
+        };
+
+        let Some(interval) = interval else {
+            return;
+        };
+
+        let window = self.downgrade();
+        let app = self.0.app.clone();
+
+        std::thread::spawn(move || {
+            loop {
+                std::thread::sleep(interval);
+
+                let (Some(w), Some(app)) = (window.upgrade(), app.upgrade()) else {
+                    break;
+                };
+
+                if w.0.interaction.lock_unpoisoned().move_generation != generation
+                    || w.cached().is_closed
+                {
+                    break;
+                }
+
+                drop(w); // Don't keep the window alive while sleeping
+
+                let window = window.clone();
+                app.post(move |_| {
+                    if let Some(w) = window.upgrade() {
+                        w.poll_position(generation);
+                    }
+                });
+            }
+        });
+    }
+
+    /// Gets the polling interval of move tracking. See
+    /// [`Self::set_move_tracking`].
+    pub fn move_tracking(&self) -> Option<Duration> {
+        self.0.interaction.lock_unpoisoned().move_interval
+    }
+
+    fn poll_position(&self, generation: u64) {
+        let Ok(pos) = self.position() else {
+            return;
+        };
+
+        enum Change {
+            Begin,
+            Moved,
+            End,
+        }
+
+        let change = {
+            let mut state = self.0.interaction.lock_unpoisoned();
+            if state.move_generation != generation {
+                return;
+            }
+
+            let last = state.last_position.replace(pos);
+            match last {
+                Some(last) if last != pos && !state.moving => {
+                    state.moving = true;
+                    Some(Change::Begin)
+                }
+                Some(last) if last != pos => Some(Change::Moved),
+                Some(_) if state.moving => {
+                    state.moving = false;
+                    Some(Change::End)
+                }
+                _ => None,
+            }
+        };
+
+        let listener = self.listener();
+        match change {
+            Some(Change::Begin) => {
+                listener.on_move_begin(self.clone());
+                listener.on_moved(self.clone(), pos);
+            }
+            Some(Change::Moved) => listener.on_moved(self.clone(), pos),
+            Some(Change::End) => listener.on_move_end(self.clone()),
+            None => {}
+        }
+    }
+
+    /// Records a resize, returning whether it begins a series.
+    pub(crate) fn record_resize(&self) -> bool {
+        let begins = self
+            .0
+            .interaction
+            .lock_unpoisoned()
+            .last_resize
+            .replace(Instant::now())
+            .is_none();
+
+        if begins {
+            self.watch_resize_settle();
+        }
+
+        begins
+    }
+
+    /// Waits on a background thread until the size has been stable for long
+    /// enough, then posts [`Self::finish_resize`].
+    fn watch_resize_settle(&self) {
+        let window = self.downgrade();
+        let app = self.0.app.clone();
+
+        std::thread::spawn(move || {
+            let mut wait = RESIZE_SETTLE;
+
+            loop {
+                std::thread::sleep(wait);
+
+                let (Some(w), Some(app)) = (window.upgrade(), app.upgrade()) else {
+                    break;
+                };
+
+                let Some(last) = w.0.interaction.lock_unpoisoned().last_resize else {
+                    break;
+                };
+
+                let elapsed = last.elapsed();
+                if elapsed < RESIZE_SETTLE {
+                    wait = RESIZE_SETTLE - elapsed;
+                    continue;
+                }
+
+                drop(w);
+
+                let window = window.clone();
+                app.post(move |_| {
+                    if let Some(w) = window.upgrade() {
+                        w.finish_resize();
+                    }
+                });
+
+                break;
+            }
+        });
+    }
+
+    /// Ends the series of resizes, unless another resize has happened since
+    /// the check was posted.
+    fn finish_resize(&self) {
+        let settled = {
+            let mut state = self.0.interaction.lock_unpoisoned();
+            match state.last_resize {
+                Some(last) if last.elapsed() >= RESIZE_SETTLE => {
+                    state.last_resize = None;
+                    true
+                }
+                Some(_) => false,
+                None => return,
+            }
+        };
+
+        if settled {
+            self.listener().on_resize_end(self.clone());
+        } else {
+            self.watch_resize_settle();
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Kiosk mode module.
+//!
+//! See [`crate::window::Window::set_kiosk`] for details.
+use crate::util::MutexExt;
+use crate::window::Window;
+use crate::window::WindowDecoration;
+use crate::window::WindowSignal;
+
+/// Options of kiosk mode. See [`Window::set_kiosk_with`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KioskOptions {
+    /// Whether to disable the context menu of webviews in the window.
+    pub disable_context_menu: bool,
+    /// Whether to close and disable devtools of webviews in the window.
+    pub disable_dev_tools: bool,
+    /// Whether the user may leave fullscreen, e.g. with `Esc` or `F11`.
+    pub allow_escape: bool,
+}
+
+impl Default for KioskOptions {
+    fn default() -> Self {
+        Self {
+            disable_context_menu: true,
+            disable_dev_tools: true,
+            allow_escape: false,
+        }
+    }
+}
+
+/// The kiosk mode of a window and the states to restore when leaving it.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Kiosk {
+    pub(crate) options: KioskOptions,
+    fullscreen: bool,
+    always_on_top: bool,
+    decorations: WindowDecoration,
+    resizable: bool,
+    focus_trap: bool,
+}
+
+impl Window {
+    /// Enters or leaves kiosk mode with the default [`KioskOptions`]. See
+    /// [`Self::set_kiosk_with`].
+    pub fn set_kiosk(&self, kiosk: bool) -> crate::error::Result<()> {
+        if kiosk {
+            self.set_kiosk_with(KioskOptions::default())
+        } else {
+            self.exit_kiosk()
+        }
+    }
+
+    /// Enters kiosk mode with the given options, which makes the window
+    /// fullscreen, always on top, undecorated, non-resizable and reclaim focus
+    /// (see [`Self::set_focus_trap`]), and applies the options to the
+    /// webviews in the window, including those created later. Leaving kiosk
+    /// mode restores the window as it was before.
+    ///
+    /// Unless [`KioskOptions::allow_escape`] is set, the page doesn't receive
+    /// `Esc` and `F11`, and the window returns to fullscreen if it leaves it
+    /// anyway. Closing the window is not blocked, see
+    /// [`crate::window::WindowEventListener::on_close`] for that.
+    ///
+    /// The states are read before anything changes, thus an error leaves the
+    /// window untouched.
+    pub fn set_kiosk_with(&self, options: KioskOptions) -> crate::error::Result<()> {
+        self.ensure_alive()?;
+
+        let prev = *self.0.kiosk.lock_unpoisoned();
+        let kiosk = match prev {
+            Some(k) => Kiosk { options, ..k },
+            None => Kiosk {
+                options,
+                fullscreen: self.is_fullscreen()?,
+                always_on_top: self.is_always_on_top()?,
+                decorations: self.decorations()?,
+                resizable: self.is_resizable()?,
+                focus_trap: self.has_focus_trap(),
+            },
+        };
+
+        *self.0.kiosk.lock_unpoisoned() = Some(kiosk);
+
+        self.set_decorations(WindowDecoration::None);
+        self.set_resizable(false);
+        self.set_always_on_top(true);
+        self.set_fullscreen(true);
+        self.set_focus_trap(true);
+        self.notify(WindowSignal::Kiosk);
+        Ok(())
+    }
+
+    /// Leaves kiosk mode. Does nothing if the window is not in kiosk mode.
+    fn exit_kiosk(&self) -> crate::error::Result<()> {
+        self.ensure_alive()?;
+
+        let Some(k) = self.0.kiosk.lock_unpoisoned().take() else {
+            return Ok(());
+        };
+
+        self.set_fullscreen(k.fullscreen);
+        self.set_always_on_top(k.always_on_top);
+        self.set_decorations(k.decorations);
+        self.set_resizable(k.resizable);
+        self.set_focus_trap(k.focus_trap);
+        self.notify(WindowSignal::Kiosk);
+        Ok(())
+    }
+
+    /// Gets the options of kiosk mode, or [`None`] if the window is not in
+    /// kiosk mode. See [`Self::set_kiosk_with`].
+    pub fn kiosk(&self) -> Option<KioskOptions> {
+        self.0.kiosk.lock_unpoisoned().map(|k| k.options)
+    }
+
+    /// Returns to fullscreen if the window has left it in kiosk mode.
+    pub(crate) fn enforce_kiosk(&self) {
+        let locked = self.kiosk().is_some_and(|o| !o.allow_escape);
+
+        if locked && !self.is_fullscreen().unwrap_or(true) {
+            self.set_fullscreen(true);
+        }
+    }
+}
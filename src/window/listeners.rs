@@ -0,0 +1,210 @@
+use std::panic::RefUnwindSafe;
+
+use crate::geometry::Position;
+use crate::policy::Policy;
+use crate::window::ProgressState;
+use crate::window::Window;
+use crate::window::WindowDecoration;
+use crate::window::WindowEventListener;
+
+type Listener<A, R = ()> = Option<Box<dyn Fn(Window, A) -> R + RefUnwindSafe + 'static>>;
+
+/// A [`WindowEventListener`] built from closures.
+///
+/// Events without a closure behave as the defaults of the trait, so only the
+/// events of interest need to be set:
+///
+/// ```no_run
+/// use saucers::policy::Policy;
+/// use saucers::window::WindowListeners;
+///
+/// let listeners = WindowListeners::new()
+///     .on_resize(|_, w, h| println!("Resized to {w}x{h}"))
+///     .on_close(|_| Policy::Block);
+/// ```
+///
+/// The same caveat about captured handles as for [`WindowEventListener`]
+/// applies to the closures.
+#[derive(Default)]
+pub struct WindowListeners {
+    decorated: Listener<WindowDecoration>,
+    maximize: Listener<bool>,
+    minimize: Listener<bool>,
+    closed: Listener<()>,
+    resize: Listener<(u32, u32)>,
+    resize_begin: Listener<()>,
+    resize_end: Listener<()>,
+    moved: Listener<Position>,
+    move_begin: Listener<()>,
+    move_end: Listener<()>,
+    progress: Listener<ProgressState>,
+    focus: Listener<bool>,
+    close: Listener<(), Policy>,
+}
+
+impl WindowListeners {
+    /// Creates a set of listeners doing nothing.
+    pub fn new() -> Self { Self::default() }
+
+    /// Sets the closure for [`WindowEventListener::on_decorated`].
+    pub fn on_decorated(
+        mut self,
+        f: impl Fn(Window, WindowDecoration) + RefUnwindSafe + 'static,
+    ) -> Self {
+        self.decorated = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the closure for [`WindowEventListener::on_maximize`].
+    pub fn on_maximize(mut self, f: impl Fn(Window, bool) + RefUnwindSafe + 'static) -> Self {
+        self.maximize = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the closure for [`WindowEventListener::on_minimize`].
+    pub fn on_minimize(mut self, f: impl Fn(Window, bool) + RefUnwindSafe + 'static) -> Self {
+        self.minimize = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the closure for [`WindowEventListener::on_closed`].
+    pub fn on_closed(mut self, f: impl Fn(Window) + RefUnwindSafe + 'static) -> Self {
+        self.closed = Some(Box::new(move |w, ()| f(w)));
+        self
+    }
+
+    /// Sets the closure for [`WindowEventListener::on_resize`].
+    pub fn on_resize(mut self, f: impl Fn(Window, u32, u32) + RefUnwindSafe + 'static) -> Self {
+        self.resize = Some(Box::new(move |win, (w, h)| f(win, w, h)));
+        self
+    }
+
+    /// Sets the closure for [`WindowEventListener::on_resize_begin`].
+    pub fn on_resize_begin(mut self, f: impl Fn(Window) + RefUnwindSafe + 'static) -> Self {
+        self.resize_begin = Some(Box::new(move |w, ()| f(w)));
+        self
+    }
+
+    /// Sets the closure for [`WindowEventListener::on_resize_end`].
+    pub fn on_resize_end(mut self, f: impl Fn(Window) + RefUnwindSafe + 'static) -> Self {
+        self.resize_end = Some(Box::new(move |w, ()| f(w)));
+        self
+    }
+
+    /// Sets the closure for [`WindowEventListener::on_moved`].
+    pub fn on_moved(mut self, f: impl Fn(Window, Position) + RefUnwindSafe + 'static) -> Self {
+        self.moved = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the closure for [`WindowEventListener::on_move_begin`].
+    pub fn on_move_begin(mut self, f: impl Fn(Window) + RefUnwindSafe + 'static) -> Self {
+        self.move_begin = Some(Box::new(move |w, ()| f(w)));
+        self
+    }
+
+    /// Sets the closure for [`WindowEventListener::on_move_end`].
+    pub fn on_move_end(mut self, f: impl Fn(Window) + RefUnwindSafe + 'static) -> Self {
+        self.move_end = Some(Box::new(move |w, ()| f(w)));
+        self
+    }
+
+    /// Sets the closure for [`WindowEventListener::on_progress`].
+    pub fn on_progress(
+        mut self,
+        f: impl Fn(Window, ProgressState) + RefUnwindSafe + 'static,
+    ) -> Self {
+        self.progress = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the closure for [`WindowEventListener::on_focus`].
+    pub fn on_focus(mut self, f: impl Fn(Window, bool) + RefUnwindSafe + 'static) -> Self {
+        self.focus = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the closure for [`WindowEventListener::on_close`].
+    pub fn on_close(mut self, f: impl Fn(Window) -> Policy + RefUnwindSafe + 'static) -> Self {
+        self.close = Some(Box::new(move |w, ()| f(w)));
+        self
+    }
+}
+
+impl WindowEventListener for WindowListeners {
+    fn on_decorated(&self, window: Window, decoration: WindowDecoration) {
+        if let Some(f) = &self.decorated {
+            f(window, decoration)
+        }
+    }
+
+    fn on_maximize(&self, window: Window, maximized: bool) {
+        if let Some(f) = &self.maximize {
+            f(window, maximized)
+        }
+    }
+
+    fn on_minimize(&self, window: Window, minimized: bool) {
+        if let Some(f) = &self.minimize {
+            f(window, minimized)
+        }
+    }
+
+    fn on_closed(&self, window: Window) {
+        if let Some(f) = &self.closed {
+            f(window, ())
+        }
+    }
+
+    fn on_resize(&self, window: Window, width: u32, height: u32) {
+        if let Some(f) = &self.resize {
+            f(window, (width, height))
+        }
+    }
+
+    fn on_resize_begin(&self, window: Window) {
+        if let Some(f) = &self.resize_begin {
+            f(window, ())
+        }
+    }
+
+    fn on_resize_end(&self, window: Window) {
+        if let Some(f) = &self.resize_end {
+            f(window, ())
+        }
+    }
+
+    fn on_moved(&self, window: Window, position: Position) {
+        if let Some(f) = &self.moved {
+            f(window, position)
+        }
+    }
+
+    fn on_move_begin(&self, window: Window) {
+        if let Some(f) = &self.move_begin {
+            f(window, ())
+        }
+    }
+
+    fn on_move_end(&self, window: Window) {
+        if let Some(f) = &self.move_end {
+            f(window, ())
+        }
+    }
+
+    fn on_progress(&self, window: Window, progress: ProgressState) {
+        if let Some(f) = &self.progress {
+            f(window, progress)
+        }
+    }
+
+    fn on_focus(&self, window: Window, focused: bool) {
+        if let Some(f) = &self.focus {
+            f(window, focused)
+        }
+    }
+
+    fn on_close(&self, window: Window) -> Policy {
+        self.close.as_ref().map_or(Policy::Allow, |f| f(window, ()))
+    }
+}
@@ -0,0 +1,57 @@
+//! Window menu module.
+//!
+//! See [`crate::window::Window::set_menu_accelerators`] for details.
+use crate::menu::Menu;
+use crate::menu::MenuItemId;
+use crate::util::MutexExt;
+use crate::webview::EditCommand;
+use crate::window::Window;
+use crate::window::WindowSignal;
+
+impl Window {
+    /// Binds accelerators of the items of the menu to the webviews in the
+    /// window. Passing [`None`] unbinds them. The menu is not drawn natively,
+    /// but can be rendered in the page from [`Menu::items`].
+    ///
+    /// Accelerators work while a webview in the window is focused, and invoke
+    /// the callbacks as clicks would. Items can also be activated with
+    /// [`Self::activate_menu_item`], e.g. from a menu rendered in the page.
+    /// Editing items (see [`crate::menu::MenuBuilder::edit_item`]) are left to
+    /// the page, which handles their shortcuts natively.
+    ///
+    /// Only key presses of the user activate items. Pages can't activate
+    /// items on their own, nor those without accelerators.
+    pub fn set_menu_accelerators(&self, menu: Option<Menu>) {
+        *self.0.menu.lock_unpoisoned() = menu;
+        self.notify(WindowSignal::Menu);
+    }
+
+    /// Gets the menu bound to the window. See [`Self::set_menu_accelerators`].
+    pub fn menu(&self) -> Option<Menu> { self.0.menu.lock_unpoisoned().clone() }
+
+    /// Activates the item of the menu with the given ID as if it's clicked,
+    /// toggling it if checkable and invoking its callback. Returns whether the
+    /// item exists and is enabled.
+    pub fn activate_menu_item(&self, id: MenuItemId) -> bool {
+        // Callbacks may change the menu
+        let menu = self.menu();
+        menu.is_some_and(|m| m.activate(self, id))
+    }
+
+    /// Activates the item with the given ID for an accelerator pressed in a
+    /// page. Items not bound to accelerators are ignored, as the request
+    /// comes from the page.
+    pub(crate) fn activate_accelerator(&self, id: MenuItemId) -> bool {
+        let menu = self.menu();
+        let bound = menu
+            .as_ref()
+            .and_then(|m| m.find(id))
+            .is_some_and(|a| a.accelerator().is_some() && a.edit_command().is_none());
+
+        bound && menu.is_some_and(|m| m.activate(self, id))
+    }
+
+    /// Runs an editing command in the focused webview of the window. See
+    /// [`crate::webview::Webview::exec_command`].
+    pub fn exec_command(&self, command: EditCommand) { self.notify(WindowSignal::Edit(command)); }
+}
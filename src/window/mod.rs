@@ -1,36 +1,144 @@
+mod attention;
+mod constraints;
+mod cursor;
 mod decoration;
 mod edge;
+mod effect;
 mod events;
+mod fullscreen;
+mod interaction;
+mod kiosk;
+mod listeners;
+mod menu;
+mod native;
+mod owner;
+mod progress;
+mod taskbar;
 
 use std::ffi::c_char;
 use std::ffi::c_void;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::ptr::NonNull;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::sync::Weak;
-use std::sync::mpsc::Sender;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::thread::ThreadId;
 
+pub use attention::AttentionType;
+pub use cursor::CursorIcon;
 pub use decoration::*;
+pub use edge::*;
+pub use effect::*;
 pub use events::*;
+pub use fullscreen::FullscreenMode;
+pub use kiosk::KioskOptions;
+pub use listeners::*;
+pub(crate) use native::read_native;
+pub use progress::Progress;
+pub use progress::ProgressState;
 use saucer_sys::*;
 
 use crate::app::App;
+use crate::app::AppRef;
+use crate::app::HandleKind;
 use crate::cleanup::CleanUpHolder;
+use crate::cleanup::DropSender;
+use crate::geometry::Position;
+use crate::geometry::Rect;
+use crate::geometry::Size;
 use crate::icon::Icon;
 use crate::macros::ffi_forward;
 use crate::macros::load_range;
 use crate::macros::use_string;
+use crate::menu::Menu;
 use crate::policy::Policy;
 use crate::screen::Screen;
+use crate::thread::check_event_thread;
+use crate::util::MutexExt;
 use crate::util::ffi_callback;
-use crate::window::edge::WindowEdge;
+use crate::webview::EditCommand;
+use crate::webview::MemoryPressure;
+use crate::webview::Webview;
+use crate::webview::WebviewRef;
+use crate::window::constraints::SizeConstraints;
+use crate::window::cursor::CursorState;
+use crate::window::fullscreen::BorderlessRestore;
+use crate::window::interaction::Interaction;
+use crate::window::kiosk::Kiosk;
+use crate::window::progress::WindowProgress;
 
 /// An unprotected owned window handle.
 struct RawWindow {
     inner: NonNull<saucer_window>,
-    drop_sender: Sender<CleanUpHolder>,
+    id: u64,
+    drop_sender: DropSender,
     host_tid: ThreadId,
+    app: AppRef,
     event_listener_data: *mut EventListenerData,
+    /// Set once the closed event fires. Native state may be freed afterwards.
+    closed: AtomicBool,
+    relations: Mutex<WindowRelations>,
+    cached: Mutex<CachedWindowState>,
+    /// Whether the window reclaims focus when losing it.
+    focus_trap: AtomicBool,
+    observers: Mutex<Vec<WindowObserver>>,
+    constraints: Mutex<SizeConstraints>,
+    interaction: Mutex<Interaction>,
+    cursor: Mutex<CursorState>,
+    progress: Mutex<WindowProgress>,
+    /// Set while in borderless fullscreen.
+    borderless: Mutex<Option<BorderlessRestore>>,
+    kiosk: Mutex<Option<Kiosk>>,
+    menu: Mutex<Option<Menu>>,
+    /// Webviews in the window, in the order they're created.
+    webviews: Mutex<Vec<WebviewRef>>,
+}
+
+/// A change of window states, reported to observers added with
+/// [`Window::observe`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum WindowSignal {
+    Focus(bool),
+    Resize(Size),
+    Maximize(bool),
+    Minimize(bool),
+    /// The cursor has changed, see [`Window::cursor_override`].
+    Cursor,
+    /// The progress has changed, see [`Window::progress_state`].
+    Progress,
+    /// Kiosk mode has been entered, changed or left, see [`Window::kiosk`].
+    Kiosk,
+    /// The menu has been bound or unbound, see [`Window::menu`].
+    Menu,
+    /// An editing command is to be run in the focused webview, see
+    /// [`Window::exec_command`].
+    Edit(EditCommand),
+}
+
+/// Observes window states inside the bindings, without taking the place of the
+/// event listener. Returns whether to keep observing.
+pub(crate) type WindowObserver = Box<dyn Fn(WindowSignal) -> bool + Send + Sync>;
+
+/// A snapshot of window states, maintained from events. See
+/// [`Window::cached`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CachedWindowState {
+    pub size: Size,
+    pub is_maximized: bool,
+    pub is_minimized: bool,
+    pub is_focused: bool,
+    pub is_closed: bool,
+}
+
+/// Parent-child relationships between windows, maintained by the bindings.
+#[derive(Default)]
+struct WindowRelations {
+    parent: Option<WindowRef>,
+    children: Vec<WindowRef>,
+    modal: bool,
 }
 
 unsafe impl Send for RawWindow {}
@@ -44,10 +152,10 @@ impl Drop for RawWindow {
         };
 
         if self.is_thread_safe() {
-            unsafe { cleanup.discard() }; // SAFETY: On the event thread
+            unsafe { self.drop_sender.discard(self.id, cleanup) }; // SAFETY: On the event thread
         } else {
             self.drop_sender
-                .send(cleanup)
+                .send(self.id, cleanup)
                 .expect("failed to post window destruction");
         }
     }
@@ -67,27 +175,51 @@ impl RawWindow {
 #[derive(Clone)]
 pub struct Window(Arc<RawWindow>);
 
+/// Handles are equal if they refer to the same window.
+impl PartialEq for Window {
+    fn eq(&self, other: &Self) -> bool { Arc::ptr_eq(&self.0, &other.0) }
+}
+
+impl Eq for Window {}
+
+impl Hash for Window {
+    fn hash<H: Hasher>(&self, state: &mut H) { self.id().hash(state) }
+}
+
+/// An identifier of a window, obtained with [`Window::id`].
+///
+/// IDs are unique in the process and never reused. They're the same as the
+/// IDs listed by [`crate::app::App::live_handles`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WindowId(u64);
+
+impl WindowId {
+    /// Gets the raw value of the ID.
+    pub fn get(self) -> u64 { self.0 }
+}
+
 impl Window {
     ffi_forward! {
         /// Checks whether the window is visible.
-        pub fn is_visible(&Self) -> bool => saucer_window_visible;
+        pub fn is_visible(&Self) -> crate::error::Result<bool> => saucer_window_visible;
         /// Checks whether the window is focused.
-        pub fn is_focused(&Self) -> bool => saucer_window_focused;
+        pub fn is_focused(&Self) -> crate::error::Result<bool> => saucer_window_focused;
         /// Checks whether the window is maximized.
-        pub fn is_maximized(&Self) -> bool => saucer_window_maximized;
+        pub fn is_maximized(&Self) -> crate::error::Result<bool> => saucer_window_maximized;
         /// Checks whether the window is minimized.
-        pub fn is_minimized(&Self) -> bool => saucer_window_minimized;
+        pub fn is_minimized(&Self) -> crate::error::Result<bool> => saucer_window_minimized;
         /// Checks whether the window is resizable.
-        pub fn is_resizable(&Self) -> bool => saucer_window_resizable;
+        pub fn is_resizable(&Self) -> crate::error::Result<bool> => saucer_window_resizable;
         /// Checks whether the window is fullscreen.
-        pub fn is_fullscreen(&Self) -> bool => saucer_window_fullscreen;
+        pub fn is_fullscreen(&Self) -> crate::error::Result<bool> => saucer_window_fullscreen;
         /// Checks whether the window is always on top.
-        pub fn is_always_on_top(&Self) -> bool => saucer_window_always_on_top;
+        pub fn is_always_on_top(&Self) -> crate::error::Result<bool> => saucer_window_always_on_top;
         /// Checks whether the window is click-through.
-        pub fn is_click_through(&Self) -> bool => saucer_window_click_through;
+        pub fn is_click_through(&Self) -> crate::error::Result<bool> => saucer_window_click_through;
     }
 
     ffi_forward! {
+        @alive
         /// Hides the window.
         pub fn hide(&Self) => saucer_window_hide;
         /// Shows the window.
@@ -101,13 +233,15 @@ impl Window {
     }
 
     ffi_forward! {
+        @alive
         /// Toggles window maximization.
         pub fn set_maximized(&Self, maximized: bool) => saucer_window_set_maximized;
         /// Toggles window minimization.
         pub fn set_minimized(&Self, minimized: bool) => saucer_window_set_minimized;
         /// Toggles window resizability.
         pub fn set_resizable(&Self, resizable: bool) => saucer_window_set_resizable;
-        /// Toggles window fullscreen.
+        /// Toggles window fullscreen on the screen the window is on. See
+        /// [`Self::set_fullscreen_on`] for choosing the screen and mode.
         pub fn set_fullscreen(&Self, fullscreen: bool) => saucer_window_set_fullscreen;
         /// Sets whether the window is always on top.
         pub fn set_always_on_top(&Self, always_on_top: bool) => saucer_window_set_always_on_top;
@@ -122,31 +256,57 @@ impl Window {
     ///
     /// # Panics
     ///
-    /// Panics if not called on the event thread.
+    /// Panics if not called on the event thread, unless allowed by the
+    /// [`crate::thread::ThreadViolationPolicy`].
     pub fn new(
         app: &App,
         event_listener: impl WindowEventListener + 'static,
     ) -> crate::error::Result<Self> {
-        if !app.is_thread_safe() {
-            panic!("windows must be created on the event thread");
+        if check_event_thread(app.is_thread_safe(), "creating windows").is_some() {
+            return Err(crate::error::Error::WrongThread);
         }
 
         let mut ex = -1;
         let ptr = unsafe { saucer_window_new(app.as_ptr(), &raw mut ex) };
 
         let wnd = NonNull::new(ptr).ok_or(crate::error::Error::Saucer(ex))?;
+        let ds = app.drop_sender();
+        let id = ds.track(HandleKind::Window);
 
         let wnd = Self(Arc::new_cyclic(|weak| RawWindow {
             inner: wnd,
-            drop_sender: app.drop_sender(),
+            id,
+            drop_sender: ds,
             host_tid: std::thread::current().id(),
+            app: app.downgrade(),
             event_listener_data: Box::into_raw(Box::new(EventListenerData::new(
                 event_listener,
                 WindowRef(weak.clone()),
             ))),
+            closed: AtomicBool::new(false),
+            relations: Mutex::new(WindowRelations::default()),
+            cached: Mutex::new(CachedWindowState::default()),
+            focus_trap: AtomicBool::new(false),
+            observers: Mutex::new(Vec::new()),
+            constraints: Mutex::new(SizeConstraints::default()),
+            interaction: Mutex::new(Interaction::default()),
+            cursor: Mutex::new(CursorState::new()),
+            progress: Mutex::new(WindowProgress::default()),
+            borderless: Mutex::new(None),
+            kiosk: Mutex::new(None),
+            menu: Mutex::new(None),
+            webviews: Mutex::new(Vec::new()),
         }));
         let data = wnd.0.event_listener_data;
 
+        *wnd.0.cached.lock_unpoisoned() = CachedWindowState {
+            size: wnd.size()?,
+            is_maximized: wnd.is_maximized()?,
+            is_minimized: wnd.is_minimized()?,
+            is_focused: wnd.is_focused()?,
+            is_closed: false,
+        };
+
         macro_rules! bind_event {
             ($ev:expr, $cb:expr) => {
                 unsafe {
@@ -163,23 +323,53 @@ impl Window {
         bind_event!(SAUCER_WINDOW_EVENT_FOCUS, ev_on_focus_tp);
         bind_event!(SAUCER_WINDOW_EVENT_CLOSE, ev_on_close_tp);
 
+        if let Some(icon) = app.metadata().icon {
+            wnd.set_icon(icon);
+        }
+
         Ok(wnd)
     }
 
     /// Checks we're on the event thread.
     pub fn is_thread_safe(&self) -> bool { self.0.is_thread_safe() }
 
+    /// Checks whether the window is still alive, i.e. has not been closed.
+    ///
+    /// State getters return [`crate::error::Error::Closed`] once the window is
+    /// closed, as the native state may have been freed. Setters and actions
+    /// (e.g. [`Self::set_size`], [`Self::focus`]) do nothing then.
+    pub fn is_alive(&self) -> bool { !self.0.closed.load(Ordering::Acquire) }
+
+    /// Gets a snapshot of the window states as last reported by events.
+    ///
+    /// Unlike the getters (e.g. [`Self::size`]), this doesn't query the
+    /// backend and stays available after the window is closed, thus it's cheap
+    /// and safe to call from any thread (e.g. render loops).
+    pub fn cached(&self) -> CachedWindowState { *self.0.cached.lock_unpoisoned() }
+
+    pub(crate) fn ensure_alive(&self) -> crate::error::Result<()> {
+        if self.is_alive() {
+            Ok(())
+        } else {
+            Err(crate::error::Error::Closed)
+        }
+    }
+
     /// Gets the window title.
-    pub fn title(&self) -> String {
+    pub fn title(&self) -> crate::error::Result<String> {
+        self.ensure_alive()?;
+
         let st = load_range!(ptr[size] = 0u8; {
             unsafe { saucer_window_title(self.as_ptr(), ptr as *mut c_char, size) };
         });
 
-        String::from_utf8_lossy(&st).into_owned()
+        Ok(String::from_utf8_lossy(&st).into_owned())
     }
 
     /// Gets the window background color.
-    pub fn background(&self) -> (u8, u8, u8, u8) {
+    pub fn background(&self) -> crate::error::Result<(u8, u8, u8, u8)> {
+        self.ensure_alive()?;
+
         let mut r = 0;
         let mut g = 0;
         let mut b = 0;
@@ -193,65 +383,89 @@ impl Window {
                 &raw mut a,
             )
         };
-        (r, g, b, a)
+        Ok((r, g, b, a))
     }
 
     /// Gets the window decoration status.
-    pub fn decorations(&self) -> WindowDecoration {
-        unsafe { saucer_window_decorations(self.as_ptr()) as saucer_window_decoration }.into()
+    pub fn decorations(&self) -> crate::error::Result<WindowDecoration> {
+        self.ensure_alive()?;
+        let dec = unsafe { saucer_window_decorations(self.as_ptr()) as saucer_window_decoration };
+        Ok(dec.into())
     }
 
     /// Gets the window size.
-    pub fn size(&self) -> (i32, i32) {
-        let mut x = 0;
-        let mut y = 0;
-        unsafe { saucer_window_size(self.as_ptr(), &raw mut x, &raw mut y) };
+    pub fn size(&self) -> crate::error::Result<Size> {
+        self.ensure_alive()?;
+        let mut w = 0;
+        let mut h = 0;
+        unsafe { saucer_window_size(self.as_ptr(), &raw mut w, &raw mut h) };
 
-        (x, y)
+        Ok(Size::new(w, h))
     }
 
     /// Gets the window maximum size.
-    pub fn max_size(&self) -> (i32, i32) {
-        let mut x = 0;
-        let mut y = 0;
-        unsafe { saucer_window_max_size(self.as_ptr(), &raw mut x, &raw mut y) };
-        (x, y)
+    pub fn max_size(&self) -> crate::error::Result<Size> {
+        self.ensure_alive()?;
+        let mut w = 0;
+        let mut h = 0;
+        unsafe { saucer_window_max_size(self.as_ptr(), &raw mut w, &raw mut h) };
+        Ok(Size::new(w, h))
     }
 
     /// Gets the window minimum size.
-    pub fn min_size(&self) -> (i32, i32) {
-        let mut x = 0;
-        let mut y = 0;
-        unsafe { saucer_window_min_size(self.as_ptr(), &raw mut x, &raw mut y) };
-        (x, y)
+    pub fn min_size(&self) -> crate::error::Result<Size> {
+        self.ensure_alive()?;
+        let mut w = 0;
+        let mut h = 0;
+        unsafe { saucer_window_min_size(self.as_ptr(), &raw mut w, &raw mut h) };
+        Ok(Size::new(w, h))
     }
 
     /// Gets the window position.
-    pub fn position(&self) -> (i32, i32) {
+    pub fn position(&self) -> crate::error::Result<Position> {
+        self.ensure_alive()?;
         let mut x = 0;
         let mut y = 0;
         unsafe { saucer_window_position(self.as_ptr(), &raw mut x, &raw mut y) };
-        (x, y)
+        Ok(Position::new(x, y))
+    }
+
+    /// Gets the window position and size.
+    pub fn bounds(&self) -> crate::error::Result<Rect> {
+        Ok(Rect::new(self.position()?, self.size()?))
     }
 
     /// Gets the screen this window is on. Returns [`None`] if the screen can't
     /// be determined.
-    pub fn screen(&self) -> Option<Screen> {
-        unsafe { Screen::from_raw(saucer_window_screen(self.as_ptr())) }
+    pub fn screen(&self) -> crate::error::Result<Option<Screen>> {
+        self.ensure_alive()?;
+        Ok(unsafe { Screen::from_raw(saucer_window_screen(self.as_ptr())) })
     }
 
     /// Starts a resize operation on the given edge.
     pub fn start_resize(&self, edge: WindowEdge) {
+        if !self.is_alive() {
+            return;
+        }
+
         unsafe { saucer_window_start_resize(self.as_ptr(), edge.into()) }
     }
 
     /// Sets the window icon.
     pub fn set_icon(&self, icon: impl AsRef<Icon>) {
+        if !self.is_alive() {
+            return;
+        }
+
         unsafe { saucer_window_set_icon(self.as_ptr(), icon.as_ref().as_ptr()) }
     }
 
     /// Sets the window title.
     pub fn set_title(&self, title: impl Into<Vec<u8>>) {
+        if !self.is_alive() {
+            return;
+        }
+
         use_string!(
             t: title;
             unsafe { saucer_window_set_title(self.as_ptr(), t) }
@@ -260,40 +474,392 @@ impl Window {
 
     /// Sets the window background color.
     pub fn set_background(&self, color: (u8, u8, u8, u8)) {
+        if !self.is_alive() {
+            return;
+        }
+
         unsafe { saucer_window_set_background(self.as_ptr(), color.0, color.1, color.2, color.3) }
     }
 
+    /// Sets the parent window, or detaches this window from its parent if
+    /// [`None`] is given. The window is centered over the new parent. Fails
+    /// with [`crate::error::Error::ParentCycle`] if the parent is this window
+    /// or one of its descendants.
+    ///
+    /// The window is owned by the parent natively (an owned window on
+    /// WebView2, a child window on macOS and a transient window on GTK),
+    /// which keeps it above the parent. Qt doesn't expose ownership. The
+    /// bindings also minimize and restore child windows with their parent
+    /// and close them when the parent closes. See [`Self::set_modal`] for
+    /// modal windows.
+    pub fn set_parent(&self, parent: Option<&Window>) -> crate::error::Result<()> {
+        if let Some(parent) = parent {
+            let mut ancestor = Some(parent.clone());
+            while let Some(a) = ancestor {
+                if a == *self {
+                    return Err(crate::error::Error::ParentCycle);
+                }
+                ancestor = a.parent();
+            }
+        }
+
+        let old = self
+            .0
+            .relations
+            .lock_unpoisoned()
+            .parent
+            .take()
+            .and_then(|p| p.upgrade());
+
+        if let Some(old) = &old {
+            old.0
+                .relations
+                .lock_unpoisoned()
+                .children
+                .retain(|c| !Weak::ptr_eq(&c.0, &Arc::downgrade(&self.0)));
+        }
+
+        let _ = owner::set_native_owner(self, old.as_ref(), parent); // Kept by the bindings only otherwise
+
+        if let Some(parent) = parent {
+            parent
+                .0
+                .relations
+                .lock_unpoisoned()
+                .children
+                .push(self.downgrade());
+            self.0.relations.lock_unpoisoned().parent = Some(parent.downgrade());
+            self.center_on(parent)?;
+        }
+
+        Ok(())
+    }
+
+    /// Gets the parent window.
+    pub fn parent(&self) -> Option<Window> {
+        self.0
+            .relations
+            .lock_unpoisoned()
+            .parent
+            .as_ref()?
+            .upgrade()
+    }
+
+    /// Sets whether this window is modal to its parent. When a visible modal
+    /// window exists, focusing its parent focuses the modal window instead,
+    /// which approximates blocking input to the parent.
+    pub fn set_modal(&self, modal: bool) { self.0.relations.lock_unpoisoned().modal = modal; }
+
+    /// Checks whether this window is modal to its parent.
+    pub fn is_modal(&self) -> bool { self.0.relations.lock_unpoisoned().modal }
+
+    /// Sets whether the window reclaims focus whenever it loses it to other
+    /// apps, e.g. for kiosk deployments.
+    ///
+    /// None of the backends can intercept system shortcuts (e.g. Alt+Tab, the
+    /// Windows key or edge gestures), thus they still work, but the window
+    /// brings itself back once it loses focus. Focus moving to child windows
+    /// is not affected. Minimized and hidden windows don't reclaim focus. For
+    /// the best effect, combine this with [`Self::set_fullscreen`] and
+    /// [`Self::set_always_on_top`], and confine focus within the page with
+    /// [`crate::webview::Webview::set_focus_trap`].
+    pub fn set_focus_trap(&self, enabled: bool) {
+        self.0.focus_trap.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Checks whether the window reclaims focus. See [`Self::set_focus_trap`].
+    pub fn has_focus_trap(&self) -> bool { self.0.focus_trap.load(Ordering::Relaxed) }
+
+    /// Gets the event listener given at creation.
+    fn listener(&self) -> &dyn WindowEventListener {
+        // SAFETY: The data is freed only after the window is dropped
+        unsafe { (*self.0.event_listener_data).listener.as_ref() }
+    }
+
+    /// Gets the webviews in the window, in the order they're stacked, from the
+    /// bottom to the top. See [`Webview::set_bounds`] for placing multiple
+    /// webviews.
+    pub fn webviews(&self) -> Vec<Webview> {
+        let mut webviews = Vec::new();
+
+        self.0.webviews.lock_unpoisoned().retain(|w| {
+            let webview = w.upgrade();
+            let alive = webview.is_some();
+            webviews.extend(webview);
+            alive
+        });
+
+        webviews
+    }
+
+    /// Sends a memory-pressure hint to all webviews in the window. See
+    /// [`Webview::send_memory_pressure`].
+    pub fn send_memory_pressure(&self, level: MemoryPressure) {
+        for w in self.webviews() {
+            w.send_memory_pressure(level);
+        }
+    }
+
+    /// Records a webview created in the window.
+    pub(crate) fn attach_webview(&self, webview: WebviewRef) {
+        self.0.webviews.lock_unpoisoned().push(webview);
+    }
+
+    /// Adds an observer of window states.
+    pub(crate) fn observe(&self, observer: impl Fn(WindowSignal) -> bool + Send + Sync + 'static) {
+        self.0.observers.lock_unpoisoned().push(Box::new(observer));
+    }
+
+    /// Reports the signal to observers, removing those no longer interested.
+    fn notify(&self, signal: WindowSignal) {
+        self.0.observers.lock_unpoisoned().retain(|o| o(signal));
+    }
+
+    /// Focuses the window again after losing it, unless the focus has moved
+    /// to a child window.
+    fn reclaim_focus(&self) {
+        let cached = self.cached();
+        if !self.has_focus_trap() || cached.is_focused || cached.is_minimized || cached.is_closed {
+            return;
+        }
+
+        if !self.is_visible().unwrap_or(false)
+            || self.children().iter().any(|c| c.cached().is_focused)
+        {
+            return;
+        }
+
+        self.focus();
+    }
+
+    /// Moves this window to the center of the given window.
+    pub fn center_on(&self, other: &Window) -> crate::error::Result<()> {
+        let rect = other.bounds()?.centered(self.size()?);
+        self.set_position(rect.pos);
+        Ok(())
+    }
+
+    /// Gets alive child windows.
+    fn children(&self) -> Vec<Window> {
+        let mut relations = self.0.relations.lock_unpoisoned();
+        relations
+            .children
+            .retain(|c| c.upgrade().is_some_and(|c| c.is_alive()));
+        relations
+            .children
+            .iter()
+            .filter_map(WindowRef::upgrade)
+            .collect()
+    }
+
+    /// Applies a background effect.
+    ///
+    /// Only [`WindowEffect::None`] and [`WindowEffect::Transparent`] are
+    /// supported, which change the alpha of the background color. Material
+    /// effects need native support from saucer and fail with
+    /// [`crate::error::Error::Unsupported`] for now. To see through the page,
+    /// the webview background must be transparent too (see
+    /// [`crate::webview::WebviewOptions::transparent`]).
+    pub fn set_effect(&self, effect: WindowEffect) -> crate::error::Result<()> {
+        let (r, g, b, _) = self.background()?;
+
+        match effect {
+            WindowEffect::None => self.set_background((r, g, b, 255)),
+            WindowEffect::Transparent => self.set_background((r, g, b, 0)),
+            WindowEffect::Acrylic | WindowEffect::Mica | WindowEffect::Vibrancy => {
+                return Err(crate::error::Error::unsupported(
+                    "material window effects",
+                    Some("use WindowEffect::Transparent with a translucent page background"),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Sets the window decoration status.
     pub fn set_decorations(&self, dec: WindowDecoration) {
+        if !self.is_alive() {
+            return;
+        }
+
         unsafe { saucer_window_set_decorations(self.as_ptr(), dec.into()) }
     }
 
+    /// Gets the decoration parts currently applied to the window.
+    pub fn decoration_parts(&self) -> crate::error::Result<DecorationParts> {
+        let parts = DecorationParts::from(self.decorations()?);
+
+        if parts.contains(DecorationParts::TITLEBAR) {
+            Ok(parts - decoration::disabled_buttons(self))
+        } else {
+            Ok(parts)
+        }
+    }
+
+    /// Sets the window decoration using individual parts.
+    ///
+    /// The parts are mapped to the closest [`WindowDecoration`] supported by
+    /// the backend, then titlebar buttons missing from the set are disabled
+    /// (see [`DecorationParts`]). Returns the parts that have actually been
+    /// applied, which may differ from the requested ones.
+    ///
+    /// Returns [`crate::error::Error::Unsupported`] without changing anything
+    /// if buttons are to be disabled where the backend can't do so.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, unless allowed by the
+    /// [`crate::thread::ThreadViolationPolicy`].
+    pub fn set_decoration_parts(
+        &self,
+        parts: DecorationParts,
+    ) -> crate::error::Result<DecorationParts> {
+        if check_event_thread(self.is_thread_safe(), "setting decoration parts").is_some() {
+            return Err(crate::error::Error::WrongThread);
+        }
+
+        decoration::check_buttons(parts)?;
+
+        let level = parts.closest();
+        self.set_decorations(level);
+
+        if level == WindowDecoration::Full {
+            decoration::set_buttons(self, parts)?;
+        }
+
+        self.decoration_parts()
+    }
+
     /// Sets the window size.
-    pub fn set_size(&self, size: (i32, i32)) {
-        unsafe { saucer_window_set_size(self.as_ptr(), size.0, size.1) }
+    pub fn set_size(&self, size: impl Into<Size>) {
+        if !self.is_alive() {
+            return;
+        }
+
+        let size = size.into();
+        unsafe { saucer_window_set_size(self.as_ptr(), size.width, size.height) }
     }
 
     /// Sets the window maximum size.
-    pub fn set_max_size(&self, size: (i32, i32)) {
-        unsafe { saucer_window_set_max_size(self.as_ptr(), size.0, size.1) }
+    pub fn set_max_size(&self, size: impl Into<Size>) {
+        if !self.is_alive() {
+            return;
+        }
+
+        let size = size.into();
+        unsafe { saucer_window_set_max_size(self.as_ptr(), size.width, size.height) }
     }
 
     /// Sets the window minimum size.
-    pub fn set_min_size(&self, size: (i32, i32)) {
-        unsafe { saucer_window_set_min_size(self.as_ptr(), size.0, size.1) }
+    pub fn set_min_size(&self, size: impl Into<Size>) {
+        if !self.is_alive() {
+            return;
+        }
+
+        let size = size.into();
+        unsafe { saucer_window_set_min_size(self.as_ptr(), size.width, size.height) }
     }
 
     /// Sets the window position.
-    pub fn set_position(&self, pos: (i32, i32)) {
-        unsafe { saucer_window_set_position(self.as_ptr(), pos.0, pos.1) }
+    pub fn set_position(&self, pos: impl Into<Position>) {
+        if !self.is_alive() {
+            return;
+        }
+
+        let pos = pos.into();
+        unsafe { saucer_window_set_position(self.as_ptr(), pos.x, pos.y) }
     }
 
+    /// Sets the window position and size.
+    pub fn set_bounds(&self, bounds: Rect) {
+        self.set_position(bounds.pos);
+        self.set_size(bounds.size);
+    }
+
+    /// Keeps the ratio of width to height when the window is resized. Passing
+    /// [`None`] removes the constraint.
+    ///
+    /// The window is constrained while being dragged on WebView2 (via
+    /// `WM_SIZING`) and on macOS (unless combined with
+    /// [`Self::set_size_increment`]). Elsewhere, the bindings correct the size
+    /// after each resize, following the dimension that changed more, thus the
+    /// window may briefly show the unconstrained size. Minimum and maximum
+    /// sizes are not considered.
+    pub fn set_aspect_ratio(&self, ratio: Option<f64>) {
+        self.0.constraints.lock_unpoisoned().aspect_ratio = ratio;
+        self.update_constraints();
+    }
+
+    /// Gets the aspect ratio set with [`Self::set_aspect_ratio`].
+    pub fn aspect_ratio(&self) -> Option<f64> { self.0.constraints.lock_unpoisoned().aspect_ratio }
+
+    /// Limits resizing to steps of the given width and height, counted from the
+    /// minimum size (e.g. the size of a character cell for terminals). Passing
+    /// `0` or `1` removes the constraint of a dimension. Increments apply
+    /// after the aspect ratio.
+    ///
+    /// Like [`Self::set_aspect_ratio`], this is enforced natively on WebView2
+    /// and macOS, and by the bindings after each resize elsewhere.
+    pub fn set_size_increment(&self, width: i32, height: i32) {
+        self.0.constraints.lock_unpoisoned().increment = Size::new(width, height);
+        self.update_constraints();
+    }
+
+    /// Gets the increments set with [`Self::set_size_increment`].
+    pub fn size_increment(&self) -> Size { self.0.constraints.lock_unpoisoned().increment }
+
+    /// Passes changed constraints to the backend and applies them to the
+    /// current size.
+    fn update_constraints(&self) {
+        let constraints = *self.0.constraints.lock_unpoisoned();
+        let native = constraints::apply_native(self, constraints);
+        self.0.constraints.lock_unpoisoned().native = native;
+
+        // Native constraints only apply to later resizes
+        let size = self.cached().size;
+        self.correct_size(size, constraints);
+    }
+
+    /// Resizes the window if its size violates the constraints, with `prev`
+    /// being the size before the last resize. Does nothing if the backend
+    /// enforces them.
+    fn enforce_constraints(&self, prev: Size) {
+        let constraints = *self.0.constraints.lock_unpoisoned();
+        if constraints.native {
+            return;
+        }
+
+        self.correct_size(prev, constraints);
+    }
+
+    fn correct_size(&self, prev: Size, constraints: SizeConstraints) {
+        if constraints.is_empty() {
+            return;
+        }
+
+        let (Ok(size), Ok(min)) = (self.size(), self.min_size()) else {
+            return;
+        };
+
+        let fixed = constraints.apply(prev, size, min);
+        if fixed != size {
+            self.set_size(fixed);
+        }
+    }
+
+    /// Gets the app this window belongs to, or [`None`] if it has been dropped.
+    pub fn app(&self) -> Option<App> { self.0.app.upgrade() }
+
     /// Gets a weak [`WindowRef`].
     pub fn downgrade(&self) -> WindowRef { WindowRef(Arc::downgrade(&self.0)) }
 
+    /// Gets the ID of the window, which identifies it for its whole lifetime.
+    pub fn id(&self) -> WindowId { WindowId(self.0.id) }
+
     pub(crate) fn as_ptr(&self) -> *mut saucer_window { self.0.inner.as_ptr() }
 
-    pub(crate) fn drop_sender(&self) -> Sender<CleanUpHolder> { self.0.drop_sender.clone() }
+    pub(crate) fn drop_sender(&self) -> DropSender { self.0.drop_sender.clone() }
 }
 
 /// A weak window handle.
@@ -303,6 +869,18 @@ impl Window {
 #[derive(Clone)]
 pub struct WindowRef(Weak<RawWindow>);
 
+/// Refs are equal if they refer to the same window, even after it's dropped.
+impl PartialEq for WindowRef {
+    fn eq(&self, other: &Self) -> bool { Weak::ptr_eq(&self.0, &other.0) }
+}
+
+impl Eq for WindowRef {}
+
+impl Hash for WindowRef {
+    // The allocation is kept by the weak pointer, thus the address is not reused
+    fn hash<H: Hasher>(&self, state: &mut H) { self.0.as_ptr().hash(state) }
+}
+
 impl WindowRef {
     /// Tries to upgrade to a strong handle.
     pub fn upgrade(&self) -> Option<Window> { Some(Window(self.0.upgrade()?)) }
@@ -328,7 +906,7 @@ extern "C" fn ev_on_decorated_tp(
     data: *mut c_void,
 ) {
     let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback((), || {
+    ffi_callback("window decorated event", (), || {
         if let Some(wnd) = data.window.upgrade() {
             data.listener.on_decorated(wnd.clone(), dec.into()); // Clone to avoid dropping in the handler
         }
@@ -337,8 +915,10 @@ extern "C" fn ev_on_decorated_tp(
 
 extern "C" fn ev_on_maximize_tp(_: *mut saucer_window, maximized: bool, data: *mut c_void) {
     let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback((), || {
+    ffi_callback("window maximize event", (), || {
         if let Some(wnd) = data.window.upgrade() {
+            wnd.0.cached.lock_unpoisoned().is_maximized = maximized;
+            wnd.notify(WindowSignal::Maximize(maximized));
             data.listener.on_maximize(wnd.clone(), maximized);
         }
     });
@@ -346,8 +926,15 @@ extern "C" fn ev_on_maximize_tp(_: *mut saucer_window, maximized: bool, data: *m
 
 extern "C" fn ev_on_minimize_tp(_: *mut saucer_window, minimized: bool, data: *mut c_void) {
     let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback((), || {
+    ffi_callback("window minimize event", (), || {
         if let Some(wnd) = data.window.upgrade() {
+            wnd.0.cached.lock_unpoisoned().is_minimized = minimized;
+            wnd.notify(WindowSignal::Minimize(minimized));
+
+            for c in wnd.children() {
+                c.set_minimized(minimized);
+            }
+
             data.listener.on_minimize(wnd.clone(), minimized);
         }
     });
@@ -355,8 +942,15 @@ extern "C" fn ev_on_minimize_tp(_: *mut saucer_window, minimized: bool, data: *m
 
 extern "C" fn ev_on_closed_tp(_: *mut saucer_window, data: *mut c_void) {
     let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback((), || {
+    ffi_callback("window closed event", (), || {
         if let Some(wnd) = data.window.upgrade() {
+            wnd.0.closed.store(true, Ordering::Release);
+            wnd.0.cached.lock_unpoisoned().is_closed = true;
+
+            for c in wnd.children() {
+                c.close();
+            }
+
             data.listener.on_closed(wnd.clone());
         }
     });
@@ -364,8 +958,41 @@ extern "C" fn ev_on_closed_tp(_: *mut saucer_window, data: *mut c_void) {
 
 extern "C" fn ev_on_resize_tp(_: *mut saucer_window, width: u32, height: u32, data: *mut c_void) {
     let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback((), || {
+    ffi_callback("window resize event", (), || {
         if let Some(wnd) = data.window.upgrade() {
+            let size = Size::new(width as i32, height as i32);
+            let prev = std::mem::replace(&mut wnd.0.cached.lock_unpoisoned().size, size);
+            wnd.notify(WindowSignal::Resize(size));
+
+            if wnd.record_resize() {
+                data.listener.on_resize_begin(wnd.clone());
+            }
+
+            // Resizing inside the event is not reentrant on all backends
+            let constraints = *wnd.0.constraints.lock_unpoisoned();
+            if !constraints.is_empty()
+                && !constraints.native
+                && let Some(app) = wnd.app()
+            {
+                let window = wnd.downgrade();
+                app.post(move |_| {
+                    if let Some(w) = window.upgrade() {
+                        w.enforce_constraints(prev);
+                    }
+                });
+            }
+
+            // Leaving fullscreen resizes the window
+            if wnd.kiosk().is_some()
+                && let Some(app) = wnd.app()
+            {
+                let window = wnd.downgrade();
+                app.post(move |_| {
+                    if let Some(w) = window.upgrade() {
+                        w.enforce_kiosk();
+                    }
+                });
+            }
             data.listener.on_resize(wnd.clone(), width, height);
         }
     });
@@ -373,8 +1000,33 @@ extern "C" fn ev_on_resize_tp(_: *mut saucer_window, width: u32, height: u32, da
 
 extern "C" fn ev_on_focus_tp(_: *mut saucer_window, focused: bool, data: *mut c_void) {
     let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback((), || {
+    ffi_callback("window focus event", (), || {
         if let Some(wnd) = data.window.upgrade() {
+            wnd.0.cached.lock_unpoisoned().is_focused = focused;
+            wnd.notify(WindowSignal::Focus(focused));
+
+            // Posted so that focus events of child windows arrive first
+            if !focused
+                && wnd.has_focus_trap()
+                && let Some(app) = wnd.app()
+            {
+                let window = wnd.downgrade();
+                app.post(move |_| {
+                    if let Some(w) = window.upgrade() {
+                        w.reclaim_focus();
+                    }
+                });
+            }
+
+            if focused
+                && let Some(modal) = wnd
+                    .children()
+                    .into_iter()
+                    .find(|c| c.is_modal() && c.is_visible().unwrap_or(false))
+            {
+                modal.focus();
+            }
+
             data.listener.on_focus(wnd.clone(), focused);
         }
     });
@@ -382,7 +1034,7 @@ extern "C" fn ev_on_focus_tp(_: *mut saucer_window, focused: bool, data: *mut c_
 
 extern "C" fn ev_on_close_tp(_: *mut saucer_window, data: *mut c_void) -> saucer_policy {
     let data = unsafe { &*(data as *const EventListenerData) };
-    ffi_callback(Policy::Allow.into(), || {
+    ffi_callback("window close event", Policy::Allow.into(), || {
         if let Some(wnd) = data.window.upgrade() {
             data.listener.on_close(wnd.clone()).into()
         } else {
@@ -0,0 +1,169 @@
+//! Native window handle module.
+//!
+//! See [`crate::window::Window::native_handle`] for details.
+use std::ffi::c_void;
+use std::ptr::NonNull;
+
+#[cfg(feature = "raw-window-handle")]
+use raw_window_handle::DisplayHandle;
+#[cfg(feature = "raw-window-handle")]
+use raw_window_handle::HandleError;
+#[cfg(feature = "raw-window-handle")]
+use raw_window_handle::HasDisplayHandle;
+#[cfg(feature = "raw-window-handle")]
+use raw_window_handle::HasWindowHandle;
+#[cfg(feature = "raw-window-handle")]
+use raw_window_handle::RawWindowHandle;
+#[cfg(feature = "raw-window-handle")]
+use raw_window_handle::WindowHandle;
+use saucer_sys::*;
+
+#[cfg(feature = "native-handles")]
+use crate::thread::check_event_thread;
+use crate::window::Window;
+
+/// Reads a native handle written by saucer into a pointer-sized buffer.
+#[allow(unused)] // Not used by all backends
+pub(crate) fn read_native(f: impl FnOnce(*mut c_void, *mut usize)) -> Option<NonNull<c_void>> {
+    let mut ptr: *mut c_void = std::ptr::null_mut();
+    let mut size = size_of::<*mut c_void>();
+
+    f((&raw mut ptr).cast(), &raw mut size);
+
+    if size != size_of::<*mut c_void>() {
+        return None;
+    }
+
+    NonNull::new(ptr)
+}
+
+impl Window {
+    /// Gets the native window of the backend, for implementing platform
+    /// features the bindings don't cover:
+    ///
+    /// | Backend | Type |
+    /// |---|---|
+    /// | WebView2 | `HWND` |
+    /// | WebKit | `NSWindow *` |
+    /// | WebKitGTK | `GtkWindow *` |
+    /// | Qt | `QMainWindow *` |
+    ///
+    /// # Safety
+    ///
+    /// The window is owned by the backend. Callers must not free it, and must
+    /// only use it on the event thread while the window is alive. Changing
+    /// states the bindings keep track of (e.g. the size or the title) leaves
+    /// them out of sync, and replacing delegates or event handlers of the
+    /// backend breaks it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, unless allowed by the
+    /// [`crate::thread::ThreadViolationPolicy`].
+    #[cfg(feature = "native-handles")]
+    pub unsafe fn native_handle(&self) -> crate::error::Result<NonNull<c_void>> {
+        if check_event_thread(self.is_thread_safe(), "getting native handles").is_some() {
+            return Err(crate::error::Error::WrongThread);
+        }
+
+        self.query_native_handle()
+    }
+
+    /// Gets the native window without checking the thread.
+    #[allow(unused)] // Not used by all backends
+    pub(crate) fn query_native_handle(&self) -> crate::error::Result<NonNull<c_void>> {
+        self.ensure_alive()?;
+
+        read_native(|r, s| unsafe { saucer_window_native(self.as_ptr(), 0, r, s) })
+            .ok_or_else(|| crate::error::Error::unsupported("native window handles", None))
+    }
+}
+
+/// Provides the native window to crates taking `raw-window-handle` types,
+/// e.g. for rendering into it with `wgpu`.
+///
+/// Only WebView2 and WebKit are supported, where the handle is the `HWND` and
+/// the content `NSView` respectively. The GTK and Qt backends fail with
+/// [`HandleError::NotSupported`], as their windows don't map to a single
+/// handle of the windowing system. [`HandleError::Unavailable`] is returned
+/// after the window is closed, and on WebKit when not called on the event
+/// thread.
+///
+/// The backend keeps drawing the webview into the window, thus native content
+/// is usually rendered into a separate child window or layer.
+#[cfg(feature = "raw-window-handle")]
+impl HasWindowHandle for Window {
+    fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        let native = self
+            .query_native_handle()
+            .map_err(|_| HandleError::Unavailable)?;
+
+        let raw = raw_window_handle(self, native)?;
+
+        // SAFETY: The handle stays valid while the window is alive, which is
+        // ensured by the borrow for the most part. Closing the window is up
+        // to the user, as with other windowing crates.
+        Ok(unsafe { WindowHandle::borrow_raw(raw) })
+    }
+}
+
+/// See [`HasWindowHandle`] for supported backends.
+#[cfg(feature = "raw-window-handle")]
+impl HasDisplayHandle for Window {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        if cfg!(target_os = "windows") && !cfg!(feature = "qt") {
+            Ok(DisplayHandle::windows())
+        } else if cfg!(target_os = "macos") && !cfg!(feature = "qt") {
+            Ok(DisplayHandle::appkit())
+        } else {
+            Err(HandleError::NotSupported)
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "raw-window-handle",
+    target_os = "windows",
+    not(feature = "qt")
+))]
+fn raw_window_handle(_: &Window, hwnd: NonNull<c_void>) -> Result<RawWindowHandle, HandleError> {
+    use std::num::NonZeroIsize;
+
+    use raw_window_handle::Win32WindowHandle;
+
+    let hwnd = NonZeroIsize::new(hwnd.as_ptr() as isize).ok_or(HandleError::Unavailable)?;
+    Ok(Win32WindowHandle::new(hwnd).into())
+}
+
+#[cfg(all(
+    feature = "raw-window-handle",
+    target_os = "macos",
+    not(feature = "qt")
+))]
+fn raw_window_handle(
+    window: &Window,
+    ns_window: NonNull<c_void>,
+) -> Result<RawWindowHandle, HandleError> {
+    use objc2::msg_send;
+    use objc2::runtime::AnyObject;
+    use raw_window_handle::AppKitWindowHandle;
+
+    // AppKit must only be used on the main thread
+    if !window.is_thread_safe() {
+        return Err(HandleError::Unavailable);
+    }
+
+    // SAFETY: On the main thread, and the window is alive
+    let view: *mut AnyObject =
+        unsafe { msg_send![ns_window.as_ptr().cast::<AnyObject>(), contentView] };
+    let view = NonNull::new(view.cast()).ok_or(HandleError::Unavailable)?;
+    Ok(AppKitWindowHandle::new(view).into())
+}
+
+#[cfg(all(
+    feature = "raw-window-handle",
+    not(all(any(target_os = "windows", target_os = "macos"), not(feature = "qt")))
+))]
+fn raw_window_handle(_: &Window, _: NonNull<c_void>) -> Result<RawWindowHandle, HandleError> {
+    Err(HandleError::NotSupported)
+}
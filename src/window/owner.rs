@@ -0,0 +1,103 @@
+//! Native window ownership module.
+//!
+//! See [`crate::window::Window::set_parent`] for details.
+use crate::window::Window;
+
+/// Makes the native window owned by the new parent (or by none), so that it
+/// stays above it and follows it, as the platform does for dialogs.
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+pub(crate) fn set_native_owner(
+    window: &Window,
+    _: Option<&Window>,
+    parent: Option<&Window>,
+) -> crate::error::Result<()> {
+    const GWLP_HWNDPARENT: i32 = -8;
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn SetWindowLongPtrW(hwnd: isize, index: i32, value: isize) -> isize;
+    }
+
+    let hwnd = window.query_native_handle()?.as_ptr() as isize;
+    let owner = match parent {
+        Some(p) => p.query_native_handle()?.as_ptr() as isize,
+        None => 0,
+    };
+
+    // SAFETY: Both windows are alive, and we're on the event thread, which
+    // owns them
+    unsafe { SetWindowLongPtrW(hwnd, GWLP_HWNDPARENT, owner) };
+
+    Ok(())
+}
+
+#[cfg(all(target_os = "macos", not(feature = "qt")))]
+pub(crate) fn set_native_owner(
+    window: &Window,
+    old: Option<&Window>,
+    parent: Option<&Window>,
+) -> crate::error::Result<()> {
+    use objc2::msg_send;
+    use objc2::runtime::AnyObject;
+
+    // NSWindowAbove
+    const ABOVE: isize = 1;
+
+    let ns_window = window.query_native_handle()?.as_ptr().cast::<AnyObject>();
+
+    // SAFETY: On the main thread, which is the event thread on macOS, and the
+    // windows are alive
+    unsafe {
+        if let Some(Ok(old)) = old.map(Window::query_native_handle) {
+            let old = old.as_ptr().cast::<AnyObject>();
+            let _: () = msg_send![old, removeChildWindow: ns_window];
+        }
+
+        if let Some(parent) = parent {
+            let parent = parent.query_native_handle()?.as_ptr().cast::<AnyObject>();
+            let _: () = msg_send![parent, addChildWindow: ns_window, ordered: ABOVE];
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(all(
+    not(any(target_os = "windows", target_os = "macos")),
+    not(feature = "qt")
+))]
+pub(crate) fn set_native_owner(
+    window: &Window,
+    _: Option<&Window>,
+    parent: Option<&Window>,
+) -> crate::error::Result<()> {
+    use std::ffi::c_void;
+
+    unsafe extern "C" {
+        fn gtk_window_set_transient_for(window: *mut c_void, parent: *mut c_void);
+    }
+
+    let gtk_window = window.query_native_handle()?.as_ptr();
+    let parent = match parent {
+        Some(p) => p.query_native_handle()?.as_ptr(),
+        None => std::ptr::null_mut(),
+    };
+
+    // SAFETY: Both are `GtkWindow`s which are alive, and we're on the event
+    // thread, which owns them
+    unsafe { gtk_window_set_transient_for(gtk_window, parent) };
+
+    Ok(())
+}
+
+#[cfg(feature = "qt")]
+pub(crate) fn set_native_owner(
+    _: &Window,
+    _: Option<&Window>,
+    _: Option<&Window>,
+) -> crate::error::Result<()> {
+    Err(crate::error::Error::unsupported(
+        "native window owners",
+        None,
+    ))
+}
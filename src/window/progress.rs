@@ -0,0 +1,347 @@
+//! Window progress module.
+//!
+//! See [`Progress`] for details.
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use crate::util::MutexExt;
+use crate::window::Window;
+use crate::window::WindowRef;
+use crate::window::WindowSignal;
+
+/// A snapshot of the progress of an operation. See [`Progress`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProgressState {
+    /// The description of the operation, e.g. `Exporting…`.
+    pub label: String,
+    /// The fraction done, between 0 and 1, or [`None`] if unknown.
+    pub value: Option<f64>,
+    /// Whether the operation has finished, after which the progress is no
+    /// longer shown.
+    pub finished: bool,
+}
+
+/// The progress shown by a window.
+#[derive(Default)]
+pub(crate) struct WindowProgress {
+    /// Identifies the handle owning the progress, as newer ones take over.
+    pub(crate) owner: u64,
+    pub(crate) state: Option<ProgressState>,
+    pub(crate) toast: bool,
+}
+
+/// A handle reporting the progress of a long-running operation to a window,
+/// created with [`Window::progress`].
+///
+/// Each update fires [`crate::window::WindowEventListener::on_progress`] on
+/// the event thread for custom UIs, and updates the toast shown in the pages
+/// of the window if enabled with [`Window::set_progress_toast`]. Handles can
+/// be moved to other threads, and updates made there are posted to the event
+/// thread.
+///
+/// The progress is also shown in the taskbar button of the window on
+/// WebView2, and as a percentage badge on the dock icon on macOS (shared by
+/// all windows of the app). GTK 4 and Qt have no such API, thus it's not
+/// shown outside the window there.
+///
+/// A window shows one progress at a time. Creating another handle takes
+/// over, after which updates of the previous one are ignored. Dropping the
+/// handle finishes the progress.
+pub struct Progress {
+    window: WindowRef,
+    id: u64,
+    finished: AtomicBool,
+}
+
+impl Progress {
+    /// Sets the fraction done, clamped to between 0 and 1.
+    pub fn set(&self, value: f64) {
+        let value = if value.is_nan() {
+            0.0
+        } else {
+            value.clamp(0.0, 1.0)
+        };
+        self.update(|s| s.value = Some(value));
+    }
+
+    /// Marks the amount of work as unknown.
+    pub fn set_indeterminate(&self) { self.update(|s| s.value = None); }
+
+    /// Changes the description of the operation.
+    pub fn set_label(&self, label: impl Into<String>) {
+        let label = label.into();
+        self.update(|s| s.label = label);
+    }
+
+    /// Gets the fraction done, or [`None`] if unknown.
+    pub fn value(&self) -> Option<f64> {
+        let window = self.window.upgrade()?;
+        let progress = window.0.progress.lock_unpoisoned();
+        (progress.owner == self.id)
+            .then(|| progress.state.as_ref()?.value)
+            .flatten()
+    }
+
+    /// Finishes the operation and hides the progress.
+    pub fn finish(self) { drop(self) }
+
+    fn update(&self, f: impl FnOnce(&mut ProgressState)) {
+        if !self.finished.load(Ordering::Relaxed) {
+            self.modify(f);
+        }
+    }
+
+    fn modify(&self, f: impl FnOnce(&mut ProgressState)) {
+        let Some(window) = self.window.upgrade() else {
+            return;
+        };
+
+        {
+            let mut progress = window.0.progress.lock_unpoisoned();
+            if progress.owner != self.id {
+                return;
+            }
+
+            let Some(state) = progress.state.as_mut() else {
+                return;
+            };
+
+            f(state);
+        }
+
+        window.dispatch_progress();
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if !self.finished.swap(true, Ordering::Relaxed) {
+            self.modify(|s| s.finished = true);
+        }
+    }
+}
+
+impl Window {
+    /// Starts reporting the progress of an operation with the given
+    /// description. The amount of work is unknown until [`Progress::set`] is
+    /// called. See [`Progress`] for details.
+    ///
+    /// ```no_run
+    /// # fn f(window: saucers::window::Window) {
+    /// let p = window.progress("Exporting…");
+    /// p.set(0.4);
+    /// p.finish();
+    /// # }
+    /// ```
+    pub fn progress(&self, label: impl Into<String>) -> Progress {
+        let id = {
+            let mut progress = self.0.progress.lock_unpoisoned();
+            progress.owner += 1;
+            progress.state = Some(ProgressState {
+                label: label.into(),
+                value: None,
+                finished: false,
+            });
+            progress.owner
+        };
+
+        self.dispatch_progress();
+
+        Progress {
+            window: self.downgrade(),
+            id,
+            finished: AtomicBool::new(false),
+        }
+    }
+
+    /// Gets the progress currently shown, if any. See [`Self::progress`].
+    pub fn progress_state(&self) -> Option<ProgressState> {
+        self.0.progress.lock_unpoisoned().state.clone()
+    }
+
+    /// Sets whether a toast showing the progress (see [`Self::progress`]) is
+    /// injected into the pages of the window. Disabled by default.
+    ///
+    /// The toast is a small card at the bottom-right corner of the page, with
+    /// the description and a progress bar. It's shown while an operation is in
+    /// progress and removed when it finishes.
+    pub fn set_progress_toast(&self, enabled: bool) {
+        self.0.progress.lock_unpoisoned().toast = enabled;
+        self.notify(WindowSignal::Progress);
+    }
+
+    /// Checks whether the progress toast is enabled. See
+    /// [`Self::set_progress_toast`].
+    pub fn has_progress_toast(&self) -> bool { self.0.progress.lock_unpoisoned().toast }
+
+    /// Reports the current progress on the event thread.
+    fn dispatch_progress(&self) {
+        if self.is_thread_safe() {
+            self.fire_progress();
+            return;
+        }
+
+        let Some(app) = self.app() else {
+            return;
+        };
+
+        let window = self.downgrade();
+        app.post(move |_| {
+            if let Some(w) = window.upgrade() {
+                w.fire_progress();
+            }
+        });
+    }
+
+    fn fire_progress(&self) {
+        let state = {
+            let mut progress = self.0.progress.lock_unpoisoned();
+            let Some(state) = progress.state.clone() else {
+                return;
+            };
+
+            // Only unfinished operations are kept
+            if state.finished {
+                progress.state = None;
+            }
+
+            state
+        };
+
+        let _ = show_native(self, &state); // Only shown in the window otherwise
+        self.notify(WindowSignal::Progress);
+        self.listener().on_progress(self.clone(), state);
+    }
+}
+
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+fn show_native(window: &Window, state: &ProgressState) -> crate::error::Result<()> {
+    use std::cell::Cell;
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    struct Guid(u32, u16, u16, [u8; 8]);
+
+    const CLSID_TASKBAR_LIST: Guid = Guid(0x56fdf344, 0xfd6d, 0x11d0, [
+        0x95, 0x8a, 0x00, 0x60, 0x97, 0xc9, 0xa0, 0x90,
+    ]);
+    const IID_ITASKBAR_LIST3: Guid = Guid(0xea1afb91, 0x9e28, 0x4b86, [
+        0x90, 0xe9, 0x9e, 0x9f, 0x8a, 0x5e, 0xef, 0xaf,
+    ]);
+    const CLSCTX_INPROC_SERVER: u32 = 1;
+    // ITaskbarList3
+    const HR_INIT: usize = 3;
+    const SET_PROGRESS_VALUE: usize = 9;
+    const SET_PROGRESS_STATE: usize = 10;
+    // TBPFLAG
+    const NO_PROGRESS: u32 = 0;
+    const INDETERMINATE: u32 = 1;
+    const NORMAL: u32 = 2;
+    /// The total passed to `SetProgressValue`.
+    const STEPS: u64 = 10_000;
+
+    #[link(name = "ole32")]
+    unsafe extern "system" {
+        fn CoCreateInstance(
+            clsid: *const Guid,
+            outer: *mut c_void,
+            context: u32,
+            iid: *const Guid,
+            out: *mut *mut c_void,
+        ) -> i32;
+    }
+
+    thread_local! {
+        /// The taskbar list of the event thread, kept for the whole process.
+        static TASKBAR: Cell<*mut c_void> = const { Cell::new(std::ptr::null_mut()) };
+    }
+
+    unsafe fn method<F: Copy>(obj: *mut c_void, slot: usize) -> F {
+        unsafe {
+            let vtbl = *(obj as *const *const *const c_void);
+            std::mem::transmute_copy(&*vtbl.add(slot))
+        }
+    }
+
+    let hwnd = window.query_native_handle()?.as_ptr();
+
+    // SAFETY: COM is initialized on the event thread by WebView2, and the
+    // methods match the slots of `ITaskbarList3`
+    unsafe {
+        let mut taskbar = TASKBAR.get();
+        if taskbar.is_null() {
+            let hr = CoCreateInstance(
+                &CLSID_TASKBAR_LIST,
+                std::ptr::null_mut(),
+                CLSCTX_INPROC_SERVER,
+                &IID_ITASKBAR_LIST3,
+                &raw mut taskbar,
+            );
+            if hr < 0 || taskbar.is_null() {
+                return Err(crate::error::Error::unsupported("taskbar progress", None));
+            }
+
+            let init: unsafe extern "system" fn(*mut c_void) -> i32 = method(taskbar, HR_INIT);
+            init(taskbar);
+            TASKBAR.set(taskbar);
+        }
+
+        let set_state: unsafe extern "system" fn(*mut c_void, *mut c_void, u32) -> i32 =
+            method(taskbar, SET_PROGRESS_STATE);
+        let set_value: unsafe extern "system" fn(*mut c_void, *mut c_void, u64, u64) -> i32 =
+            method(taskbar, SET_PROGRESS_VALUE);
+
+        match state.value {
+            _ if state.finished => {
+                set_state(taskbar, hwnd, NO_PROGRESS);
+            }
+            None => {
+                set_state(taskbar, hwnd, INDETERMINATE);
+            }
+            Some(v) => {
+                set_state(taskbar, hwnd, NORMAL);
+                set_value(taskbar, hwnd, (v * STEPS as f64) as u64, STEPS);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(all(target_os = "macos", not(feature = "qt")))]
+fn show_native(window: &Window, state: &ProgressState) -> crate::error::Result<()> {
+    use std::ffi::CString;
+
+    use objc2::class;
+    use objc2::msg_send;
+    use objc2::runtime::AnyObject;
+
+    window.ensure_alive()?;
+
+    // The dock has no progress bar, thus the percentage is shown as a badge
+    let badge = match state.value {
+        _ if state.finished => None,
+        None => Some("…".to_owned()),
+        Some(v) => Some(format!("{}%", (v * 100.0).round() as u32)),
+    };
+    let badge = badge.and_then(|b| CString::new(b).ok());
+
+    // SAFETY: On the main thread, which is the event thread on macOS, and the
+    // string is copied by the call
+    unsafe {
+        let app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+        let tile: *mut AnyObject = msg_send![app, dockTile];
+        let label: *mut AnyObject = match &badge {
+            Some(b) => msg_send![class!(NSString), stringWithUTF8String: b.as_ptr()],
+            None => std::ptr::null_mut(),
+        };
+        let _: () = msg_send![tile, setBadgeLabel: label];
+    }
+
+    Ok(())
+}
+
+#[cfg(not(all(any(target_os = "windows", target_os = "macos"), not(feature = "qt"))))]
+fn show_native(_: &Window, _: &ProgressState) -> crate::error::Result<()> {
+    Err(crate::error::Error::unsupported("taskbar progress", None))
+}
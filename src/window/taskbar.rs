@@ -0,0 +1,162 @@
+//! Window taskbar module.
+//!
+//! See [`crate::window::Window::set_skip_taskbar`] for details.
+use crate::thread::check_event_thread;
+use crate::window::Window;
+
+impl Window {
+    /// Hides the window from the taskbar, like tool and palette windows.
+    ///
+    /// On WebView2, the window becomes a tool window, which also gives it a
+    /// slimmer titlebar and keeps it out of Alt+Tab. On macOS, where windows
+    /// only reach the dock when minimized, the window is kept out of Mission
+    /// Control and window cycling instead. This fails with
+    /// [`crate::error::Error::Unsupported`] elsewhere: GTK 4, which WebKitGTK
+    /// is built on, has dropped taskbar hints, and the window APIs of Qt are
+    /// not reachable from the bindings. Showing (the default) always succeeds
+    /// there.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, unless allowed by the
+    /// [`crate::thread::ThreadViolationPolicy`].
+    pub fn set_skip_taskbar(&self, skip: bool) -> crate::error::Result<()> {
+        if check_event_thread(self.is_thread_safe(), "skipping the taskbar").is_some() {
+            return Err(crate::error::Error::WrongThread);
+        }
+
+        self.ensure_alive()?;
+        set_skip_taskbar(self, skip)
+    }
+
+    /// Shows the window on all virtual desktops (spaces), instead of only the
+    /// one it's on.
+    ///
+    /// Only macOS supports this, enabling fails with
+    /// [`crate::error::Error::Unsupported`] elsewhere. Windows has no public
+    /// API for virtual desktops, and neither do GTK 4 and the bindings of Qt.
+    /// Disabling (the default) always succeeds there.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called on the event thread, unless allowed by the
+    /// [`crate::thread::ThreadViolationPolicy`].
+    pub fn set_visible_on_all_workspaces(&self, visible: bool) -> crate::error::Result<()> {
+        if check_event_thread(self.is_thread_safe(), "showing on all workspaces").is_some() {
+            return Err(crate::error::Error::WrongThread);
+        }
+
+        self.ensure_alive()?;
+        set_visible_on_all_workspaces(self, visible)
+    }
+}
+
+#[cfg(all(target_os = "windows", not(feature = "qt")))]
+fn set_skip_taskbar(window: &Window, skip: bool) -> crate::error::Result<()> {
+    const GWL_EXSTYLE: i32 = -20;
+    const WS_EX_TOOLWINDOW: isize = 0x0000_0080;
+    const WS_EX_APPWINDOW: isize = 0x0004_0000;
+    const SW_HIDE: i32 = 0;
+    const SW_SHOWNA: i32 = 8;
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn GetWindowLongPtrW(hwnd: isize, index: i32) -> isize;
+        fn SetWindowLongPtrW(hwnd: isize, index: i32, value: isize) -> isize;
+        fn IsWindowVisible(hwnd: isize) -> i32;
+        fn ShowWindow(hwnd: isize, cmd: i32) -> i32;
+    }
+
+    let hwnd = window.query_native_handle()?.as_ptr() as isize;
+
+    // SAFETY: The window is alive, and we're on the event thread, which owns
+    // it
+    unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        let style = if skip {
+            (style | WS_EX_TOOLWINDOW) & !WS_EX_APPWINDOW
+        } else {
+            style & !WS_EX_TOOLWINDOW
+        };
+
+        // The taskbar only picks up the change when the window is shown again
+        let visible = IsWindowVisible(hwnd) != 0;
+        if visible {
+            ShowWindow(hwnd, SW_HIDE);
+        }
+
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style);
+
+        if visible {
+            ShowWindow(hwnd, SW_SHOWNA);
+        }
+    }
+
+    Ok(())
+}
+
+/// `NSWindowCollectionBehavior` flags.
+#[cfg(all(target_os = "macos", not(feature = "qt")))]
+mod behavior {
+    pub const CAN_JOIN_ALL_SPACES: usize = 1 << 0;
+    pub const TRANSIENT: usize = 1 << 3;
+    pub const IGNORES_CYCLE: usize = 1 << 6;
+}
+
+/// Sets or clears flags of the collection behavior of the window.
+#[cfg(all(target_os = "macos", not(feature = "qt")))]
+fn set_collection_behavior(window: &Window, flags: usize, on: bool) -> crate::error::Result<()> {
+    use objc2::msg_send;
+    use objc2::runtime::AnyObject;
+
+    let ns_window = window.query_native_handle()?.as_ptr().cast::<AnyObject>();
+
+    // SAFETY: On the main thread, which is the event thread on macOS, and the
+    // window is alive
+    unsafe {
+        let behavior: usize = msg_send![ns_window, collectionBehavior];
+        let behavior = if on {
+            behavior | flags
+        } else {
+            behavior & !flags
+        };
+        let _: () = msg_send![ns_window, setCollectionBehavior: behavior];
+    }
+
+    Ok(())
+}
+
+#[cfg(all(target_os = "macos", not(feature = "qt")))]
+fn set_skip_taskbar(window: &Window, skip: bool) -> crate::error::Result<()> {
+    let flags = behavior::TRANSIENT | behavior::IGNORES_CYCLE;
+    set_collection_behavior(window, flags, skip)
+}
+
+#[cfg(all(target_os = "macos", not(feature = "qt")))]
+fn set_visible_on_all_workspaces(window: &Window, visible: bool) -> crate::error::Result<()> {
+    set_collection_behavior(window, behavior::CAN_JOIN_ALL_SPACES, visible)
+}
+
+#[cfg(not(all(any(target_os = "windows", target_os = "macos"), not(feature = "qt"))))]
+fn set_skip_taskbar(_: &Window, skip: bool) -> crate::error::Result<()> {
+    if skip {
+        return Err(crate::error::Error::unsupported(
+            "skipping the taskbar",
+            None,
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(not(all(target_os = "macos", not(feature = "qt"))))]
+fn set_visible_on_all_workspaces(_: &Window, visible: bool) -> crate::error::Result<()> {
+    if visible {
+        return Err(crate::error::Error::unsupported(
+            "showing on all workspaces",
+            None,
+        ));
+    }
+
+    Ok(())
+}
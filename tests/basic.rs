@@ -1,14 +1,15 @@
 use std::borrow::Cow;
 use std::cell::RefCell;
-use std::panic::AssertUnwindSafe;
-use std::rc::Rc;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
 
 use libtest_mimic::Arguments;
 use libtest_mimic::Trial;
 use saucers::app::App;
 use saucers::app::AppEventListener;
-use saucers::app::AppManager;
 use saucers::app::AppOptions;
 use saucers::navigation::Navigation;
 use saucers::policy::Policy;
@@ -19,22 +20,36 @@ use saucers::scheme::register_scheme;
 use saucers::stash::Stash;
 use saucers::state::LoadState;
 use saucers::status::HandleStatus;
+use saucers::testing::TestHarness;
+use saucers::webview::DomReadyEvent;
+use saucers::webview::LoadEvent;
+use saucers::webview::MessageEvent;
+use saucers::webview::RegistrationScope;
 use saucers::webview::ScriptTime;
+use saucers::webview::TitleEvent;
 use saucers::webview::Webview;
 use saucers::webview::WebviewEventListener;
 use saucers::webview::WebviewOptions;
 use saucers::webview::WebviewSchemeHandler;
 use saucers::window::Window;
 
+const EVENT_TIMEOUT: Duration = Duration::from_secs(10);
+
 fn main() {
     let mut args = Arguments::from_args();
 
     args.test_threads = Some(1);
 
-    let tests = vec![Trial::test("app_lifecycle", || {
-        app_lifecycle();
-        Ok(())
-    })];
+    let tests = vec![
+        Trial::test("app_lifecycle", || {
+            app_lifecycle();
+            Ok(())
+        }),
+        Trial::test("scoped_handlers_are_removed_on_drop", || {
+            scoped_handlers_are_removed_on_drop();
+            Ok(())
+        }),
+    ];
 
     libtest_mimic::run(&args, tests).exit();
 }
@@ -42,8 +57,6 @@ fn main() {
 fn app_lifecycle() {
     register_scheme("test");
 
-    let app = AppManager::new(AppOptions::new_with_id("test"));
-
     #[derive(Default)]
     struct Trace {
         quit_fired: bool,
@@ -68,51 +81,44 @@ fn app_lifecycle() {
         }
     }
 
-    struct SharedTrace(AssertUnwindSafe<Rc<RefCell<Trace>>>); // If `borrow_mut` panics, the event listener is unsound!
-
-    impl Clone for SharedTrace {
-        fn clone(&self) -> Self { SharedTrace(AssertUnwindSafe(self.0.clone())) }
-    }
+    #[derive(Clone, Default)]
+    struct SharedTrace(Arc<Mutex<Trace>>);
 
-    let trace = SharedTrace(AssertUnwindSafe(Rc::new(RefCell::new(Trace::default()))));
+    let trace = SharedTrace::default();
 
     impl AppEventListener for SharedTrace {
         fn on_quit(&self, _app: App) -> Policy {
-            self.0.borrow_mut().quit_fired = true;
+            self.0.lock().unwrap().quit_fired = true;
             Policy::Allow
         }
     }
 
     impl WebviewEventListener for SharedTrace {
         fn on_dom_ready(&self, webview: Webview) {
-            self.0.borrow_mut().dom_ready_fired = true;
+            self.0.lock().unwrap().dom_ready_fired = true;
             webview.execute("window.saucer.internal.message(window._injected.toString());");
         }
 
         fn on_navigate(&self, _webview: Webview, _nav: &Navigation) -> Policy {
-            self.0.borrow_mut().navigate_fired = true;
+            self.0.lock().unwrap().navigate_fired = true;
             Policy::Allow
         }
 
-        fn on_message(&self, webview: Webview, msg: Cow<str>) -> HandleStatus {
+        fn on_message(&self, _webview: Webview, msg: Cow<str>) -> HandleStatus {
             if msg == "true" {
-                self.0.borrow_mut().inject_script_executed = true;
-                webview.window().close();
+                self.0.lock().unwrap().inject_script_executed = true;
             } else {
-                self.0.borrow_mut().message_received = true;
+                self.0.lock().unwrap().message_received = true;
             }
 
             HandleStatus::Handled
         }
 
         fn on_load(&self, _webview: Webview, _state: LoadState) {
-            self.0.borrow_mut().load_fired = true;
+            self.0.lock().unwrap().load_fired = true;
         }
     }
 
-    let trace_app = trace.clone();
-    let trace_webview = trace.clone();
-
     const PAGE_HTML: &str = r#"
         <script>
             window.saucer.internal.message('Hello');
@@ -141,29 +147,97 @@ fn app_lifecycle() {
 
     let counter = Arc::new(());
 
-    app.run(
-        {
-            let counter = counter.clone();
-            |app| {
-                let wnd = Window::new(&app, ()).unwrap();
-                wnd.show();
-
-                let wv =
-                    Webview::new(WebviewOptions::default(), wnd, trace_webview, SchemeHd).unwrap();
-
-                wv.inject("window._injected = true;", ScriptTime::Creation, true, true);
-                wv.set_url_str(SCHEME_URL);
-
-                move |_| {
-                    drop(wv);
-                    drop(counter);
+    TestHarness::new(AppOptions::new_with_id("test"))
+        .run(
+            {
+                let trace = trace.clone();
+                let counter = counter.clone();
+                move |h| {
+                    h.step(move |s| {
+                        let wnd = Window::new(s.app(), ()).unwrap();
+                        wnd.show();
+
+                        let wv =
+                            Webview::new(WebviewOptions::default(), wnd, trace.clone(), SchemeHd)
+                                .unwrap();
+                        s.watch(&wv);
+
+                        wv.inject("window._injected = true;", ScriptTime::Creation, true, true);
+                        wv.set_url_str(SCHEME_URL);
+
+                        s.keep(counter);
+                    });
+
+                    h.wait_for::<LoadEvent>(EVENT_TIMEOUT)
+                        .expect("load event should be fired");
+                    h.wait_for::<DomReadyEvent>(EVENT_TIMEOUT)
+                        .expect("DOM ready event should be fired");
+
+                    // One from the page, the other from the DOM ready listener
+                    for _ in 0..2 {
+                        h.wait_for::<MessageEvent>(EVENT_TIMEOUT)
+                            .expect("message should be received");
+                    }
                 }
-            }
-        },
-        trace_app,
-    )
-    .unwrap();
+            },
+            trace.clone(),
+        )
+        .unwrap();
 
     assert_eq!(Arc::strong_count(&counter), 1, "closures should be dropped");
-    trace.0.borrow().verify();
+    trace.0.lock().unwrap().verify();
+}
+
+fn scoped_handlers_are_removed_on_drop() {
+    thread_local! {
+        static SCOPE: RefCell<Option<RegistrationScope>> = const { RefCell::new(None) };
+    }
+
+    let fired = Arc::new(AtomicUsize::new(0));
+
+    TestHarness::new(AppOptions::new_with_id("test"))
+        .run(
+            move |h| {
+                h.step({
+                    let fired = fired.clone();
+                    move |s| {
+                        let wnd = Window::new(s.app(), ()).unwrap();
+                        let wv = Webview::new(WebviewOptions::default(), wnd, (), ()).unwrap();
+                        s.watch(&wv);
+
+                        let scope = wv.scope();
+                        scope.on::<TitleEvent>(move |_, _| {
+                            fired.fetch_add(1, Ordering::SeqCst);
+                        });
+                        SCOPE.set(Some(scope));
+
+                        wv.set_html("<title>Before</title>");
+                    }
+                });
+
+                h.wait_for::<TitleEvent>(EVENT_TIMEOUT)
+                    .expect("title event should be fired");
+
+                // Steps run after the event is dispatched to every handler
+                let f = fired.clone();
+                assert_eq!(h.step(move |_| f.load(Ordering::SeqCst)), 1);
+
+                h.step(|s| {
+                    drop(SCOPE.take());
+                    s.webview().set_html("<title>After</title>");
+                });
+
+                h.wait_for::<TitleEvent>(EVENT_TIMEOUT)
+                    .expect("title event should be fired");
+
+                let f = fired.clone();
+                assert_eq!(
+                    h.step(move |_| f.load(Ordering::SeqCst)),
+                    1,
+                    "scoped handler should not be fired after the scope is dropped"
+                );
+            },
+            (),
+        )
+        .unwrap();
 }
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use libtest_mimic::Arguments;
+use libtest_mimic::Trial;
+use saucers::navigation::NavigationRules;
+use saucers::prelude::*;
+use saucers::testing::Stage;
+use saucers::testing::TestHarness;
+use saucers::webview::ConsoleLevel;
+use saucers::webview::ConsoleMessageEvent;
+use saucers::window::CachedWindowState;
+
+use crate::window_state::WindowState;
+
+#[path = "../examples/full_app/rpc.rs"]
+mod rpc;
+#[allow(dead_code)] // `WindowState::apply` is only used by the example
+#[path = "../examples/full_app/window_state.rs"]
+mod window_state;
+
+const EVENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+static HTML_FILE: &[u8] = include_bytes!("../examples/full_app/index.html");
+
+fn main() {
+    let mut args = Arguments::from_args();
+
+    args.test_threads = Some(1);
+
+    let tests = vec![
+        Trial::test("rpc_replies_with_result", || {
+            rpc_replies_with_result();
+            Ok(())
+        }),
+        Trial::test("rpc_rejects_unknown_method", || {
+            rpc_rejects_unknown_method();
+            Ok(())
+        }),
+        Trial::test("window_state_round_trips", || {
+            window_state_round_trips();
+            Ok(())
+        }),
+    ];
+
+    libtest_mimic::run(&args, tests).exit();
+}
+
+/// Console messages forwarded by the page, with their levels.
+type ConsoleLog = Arc<Mutex<Vec<(ConsoleLevel, String)>>>;
+
+/// Sets up the page of the `full_app` example the same way it does, recording
+/// console messages into the given log.
+fn serve_example(s: &mut Stage, console: ConsoleLog) {
+    let window = Window::new(s.app(), ()).unwrap();
+    window.show();
+
+    let webview = Webview::new(WebviewOptions::default(), window, (), ()).unwrap();
+    s.watch(&webview);
+
+    webview.set_navigation_rules(Some(NavigationRules::new().block_external()));
+
+    webview.on::<MessageEvent>(rpc::handle);
+
+    webview.on::<ConsoleMessageEvent>(move |_, ev| {
+        console.lock().unwrap().push((ev.level, ev.text.clone()));
+    });
+
+    webview.embed("/index.html", Stash::new_view(HTML_FILE), "text/html");
+    webview.serve("/index.html");
+}
+
+fn rpc_replies_with_result() {
+    let console = ConsoleLog::default();
+
+    TestHarness::new(AppOptions::new_with_id("test"))
+        .run(
+            {
+                let console = console.clone();
+                move |h| {
+                    h.step(move |s| serve_example(s, console));
+
+                    h.wait_for::<DomReadyEvent>(EVENT_TIMEOUT)
+                        .expect("DOM ready event should be fired");
+
+                    h.step(|s| {
+                        s.webview()
+                            .execute("document.getElementById('add').click();");
+                    });
+
+                    h.wait_for::<MessageEvent>(EVENT_TIMEOUT)
+                        .expect("request should be received");
+                    h.wait_for::<ConsoleMessageEvent>(EVENT_TIMEOUT)
+                        .expect("result should be logged");
+                }
+            },
+            (),
+        )
+        .unwrap();
+
+    assert_eq!(*console.lock().unwrap(), [(
+        ConsoleLevel::Info,
+        "1 + 2 = 3".to_owned()
+    )]);
+}
+
+fn rpc_rejects_unknown_method() {
+    let console = ConsoleLog::default();
+
+    TestHarness::new(AppOptions::new_with_id("test"))
+        .run(
+            {
+                let console = console.clone();
+                move |h| {
+                    h.step(move |s| serve_example(s, console));
+
+                    h.wait_for::<DomReadyEvent>(EVENT_TIMEOUT)
+                        .expect("DOM ready event should be fired");
+
+                    h.step(|s| {
+                        s.webview().execute(
+                            "invoke('subtract', [2, 1]).catch((e) => console.error(e.message));",
+                        );
+                    });
+
+                    h.wait_for::<MessageEvent>(EVENT_TIMEOUT)
+                        .expect("request should be received");
+                    h.wait_for::<ConsoleMessageEvent>(EVENT_TIMEOUT)
+                        .expect("error should be logged");
+                }
+            },
+            (),
+        )
+        .unwrap();
+
+    assert_eq!(*console.lock().unwrap(), [(
+        ConsoleLevel::Error,
+        "unknown method".to_owned()
+    )]);
+}
+
+fn window_state_round_trips() {
+    let path = std::env::temp_dir().join("saucers-full-app-test-window.json");
+
+    let state = WindowState::from_cached(CachedWindowState {
+        size: Size::new(640, 480),
+        is_maximized: true,
+        ..Default::default()
+    });
+    state.save(&path).unwrap();
+
+    let loaded = WindowState::load(&path);
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(loaded, Some(state));
+}